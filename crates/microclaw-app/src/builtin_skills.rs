@@ -1,15 +1,167 @@
 use include_dir::{include_dir, Dir, DirEntry};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 static BUILTIN_SKILLS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/../../skills/built-in");
 
-pub fn ensure_builtin_skills(skills_root: &Path) -> std::io::Result<()> {
+/// File in the skills root recording the hash of every built-in file as written
+pub const BUILTIN_MANIFEST_FILE: &str = ".builtin-skills.json";
+
+/// Hashes of the built-in skill files as last written to disk, keyed by skill
+/// name and then by path relative to the skill directory. A file whose on-disk
+/// hash still matches is unmodified and safe to replace with a newer release.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuiltinManifest {
+    #[serde(default)]
+    skills: BTreeMap<String, BTreeMap<String, String>>,
+    /// `version` from each skill's SKILL.md as last written
+    #[serde(default)]
+    versions: BTreeMap<String, String>,
+}
+
+/// Which existing files `sync_skill_entries` may replace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Refresh {
+    /// Same or unknown release on disk: only add missing files
+    MissingOnly,
+    /// Newer bundled release: also replace files still matching their recorded hash
+    Recorded,
+    /// Newer bundled release over an install that predates the manifest:
+    /// replace every differing file, keeping the old copy as `<file>.bak`
+    Adopt,
+}
+
+/// What `ensure_builtin_skills` changed on disk.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BuiltinSkillsSummary {
+    /// Skills written for the first time
+    pub created: Vec<String>,
+    /// Existing skills that had files added or refreshed
+    pub updated: Vec<String>,
+    /// Skills not seeded because this host lacks their platform or dependencies
+    pub skipped: usize,
+}
+
+impl BuiltinSkillsSummary {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty()
+    }
+}
+
+pub fn ensure_builtin_skills(skills_root: &Path) -> std::io::Result<BuiltinSkillsSummary> {
     std::fs::create_dir_all(skills_root)?;
-    copy_compatible_skills(&BUILTIN_SKILLS_DIR, skills_root)
+    let mut manifest = read_manifest(skills_root);
+    let summary = copy_compatible_skills(&BUILTIN_SKILLS_DIR, skills_root, &mut manifest)?;
+    write_manifest(skills_root, &manifest)?;
+    Ok(summary)
 }
 
-fn copy_compatible_skills(embedded: &Dir<'_>, destination: &Path) -> std::io::Result<()> {
+/// Names of the skills bundled with the binary.
+pub fn builtin_skill_names() -> Vec<String> {
+    BUILTIN_SKILLS_DIR
+        .dirs()
+        .filter_map(|dir| dir.path().file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Remove built-in skills that are no longer bundled with the binary.
+///
+/// Only directories recorded in the manifest are candidates, and a directory
+/// is kept when `keep` claims it (e.g. a ClawHub install took the name over)
+/// or when any file was edited, added or removed since it was written.
+/// Returns the names of the pruned skills.
+pub fn prune_retired_builtin_skills(
+    skills_root: &Path,
+    keep: impl Fn(&str) -> bool,
+) -> std::io::Result<Vec<String>> {
+    let mut manifest = read_manifest(skills_root);
+    let current = builtin_skill_names();
+    let retired: Vec<String> = manifest
+        .skills
+        .keys()
+        .filter(|name| !current.contains(name))
+        .cloned()
+        .collect();
+    let mut pruned = Vec::new();
+    for name in retired {
+        let skill_dir = skills_root.join(&name);
+        if !skill_dir.exists() {
+            manifest.skills.remove(&name);
+            manifest.versions.remove(&name);
+            continue;
+        }
+        if keep(&name) {
+            tracing::debug!(
+                "Keeping retired built-in skill '{}': claimed elsewhere",
+                name
+            );
+            continue;
+        }
+        let recorded = &manifest.skills[&name];
+        if hash_dir_files(&skill_dir)? != *recorded {
+            tracing::info!(
+                "Keeping retired built-in skill '{}': it has local changes",
+                name
+            );
+            continue;
+        }
+        std::fs::remove_dir_all(&skill_dir)?;
+        manifest.skills.remove(&name);
+        manifest.versions.remove(&name);
+        pruned.push(name);
+    }
+    write_manifest(skills_root, &manifest)?;
+    Ok(pruned)
+}
+
+/// Hashes of every file under `dir`, keyed like the manifest
+fn hash_dir_files(dir: &Path) -> std::io::Result<BTreeMap<String, String>> {
+    fn walk(root: &Path, dir: &Path, out: &mut BTreeMap<String, String>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(root, &path, out)?;
+            } else {
+                let key = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                out.insert(key, sha256_hex(&std::fs::read(&path)?));
+            }
+        }
+        Ok(())
+    }
+    let mut out = BTreeMap::new();
+    walk(dir, dir, &mut out)?;
+    Ok(out)
+}
+
+fn read_manifest(skills_root: &Path) -> BuiltinManifest {
+    std::fs::read_to_string(skills_root.join(BUILTIN_MANIFEST_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(skills_root: &Path, manifest: &BuiltinManifest) -> std::io::Result<()> {
+    let raw = serde_json::to_string_pretty(manifest).map_err(std::io::Error::other)?;
+    std::fs::write(skills_root.join(BUILTIN_MANIFEST_FILE), raw)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn copy_compatible_skills(
+    embedded: &Dir<'_>,
+    destination: &Path,
+    manifest: &mut BuiltinManifest,
+) -> std::io::Result<BuiltinSkillsSummary> {
+    let mut summary = BuiltinSkillsSummary::default();
     for entry in embedded.entries() {
         let DirEntry::Dir(skill_dir) = entry else {
             continue;
@@ -17,7 +169,7 @@ fn copy_compatible_skills(embedded: &Dir<'_>, destination: &Path) -> std::io::Re
         let Some(skill_name) = skill_dir.path().file_name() else {
             continue;
         };
-        let Some(skill_md) = skill_dir.get_file("SKILL.md") else {
+        let Some(skill_md) = skill_dir.get_file(skill_dir.path().join("SKILL.md")) else {
             continue;
         };
         let content = String::from_utf8_lossy(skill_md.contents());
@@ -27,16 +179,64 @@ fn copy_compatible_skills(embedded: &Dir<'_>, destination: &Path) -> std::io::Re
                 skill_name.to_string_lossy(),
                 reason
             );
+            summary.skipped += 1;
             continue;
         }
+        let name = skill_name.to_string_lossy().to_string();
         let next_dest = destination.join(skill_name);
+        let existed = next_dest.exists();
+        let embedded_version = parse_frontmatter(&content).and_then(|fm| fm.version);
+        let on_disk = existed
+            .then(|| std::fs::read_to_string(next_dest.join("SKILL.md")).ok())
+            .flatten()
+            .and_then(|raw| parse_frontmatter(&raw))
+            .unwrap_or_default();
+        let installed_version = manifest.versions.get(&name).cloned().or(on_disk.version);
+        let bundled = version_key(embedded_version.as_deref());
+        let installed = version_key(installed_version.as_deref());
+        if existed && installed > bundled {
+            tracing::debug!(
+                "Keeping built-in skill '{}' at {}: newer than the bundled release",
+                name,
+                installed_version.as_deref().unwrap_or("?")
+            );
+            continue;
+        }
+        // A directory without a manifest entry is ours only if its SKILL.md
+        // names the skill, i.e. it was written by a release before the manifest.
+        let predates_manifest =
+            existed && !manifest.skills.contains_key(&name) && on_disk.name.as_ref() == Some(&name);
+        let refresh = match (installed < bundled, predates_manifest) {
+            (false, _) => Refresh::MissingOnly,
+            (true, false) => Refresh::Recorded,
+            (true, true) => Refresh::Adopt,
+        };
         std::fs::create_dir_all(&next_dest)?;
-        copy_missing_entries(skill_dir, &next_dest)?;
+        let hashes = manifest.skills.entry(name.clone()).or_default();
+        let written = sync_skill_entries(skill_dir, skill_dir.path(), &next_dest, hashes, refresh)?;
+        if let Some(version) = embedded_version {
+            manifest.versions.insert(name.clone(), version);
+        }
+        if !existed {
+            summary.created.push(name);
+        } else if written > 0 {
+            summary.updated.push(name);
+        }
     }
-    Ok(())
+    Ok(summary)
 }
 
-fn copy_missing_entries(embedded: &Dir<'_>, destination: &Path) -> std::io::Result<()> {
+/// Write missing files and, as `refresh` allows, replace files from an older
+/// release; a file not matching the hash recorded when it was last written is
+/// a local edit and is kept. Returns the number of files written.
+fn sync_skill_entries(
+    embedded: &Dir<'_>,
+    skill_root: &Path,
+    destination: &Path,
+    hashes: &mut BTreeMap<String, String>,
+    refresh: Refresh,
+) -> std::io::Result<usize> {
+    let mut written = 0;
     for entry in embedded.entries() {
         match entry {
             DirEntry::Dir(dir) => {
@@ -45,24 +245,57 @@ fn copy_missing_entries(embedded: &Dir<'_>, destination: &Path) -> std::io::Resu
                 };
                 let next_dest = destination.join(name);
                 std::fs::create_dir_all(&next_dest)?;
-                copy_missing_entries(dir, &next_dest)?;
+                written += sync_skill_entries(dir, skill_root, &next_dest, hashes, refresh)?;
             }
             DirEntry::File(file) => {
                 let Some(name) = file.path().file_name() else {
                     continue;
                 };
+                let key = file
+                    .path()
+                    .strip_prefix(skill_root)
+                    .unwrap_or(file.path())
+                    .to_string_lossy()
+                    .replace('\\', "/");
                 let out_path = destination.join(name);
+                let embedded_hash = sha256_hex(file.contents());
                 if !out_path.exists() {
-                    std::fs::write(out_path, file.contents())?;
+                    std::fs::write(&out_path, file.contents())?;
+                    hashes.insert(key, embedded_hash);
+                    written += 1;
+                    continue;
+                }
+                let disk_hash = sha256_hex(&std::fs::read(&out_path)?);
+                if disk_hash == embedded_hash {
+                    hashes.insert(key, embedded_hash);
+                } else if refresh != Refresh::MissingOnly && hashes.get(&key) == Some(&disk_hash) {
+                    std::fs::write(&out_path, file.contents())?;
+                    hashes.insert(key, embedded_hash);
+                    written += 1;
+                } else if refresh == Refresh::Adopt {
+                    let backup = out_path.with_file_name(format!("{}.bak", name.to_string_lossy()));
+                    std::fs::rename(&out_path, &backup)?;
+                    tracing::info!(
+                        "Updating built-in skill file {}; previous copy kept at {}",
+                        out_path.display(),
+                        backup.display()
+                    );
+                    std::fs::write(&out_path, file.contents())?;
+                    hashes.insert(key, embedded_hash);
+                    written += 1;
                 }
             }
         }
     }
-    Ok(())
+    Ok(written)
 }
 
 #[derive(Debug, Deserialize, Default)]
 struct SkillFrontmatter {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
     #[serde(default)]
     platforms: Vec<String>,
     #[serde(default)]
@@ -87,6 +320,23 @@ fn parse_frontmatter(content: &str) -> Option<SkillFrontmatter> {
     serde_yaml::from_str(yaml).ok()
 }
 
+/// Comparable form of a dotted numeric `version`; a missing marker sorts
+/// before every release that has one.
+fn version_key(version: Option<&str>) -> Vec<u64> {
+    let mut key: Vec<u64> = version
+        .map(|v| {
+            v.trim()
+                .split('.')
+                .map(|part| part.parse().unwrap_or(0))
+                .collect()
+        })
+        .unwrap_or_default();
+    while key.last() == Some(&0) {
+        key.pop();
+    }
+    key
+}
+
 fn skill_skip_reason(content: &str) -> Option<String> {
     let fm = parse_frontmatter(content)?;
     let mut supported = fm.platforms;
@@ -203,11 +453,20 @@ mod tests {
     fn test_ensure_builtin_skills_writes_missing_files() {
         let root = temp_root();
         let skills_root = root.join("skills");
-        ensure_builtin_skills(&skills_root).unwrap();
+        let summary = ensure_builtin_skills(&skills_root).unwrap();
         let sample = skills_root.join("pdf").join("SKILL.md");
         assert!(sample.exists());
         let content = std::fs::read_to_string(sample).unwrap();
         assert!(!content.trim().is_empty());
+        assert!(summary.created.contains(&"pdf".to_string()));
+        assert!(summary.updated.is_empty());
+        assert_eq!(
+            summary.created.len() + summary.skipped,
+            builtin_skill_names().len()
+        );
+
+        let again = ensure_builtin_skills(&skills_root).unwrap();
+        assert!(again.is_empty());
         cleanup(&root);
     }
 
@@ -220,9 +479,137 @@ mod tests {
         let custom_file = custom_pdf.join("SKILL.md");
         std::fs::write(&custom_file, "custom-content").unwrap();
 
-        ensure_builtin_skills(&skills_root).unwrap();
+        let summary = ensure_builtin_skills(&skills_root).unwrap();
         let content = std::fs::read_to_string(custom_file).unwrap();
         assert_eq!(content, "custom-content");
+        assert!(!summary.created.contains(&"pdf".to_string()));
+        cleanup(&root);
+    }
+
+    fn record_hash(skills_root: &Path, skill: &str, file: &str, content: &str) {
+        let mut manifest = read_manifest(skills_root);
+        manifest
+            .skills
+            .entry(skill.to_string())
+            .or_default()
+            .insert(file.to_string(), sha256_hex(content.as_bytes()));
+        write_manifest(skills_root, &manifest).unwrap();
+    }
+
+    #[test]
+    fn test_ensure_builtin_skills_updates_unmodified_file() {
+        let root = temp_root();
+        let skills_root = root.join("skills");
+        let pdf = skills_root.join("pdf");
+        std::fs::create_dir_all(&pdf).unwrap();
+        // An older release wrote this file and recorded its hash.
+        std::fs::write(pdf.join("SKILL.md"), "old built-in").unwrap();
+        record_hash(&skills_root, "pdf", "SKILL.md", "old built-in");
+
+        let summary = ensure_builtin_skills(&skills_root).unwrap();
+        assert!(summary.updated.contains(&"pdf".to_string()));
+        let content = std::fs::read_to_string(pdf.join("SKILL.md")).unwrap();
+        let embedded = BUILTIN_SKILLS_DIR.get_file("pdf/SKILL.md").unwrap();
+        assert_eq!(content.as_bytes(), embedded.contents());
+        assert_eq!(
+            read_manifest(&skills_root).skills["pdf"]["SKILL.md"],
+            sha256_hex(embedded.contents())
+        );
+        cleanup(&root);
+    }
+
+    #[test]
+    fn test_ensure_builtin_skills_updates_install_predating_manifest() {
+        let root = temp_root();
+        let skills_root = root.join("skills");
+        let pdf = skills_root.join("pdf");
+        std::fs::create_dir_all(&pdf).unwrap();
+        let old = "---\nname: pdf\ndescription: old\n---\nold body\n";
+        std::fs::write(pdf.join("SKILL.md"), old).unwrap();
+
+        let summary = ensure_builtin_skills(&skills_root).unwrap();
+        assert!(summary.updated.contains(&"pdf".to_string()));
+        let embedded = BUILTIN_SKILLS_DIR.get_file("pdf/SKILL.md").unwrap();
+        assert_eq!(
+            std::fs::read(pdf.join("SKILL.md")).unwrap(),
+            embedded.contents()
+        );
+        assert_eq!(
+            std::fs::read_to_string(pdf.join("SKILL.md.bak")).unwrap(),
+            old
+        );
+        assert_eq!(read_manifest(&skills_root).versions["pdf"], "1.0.0");
+        cleanup(&root);
+    }
+
+    #[test]
+    fn test_ensure_builtin_skills_does_not_downgrade_newer_release() {
+        let root = temp_root();
+        let skills_root = root.join("skills");
+        let pdf = skills_root.join("pdf");
+        std::fs::create_dir_all(&pdf).unwrap();
+        // A newer binary wrote this file; the running one bundles an older copy.
+        std::fs::write(pdf.join("SKILL.md"), "newer built-in").unwrap();
+        record_hash(&skills_root, "pdf", "SKILL.md", "newer built-in");
+        let mut manifest = read_manifest(&skills_root);
+        manifest.versions.insert("pdf".into(), "99.0.0".into());
+        write_manifest(&skills_root, &manifest).unwrap();
+
+        let summary = ensure_builtin_skills(&skills_root).unwrap();
+        assert!(!summary.updated.contains(&"pdf".to_string()));
+        let content = std::fs::read_to_string(pdf.join("SKILL.md")).unwrap();
+        assert_eq!(content, "newer built-in");
+        assert_eq!(read_manifest(&skills_root).versions["pdf"], "99.0.0");
+        cleanup(&root);
+    }
+
+    #[test]
+    fn test_version_key_orders_releases() {
+        assert!(version_key(None) < version_key(Some("1.0.0")));
+        assert!(version_key(Some("1.2")) < version_key(Some("1.10")));
+        assert_eq!(version_key(Some("1")), version_key(Some("1.0.0")));
+    }
+
+    #[test]
+    fn test_ensure_builtin_skills_preserves_locally_modified_file() {
+        let root = temp_root();
+        let skills_root = root.join("skills");
+        let pdf = skills_root.join("pdf");
+        std::fs::create_dir_all(&pdf).unwrap();
+        record_hash(&skills_root, "pdf", "SKILL.md", "old built-in");
+        std::fs::write(pdf.join("SKILL.md"), "edited by user").unwrap();
+
+        ensure_builtin_skills(&skills_root).unwrap();
+        let content = std::fs::read_to_string(pdf.join("SKILL.md")).unwrap();
+        assert_eq!(content, "edited by user");
+        cleanup(&root);
+    }
+
+    #[test]
+    fn test_prune_retired_builtin_skills_keeps_user_skills() {
+        let root = temp_root();
+        let skills_root = root.join("skills");
+        ensure_builtin_skills(&skills_root).unwrap();
+
+        for name in ["retired", "edited", "claimed"] {
+            let dir = skills_root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("SKILL.md"), "retired built-in").unwrap();
+            record_hash(&skills_root, name, "SKILL.md", "retired built-in");
+        }
+        std::fs::write(skills_root.join("edited").join("notes.md"), "mine").unwrap();
+        let user = skills_root.join("my-skill");
+        std::fs::create_dir_all(&user).unwrap();
+        std::fs::write(user.join("SKILL.md"), "user skill").unwrap();
+
+        let pruned = prune_retired_builtin_skills(&skills_root, |name| name == "claimed").unwrap();
+        assert_eq!(pruned, vec!["retired".to_string()]);
+        assert!(!skills_root.join("retired").exists());
+        assert!(skills_root.join("edited").exists());
+        assert!(skills_root.join("claimed").exists());
+        assert!(user.exists());
+        assert!(skills_root.join("pdf").exists());
+        assert!(!read_manifest(&skills_root).skills.contains_key("retired"));
         cleanup(&root);
     }
 
@@ -5,38 +5,124 @@ use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use tracing::Subscriber;
 use tracing_subscriber::fmt::writer::MakeWriter;
+use tracing_subscriber::util::SubscriberInitExt;
 
 pub const LOG_FILE_PREFIX: &str = "microclaw-";
 pub const LOG_FILE_SUFFIX: &str = ".log";
 pub const LOG_RETENTION_DAYS: i64 = 30;
 
-pub fn init_logging(runtime_data_dir: &str) -> Result<()> {
+/// Value written in place of fields whose name marks them as secret.
+const REDACTED: &str = "***";
+
+fn env_filter() -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into())
+}
+
+/// One JSON object per line: event fields (including `message`) at the top
+/// level, span fields such as `channel` and `chat_id` under `span`/`spans`.
+/// Fields with secret-looking names are masked before they reach `writer`.
+fn json_subscriber<W>(writer: W) -> impl Subscriber + Send + Sync + 'static
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_env_filter(env_filter())
+        .with_writer(RedactingMakeWriter(writer))
+        .finish()
+}
+
+pub fn init_logging(runtime_data_dir: &str, json: bool) -> Result<()> {
     let log_dir = PathBuf::from(runtime_data_dir).join("logs");
     fs::create_dir_all(&log_dir)
         .with_context(|| format!("Failed to create log directory: {}", log_dir.display()))?;
     cleanup_old_logs(&log_dir, Utc::now(), LOG_RETENTION_DAYS)?;
 
     let writer = HourlyLogWriter::new(log_dir, LOG_RETENTION_DAYS)?;
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .with_ansi(false)
-        .with_writer(writer)
-        .init();
+    if json {
+        json_subscriber(writer).init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter())
+            .with_ansi(false)
+            .with_writer(writer)
+            .init();
+    }
 
     Ok(())
 }
 
-pub fn init_console_logging() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
+pub fn init_console_logging(json: bool) {
+    if json {
+        json_subscriber(io::stdout).init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter())
+            .init();
+    }
+}
+
+fn is_secret_field(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    matches!(
+        k.as_str(),
+        "api_key" | "token" | "secret" | "password" | "authorization"
+    ) || k.ends_with("_token")
+        || k.ends_with("_secret")
+        || k.ends_with("_password")
+        || k.ends_with("_api_key")
+}
+
+fn redact_secret_fields(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if is_secret_field(k) {
+                    *v = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secret_fields(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secret_fields),
+        _ => {}
+    }
+}
+
+/// Wraps a writer so each JSON log record is scrubbed of secret fields.
+/// Lines that are not JSON pass through untouched.
+struct RedactingMakeWriter<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match serde_json::from_slice::<serde_json::Value>(buf) {
+            Ok(mut record) => {
+                redact_secret_fields(&mut record);
+                let mut line = serde_json::to_vec(&record).map_err(io::Error::other)?;
+                line.push(b'\n');
+                self.0.write_all(&line)?;
+            }
+            Err(_) => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
 }
 
 #[derive(Debug)]
@@ -236,6 +322,64 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[derive(Clone, Default)]
+    struct BufferWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for BufferWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for BufferWriter {
+        type Writer = BufferWriter;
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_logging_emits_structured_redacted_records() {
+        let buffer = BufferWriter::default();
+        tracing::subscriber::with_default(json_subscriber(buffer.clone()), || {
+            let span = tracing::info_span!(
+                "handle_message",
+                channel = "matrix",
+                chat_id = 42,
+                event_id = "$ev1"
+            );
+            let _guard = span.enter();
+            tracing::info!(api_key = "sk-live-123", attempt = 2, "reply sent");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1, "{output}");
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["level"], "INFO");
+        assert_eq!(record["message"], "reply sent");
+        assert_eq!(record["attempt"], 2);
+        assert_eq!(record["api_key"], REDACTED);
+        assert_eq!(record["span"]["name"], "handle_message");
+        assert_eq!(record["span"]["channel"], "matrix");
+        assert_eq!(record["span"]["chat_id"], 42);
+        assert_eq!(record["span"]["event_id"], "$ev1");
+        assert!(!output.contains("sk-live-123"));
+    }
+
+    #[test]
+    fn test_is_secret_field() {
+        assert!(is_secret_field("api_key"));
+        assert!(is_secret_field("matrix_access_token"));
+        assert!(is_secret_field("Authorization"));
+        assert!(!is_secret_field("chat_id"));
+        assert!(!is_secret_field("event_id"));
+    }
+
     #[test]
     fn test_read_last_lines_from_logs() {
         let dir = test_dir();
@@ -1,6 +1,7 @@
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::channel_adapter::ChannelRegistry;
+use crate::channel_adapter::{ChannelAdapter, ChannelRegistry};
 use microclaw_storage::db::{call_blocking, Database, StoredMessage};
 
 #[derive(Clone, Debug)]
@@ -160,15 +161,31 @@ pub async fn deliver_and_store_bot_message(
     chat_id: i64,
     text: &str,
 ) -> Result<(), String> {
+    deliver_and_store_bot_message_with_id(registry, db, bot_username, chat_id, text)
+        .await
+        .map(|_| ())
+}
+
+/// Like [`deliver_and_store_bot_message`], but returns the id the message was
+/// stored under: the channel's own message id when the adapter reports one,
+/// so the message can be edited later, otherwise a fresh UUID.
+pub async fn deliver_and_store_bot_message_with_id(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    bot_username: &str,
+    chat_id: i64,
+    text: &str,
+) -> Result<String, String> {
     let routing = get_required_chat_routing(registry, db.clone(), chat_id).await?;
     let external_chat_id = call_blocking(db.clone(), move |d| d.get_chat_external_id(chat_id))
         .await
         .map_err(|e| format!("Failed to read external chat id for chat {chat_id}: {e}"))?
         .unwrap_or_else(|| chat_id.to_string());
 
+    let mut external_message_id = None;
     if let Some(adapter) = registry.get(&routing.channel_name) {
         if !adapter.is_local_only() {
-            adapter.send_text(&external_chat_id, text).await?;
+            external_message_id = adapter.send_text_with_id(&external_chat_id, text).await?;
         }
     } else {
         return Err(format!(
@@ -177,8 +194,9 @@ pub async fn deliver_and_store_bot_message(
         ));
     }
 
+    let id = external_message_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     let msg = StoredMessage {
-        id: uuid::Uuid::new_v4().to_string(),
+        id: id.clone(),
         chat_id,
         sender_name: bot_username.to_string(),
         content: text.to_string(),
@@ -187,5 +205,165 @@ pub async fn deliver_and_store_bot_message(
     };
     call_blocking(db.clone(), move |d| d.store_message(&msg))
         .await
-        .map_err(|e| format!("Failed to store sent message: {e}"))
+        .map_err(|e| format!("Failed to store sent message: {e}"))?;
+    Ok(id)
+}
+
+/// Send a file to `chat_id` through its channel adapter and record the bot
+/// message the adapter reports, mirroring [`deliver_and_store_bot_message`].
+pub async fn deliver_and_store_bot_attachment(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    bot_username: &str,
+    chat_id: i64,
+    file_path: &Path,
+    caption: Option<&str>,
+) -> Result<(), String> {
+    let routing = get_required_chat_routing(registry, db.clone(), chat_id).await?;
+    let external_chat_id = call_blocking(db.clone(), move |d| d.get_chat_external_id(chat_id))
+        .await
+        .map_err(|e| format!("Failed to read external chat id for chat {chat_id}: {e}"))?
+        .unwrap_or_else(|| chat_id.to_string());
+
+    let adapter = registry.get(&routing.channel_name).ok_or_else(|| {
+        format!(
+            "No adapter registered for channel '{}'",
+            routing.channel_name
+        )
+    })?;
+    if !adapter.capabilities().attachments {
+        return Err(format!(
+            "attachments not supported for {}",
+            routing.channel_name
+        ));
+    }
+    let content = adapter
+        .send_attachment(&external_chat_id, file_path, caption)
+        .await?;
+
+    let msg = StoredMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        chat_id,
+        sender_name: bot_username.to_string(),
+        content,
+        is_from_bot: true,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    call_blocking(db.clone(), move |d| d.store_message(&msg))
+        .await
+        .map_err(|e| format!("Failed to store sent attachment: {e}"))
+}
+
+/// Adapter and external chat id for reacting in `chat_id`, failing when the
+/// chat's channel does not support reactions.
+async fn reaction_adapter(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+) -> Result<(Arc<dyn ChannelAdapter>, String), String> {
+    let routing = get_required_chat_routing(registry, db.clone(), chat_id).await?;
+    let external_chat_id = call_blocking(db, move |d| d.get_chat_external_id(chat_id))
+        .await
+        .map_err(|e| format!("Failed to read external chat id for chat {chat_id}: {e}"))?
+        .unwrap_or_else(|| chat_id.to_string());
+
+    let adapter = registry.get(&routing.channel_name).ok_or_else(|| {
+        format!(
+            "No adapter registered for channel '{}'",
+            routing.channel_name
+        )
+    })?;
+    if !adapter.capabilities().reactions {
+        return Err(format!(
+            "reactions not supported on this channel ({})",
+            routing.channel_name
+        ));
+    }
+    Ok((adapter.clone(), external_chat_id))
+}
+
+/// React to `message_id` in `chat_id` through the chat's channel adapter.
+pub async fn send_chat_reaction(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    message_id: &str,
+    emoji: &str,
+) -> Result<(), String> {
+    let (adapter, external_chat_id) = reaction_adapter(registry, db, chat_id).await?;
+    adapter
+        .send_reaction(&external_chat_id, message_id, emoji)
+        .await
+}
+
+/// Withdraw the bot's `emoji` reaction on `message_id` in `chat_id`.
+pub async fn remove_chat_reaction(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    message_id: &str,
+    emoji: &str,
+) -> Result<(), String> {
+    let (adapter, external_chat_id) = reaction_adapter(registry, db, chat_id).await?;
+    adapter
+        .remove_reaction(&external_chat_id, message_id, emoji)
+        .await
+}
+
+/// Replace the text of bot message `message_id` in `chat_id` through the
+/// chat's channel adapter, then update the stored copy in place.
+pub async fn edit_chat_message(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    message_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let routing = get_required_chat_routing(registry, db.clone(), chat_id).await?;
+    let adapter = registry.get(&routing.channel_name).ok_or_else(|| {
+        format!(
+            "No adapter registered for channel '{}'",
+            routing.channel_name
+        )
+    })?;
+    let capabilities = adapter.capabilities();
+    if !capabilities.edits {
+        return Err(format!(
+            "editing not supported on this channel ({})",
+            routing.channel_name
+        ));
+    }
+    if text.chars().count() > capabilities.max_message_len {
+        return Err(format!(
+            "Edited text exceeds the {} character limit for {}",
+            capabilities.max_message_len, routing.channel_name
+        ));
+    }
+
+    let lookup_id = message_id.to_string();
+    let stored = call_blocking(db.clone(), move |d| d.get_message(chat_id, &lookup_id))
+        .await
+        .map_err(|e| format!("Failed to load message {message_id}: {e}"))?;
+    match stored {
+        Some(m) if m.is_from_bot => {}
+        Some(_) => return Err(format!("Message {message_id} was not sent by the bot")),
+        None => return Err(format!("No message {message_id} in chat {chat_id}")),
+    }
+
+    let external_chat_id = call_blocking(db.clone(), move |d| d.get_chat_external_id(chat_id))
+        .await
+        .map_err(|e| format!("Failed to read external chat id for chat {chat_id}: {e}"))?
+        .unwrap_or_else(|| chat_id.to_string());
+    adapter
+        .edit_text(&external_chat_id, message_id, text)
+        .await?;
+
+    let message_id = message_id.to_string();
+    let text = text.to_string();
+    call_blocking(db, move |d| {
+        d.update_message_content(chat_id, &message_id, &text)
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Failed to update stored message: {e}"))
 }
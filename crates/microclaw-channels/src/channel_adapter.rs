@@ -6,6 +6,37 @@ use async_trait::async_trait;
 
 use crate::channel::ConversationKind;
 
+/// What a channel can do beyond sending plain text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCapabilities {
+    pub attachments: bool,
+    pub reactions: bool,
+    pub edits: bool,
+    pub threads: bool,
+    /// Longest text accepted in a single outbound message.
+    pub max_message_len: usize,
+}
+
+impl Default for ChannelCapabilities {
+    fn default() -> Self {
+        Self {
+            attachments: false,
+            reactions: false,
+            edits: false,
+            threads: false,
+            max_message_len: 4000,
+        }
+    }
+}
+
+impl ChannelCapabilities {
+    /// Split `text` into messages no longer than `max_message_len`, keeping
+    /// fenced code blocks intact across the split.
+    pub fn split_text(&self, text: &str) -> Vec<String> {
+        microclaw_core::text::split_text_preserving_code(text, self.max_message_len)
+    }
+}
+
 #[async_trait]
 pub trait ChannelAdapter: Send + Sync {
     /// Unique name: "telegram", "discord", "slack", "web"
@@ -24,9 +55,25 @@ pub trait ChannelAdapter: Send + Sync {
         true
     }
 
+    /// Optional features this channel supports. Default: text only.
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities::default()
+    }
+
     /// Send text to external chat. Called by deliver_and_store_bot_message.
     async fn send_text(&self, external_chat_id: &str, text: &str) -> Result<(), String>;
 
+    /// Send text and return the external message id when the channel reports one.
+    /// Default: plain `send_text` with no id.
+    async fn send_text_with_id(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        self.send_text(external_chat_id, text).await?;
+        Ok(None)
+    }
+
     /// Send file attachment. Default: not supported.
     async fn send_attachment(
         &self,
@@ -36,6 +83,57 @@ pub trait ChannelAdapter: Send + Sync {
     ) -> Result<String, String> {
         Err(format!("attachments not supported for {}", self.name()))
     }
+
+    /// React to an external message with an emoji. Default: not supported.
+    async fn send_reaction(
+        &self,
+        _external_chat_id: &str,
+        _message_id: &str,
+        _emoji: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "reactions not supported on this channel ({})",
+            self.name()
+        ))
+    }
+
+    /// Withdraw a reaction the bot added with `send_reaction`. Default: not supported.
+    async fn remove_reaction(
+        &self,
+        _external_chat_id: &str,
+        _message_id: &str,
+        _emoji: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "removing reactions not supported on this channel ({})",
+            self.name()
+        ))
+    }
+
+    /// Replace the text of a previously sent message. Default: not supported.
+    async fn edit_text(
+        &self,
+        _external_chat_id: &str,
+        _message_id: &str,
+        _text: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "editing not supported on this channel ({})",
+            self.name()
+        ))
+    }
+
+    /// Remove a previously sent message. Default: not supported.
+    async fn delete_message(
+        &self,
+        _external_chat_id: &str,
+        _message_id: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "deleting not supported on this channel ({})",
+            self.name()
+        ))
+    }
 }
 
 #[derive(Default)]
@@ -88,4 +186,9 @@ impl ChannelRegistry {
     pub fn has_any(&self) -> bool {
         !self.adapters.is_empty()
     }
+
+    /// Names of every registered adapter.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.adapters.keys().map(String::as_str)
+    }
 }
@@ -1,10 +1,157 @@
 use crate::types::*;
+use futures_util::{Stream, StreamExt};
 use microclaw_core::error::MicroClawError;
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Default attempts per download URL before moving on to the next one;
+/// see [`ClawHubClient::with_download_retry`]
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+const DOWNLOAD_RETRY_BASE: Duration = Duration::from_millis(500);
+
+/// Sort orders accepted by the search endpoint
+pub const SEARCH_SORTS: &[&str] = &["trending", "recent", "installs"];
+pub const DEFAULT_SEARCH_SORT: &str = "trending";
+/// Largest page the registry is asked for in one search request
+pub const MAX_SEARCH_LIMIT: usize = 50;
+
+/// Check `sort` against [`SEARCH_SORTS`]; empty means [`DEFAULT_SEARCH_SORT`].
+pub fn validate_search_sort(sort: &str) -> Result<&'static str, MicroClawError> {
+    let sort = sort.trim();
+    if sort.is_empty() {
+        return Ok(DEFAULT_SEARCH_SORT);
+    }
+    SEARCH_SORTS
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case(sort))
+        .copied()
+        .ok_or_else(|| {
+            MicroClawError::Config(format!(
+                "Unknown search sort '{}'; expected one of: {}",
+                sort,
+                SEARCH_SORTS.join(", ")
+            ))
+        })
+}
+
+/// Download progress callback: `(bytes_received, total_bytes_if_known)`
+pub type DownloadProgress = dyn Fn(u64, Option<u64>) + Send + Sync;
+
+/// A skill ZIP streamed to a temp file; the file is removed on drop.
+pub struct DownloadedSkill {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+impl Drop for DownloadedSkill {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum DownloadError {
+    /// Network blips, 429 and 5xx: worth retrying
+    Transient(String),
+    /// Anything else (4xx, local I/O): retrying won't help
+    Fatal(String),
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::Transient(msg) | DownloadError::Fatal(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Run `op` up to `attempts` times, backing off exponentially between
+/// transient failures. Fatal errors are returned immediately.
+pub(crate) async fn retry_transient<T, F, Fut>(
+    attempts: u32,
+    base_delay: Duration,
+    mut op: F,
+) -> Result<T, DownloadError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, DownloadError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(DownloadError::Transient(_)) if attempt < attempts => {
+                tokio::time::sleep(base_delay * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            other => return other,
+        }
+    }
+}
+
+/// Write a chunk stream to `dest` (truncating any earlier partial attempt),
+/// hashing as it goes. Returns the byte count and hex SHA-256.
+pub(crate) async fn write_stream_to_file<S>(
+    body: S,
+    dest: &Path,
+    total: Option<u64>,
+    progress: Option<&DownloadProgress>,
+) -> Result<(u64, String), DownloadError>
+where
+    S: Stream<Item = Result<Vec<u8>, DownloadError>>,
+{
+    let io_err = |e: std::io::Error| DownloadError::Fatal(format!("{}: {}", dest.display(), e));
+    let mut file = tokio::fs::File::create(dest).await.map_err(io_err)?;
+    let mut hasher = Sha256::new();
+    let mut received = 0u64;
+    let mut body = std::pin::pin!(body);
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk).await.map_err(io_err)?;
+        hasher.update(&chunk);
+        received += chunk.len() as u64;
+        if let Some(progress) = progress {
+            progress(received, total);
+        }
+    }
+    file.flush().await.map_err(io_err)?;
+    Ok((received, format!("{:x}", hasher.finalize())))
+}
+
+/// Map a non-success registry status to an error carrying the status and an
+/// actionable message. `not_found` describes what a 404 means for this request.
+fn status_error(status: reqwest::StatusCode, not_found: &str) -> Option<MicroClawError> {
+    if status.is_success() {
+        return None;
+    }
+    let message = match status.as_u16() {
+        404 => not_found.to_string(),
+        401 | 403 => format!(
+            "ClawHub rejected the request (HTTP {}); check `clawhub.token` in your config",
+            status.as_u16()
+        ),
+        429 => "ClawHub rate limited the request (HTTP 429); try again shortly".to_string(),
+        code if status.is_server_error() => {
+            format!("ClawHub server error (HTTP {}); try again later", code)
+        }
+        code => format!("ClawHub request failed: HTTP {}", code),
+    };
+    Some(MicroClawError::HttpStatus {
+        status: status.as_u16(),
+        message,
+    })
+}
 
 pub struct ClawHubClient {
     base_url: String,
     token: Option<String>,
     client: reqwest::Client,
+    download_attempts: u32,
+    download_retry_base: Duration,
 }
 
 impl ClawHubClient {
@@ -13,29 +160,79 @@ impl ClawHubClient {
             base_url: base_url.to_string(),
             token,
             client: reqwest::Client::new(),
+            download_attempts: DOWNLOAD_ATTEMPTS,
+            download_retry_base: DOWNLOAD_RETRY_BASE,
         }
     }
 
-    /// Search skills by query
+    /// Use a preconfigured HTTP client (e.g. one with proxy settings applied).
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Attempts per download URL (at least one) and the initial backoff
+    /// between transient failures.
+    pub fn with_download_retry(mut self, attempts: u32, base_delay: Duration) -> Self {
+        self.download_attempts = attempts.max(1);
+        self.download_retry_base = base_delay;
+        self
+    }
+
+    fn search_url(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+        sort: &str,
+    ) -> Result<String, MicroClawError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(MicroClawError::Config(
+                "Search query is empty; describe the skill you need, e.g. \"pdf\"".into(),
+            ));
+        }
+        let sort = validate_search_sort(sort)?;
+        // Use the dedicated search endpoint that actually filters by query
+        Ok(format!(
+            "{}/api/v1/search?q={}&limit={}&offset={}&sort={}",
+            self.base_url,
+            urlencoding::encode(query),
+            limit.clamp(1, MAX_SEARCH_LIMIT),
+            offset,
+            sort
+        ))
+    }
+
+    /// `/api/v1/skills/{slug}` with the slug percent-encoded as one path segment
+    fn skill_url(&self, slug: &str) -> String {
+        format!(
+            "{}/api/v1/skills/{}",
+            self.base_url,
+            urlencoding::encode(slug)
+        )
+    }
+
+    /// Search skills by query, returning at most `limit` results after skipping `offset`
     pub async fn search(
         &self,
         query: &str,
         limit: usize,
-        _sort: &str,
+        offset: usize,
+        sort: &str,
     ) -> Result<Vec<SearchResult>, MicroClawError> {
-        // Use the dedicated search endpoint that actually filters by query
-        let url = format!(
-            "{}/api/v1/search?q={}&limit={}",
-            self.base_url, query, limit
-        );
+        let url = self.search_url(query, limit, offset, sort)?;
         let mut req = self.client.get(&url);
         if let Some(ref token) = self.token {
             req = req.header("Authorization", format!("Bearer {}", token));
         }
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| MicroClawError::Config(format!("ClawHub request failed: {}", e)))?;
+        let resp = req.send().await?;
+        if let Some(e) = status_error(
+            resp.status(),
+            &format!("ClawHub search endpoint not found at {}", self.base_url),
+        ) {
+            return Err(e);
+        }
         let search_response: ApiSearchResponse = resp.json().await.map_err(|e| {
             MicroClawError::Config(format!("Failed to parse search results: {}", e))
         })?;
@@ -43,22 +240,25 @@ impl ClawHubClient {
         Ok(search_response
             .results
             .into_iter()
-            .take(limit)
+            .take(limit.clamp(1, MAX_SEARCH_LIMIT))
             .map(SearchResult::from)
             .collect())
     }
 
     /// Get skill metadata by slug
     pub async fn get_skill(&self, slug: &str) -> Result<SkillMeta, MicroClawError> {
-        let url = format!("{}/api/v1/skills/{}", self.base_url, slug);
+        let url = self.skill_url(slug);
         let mut req = self.client.get(&url);
         if let Some(ref token) = self.token {
             req = req.header("Authorization", format!("Bearer {}", token));
         }
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| MicroClawError::Config(format!("ClawHub request failed: {}", e)))?;
+        let resp = req.send().await?;
+        if let Some(e) = status_error(
+            resp.status(),
+            &format!("Skill '{}' not found on ClawHub", slug),
+        ) {
+            return Err(e);
+        }
         let get_response: GetSkillResponse = resp.json().await.map_err(|e| {
             MicroClawError::Config(format!("Failed to parse skill metadata: {}", e))
         })?;
@@ -72,15 +272,30 @@ impl ClawHubClient {
         slug: &str,
         version: &str,
     ) -> Result<Vec<u8>, MicroClawError> {
+        let downloaded = self.download_skill_to_file(slug, version, None).await?;
+        Ok(std::fs::read(&downloaded.path)?)
+    }
+
+    /// Stream a skill ZIP into a temp file, reporting byte progress and
+    /// retrying transient failures before falling back to the next endpoint.
+    pub async fn download_skill_to_file(
+        &self,
+        slug: &str,
+        version: &str,
+        progress: Option<&DownloadProgress>,
+    ) -> Result<DownloadedSkill, MicroClawError> {
+        let encoded_slug = urlencoding::encode(slug);
+        let encoded_version = urlencoding::encode(version);
         // Prefer the configured registry domain first.
         let mut candidate_urls = vec![
             format!(
                 "{}/api/v1/download?slug={}&version={}",
-                self.base_url, slug, version
+                self.base_url, encoded_slug, encoded_version
             ),
             format!(
-                "{}/api/v1/skills/{}/download?version={}",
-                self.base_url, slug, version
+                "{}/download?version={}",
+                self.skill_url(slug),
+                encoded_version
             ),
         ];
 
@@ -88,61 +303,105 @@ impl ClawHubClient {
         if self.base_url.contains("clawhub.ai") {
             candidate_urls.push(format!(
                 "https://wry-manatee-359.convex.site/api/v1/download?slug={}&version={}",
-                slug, version
+                encoded_slug, encoded_version
             ));
         }
 
+        // The slug comes from the caller/registry; keep it out of the path.
+        let path =
+            std::env::temp_dir().join(format!("microclaw-skill-{}.zip", uuid::Uuid::new_v4()));
+
         let mut last_error: Option<MicroClawError> = None;
         for url in candidate_urls {
-            let mut req = self.client.get(&url);
-            if let Some(ref token) = self.token {
-                req = req.header("Authorization", format!("Bearer {}", token));
-            }
-
-            let resp = match req.send().await {
-                Ok(resp) => resp,
-                Err(e) => {
-                    last_error = Some(MicroClawError::Config(format!(
-                        "ClawHub download failed at {}: {}",
-                        url, e
-                    )));
-                    continue;
+            let result = retry_transient(self.download_attempts, self.download_retry_base, || {
+                self.download_once(&url, &path, progress)
+            })
+            .await;
+            match result {
+                Ok((size, sha256)) => {
+                    return Ok(DownloadedSkill { path, size, sha256 });
                 }
-            };
-
-            let resp = match resp.error_for_status() {
-                Ok(resp) => resp,
                 Err(e) => {
                     last_error = Some(MicroClawError::Config(format!(
-                        "ClawHub download HTTP error at {}: {}",
+                        "ClawHub download failed at {}: {}",
                         url, e
                     )));
-                    continue;
                 }
-            };
-
-            let bytes = resp.bytes().await.map_err(|e| {
-                MicroClawError::Config(format!("Failed to read download from {}: {}", url, e))
-            })?;
-            return Ok(bytes.to_vec());
+            }
         }
 
+        let _ = std::fs::remove_file(&path);
         Err(last_error.unwrap_or_else(|| {
             MicroClawError::Config("ClawHub download failed: no usable endpoint".into())
         }))
     }
 
-    /// List versions for a skill
-    pub async fn get_versions(&self, slug: &str) -> Result<Vec<SkillVersion>, MicroClawError> {
-        let url = format!("{}/api/v1/skills/{}/versions", self.base_url, slug);
-        let mut req = self.client.get(&url);
+    async fn download_once(
+        &self,
+        url: &str,
+        dest: &Path,
+        progress: Option<&DownloadProgress>,
+    ) -> Result<(u64, String), DownloadError> {
+        let mut req = self.client.get(url);
         if let Some(ref token) = self.token {
             req = req.header("Authorization", format!("Bearer {}", token));
         }
         let resp = req
             .send()
             .await
-            .map_err(|e| MicroClawError::Config(format!("ClawHub request failed: {}", e)))?;
+            .map_err(|e| DownloadError::Transient(e.to_string()))?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(DownloadError::Transient(format!("HTTP {}", status)));
+        }
+        if !status.is_success() {
+            return Err(DownloadError::Fatal(format!("HTTP {}", status)));
+        }
+
+        let total = resp.content_length();
+        let body = futures_util::stream::unfold(resp, |mut resp| async move {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), resp)),
+                Ok(None) => None,
+                Err(e) => Some((Err(DownloadError::Transient(e.to_string())), resp)),
+            }
+        });
+        write_stream_to_file(body, dest, total, progress).await
+    }
+
+    /// Registry entry for one published version of a skill. `get_skill` only
+    /// describes the latest version, so pinned installs look theirs up here.
+    pub async fn get_version(
+        &self,
+        slug: &str,
+        version: &str,
+    ) -> Result<SkillVersion, MicroClawError> {
+        self.get_versions(slug)
+            .await?
+            .into_iter()
+            .find(|v| v.version == version)
+            .ok_or_else(|| {
+                MicroClawError::Config(format!(
+                    "Version {} of '{}' not found on ClawHub",
+                    version, slug
+                ))
+            })
+    }
+
+    /// List versions for a skill
+    pub async fn get_versions(&self, slug: &str) -> Result<Vec<SkillVersion>, MicroClawError> {
+        let url = format!("{}/versions", self.skill_url(slug));
+        let mut req = self.client.get(&url);
+        if let Some(ref token) = self.token {
+            req = req.header("Authorization", format!("Bearer {}", token));
+        }
+        let resp = req.send().await?;
+        if let Some(e) = status_error(
+            resp.status(),
+            &format!("Skill '{}' not found on ClawHub", slug),
+        ) {
+            return Err(e);
+        }
         let versions: Vec<SkillVersion> = resp
             .json()
             .await
@@ -154,6 +413,7 @@ impl ClawHubClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_server::FakeRegistry;
 
     #[test]
     fn test_client_construction() {
@@ -162,9 +422,211 @@ mod tests {
         assert!(client.token.is_none());
     }
 
+    #[test]
+    fn test_search_url_includes_sort() {
+        let client = ClawHubClient::new("https://clawhub.ai", None);
+        assert_eq!(
+            client.search_url("pdf tools", 5, 0, "recent").unwrap(),
+            "https://clawhub.ai/api/v1/search?q=pdf%20tools&limit=5&offset=0&sort=recent"
+        );
+        assert!(client
+            .search_url("pdf", 5, 0, "")
+            .unwrap()
+            .ends_with("&sort=trending"));
+        assert!(client
+            .search_url("pdf", 5, 0, "Installs")
+            .unwrap()
+            .ends_with("&sort=installs"));
+        let err = client.search_url("pdf", 5, 0, "latest").unwrap_err();
+        assert!(err.to_string().contains("Unknown search sort 'latest'"));
+    }
+
+    #[test]
+    fn test_search_url_encodes_query() {
+        let client = ClawHubClient::new("https://clawhub.ai", None);
+        assert_eq!(
+            client
+                .search_url("json parser & more", 10, 0, "trending")
+                .unwrap(),
+            "https://clawhub.ai/api/v1/search?q=json%20parser%20%26%20more&limit=10&offset=0&sort=trending"
+        );
+        assert!(client
+            .search_url("c# #tag", 10, 0, "trending")
+            .unwrap()
+            .contains("q=c%23%20%23tag&"));
+        let err = client.search_url("   ", 10, 0, "trending").unwrap_err();
+        assert!(err.to_string().contains("Search query is empty"));
+    }
+
+    #[test]
+    fn test_search_url_includes_offset_and_limit() {
+        let client = ClawHubClient::new("https://clawhub.ai", None);
+        assert_eq!(
+            client.search_url("pdf", 20, 40, "trending").unwrap(),
+            "https://clawhub.ai/api/v1/search?q=pdf&limit=20&offset=40&sort=trending"
+        );
+        assert!(client
+            .search_url("pdf", 500, 0, "trending")
+            .unwrap()
+            .contains(&format!("&limit={}&", MAX_SEARCH_LIMIT)));
+        assert!(client
+            .search_url("pdf", 0, 0, "trending")
+            .unwrap()
+            .contains("&limit=1&"));
+    }
+
+    #[test]
+    fn test_status_error_not_found_names_slug() {
+        use reqwest::StatusCode;
+        assert!(status_error(StatusCode::OK, "unused").is_none());
+        let err = status_error(
+            StatusCode::NOT_FOUND,
+            "Skill 'pdf-tools' not found on ClawHub",
+        )
+        .unwrap()
+        .to_string();
+        assert!(err.contains("Skill 'pdf-tools' not found"), "{err}");
+    }
+
+    #[test]
+    fn test_status_error_auth_and_server_hints() {
+        use reqwest::StatusCode;
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let err = status_error(status, "unused").unwrap().to_string();
+            assert!(err.contains("clawhub.token"), "{err}");
+            assert!(err.contains(&status.as_u16().to_string()), "{err}");
+        }
+        let err = status_error(StatusCode::BAD_GATEWAY, "unused")
+            .unwrap()
+            .to_string();
+        assert!(err.contains("server error (HTTP 502)"), "{err}");
+        assert!(err.contains("try again"), "{err}");
+        let err = status_error(StatusCode::BAD_REQUEST, "unused")
+            .unwrap()
+            .to_string();
+        assert!(err.contains("HTTP 400"), "{err}");
+        assert!(matches!(
+            status_error(StatusCode::TOO_MANY_REQUESTS, "unused"),
+            Some(MicroClawError::HttpStatus { status: 429, .. })
+        ));
+    }
+
+    #[test]
+    fn test_skill_url_encodes_slug() {
+        let client = ClawHubClient::new("https://clawhub.ai", None);
+        assert_eq!(
+            client.skill_url("owner/my skill"),
+            "https://clawhub.ai/api/v1/skills/owner%2Fmy%20skill"
+        );
+        assert_eq!(
+            client.skill_url("pdf-tools"),
+            "https://clawhub.ai/api/v1/skills/pdf-tools"
+        );
+    }
+
     #[test]
     fn test_client_with_token() {
         let client = ClawHubClient::new("https://clawhub.ai", Some("test-token".into()));
         assert!(client.token.is_some());
     }
+
+    #[tokio::test]
+    async fn test_download_retries_transient_body_error() {
+        let dest = std::env::temp_dir().join(format!("clawhub_dl_{}.zip", uuid::Uuid::new_v4()));
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let progress = move |received: u64, total: Option<u64>| {
+            recorder.lock().unwrap().push((received, total));
+        };
+
+        let (size, sha256) = retry_transient(3, Duration::from_millis(1), || {
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let chunks: Vec<Result<Vec<u8>, DownloadError>> = if attempt == 0 {
+                vec![
+                    Ok(b"ab".to_vec()),
+                    Err(DownloadError::Transient("connection reset".into())),
+                ]
+            } else {
+                vec![Ok(b"ab".to_vec()), Ok(b"c".to_vec())]
+            };
+            let dest = dest.clone();
+            let progress = &progress;
+            async move {
+                write_stream_to_file(
+                    futures_util::stream::iter(chunks),
+                    &dest,
+                    Some(3),
+                    Some(progress),
+                )
+                .await
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(size, 3);
+        assert_eq!(std::fs::read(&dest).unwrap(), b"abc");
+        assert_eq!(
+            sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(seen.lock().unwrap().last(), Some(&(3, Some(3))));
+
+        std::fs::remove_file(&dest).ok();
+    }
+
+    #[tokio::test]
+    async fn test_download_does_not_retry_fatal_error() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result: Result<(), DownloadError> =
+            retry_transient(3, Duration::from_millis(1), || {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Err(DownloadError::Fatal("HTTP 404 Not Found".into())) }
+            })
+            .await;
+        assert!(matches!(result, Err(DownloadError::Fatal(_))));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_retry_setting_bounds_attempts_per_url() {
+        let primary = "/api/v1/download?slug=pdf&version=1.0.0";
+        for attempts in [1, 3] {
+            let registry = FakeRegistry::default().route(primary, 503, "busy");
+            let hits = registry.hits();
+            let client = registry
+                .serve()
+                .await
+                .with_download_retry(attempts, Duration::from_millis(1));
+            let result = client.download_skill_to_file("pdf", "1.0.0", None).await;
+            assert!(result.is_err());
+            assert_eq!(hits.count(primary), attempts as usize);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_temp_path_ignores_slug() {
+        let client = FakeRegistry::default()
+            .route(
+                "/api/v1/download?slug=..%2F..%2Fevil&version=1.0.0",
+                200,
+                "zip",
+            )
+            .serve()
+            .await;
+        let downloaded = client
+            .download_skill_to_file("../../evil", "1.0.0", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            downloaded.path.parent(),
+            Some(std::env::temp_dir().as_path())
+        );
+        let name = downloaded.path.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("microclaw-skill-"), "{name}");
+        assert!(!name.contains("evil"), "{name}");
+        assert_eq!(std::fs::read(&downloaded.path).unwrap(), b"zip");
+    }
 }
@@ -1,19 +1,41 @@
-use crate::client::ClawHubClient;
+use crate::client::{ClawHubClient, DownloadProgress};
 use crate::gate::check_requirements;
-use crate::lockfile::{is_clawhub_managed, read_lockfile, write_lockfile};
-use crate::types::{LockEntry, LockFile};
+use crate::lockfile::{is_clawhub_managed, read_lockfile, remove_entry, write_lockfile};
+use crate::types::{LockEntry, LockFile, SkillMeta, SkillVersion, VirusTotal};
+use crate::verify::hash_skill_dir;
+use futures_util::future::join_all;
 use microclaw_core::error::MicroClawError;
 use sha2::{Digest, Sha256};
-use std::path::Path;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, Weak};
+use tokio::sync::{Mutex, Semaphore};
 use zip::ZipArchive;
 
+/// Lockfile `source` marker for skills installed from a local ZIP
+pub const LOCAL_SOURCE: &str = "local";
+
+/// Lockfile `installedAs` marker for skills pulled in by another skill
+pub const DEPENDENCY_INSTALL: &str = "dependency";
+
 #[derive(Clone)]
 pub struct InstallOptions {
     pub force: bool,
     pub skip_gates: bool,
     pub skip_security: bool,
+    /// Skip verifying the download against the registry's published SHA-256
+    pub skip_checksum: bool,
+    /// Called with byte progress while the ZIP downloads
+    pub progress: Option<Arc<DownloadProgress>>,
+    /// Record the installed version as pinned in the lockfile
+    pub pin: bool,
+    /// Refuse skills with more VirusTotal detections than this (unless forced)
+    pub block_on_virustotal_detections: Option<u32>,
 }
 
+#[derive(Debug)]
 pub struct InstallResult {
     pub success: bool,
     pub message: String,
@@ -35,6 +57,12 @@ pub struct SecurityWarning {
     pub url: String,
 }
 
+/// A downloaded and extracted skill whose lockfile entry is not yet written
+pub struct PreparedInstall {
+    pub result: InstallResult,
+    pub entry: Option<LockEntry>,
+}
+
 /// Main install function
 pub async fn install_skill(
     client: &ClawHubClient,
@@ -44,20 +72,528 @@ pub async fn install_skill(
     lockfile_path: &Path,
     options: &InstallOptions,
 ) -> Result<InstallResult, MicroClawError> {
-    // 1. Get skill metadata
-    let meta = client.get_skill(slug).await?;
+    let lock = read_lockfile(lockfile_path)?;
+    let prepared = prepare_install(client, slug, version, skills_dir, &lock, options).await?;
+    if let Some(entry) = prepared.entry {
+        let mut lock = read_lockfile(lockfile_path)?;
+        lock.skills.insert(entry.slug.clone(), entry);
+        write_lockfile(lockfile_path, &lock)?;
+    }
+    Ok(prepared.result)
+}
 
-    // 2. Resolve version
-    let target_version = version.unwrap_or("latest");
-    let actual_version = if target_version == "latest" {
-        meta.versions
+/// Order in which `root` and its transitive dependencies must be installed:
+/// every skill comes after the skills it depends on, `root` last. Each skill
+/// is looked up once; a dependency cycle is an error naming the loop.
+pub async fn resolve_install_order<F, Fut>(
+    root: &str,
+    mut dependencies_of: F,
+) -> Result<Vec<String>, MicroClawError>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<String>, MicroClawError>>,
+{
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    // Depth-first path from `root`, each with the dependencies still to visit
+    let root_deps = dependencies_of(root.to_string()).await?;
+    let mut path: Vec<(String, Vec<String>)> =
+        vec![(root.to_string(), root_deps.into_iter().rev().collect())];
+    while let Some((_, pending)) = path.last_mut() {
+        let Some(dep) = pending.pop() else {
+            if let Some((slug, _)) = path.pop() {
+                visited.insert(slug.clone());
+                order.push(slug);
+            }
+            continue;
+        };
+        if visited.contains(&dep) {
+            continue;
+        }
+        if let Some(start) = path.iter().position(|(slug, _)| *slug == dep) {
+            let mut cycle: Vec<&str> = path[start..].iter().map(|(s, _)| s.as_str()).collect();
+            cycle.push(&dep);
+            return Err(MicroClawError::Config(format!(
+                "Dependency cycle: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        let deps = dependencies_of(dep.clone()).await?;
+        path.push((dep, deps.into_iter().rev().collect()));
+    }
+    Ok(order)
+}
+
+/// Concrete version an install of `version` resolves to: the registry's
+/// latest for `None`/`latest`, otherwise `version` itself.
+fn resolve_target_version(meta: &SkillMeta, version: Option<&str>) -> String {
+    match version.unwrap_or("latest") {
+        "latest" => meta
+            .versions
             .iter()
             .find(|v| v.latest)
             .map(|v| v.version.clone())
-            .unwrap_or_else(|| "latest".to_string())
-    } else {
-        target_version.to_string()
+            .unwrap_or_else(|| "latest".to_string()),
+        pinned => pinned.to_string(),
+    }
+}
+
+/// Registry entry for `version`, from `meta` when it covers it (it only
+/// describes latest) or else from the version list.
+async fn version_entry(
+    client: &ClawHubClient,
+    slug: &str,
+    meta: &SkillMeta,
+    version: &str,
+) -> Result<SkillVersion, MicroClawError> {
+    match meta.versions.iter().find(|v| v.version == version) {
+        Some(entry) => Ok(entry.clone()),
+        // No version list at all: the skill-level metadata is all there is
+        None if version == "latest" => Ok(SkillVersion {
+            version: version.to_string(),
+            latest: true,
+            sha256: None,
+            dependencies: meta.dependencies.clone(),
+        }),
+        None => client.get_version(slug, version).await,
+    }
+}
+
+/// Published SHA-256 for `version`. The get-skill response can omit it, in
+/// which case the per-version list is consulted; with no hash anywhere the
+/// install is refused rather than left unverified. Failing to fetch or parse
+/// that list is reported as such, not as a missing hash.
+async fn expected_checksum(
+    client: &ClawHubClient,
+    slug: &str,
+    version: &SkillVersion,
+) -> Result<String, MicroClawError> {
+    if let Some(sha256) = &version.sha256 {
+        return Ok(sha256.clone());
+    }
+    let listed = client.get_versions(slug).await?;
+    let listed = listed.into_iter().find(|v| v.version == version.version);
+    listed.and_then(|v| v.sha256).ok_or_else(|| {
+        MicroClawError::Config(format!(
+            "Refusing to install {} v{}: ClawHub publishes no sha256 for this version. Use --skip-checksum to install anyway.",
+            slug, version.version
+        ))
+    })
+}
+
+/// Install order for `root` at `version` and its transitive dependencies at
+/// their latest versions, dependencies first. Every slug, including the ones
+/// the registry hands back, is validated before anything is installed.
+pub async fn resolve_dependencies(
+    client: &ClawHubClient,
+    root: &str,
+    version: Option<&str>,
+) -> Result<Vec<String>, MicroClawError> {
+    validate_slug(root)?;
+    resolve_install_order(root, |slug| async move {
+        let meta = client.get_skill(&slug).await?;
+        let requested = if slug == root { version } else { None };
+        let target = resolve_target_version(&meta, requested);
+        let deps = version_entry(client, &slug, &meta, &target)
+            .await?
+            .dependencies;
+        for dep in &deps {
+            validate_slug(dep).map_err(|e| {
+                MicroClawError::Config(format!(
+                    "Skill '{}' declares an invalid dependency: {}",
+                    slug, e
+                ))
+            })?;
+        }
+        Ok(deps)
+    })
+    .await
+}
+
+/// Options for installing a skill as someone else's dependency: never
+/// forced over an existing copy and never pinned.
+fn dependency_options(options: &InstallOptions) -> InstallOptions {
+    InstallOptions {
+        force: false,
+        pin: false,
+        ..options.clone()
+    }
+}
+
+/// Uninstall dependencies installed for a skill whose own install failed,
+/// newest first. Best effort: a dependency that cannot be removed stays.
+fn rollback_dependencies(installed: &[String], skills_dir: &Path, lockfile_path: &Path) {
+    for dep in installed.iter().rev() {
+        let _ = uninstall_skill(dep, skills_dir, lockfile_path);
+    }
+}
+
+/// Install `slug` after any of its dependencies that are not yet present.
+/// Dependencies are recorded in the lockfile as `installedAs: dependency`
+/// and removed again if `slug` itself fails to install.
+pub async fn install_skill_with_dependencies(
+    client: &ClawHubClient,
+    slug: &str,
+    version: Option<&str>,
+    skills_dir: &Path,
+    lockfile_path: &Path,
+    options: &InstallOptions,
+) -> Result<InstallResult, MicroClawError> {
+    let order = resolve_dependencies(client, slug, version).await?;
+
+    let lock = read_lockfile(lockfile_path)?;
+    let dep_options = dependency_options(options);
+    let mut installed_deps = Vec::new();
+    for dep in order.iter().filter(|s| s.as_str() != slug) {
+        if is_clawhub_managed(&lock, dep) || skills_dir.join(dep).exists() {
+            continue;
+        }
+        let prepared =
+            match prepare_install(client, dep, None, skills_dir, &lock, &dep_options).await {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    rollback_dependencies(&installed_deps, skills_dir, lockfile_path);
+                    return Err(MicroClawError::Config(format!(
+                        "Failed to install dependency '{}' of '{}': {}",
+                        dep, slug, e
+                    )));
+                }
+            };
+        if let Some(mut entry) = prepared.entry {
+            entry.installed_as = Some(DEPENDENCY_INSTALL.to_string());
+            let mut lock = read_lockfile(lockfile_path)?;
+            lock.skills.insert(entry.slug.clone(), entry);
+            write_lockfile(lockfile_path, &lock)?;
+            installed_deps.push(dep.clone());
+        }
+    }
+
+    let mut result =
+        match install_skill(client, slug, version, skills_dir, lockfile_path, options).await {
+            Ok(result) => result,
+            Err(e) => {
+                rollback_dependencies(&installed_deps, skills_dir, lockfile_path);
+                return Err(e);
+            }
+        };
+    if !installed_deps.is_empty() {
+        result.message = format!(
+            "{} (with dependencies: {})",
+            result.message,
+            installed_deps.join(", ")
+        );
+        result.requires_restart = true;
+    }
+    Ok(result)
+}
+
+/// Installed skills whose lockfile entry lists `slug` as a dependency
+pub fn dependents_of(lock: &LockFile, slug: &str) -> Vec<String> {
+    let mut dependents: Vec<String> = lock
+        .skills
+        .values()
+        .filter(|entry| entry.dependencies.iter().any(|d| d == slug))
+        .map(|entry| entry.slug.clone())
+        .collect();
+    dependents.sort();
+    dependents
+}
+
+/// Install several skills concurrently (at most `concurrency` at a time) and
+/// record all successful installs with a single lockfile write.
+///
+/// A failing skill does not abort the others; results are returned per slug
+/// in input order.
+/// Each spec is a slug plus an optional explicit version; explicit versions
+/// are recorded as pinned. Missing dependencies are resolved the same way as
+/// [`install_skill_with_dependencies`] and installed once, before the skills
+/// that need them; a skill whose dependency fails is not installed, and
+/// dependencies no installed skill ended up needing are rolled back.
+pub async fn install_skills(
+    client: &ClawHubClient,
+    specs: &[(String, Option<String>)],
+    skills_dir: &Path,
+    lockfile_path: &Path,
+    options: &InstallOptions,
+    concurrency: usize,
+) -> Result<Vec<(String, Result<InstallResult, MicroClawError>)>, MicroClawError> {
+    let lock = read_lockfile(lockfile_path)?;
+    let requested: HashSet<&str> = specs.iter().map(|(slug, _)| slug.as_str()).collect();
+
+    let mut resolve_errors: HashMap<&str, MicroClawError> = HashMap::new();
+    let mut missing_for: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut missing_deps: Vec<String> = Vec::new();
+    for (slug, version) in specs {
+        match resolve_dependencies(client, slug, version.as_deref()).await {
+            Ok(order) => {
+                let missing: Vec<String> = order
+                    .into_iter()
+                    .filter(|dep| {
+                        !requested.contains(dep.as_str())
+                            && !is_clawhub_managed(&lock, dep)
+                            && !skills_dir.join(dep).exists()
+                    })
+                    .collect();
+                for dep in &missing {
+                    if !missing_deps.contains(dep) {
+                        missing_deps.push(dep.clone());
+                    }
+                }
+                missing_for.insert(slug.as_str(), missing);
+            }
+            Err(e) => {
+                resolve_errors.insert(slug.as_str(), e);
+            }
+        }
+    }
+
+    let dep_options = dependency_options(options);
+    let dep_results = install_many(&missing_deps, concurrency, lockfile_path, |dep| {
+        let lock = &lock;
+        let dep_options = &dep_options;
+        async move {
+            let mut prepared =
+                prepare_install(client, &dep, None, skills_dir, lock, dep_options).await?;
+            if let Some(entry) = prepared.entry.as_mut() {
+                entry.installed_as = Some(DEPENDENCY_INSTALL.to_string());
+            }
+            Ok(prepared)
+        }
+    })
+    .await?;
+    let mut failed_deps: HashMap<String, MicroClawError> = HashMap::new();
+    let mut installed_deps: Vec<String> = Vec::new();
+    for (dep, outcome) in dep_results {
+        match outcome {
+            Ok(result) if result.success => installed_deps.push(dep),
+            Ok(_) => {}
+            Err(e) => {
+                failed_deps.insert(dep, e);
+            }
+        }
+    }
+
+    let blocked_by = |slug: &str| -> Option<&String> {
+        missing_for
+            .get(slug)?
+            .iter()
+            .find(|dep| failed_deps.contains_key(dep.as_str()))
     };
+    let installable: Vec<String> = specs
+        .iter()
+        .map(|(slug, _)| slug.clone())
+        .filter(|slug| !resolve_errors.contains_key(slug.as_str()) && blocked_by(slug).is_none())
+        .collect();
+    let versions: HashMap<&str, Option<&str>> = specs
+        .iter()
+        .map(|(slug, version)| (slug.as_str(), version.as_deref()))
+        .collect();
+    let mut installed = install_many(&installable, concurrency, lockfile_path, |slug| {
+        let lock = &lock;
+        let version = versions.get(slug.as_str()).copied().flatten();
+        let options = InstallOptions {
+            pin: version.is_some(),
+            ..options.clone()
+        };
+        async move { prepare_install(client, &slug, version, skills_dir, lock, &options).await }
+    })
+    .await?;
+
+    let mut still_needed: HashSet<String> = HashSet::new();
+    let mut results = Vec::with_capacity(specs.len());
+    for (slug, _) in specs {
+        if let Some(e) = resolve_errors.remove(slug.as_str()) {
+            results.push((slug.clone(), Err(e)));
+            continue;
+        }
+        if let Some(dep) = blocked_by(slug) {
+            let e = MicroClawError::Config(format!(
+                "Failed to install dependency '{}' of '{}': {}",
+                dep,
+                slug,
+                failed_deps[dep.as_str()]
+            ));
+            results.push((slug.clone(), Err(e)));
+            continue;
+        }
+        let Some(pos) = installed.iter().position(|(s, _)| s == slug) else {
+            continue;
+        };
+        let (_, mut outcome) = installed.remove(pos);
+        let missing = missing_for.get(slug.as_str()).cloned().unwrap_or_default();
+        if let Ok(result) = outcome.as_mut() {
+            still_needed.extend(missing.iter().cloned());
+            let fresh: Vec<&str> = missing
+                .iter()
+                .filter(|dep| installed_deps.contains(dep))
+                .map(String::as_str)
+                .collect();
+            if result.success && !fresh.is_empty() {
+                result.message = format!(
+                    "{} (with dependencies: {})",
+                    result.message,
+                    fresh.join(", ")
+                );
+            }
+        }
+        results.push((slug.clone(), outcome));
+    }
+
+    let unused: Vec<String> = installed_deps
+        .into_iter()
+        .filter(|dep| !still_needed.contains(dep))
+        .collect();
+    rollback_dependencies(&unused, skills_dir, lockfile_path);
+    Ok(results)
+}
+
+/// Detection count used for blocking: the malicious count when the registry
+/// breaks it down, otherwise the overall report count.
+pub fn virustotal_detections(vt: &VirusTotal) -> i32 {
+    vt.malicious.unwrap_or(vt.report_count)
+}
+
+/// Whether a VirusTotal result has more detections than `threshold` allows
+pub fn exceeds_virustotal_threshold(vt: &VirusTotal, threshold: u32) -> bool {
+    i64::from(virustotal_detections(vt)) > i64::from(threshold)
+}
+
+/// Caution to attach to an install the threshold let through: the scan has
+/// not finished yet, or several engines reported the skill.
+pub fn virustotal_warning(vt: &VirusTotal) -> Option<String> {
+    if vt.pending_scan {
+        Some("warning: VirusTotal scan still pending".to_string())
+    } else if vt.report_count >= 3 {
+        Some(format!(
+            "warning: VirusTotal status {} with {} reports",
+            vt.status, vt.report_count
+        ))
+    } else {
+        None
+    }
+}
+
+/// Split a `slug@version` install spec. A bare slug yields no version.
+pub fn parse_skill_spec(spec: &str) -> Result<(String, Option<String>), MicroClawError> {
+    let spec = spec.trim();
+    match spec.rsplit_once('@') {
+        Some((slug, version)) => {
+            let (slug, version) = (slug.trim(), version.trim());
+            if slug.is_empty() || version.is_empty() {
+                return Err(MicroClawError::Config(format!(
+                    "Invalid skill spec '{}': expected <slug>@<version>",
+                    spec
+                )));
+            }
+            Ok((slug.to_string(), Some(version.to_string())))
+        }
+        None if spec.is_empty() => Err(MicroClawError::Config("Empty skill slug".into())),
+        None => Ok((spec.to_string(), None)),
+    }
+}
+
+/// Check that `version` is one the registry publishes, listing the
+/// available ones otherwise.
+pub fn ensure_version_available(
+    slug: &str,
+    version: &str,
+    available: &[SkillVersion],
+) -> Result<(), MicroClawError> {
+    if available.is_empty() || available.iter().any(|v| v.version == version) {
+        return Ok(());
+    }
+    let mut listed: Vec<&str> = available.iter().map(|v| v.version.as_str()).collect();
+    listed.sort_by(|a, b| compare_versions(b, a).unwrap_or_else(|| b.cmp(a)));
+    Err(MicroClawError::Config(format!(
+        "Version {} of '{}' not found. Available versions: {}",
+        version,
+        slug,
+        listed.join(", ")
+    )))
+}
+
+/// Run `prepare` for every slug under a semaphore, then merge the resulting
+/// lockfile entries into the on-disk lockfile in one write.
+pub async fn install_many<F, Fut>(
+    slugs: &[String],
+    concurrency: usize,
+    lockfile_path: &Path,
+    prepare: F,
+) -> Result<Vec<(String, Result<InstallResult, MicroClawError>)>, MicroClawError>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<PreparedInstall, MicroClawError>>,
+{
+    let semaphore = Semaphore::new(concurrency.max(1));
+    let prepared = join_all(slugs.iter().map(|slug| {
+        let semaphore = &semaphore;
+        let fut = prepare(slug.clone());
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .map_err(|e| MicroClawError::Config(format!("Install queue closed: {}", e)))?;
+            fut.await
+        }
+    }))
+    .await;
+
+    let mut entries = Vec::new();
+    let mut results = Vec::with_capacity(slugs.len());
+    for (slug, outcome) in slugs.iter().zip(prepared) {
+        match outcome {
+            Ok(PreparedInstall { result, entry }) => {
+                entries.extend(entry);
+                results.push((slug.clone(), Ok(result)));
+            }
+            Err(e) => results.push((slug.clone(), Err(e))),
+        }
+    }
+
+    if !entries.is_empty() {
+        let mut lock = read_lockfile(lockfile_path)?;
+        for entry in entries {
+            lock.skills.insert(entry.slug.clone(), entry);
+        }
+        write_lockfile(lockfile_path, &lock)?;
+    }
+    Ok(results)
+}
+
+/// Per-directory install lock, so two installs of the same slug (e.g. from
+/// concurrent chat commands) cannot interleave their remove/extract steps.
+fn skill_dir_lock(skill_path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<std::sync::Mutex<HashMap<PathBuf, Weak<Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(lock) = locks.get(skill_path).and_then(Weak::upgrade) {
+        return lock;
+    }
+    let lock = Arc::new(Mutex::new(()));
+    locks.insert(skill_path.to_path_buf(), Arc::downgrade(&lock));
+    lock
+}
+
+/// Download, verify, and extract a skill without touching the lockfile.
+/// `lock` is only consulted for the already-installed check.
+pub async fn prepare_install(
+    client: &ClawHubClient,
+    slug: &str,
+    version: Option<&str>,
+    skills_dir: &Path,
+    lock: &LockFile,
+    options: &InstallOptions,
+) -> Result<PreparedInstall, MicroClawError> {
+    validate_slug(slug)?;
+
+    // 1. Get skill metadata
+    let meta = client.get_skill(slug).await?;
+
+    // 2. Resolve version
+    let actual_version = resolve_target_version(&meta, version);
+    let version_info = version_entry(client, slug, &meta, &actual_version).await?;
 
     // 3. Gate checks (unless skipped)
     if !options.skip_gates {
@@ -84,27 +620,40 @@ pub async fn install_skill(
     }
 
     // 4. Security check
+    let mut security_warning = None;
     if !options.skip_security {
         if let Some(vt) = &meta.virustotal {
-            if vt.report_count >= 3 || vt.pending_scan {
-                // TODO: Return warning
+            if let Some(threshold) = options.block_on_virustotal_detections {
+                if !options.force && exceeds_virustotal_threshold(vt, threshold) {
+                    return Err(MicroClawError::Config(format!(
+                        "Refusing to install {}: VirusTotal reports {} detections (threshold {}). Use --force to install anyway.",
+                        slug,
+                        virustotal_detections(vt),
+                        threshold
+                    )));
+                }
             }
+            security_warning = virustotal_warning(vt);
         }
     }
 
-    // 5. Check existing installation
+    // 5. Check existing installation (held until extraction finishes)
     let skill_path = skills_dir.join(slug);
-    let lock = read_lockfile(lockfile_path)?;
-    let is_managed = is_clawhub_managed(&lock, slug);
+    let dir_lock = skill_dir_lock(&skill_path);
+    let _dir_guard = dir_lock.lock().await;
+    let is_managed = is_clawhub_managed(lock, slug);
 
     if skill_path.exists() && !options.force && is_managed {
-        return Ok(InstallResult {
-            success: false,
-            message: format!(
-                "Skill '{}' is already installed. Use --force to update.",
-                slug
-            ),
-            requires_restart: false,
+        return Ok(PreparedInstall {
+            result: InstallResult {
+                success: false,
+                message: format!(
+                    "Skill '{}' is already installed. Use --force to update.",
+                    slug
+                ),
+                requires_restart: false,
+            },
+            entry: None,
         });
     }
     if skill_path.exists() && !options.force {
@@ -112,62 +661,431 @@ pub async fn install_skill(
     }
 
     // 6. Download
-    let bytes = client.download_skill(slug, &actual_version).await?;
+    let expected_sha256 = if options.skip_checksum {
+        None
+    } else {
+        Some(expected_checksum(client, slug, &version_info).await?)
+    };
+    let downloaded = client
+        .download_skill_to_file(slug, &actual_version, options.progress.as_deref())
+        .await?;
 
-    // 7. Verify hash (if provided)
-    let hash = format!("sha256:{:x}", Sha256::digest(&bytes));
+    // 7. Verify hash
+    let hash = format!("sha256:{}", downloaded.sha256);
+    if let Some(expected) = expected_sha256 {
+        verify_digest(&downloaded.sha256, &expected)
+            .map_err(|e| MicroClawError::Config(format!("Refusing to install {}: {}", slug, e)))?;
+    }
 
     // 8. Extract
-    if skill_path.exists() && options.force {
-        std::fs::remove_dir_all(&skill_path)?;
+    let zip_file = std::fs::File::open(&downloaded.path)?;
+    extract_skill_zip(zip_file, &skill_path, options.force)?;
+    let files_hash = hash_skill_dir(&skill_path)?;
+
+    // 9. Lockfile entry (written by the caller)
+    let entry = LockEntry {
+        slug: slug.to_string(),
+        installed_version: actual_version.clone(),
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        content_hash: hash,
+        local_path: skill_path.to_string_lossy().to_string(),
+        source: None,
+        files_hash: Some(files_hash),
+        pinned: options.pin,
+        dependencies: version_info.dependencies.clone(),
+        installed_as: None,
+    };
+
+    Ok(PreparedInstall {
+        result: InstallResult {
+            success: true,
+            message: match security_warning {
+                Some(warning) => format!("Installed {} v{} ({})", slug, actual_version, warning),
+                None => format!("Installed {} v{}", slug, actual_version),
+            },
+            requires_restart: true,
+        },
+        entry: Some(entry),
+    })
+}
+
+/// Install a skill from a ZIP file on disk without touching the network.
+///
+/// The slug defaults to the file stem. The lockfile entry is marked with
+/// `source: local` so `skill update` leaves it alone.
+pub fn install_local_skill(
+    zip_path: &Path,
+    slug: Option<&str>,
+    skills_dir: &Path,
+    lockfile_path: &Path,
+    options: &InstallOptions,
+) -> Result<InstallResult, MicroClawError> {
+    let slug = match slug {
+        Some(slug) => slug.to_string(),
+        None => zip_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string(),
+    };
+    validate_slug(&slug)?;
+
+    let skill_path = skills_dir.join(&slug);
+    let lock = read_lockfile(lockfile_path)?;
+    if skill_path.exists() && !options.force && is_clawhub_managed(&lock, &slug) {
+        return Ok(InstallResult {
+            success: false,
+            message: format!(
+                "Skill '{}' is already installed. Use --force to update.",
+                slug
+            ),
+            requires_restart: false,
+        });
     }
-    std::fs::create_dir_all(&skill_path)?;
 
-    let cursor = std::io::Cursor::new(bytes);
-    let mut archive = ZipArchive::new(cursor)
-        .map_err(|e| MicroClawError::Config(format!("Failed to read ZIP: {}", e)))?;
-    archive
-        .extract(&skill_path)
-        .map_err(|e| MicroClawError::Config(format!("Failed to extract ZIP: {}", e)))?;
+    let bytes = std::fs::read(zip_path).map_err(|e| {
+        MicroClawError::Config(format!("Failed to read {}: {}", zip_path.display(), e))
+    })?;
+    let hash = format!("sha256:{}", sha256_hex(&bytes));
+    extract_skill_zip(std::io::Cursor::new(bytes), &skill_path, options.force)?;
+    let files_hash = hash_skill_dir(&skill_path)?;
 
-    // 9. Update lockfile
     let mut lock = read_lockfile(lockfile_path)?;
-    let now = chrono::Utc::now().to_rfc3339();
     lock.skills.insert(
-        slug.to_string(),
+        slug.clone(),
         LockEntry {
-            slug: slug.to_string(),
-            installed_version: actual_version.clone(),
-            installed_at: now,
+            slug: slug.clone(),
+            installed_version: "local".to_string(),
+            installed_at: chrono::Utc::now().to_rfc3339(),
             content_hash: hash,
             local_path: skill_path.to_string_lossy().to_string(),
+            source: Some(LOCAL_SOURCE.to_string()),
+            files_hash: Some(files_hash),
+            pinned: false,
+            dependencies: Vec::new(),
+            installed_as: None,
         },
     );
     write_lockfile(lockfile_path, &lock)?;
 
     Ok(InstallResult {
         success: true,
-        message: format!("Installed {} v{}", slug, actual_version),
+        message: format!("Installed {} from {}", slug, zip_path.display()),
         requires_restart: true,
     })
 }
 
+/// Largest total uncompressed size accepted for a skill ZIP
+pub const MAX_SKILL_UNCOMPRESSED_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Most entries accepted in a skill ZIP
+pub const MAX_SKILL_FILES: usize = 1000;
+
+/// Check that a skill ZIP has a top-level `SKILL.md`, no entries escaping the
+/// skill directory and stays within the size limits, then extract it into
+/// `skill_path` (replacing an existing directory when `force` is set).
+/// A failed extraction removes the partially written directory.
+fn extract_skill_zip<R: Read + Seek>(
+    reader: R,
+    skill_path: &Path,
+    force: bool,
+) -> Result<(), MicroClawError> {
+    extract_skill_zip_limited(
+        reader,
+        skill_path,
+        force,
+        MAX_SKILL_UNCOMPRESSED_BYTES,
+        MAX_SKILL_FILES,
+    )
+}
+
+fn extract_skill_zip_limited<R: Read + Seek>(
+    reader: R,
+    skill_path: &Path,
+    force: bool,
+    max_bytes: u64,
+    max_files: usize,
+) -> Result<(), MicroClawError> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| MicroClawError::Config(format!("Failed to read ZIP: {}", e)))?;
+    validate_skill_archive(&mut archive, max_bytes, max_files)?;
+
+    if skill_path.exists() && force {
+        std::fs::remove_dir_all(skill_path)?;
+    }
+    std::fs::create_dir_all(skill_path)?;
+    if let Err(e) = write_skill_entries(&mut archive, skill_path, max_bytes) {
+        let _ = std::fs::remove_dir_all(skill_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Reject archives that could write outside the skill directory, lack a
+/// top-level `SKILL.md`, or declare more files or bytes than allowed.
+fn validate_skill_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    max_bytes: u64,
+    max_files: usize,
+) -> Result<(), MicroClawError> {
+    if archive.len() > max_files {
+        return Err(MicroClawError::Config(format!(
+            "Skill ZIP has {} entries (limit {})",
+            archive.len(),
+            max_files
+        )));
+    }
+    let mut total: u64 = 0;
+    let mut has_skill_md = false;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| MicroClawError::Config(format!("Failed to read ZIP: {}", e)))?;
+        let name = entry.name().to_string();
+        if entry.enclosed_name().is_none() || !is_safe_entry_name(&name) {
+            return Err(MicroClawError::Config(format!(
+                "Skill ZIP entry '{}' escapes the skill directory",
+                name
+            )));
+        }
+        has_skill_md |= name == "SKILL.md";
+        total = total.saturating_add(entry.size());
+        if total > max_bytes {
+            return Err(MicroClawError::Config(format!(
+                "Skill ZIP expands to more than {} bytes",
+                max_bytes
+            )));
+        }
+    }
+    if !has_skill_md {
+        return Err(MicroClawError::Config(
+            "Skill ZIP does not contain a top-level SKILL.md".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Relative path made only of normal components
+fn is_safe_entry_name(name: &str) -> bool {
+    let path = Path::new(name);
+    !name.starts_with(['/', '\\'])
+        && !path.is_absolute()
+        && !name.split(['/', '\\']).any(|part| part == "..")
+        && path.components().all(|c| {
+            matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        })
+}
+
+/// Write the archive entries, counting actual bytes so a ZIP that understates
+/// its sizes still cannot exceed `max_bytes`.
+fn write_skill_entries<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    skill_path: &Path,
+    max_bytes: u64,
+) -> Result<(), MicroClawError> {
+    let mut written: u64 = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| MicroClawError::Config(format!("Failed to extract ZIP: {}", e)))?;
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(MicroClawError::Config(format!(
+                "Skill ZIP entry '{}' escapes the skill directory",
+                entry.name()
+            )));
+        };
+        let target = skill_path.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = std::fs::File::create(&target)?;
+        let remaining = max_bytes - written;
+        let copied = std::io::copy(&mut (&mut entry).take(remaining + 1), &mut out)?;
+        written += copied;
+        if written > max_bytes {
+            return Err(MicroClawError::Config(format!(
+                "Skill ZIP expands to more than {} bytes",
+                max_bytes
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reject slugs that could escape the skills directory
+fn validate_slug(slug: &str) -> Result<(), MicroClawError> {
+    if slug.is_empty()
+        || slug == "."
+        || slug == ".."
+        || slug.contains(['/', '\\'])
+        || Path::new(slug).is_absolute()
+    {
+        return Err(MicroClawError::Config(format!(
+            "Invalid skill slug '{}'",
+            slug
+        )));
+    }
+    Ok(())
+}
+
+/// Lowercase hex SHA-256 digest of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Compare `bytes` against an expected hex digest (optionally `sha256:`-prefixed).
+pub fn verify_checksum(bytes: &[u8], expected: &str) -> Result<(), MicroClawError> {
+    verify_digest(&sha256_hex(bytes), expected)
+}
+
+/// Compare an already-computed hex digest against an expected one.
+pub fn verify_digest(actual: &str, expected: &str) -> Result<(), MicroClawError> {
+    let expected = expected.trim();
+    let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(MicroClawError::Config(format!(
+            "checksum mismatch (expected sha256 {}, got {})",
+            expected, actual
+        )))
+    }
+}
+
+/// Remove a ClawHub-installed skill and its lockfile entry.
+///
+/// Only skills recorded in the lockfile are removed, and only the
+/// `<skills_dir>/<slug>` directory is ever deleted.
+pub fn uninstall_skill(
+    slug: &str,
+    skills_dir: &Path,
+    lockfile_path: &Path,
+) -> Result<InstallResult, MicroClawError> {
+    validate_slug(slug)?;
+
+    let mut lock = read_lockfile(lockfile_path)?;
+    if remove_entry(&mut lock, slug).is_none() {
+        return Ok(InstallResult {
+            success: false,
+            message: format!(
+                "Skill '{}' is not installed from ClawHub; nothing to uninstall.",
+                slug
+            ),
+            requires_restart: false,
+        });
+    }
+
+    let skill_path = skills_dir.join(slug);
+    if skill_path.exists() {
+        let canonical_dir = skills_dir.canonicalize()?;
+        let canonical_skill = skill_path.canonicalize()?;
+        if canonical_skill.parent() != Some(canonical_dir.as_path()) {
+            return Err(MicroClawError::Config(format!(
+                "Refusing to remove '{}': it is outside the skills directory",
+                skill_path.display()
+            )));
+        }
+        std::fs::remove_dir_all(&canonical_skill)?;
+    }
+    write_lockfile(lockfile_path, &lock)?;
+
+    Ok(InstallResult {
+        success: true,
+        message: format!("Uninstalled {}", slug),
+        requires_restart: true,
+    })
+}
+
+/// Outcome of comparing an installed skill against the registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateDecision {
+    UpToDate,
+    Upgrade { from: String, to: String },
+}
+
+/// Compare dotted numeric versions (`1.2.10` > `1.2.9`, optional `v` prefix).
+/// Returns `None` when either side is not purely numeric.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    fn parts(v: &str) -> Option<Vec<u64>> {
+        let v = v.trim();
+        let v = v.strip_prefix('v').unwrap_or(v);
+        v.split('.').map(|p| p.parse::<u64>().ok()).collect()
+    }
+    let mut a = parts(a)?;
+    let mut b = parts(b)?;
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+/// Pick the registry's latest version: the flagged one, else the highest.
+pub fn latest_version(versions: &[SkillVersion]) -> Option<String> {
+    if let Some(v) = versions.iter().find(|v| v.latest) {
+        return Some(v.version.clone());
+    }
+    versions
+        .iter()
+        .max_by(|a, b| compare_versions(&a.version, &b.version).unwrap_or(Ordering::Equal))
+        .map(|v| v.version.clone())
+}
+
+/// Decide whether an installed version should be upgraded to `latest`.
+/// Non-numeric versions fall back to a plain inequality check.
+pub fn decide_update(installed: &str, latest: &str) -> UpdateDecision {
+    let newer = match compare_versions(latest, installed) {
+        Some(ordering) => ordering == Ordering::Greater,
+        None => installed != latest,
+    };
+    if newer {
+        UpdateDecision::Upgrade {
+            from: installed.to_string(),
+            to: latest.to_string(),
+        }
+    } else {
+        UpdateDecision::UpToDate
+    }
+}
+
 /// Check if update is needed
 pub fn check_update_available(
     _lock: &LockFile,
     current_version: &str,
     latest_version: &str,
 ) -> bool {
-    // Simple version comparison - could use semver for more accuracy
-    current_version != latest_version
+    matches!(
+        decide_update(current_version, latest_version),
+        UpdateDecision::Upgrade { .. }
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use crate::types::LockFile;
     use std::collections::HashMap;
+    use std::sync::Arc;
 
-    use super::check_update_available;
+    use super::{
+        check_update_available, compare_versions, decide_update, dependents_of,
+        ensure_version_available, exceeds_virustotal_threshold, extract_skill_zip_limited,
+        install_local_skill, install_many, install_skill, install_skill_with_dependencies,
+        install_skills, latest_version, parse_skill_spec, resolve_install_order, sha256_hex,
+        skill_dir_lock, uninstall_skill, verify_checksum, virustotal_detections, InstallOptions,
+        InstallResult, PreparedInstall, UpdateDecision, DEPENDENCY_INSTALL, LOCAL_SOURCE,
+    };
+    use crate::lockfile::{read_lockfile, write_lockfile};
+    use crate::test_server::{skill_zip, FakeRegistry};
+    use crate::types::{LockEntry, SkillVersion, VirusTotal};
+    use microclaw_core::error::MicroClawError;
+    use serde_json::json;
+    use std::cmp::Ordering;
 
     #[test]
     fn test_check_update_available_true_when_version_changes() {
@@ -186,4 +1104,814 @@ mod tests {
         };
         assert!(!check_update_available(&lock, "1.0.0", "1.0.0"));
     }
+
+    #[test]
+    fn test_sha256_known_vector_and_verify() {
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(sha256_hex(b"abc"), digest);
+        assert!(verify_checksum(b"abc", digest).is_ok());
+        assert!(verify_checksum(b"abc", &format!("sha256:{}", digest.to_uppercase())).is_ok());
+
+        let err = verify_checksum(b"abd", digest).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_skill_spec() {
+        assert_eq!(
+            parse_skill_spec("weather").unwrap(),
+            ("weather".to_string(), None)
+        );
+        assert_eq!(
+            parse_skill_spec("weather@1.2.0").unwrap(),
+            ("weather".to_string(), Some("1.2.0".to_string()))
+        );
+        assert_eq!(
+            parse_skill_spec(" scope@tool@2.0 ").unwrap(),
+            ("scope@tool".to_string(), Some("2.0".to_string()))
+        );
+        assert!(parse_skill_spec("weather@").is_err());
+        assert!(parse_skill_spec("@1.0.0").is_err());
+        assert!(parse_skill_spec("").is_err());
+    }
+
+    #[test]
+    fn test_ensure_version_available_lists_versions() {
+        let versions = vec![
+            SkillVersion {
+                version: "1.0.0".into(),
+                latest: false,
+                sha256: None,
+                dependencies: Vec::new(),
+            },
+            SkillVersion {
+                version: "1.2.0".into(),
+                latest: true,
+                sha256: None,
+                dependencies: Vec::new(),
+            },
+        ];
+        assert!(ensure_version_available("weather", "1.0.0", &versions).is_ok());
+        let err = ensure_version_available("weather", "9.9.9", &versions)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Available versions: 1.2.0, 1.0.0"), "{err}");
+    }
+
+    #[test]
+    fn test_compare_versions_numeric() {
+        assert_eq!(compare_versions("1.2.10", "1.2.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v1.0", "1.0.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("0.9.0", "1.0.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_decide_update() {
+        assert_eq!(
+            decide_update("1.0.0", "1.1.0"),
+            UpdateDecision::Upgrade {
+                from: "1.0.0".into(),
+                to: "1.1.0".into()
+            }
+        );
+        assert_eq!(decide_update("1.1.0", "1.1.0"), UpdateDecision::UpToDate);
+        // Never downgrade when the registry lags behind a local pin.
+        assert_eq!(decide_update("2.0.0", "1.9.9"), UpdateDecision::UpToDate);
+        assert_eq!(
+            decide_update("nightly-1", "nightly-2"),
+            UpdateDecision::Upgrade {
+                from: "nightly-1".into(),
+                to: "nightly-2".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_latest_version_prefers_flag_then_highest() {
+        let versions = vec![
+            SkillVersion {
+                version: "1.10.0".into(),
+                latest: false,
+                sha256: None,
+                dependencies: Vec::new(),
+            },
+            SkillVersion {
+                version: "1.9.0".into(),
+                latest: false,
+                sha256: None,
+                dependencies: Vec::new(),
+            },
+        ];
+        assert_eq!(latest_version(&versions).as_deref(), Some("1.10.0"));
+
+        let mut flagged = versions.clone();
+        flagged[1].latest = true;
+        assert_eq!(latest_version(&flagged).as_deref(), Some("1.9.0"));
+        assert_eq!(latest_version(&[]), None);
+    }
+
+    #[test]
+    fn test_uninstall_skill_removes_dir_and_lock_entry() {
+        let root = std::env::temp_dir().join(format!("clawhub_uninstall_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(skills_dir.join("my-skill")).unwrap();
+        std::fs::create_dir_all(skills_dir.join("keep-me")).unwrap();
+        std::fs::write(skills_dir.join("my-skill").join("SKILL.md"), "# skill").unwrap();
+
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        lock.skills.insert(
+            "my-skill".into(),
+            LockEntry {
+                local_path: skills_dir.join("my-skill").to_string_lossy().to_string(),
+                ..LockEntry::for_test("my-skill")
+            },
+        );
+        write_lockfile(&lock_path, &lock).unwrap();
+
+        let result = uninstall_skill("my-skill", &skills_dir, &lock_path).unwrap();
+        assert!(result.success);
+        assert!(result.requires_restart);
+        assert!(!skills_dir.join("my-skill").exists());
+        assert!(skills_dir.join("keep-me").exists());
+        assert!(read_lockfile(&lock_path).unwrap().skills.is_empty());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_uninstall_skill_not_installed() {
+        let root = std::env::temp_dir().join(format!("clawhub_uninstall_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(skills_dir.join("manual-skill")).unwrap();
+
+        let result = uninstall_skill("manual-skill", &skills_dir, &lock_path).unwrap();
+        assert!(!result.success);
+        assert!(!result.requires_restart);
+        assert!(result.message.contains("not installed"));
+        assert!(skills_dir.join("manual-skill").exists());
+
+        assert!(uninstall_skill("../skills", &skills_dir, &lock_path).is_err());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    fn write_test_zip(path: &std::path::Path, files: &[(&str, &str)]) {
+        use std::io::Write;
+        let file = std::fs::File::create(path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        for (name, body) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn local_options() -> InstallOptions {
+        InstallOptions {
+            force: false,
+            skip_gates: true,
+            skip_security: true,
+            block_on_virustotal_detections: None,
+            skip_checksum: true,
+            progress: None,
+            pin: false,
+        }
+    }
+
+    #[test]
+    fn test_install_local_skill_extracts_and_records_source() {
+        let root = std::env::temp_dir().join(format!("clawhub_local_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("vendored-skill.zip");
+        write_test_zip(
+            &zip_path,
+            &[("SKILL.md", "# Vendored"), ("scripts/run.sh", "echo hi")],
+        );
+
+        let result =
+            install_local_skill(&zip_path, None, &skills_dir, &lock_path, &local_options())
+                .unwrap();
+        assert!(result.success);
+        assert!(skills_dir.join("vendored-skill").join("SKILL.md").exists());
+        assert!(skills_dir
+            .join("vendored-skill")
+            .join("scripts")
+            .join("run.sh")
+            .exists());
+
+        let lock = read_lockfile(&lock_path).unwrap();
+        let entry = lock.skills.get("vendored-skill").unwrap();
+        assert_eq!(entry.source.as_deref(), Some(LOCAL_SOURCE));
+        assert!(entry.content_hash.starts_with("sha256:"));
+
+        let again = install_local_skill(&zip_path, None, &skills_dir, &lock_path, &local_options())
+            .unwrap();
+        assert!(!again.success);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_install_local_skill_rejects_zip_without_skill_md() {
+        let root = std::env::temp_dir().join(format!("clawhub_local_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("broken.zip");
+        write_test_zip(&zip_path, &[("README.md", "no skill here")]);
+
+        let err = install_local_skill(
+            &zip_path,
+            Some("broken"),
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("SKILL.md"), "{err}");
+        assert!(!skills_dir.join("broken").exists());
+        assert!(read_lockfile(&lock_path).unwrap().skills.is_empty());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_install_local_skill_rejects_zip_slip() {
+        let root = std::env::temp_dir().join(format!("clawhub_local_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("evil.zip");
+        write_test_zip(
+            &zip_path,
+            &[("SKILL.md", "# Evil"), ("../escaped.txt", "gotcha")],
+        );
+
+        let err = install_local_skill(
+            &zip_path,
+            Some("evil"),
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("escapes"), "{err}");
+        assert!(!skills_dir.join("evil").exists());
+        assert!(!skills_dir.join("escaped.txt").exists());
+        assert!(read_lockfile(&lock_path).unwrap().skills.is_empty());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_extract_skill_zip_enforces_limits() {
+        let root = std::env::temp_dir().join(format!("clawhub_limits_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("big.zip");
+        write_test_zip(
+            &zip_path,
+            &[("SKILL.md", "# Big"), ("data.txt", &"x".repeat(4096))],
+        );
+        let skill_path = root.join("big");
+
+        let open = || std::fs::File::open(&zip_path).unwrap();
+        let err = extract_skill_zip_limited(open(), &skill_path, false, 1024, 10)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("bytes"), "{err}");
+        let err = extract_skill_zip_limited(open(), &skill_path, false, 1 << 20, 1)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("entries"), "{err}");
+        assert!(!skill_path.exists());
+
+        extract_skill_zip_limited(open(), &skill_path, false, 1 << 20, 10).unwrap();
+        assert!(skill_path.join("data.txt").exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_install_many_merges_lockfile_once() {
+        let root = std::env::temp_dir().join(format!("clawhub_many_{}", uuid::Uuid::new_v4()));
+        let lock_path = root.join("clawhub.lock.json");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        lock.skills.insert(
+            "existing".into(),
+            LockEntry {
+                installed_version: "0.1.0".into(),
+                ..LockEntry::for_test("existing")
+            },
+        );
+        write_lockfile(&lock_path, &lock).unwrap();
+
+        let in_flight = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let slugs: Vec<String> = ["a", "b", "broken", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let results = install_many(&slugs, 2, &lock_path, |slug| {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            async move {
+                use std::sync::atomic::Ordering as AtomicOrdering;
+                let now = in_flight.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+                peak.fetch_max(now, AtomicOrdering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, AtomicOrdering::SeqCst);
+                if slug == "broken" {
+                    return Err(MicroClawError::Config("download failed".into()));
+                }
+                Ok(PreparedInstall {
+                    result: InstallResult {
+                        success: true,
+                        message: format!("Installed {}", slug),
+                        requires_restart: true,
+                    },
+                    entry: Some(LockEntry {
+                        content_hash: "sha256:def".into(),
+                        ..LockEntry::for_test(&slug)
+                    }),
+                })
+            }
+        })
+        .await
+        .unwrap();
+
+        assert!(peak.load(std::sync::atomic::Ordering::SeqCst) <= 2);
+        let order: Vec<&str> = results.iter().map(|(slug, _)| slug.as_str()).collect();
+        assert_eq!(order, vec!["a", "b", "broken", "c", "d", "e"]);
+        assert!(results[2].1.is_err());
+        assert_eq!(results.iter().filter(|(_, r)| r.is_ok()).count(), 5);
+
+        let lock = read_lockfile(&lock_path).unwrap();
+        let mut slugs: Vec<&str> = lock.skills.keys().map(String::as_str).collect();
+        slugs.sort();
+        assert_eq!(slugs, vec!["a", "b", "c", "d", "e", "existing"]);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    async fn order_for(
+        root: &str,
+        graph: &[(&str, &[&str])],
+    ) -> (Result<Vec<String>, MicroClawError>, Vec<String>) {
+        let graph: HashMap<String, Vec<String>> = graph
+            .iter()
+            .map(|(slug, deps)| {
+                (
+                    slug.to_string(),
+                    deps.iter().map(|d| d.to_string()).collect(),
+                )
+            })
+            .collect();
+        let lookups = std::sync::Mutex::new(Vec::new());
+        let order = resolve_install_order(root, |slug| {
+            lookups.lock().unwrap().push(slug.clone());
+            let deps = graph.get(&slug).cloned().unwrap_or_default();
+            async move { Ok(deps) }
+        })
+        .await;
+        (order, lookups.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_install_order_dependencies_first() {
+        // app -> (db, http), db -> log, http -> log
+        let (order, lookups) = order_for(
+            "app",
+            &[
+                ("app", &["db", "http"]),
+                ("db", &["log"]),
+                ("http", &["log"]),
+            ],
+        )
+        .await;
+        assert_eq!(order.unwrap(), vec!["log", "db", "http", "app"]);
+        // The shared dependency is looked up once.
+        assert_eq!(lookups.iter().filter(|s| *s == "log").count(), 1);
+
+        let (order, _) = order_for("solo", &[]).await;
+        assert_eq!(order.unwrap(), vec!["solo"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_install_order_detects_cycles() {
+        let (order, _) = order_for(
+            "app",
+            &[("app", &["db"]), ("db", &["log"]), ("log", &["db"])],
+        )
+        .await;
+        let err = order.unwrap_err().to_string();
+        assert!(err.contains("Dependency cycle: db -> log -> db"), "{err}");
+
+        let (order, _) = order_for("app", &[("app", &["app"])]).await;
+        assert!(order
+            .unwrap_err()
+            .to_string()
+            .contains("Dependency cycle: app -> app"));
+    }
+
+    #[test]
+    fn test_dependents_of_lists_skills_needing_slug() {
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        for (slug, deps) in [
+            ("app", vec!["log"]),
+            ("cli", vec!["log", "db"]),
+            ("log", vec![]),
+        ] {
+            lock.skills.insert(
+                slug.to_string(),
+                LockEntry {
+                    dependencies: deps.into_iter().map(String::from).collect(),
+                    ..LockEntry::for_test(slug)
+                },
+            );
+        }
+        assert_eq!(dependents_of(&lock, "log"), vec!["app", "cli"]);
+        assert_eq!(dependents_of(&lock, "db"), vec!["cli"]);
+        assert!(dependents_of(&lock, "app").is_empty());
+    }
+
+    fn vt(report_count: i32, malicious: Option<i32>) -> VirusTotal {
+        VirusTotal {
+            report_count,
+            pending_scan: false,
+            status: "flagged".into(),
+            malicious,
+            suspicious: None,
+        }
+    }
+
+    #[test]
+    fn test_virustotal_threshold_uses_report_count() {
+        assert!(!exceeds_virustotal_threshold(&vt(0, None), 0));
+        assert!(!exceeds_virustotal_threshold(&vt(2, None), 2));
+        assert!(exceeds_virustotal_threshold(&vt(3, None), 2));
+    }
+
+    #[test]
+    fn test_virustotal_threshold_prefers_malicious_count() {
+        assert!(!exceeds_virustotal_threshold(&vt(5, Some(1)), 1));
+        assert!(exceeds_virustotal_threshold(&vt(1, Some(2)), 1));
+        assert_eq!(virustotal_detections(&vt(5, Some(0))), 0);
+    }
+
+    #[tokio::test]
+    async fn test_install_without_published_checksum_fails_closed() {
+        let (root, skills_dir, lock_path) = install_dirs("no_sha");
+        let zip = skill_md("nosha");
+        let client = FakeRegistry::default()
+            .skill("nosha", "1.0.0", None, &[], json!(null))
+            .versions("nosha", json!([{"version": "1.0.0", "latest": true}]))
+            .download("nosha", "1.0.0", zip.clone())
+            .serve()
+            .await;
+        let verified = InstallOptions {
+            skip_checksum: false,
+            ..local_options()
+        };
+
+        let err = install_skill(&client, "nosha", None, &skills_dir, &lock_path, &verified)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("publishes no sha256"), "{err}");
+        assert!(!skills_dir.join("nosha").exists());
+
+        let result = install_skill(
+            &client,
+            "nosha",
+            None,
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .await
+        .unwrap();
+        assert!(result.success);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_lookup_failure_is_not_reported_as_missing_hash() {
+        let (root, skills_dir, lock_path) = install_dirs("sha_lookup");
+        let verified = InstallOptions {
+            skip_checksum: false,
+            ..local_options()
+        };
+        let versions_path = "/api/v1/skills/flaky/versions";
+        for (status, body, expected) in [
+            (503, "busy", "HTTP 503"),
+            (200, "not json", "Failed to parse versions"),
+        ] {
+            let client = FakeRegistry::default()
+                .skill("flaky", "1.0.0", None, &[], json!(null))
+                .route(versions_path, status, body)
+                .download("flaky", "1.0.0", skill_md("flaky"))
+                .serve()
+                .await;
+            let err = install_skill(&client, "flaky", None, &skills_dir, &lock_path, &verified)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(expected), "{err}");
+            assert!(!err.contains("publishes no sha256"), "{err}");
+            assert!(!skills_dir.join("flaky").exists());
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_install_verifies_that_versions_checksum() {
+        let (root, skills_dir, lock_path) = install_dirs("pinned_sha");
+        let old_zip = skill_md("app");
+        let client = FakeRegistry::default()
+            .skill("app", "2.0.0", Some(&"0".repeat(64)), &[], json!(null))
+            .versions(
+                "app",
+                json!([
+                    {"version": "2.0.0", "latest": true, "sha256": "0".repeat(64)},
+                    {"version": "1.0.0", "sha256": sha256_hex(&old_zip)}
+                ]),
+            )
+            .download("app", "1.0.0", old_zip)
+            .serve()
+            .await;
+        let verified = InstallOptions {
+            skip_checksum: false,
+            ..local_options()
+        };
+
+        let result = install_skill(
+            &client,
+            "app",
+            Some("1.0.0"),
+            &skills_dir,
+            &lock_path,
+            &verified,
+        )
+        .await
+        .unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_install_reads_virustotal_scan_from_registry() {
+        let (root, skills_dir, lock_path) = install_dirs("vt_scan");
+        let client = FakeRegistry::default()
+            .skill(
+                "pending",
+                "1.0.0",
+                None,
+                &[],
+                json!({"virustotal": {"reportCount": 0, "pendingScan": true, "status": "pending"}}),
+            )
+            .download("pending", "1.0.0", skill_md("pending"))
+            .skill(
+                "flagged",
+                "1.0.0",
+                None,
+                &[],
+                json!({"virustotal": {"reportCount": 4, "pendingScan": false, "status": "malicious"}}),
+            )
+            .download("flagged", "1.0.0", skill_md("flagged"))
+            .serve()
+            .await;
+        let options = InstallOptions {
+            skip_security: false,
+            block_on_virustotal_detections: Some(2),
+            ..local_options()
+        };
+
+        let result = install_skill(&client, "pending", None, &skills_dir, &lock_path, &options)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(
+            result.message.contains("scan still pending"),
+            "{}",
+            result.message
+        );
+
+        let err = install_skill(&client, "flagged", None, &skills_dir, &lock_path, &options)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("4 detections"), "{err}");
+        assert!(!skills_dir.join("flagged").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    fn install_dirs(name: &str) -> (std::path::PathBuf, std::path::PathBuf, std::path::PathBuf) {
+        let root = std::env::temp_dir().join(format!("clawhub_{}_{}", name, uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        std::fs::create_dir_all(&skills_dir).unwrap();
+        let lock_path = root.join("clawhub.lock.json");
+        (root, skills_dir, lock_path)
+    }
+
+    fn skill_md(name: &str) -> Vec<u8> {
+        skill_zip(&[("SKILL.md", &format!("---\nname: {name}\n---\n"))])
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_installs_of_one_slug_are_serialized() {
+        let (root, skills_dir, lock_path) = install_dirs("same_slug");
+        let client = FakeRegistry::default()
+            .skill("app", "1.0.0", None, &[], json!(null))
+            .download("app", "1.0.0", skill_md("app"))
+            .serve()
+            .await;
+        let options = InstallOptions {
+            force: true,
+            ..local_options()
+        };
+
+        let held = skill_dir_lock(&skills_dir.join("app"));
+        assert!(Arc::ptr_eq(&held, &skill_dir_lock(&skills_dir.join("app"))));
+        assert!(!Arc::ptr_eq(
+            &held,
+            &skill_dir_lock(&skills_dir.join("other"))
+        ));
+        drop(held);
+
+        let (a, b) = tokio::join!(
+            install_skill(&client, "app", None, &skills_dir, &lock_path, &options),
+            install_skill(&client, "app", None, &skills_dir, &lock_path, &options),
+        );
+        assert!(a.unwrap().success);
+        assert!(b.unwrap().success);
+        let installed = std::fs::read_to_string(skills_dir.join("app").join("SKILL.md")).unwrap();
+        assert!(installed.contains("name: app"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_dependency_slug_escaping_skills_dir_is_rejected() {
+        let (root, skills_dir, lock_path) = install_dirs("evil_dep");
+        let client = FakeRegistry::default()
+            .skill("app", "1.0.0", None, &["../../x"], json!(null))
+            .download("app", "1.0.0", skill_md("app"))
+            .serve()
+            .await;
+
+        let err = install_skill_with_dependencies(
+            &client,
+            "app",
+            None,
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("invalid dependency"), "{err}");
+        assert!(!skills_dir.join("app").exists());
+        assert!(!root.join("x").exists());
+        assert!(read_lockfile(&lock_path).unwrap().skills.is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_install_uses_that_versions_dependencies() {
+        let (root, skills_dir, lock_path) = install_dirs("pinned_deps");
+        let client = FakeRegistry::default()
+            .skill("app", "2.0.0", None, &["new-dep"], json!(null))
+            .versions(
+                "app",
+                json!([
+                    {"version": "2.0.0", "latest": true, "dependencies": ["new-dep"]},
+                    {"version": "1.0.0", "dependencies": ["old-dep"]}
+                ]),
+            )
+            .skill("old-dep", "1.0.0", None, &[], json!(null))
+            .download("old-dep", "1.0.0", skill_md("old-dep"))
+            .download("app", "1.0.0", skill_md("app"))
+            .serve()
+            .await;
+
+        let result = install_skill_with_dependencies(
+            &client,
+            "app",
+            Some("1.0.0"),
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .await
+        .unwrap();
+        assert!(result.success, "{}", result.message);
+        assert!(skills_dir.join("old-dep").join("SKILL.md").exists());
+        assert!(!skills_dir.join("new-dep").exists());
+        let lock = read_lockfile(&lock_path).unwrap();
+        assert_eq!(lock.skills["app"].dependencies, vec!["old-dep"]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_failed_root_install_rolls_back_new_dependencies() {
+        let (root, skills_dir, lock_path) = install_dirs("rollback");
+        // `app` has no downloadable ZIP, so it fails after `dep` is installed
+        let client = FakeRegistry::default()
+            .skill("app", "1.0.0", None, &["dep"], json!(null))
+            .skill("dep", "1.0.0", None, &[], json!(null))
+            .download("dep", "1.0.0", skill_md("dep"))
+            .serve()
+            .await;
+
+        let err = install_skill_with_dependencies(
+            &client,
+            "app",
+            None,
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+        )
+        .await;
+        assert!(err.is_err());
+        assert!(!skills_dir.join("dep").exists());
+        assert!(read_lockfile(&lock_path).unwrap().skills.is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_install_skills_installs_shared_dependency_once() {
+        let (root, skills_dir, lock_path) = install_dirs("batch_deps");
+        let registry = FakeRegistry::default()
+            .skill("a", "1.0.0", None, &["shared"], json!(null))
+            .skill("b", "1.0.0", None, &["shared"], json!(null))
+            .skill("c", "1.0.0", None, &["broken"], json!(null))
+            .skill("shared", "1.0.0", None, &[], json!(null))
+            .skill("broken", "1.0.0", None, &["../escape"], json!(null))
+            .download("a", "1.0.0", skill_md("a"))
+            .download("b", "1.0.0", skill_md("b"))
+            .download("shared", "1.0.0", skill_md("shared"));
+        let hits = registry.hits();
+        let client = registry.serve().await;
+        let specs = vec![
+            ("a".to_string(), None),
+            ("b".to_string(), None),
+            ("c".to_string(), None),
+        ];
+
+        let results = install_skills(
+            &client,
+            &specs,
+            &skills_dir,
+            &lock_path,
+            &local_options(),
+            2,
+        )
+        .await
+        .unwrap();
+        let slugs: Vec<&str> = results.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(slugs, vec!["a", "b", "c"]);
+        assert!(results[0].1.as_ref().unwrap().success);
+        assert!(results[1].1.as_ref().unwrap().success);
+        let err = results[2].1.as_ref().unwrap_err().to_string();
+        assert!(err.contains("invalid dependency"), "{err}");
+
+        let lock = read_lockfile(&lock_path).unwrap();
+        assert_eq!(
+            lock.skills["shared"].installed_as.as_deref(),
+            Some(DEPENDENCY_INSTALL)
+        );
+        assert!(!lock.skills.contains_key("c"));
+        assert!(!skills_dir.join("c").exists());
+        assert_eq!(hits.count("/api/v1/download?slug=shared&version=1.0.0"), 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
@@ -2,6 +2,9 @@ pub mod client;
 pub mod gate;
 pub mod install;
 pub mod lockfile;
+#[cfg(test)]
+mod test_server;
 pub mod types;
+pub mod verify;
 
 pub use types::*;
@@ -1,9 +1,25 @@
-use crate::types::LockFile;
+use crate::types::{LockEntry, LockFile};
 use microclaw_core::error::MicroClawError;
 use std::collections::HashMap;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-/// Read lockfile from disk
+/// Path of the backup kept from the previous successful lockfile write
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn parse_lockfile(path: &Path) -> Result<LockFile, MicroClawError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| MicroClawError::Config(format!("Failed to read lockfile: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| MicroClawError::Config(format!("Failed to parse lockfile: {}", e)))
+}
+
+/// Read lockfile from disk, falling back to the `.bak` copy if the primary
+/// file is corrupt (e.g. truncated by a crash).
 pub fn read_lockfile(path: &Path) -> Result<LockFile, MicroClawError> {
     if !path.exists() {
         return Ok(LockFile {
@@ -11,26 +27,66 @@ pub fn read_lockfile(path: &Path) -> Result<LockFile, MicroClawError> {
             skills: HashMap::new(),
         });
     }
-    let content = std::fs::read_to_string(path)
-        .map_err(|e| MicroClawError::Config(format!("Failed to read lockfile: {}", e)))?;
-    let lock: LockFile = serde_json::from_str(&content)
-        .map_err(|e| MicroClawError::Config(format!("Failed to parse lockfile: {}", e)))?;
-    Ok(lock)
+    match parse_lockfile(path) {
+        Ok(lock) => Ok(lock),
+        Err(primary_err) => {
+            let backup = backup_path(path);
+            if backup.exists() {
+                if let Ok(lock) = parse_lockfile(&backup) {
+                    return Ok(lock);
+                }
+            }
+            Err(primary_err)
+        }
+    }
 }
 
-/// Write lockfile to disk
+/// Write lockfile to disk atomically: serialize to a temp file in the same
+/// directory and rename it into place. The previous file, if it parses, is
+/// kept as `<lockfile>.bak`.
 pub fn write_lockfile(path: &Path, lock: &LockFile) -> Result<(), MicroClawError> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| MicroClawError::Config(format!("Failed to create lockfile dir: {}", e)))?;
-    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)
+        .map_err(|e| MicroClawError::Config(format!("Failed to create lockfile dir: {}", e)))?;
     let content = serde_json::to_string_pretty(lock)
         .map_err(|e| MicroClawError::Config(format!("Failed to serialize lockfile: {}", e)))?;
-    std::fs::write(path, content)
-        .map_err(|e| MicroClawError::Config(format!("Failed to write lockfile: {}", e)))?;
+
+    if path.exists() && parse_lockfile(path).is_ok() {
+        std::fs::copy(path, backup_path(path))
+            .map_err(|e| MicroClawError::Config(format!("Failed to back up lockfile: {}", e)))?;
+    }
+
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(path.file_name().unwrap_or_default());
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp_path = parent.join(tmp_name);
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    };
+    if let Err(e) = write_tmp() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(MicroClawError::Config(format!(
+            "Failed to write lockfile: {}",
+            e
+        )));
+    }
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        MicroClawError::Config(format!("Failed to write lockfile: {}", e))
+    })?;
     Ok(())
 }
 
+/// Remove a skill's entry from the lockfile, returning it if present
+pub fn remove_entry(lock: &mut LockFile, slug: &str) -> Option<LockEntry> {
+    lock.skills.remove(slug)
+}
+
 /// Check if a skill is managed by ClawHub (in lockfile)
 pub fn is_clawhub_managed(lock: &LockFile, slug: &str) -> bool {
     lock.skills.contains_key(slug)
@@ -39,7 +95,6 @@ pub fn is_clawhub_managed(lock: &LockFile, slug: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::LockEntry;
 
     #[test]
     fn test_lockfile_missing_returns_empty() {
@@ -59,16 +114,8 @@ mod tests {
             version: 1,
             skills: HashMap::new(),
         };
-        lock.skills.insert(
-            "test-skill".into(),
-            LockEntry {
-                slug: "test-skill".into(),
-                installed_version: "1.0.0".into(),
-                installed_at: "2026-02-18T00:00:00Z".into(),
-                content_hash: "sha256:abc".into(),
-                local_path: "/tmp/test".into(),
-            },
-        );
+        lock.skills
+            .insert("test-skill".into(), LockEntry::for_test("test-skill"));
 
         write_lockfile(&lock_path, &lock).unwrap();
         let read = read_lockfile(&lock_path).unwrap();
@@ -86,18 +133,88 @@ mod tests {
             version: 1,
             skills: HashMap::new(),
         };
-        lock.skills.insert(
-            "my-skill".into(),
-            LockEntry {
-                slug: "my-skill".into(),
-                installed_version: "1.0.0".into(),
-                installed_at: "2026-02-18T00:00:00Z".into(),
-                content_hash: "sha256:abc".into(),
-                local_path: "/tmp/test".into(),
-            },
-        );
+        lock.skills
+            .insert("my-skill".into(), LockEntry::for_test("my-skill"));
 
         assert!(is_clawhub_managed(&lock, "my-skill"));
         assert!(!is_clawhub_managed(&lock, "other-skill"));
     }
+
+    #[test]
+    fn test_remove_entry() {
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        lock.skills
+            .insert("my-skill".into(), LockEntry::for_test("my-skill"));
+
+        assert!(remove_entry(&mut lock, "other-skill").is_none());
+        let removed = remove_entry(&mut lock, "my-skill").unwrap();
+        assert_eq!(removed.installed_version, "1.0.0");
+        assert!(lock.skills.is_empty());
+    }
+
+    fn lock_with(slugs: &[&str]) -> LockFile {
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        for slug in slugs {
+            lock.skills
+                .insert(slug.to_string(), LockEntry::for_test(slug));
+        }
+        lock
+    }
+
+    #[test]
+    fn test_write_lockfile_keeps_backup_and_no_temp_files() {
+        let temp_dir = std::env::temp_dir().join(format!("clawhub_test_{}", uuid::Uuid::new_v4()));
+        let lock_path = temp_dir.join("clawhub.lock.json");
+
+        write_lockfile(&lock_path, &lock_with(&["first"])).unwrap();
+        assert!(!backup_path(&lock_path).exists());
+        write_lockfile(&lock_path, &lock_with(&["first", "second"])).unwrap();
+
+        let backup = parse_lockfile(&backup_path(&lock_path)).unwrap();
+        assert_eq!(backup.skills.len(), 1);
+        assert_eq!(read_lockfile(&lock_path).unwrap().skills.len(), 2);
+
+        let leftovers: Vec<_> = std::fs::read_dir(&temp_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp-"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_read_lockfile_recovers_truncated_file_from_backup() {
+        let temp_dir = std::env::temp_dir().join(format!("clawhub_test_{}", uuid::Uuid::new_v4()));
+        let lock_path = temp_dir.join("clawhub.lock.json");
+
+        write_lockfile(&lock_path, &lock_with(&["first"])).unwrap();
+        write_lockfile(&lock_path, &lock_with(&["first", "second"])).unwrap();
+
+        // Simulate a crash mid-write that left the primary truncated.
+        let content = std::fs::read_to_string(&lock_path).unwrap();
+        std::fs::write(&lock_path, &content[..content.len() / 2]).unwrap();
+
+        let recovered = read_lockfile(&lock_path).unwrap();
+        assert!(recovered.skills.contains_key("first"));
+
+        // A corrupt primary must not clobber the good backup on the next write.
+        write_lockfile(&lock_path, &lock_with(&["third"])).unwrap();
+        let backup = parse_lockfile(&backup_path(&lock_path)).unwrap();
+        assert!(backup.skills.contains_key("first"));
+
+        // With no usable backup the parse error surfaces.
+        std::fs::write(&lock_path, "{").unwrap();
+        std::fs::write(backup_path(&lock_path), "").unwrap();
+        assert!(read_lockfile(&lock_path).is_err());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
 }
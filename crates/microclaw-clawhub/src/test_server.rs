@@ -0,0 +1,150 @@
+//! Canned-response HTTP server for driving `ClawHubClient` against fake
+//! registry replies in tests.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::client::ClawHubClient;
+
+/// Routes keyed by request path including the query string, e.g.
+/// `/api/v1/skills/pdf` or `/api/v1/download?slug=pdf&version=1.0.0`.
+#[derive(Default)]
+pub(crate) struct FakeRegistry {
+    routes: HashMap<String, (u16, Vec<u8>)>,
+    hits: Hits,
+}
+
+/// Requests served so far, keyed like the routes.
+#[derive(Clone, Default)]
+pub(crate) struct Hits(Arc<Mutex<HashMap<String, usize>>>);
+
+impl Hits {
+    pub(crate) fn count(&self, path: &str) -> usize {
+        self.0.lock().unwrap().get(path).copied().unwrap_or(0)
+    }
+
+    fn record(&self, path: &str) {
+        *self.0.lock().unwrap().entry(path.to_string()).or_default() += 1;
+    }
+}
+
+impl FakeRegistry {
+    pub(crate) fn route(mut self, path: &str, status: u16, body: impl Into<Vec<u8>>) -> Self {
+        self.routes.insert(path.to_string(), (status, body.into()));
+        self
+    }
+
+    /// Handle for counting requests once the registry is being served.
+    pub(crate) fn hits(&self) -> Hits {
+        self.hits.clone()
+    }
+
+    /// Get-skill response for `slug` whose latest version is `version`.
+    pub(crate) fn skill(
+        self,
+        slug: &str,
+        version: &str,
+        sha256: Option<&str>,
+        dependencies: &[&str],
+        moderation: serde_json::Value,
+    ) -> Self {
+        let body = serde_json::json!({
+            "skill": {
+                "slug": slug,
+                "displayName": slug,
+                "summary": "",
+                "tags": {},
+                "stats": {
+                    "comments": 0, "downloads": 0, "installsAllTime": 0,
+                    "installsCurrent": 0, "stars": 0, "versions": 1
+                },
+                "createdAt": 0,
+                "updatedAt": 0,
+                "dependencies": dependencies,
+            },
+            "latestVersion": {
+                "version": version,
+                "createdAt": 0,
+                "changelog": "",
+                "sha256": sha256,
+            },
+            "owner": {"handle": "o", "userId": "u", "displayName": "O", "image": ""},
+            "moderation": moderation,
+        });
+        self.route(
+            &format!("/api/v1/skills/{slug}"),
+            200,
+            body.to_string().into_bytes(),
+        )
+    }
+
+    /// Version list for `slug`.
+    pub(crate) fn versions(self, slug: &str, versions: serde_json::Value) -> Self {
+        self.route(
+            &format!("/api/v1/skills/{slug}/versions"),
+            200,
+            versions.to_string().into_bytes(),
+        )
+    }
+
+    /// Primary download endpoint for `slug@version`.
+    pub(crate) fn download(self, slug: &str, version: &str, zip: Vec<u8>) -> Self {
+        self.route(
+            &format!("/api/v1/download?slug={slug}&version={version}"),
+            200,
+            zip,
+        )
+    }
+
+    /// Serve the routes on a local port for the rest of the test; unknown
+    /// paths get a 404. Returns a client pointed at it.
+    pub(crate) async fn serve(self) -> ClawHubClient {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let routes = Arc::new(self.routes);
+        let hits = self.hits;
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let routes = routes.clone();
+                let hits = hits.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+                    hits.record(path);
+                    let (status, body) = routes.get(path).cloned().unwrap_or((404, b"{}".to_vec()));
+                    let head = format!(
+                        "HTTP/1.1 {status} Fake\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                        body.len()
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream.write_all(&body).await;
+                });
+            }
+        });
+        let http = reqwest::Client::builder().no_proxy().build().unwrap();
+        ClawHubClient::new(&format!("http://{addr}"), None).with_http_client(http)
+    }
+}
+
+/// In-memory skill ZIP with the given files.
+pub(crate) fn skill_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in files {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
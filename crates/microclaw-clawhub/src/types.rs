@@ -81,6 +81,9 @@ pub struct VersionItem {
     #[serde(rename = "createdAt")]
     pub created_at: i64,
     pub changelog: String,
+    /// Hex SHA-256 of the published ZIP, when the registry provides one
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 /// Get skill API response wrapper
@@ -106,6 +109,8 @@ pub struct SkillItem {
     pub created_at: i64,
     #[serde(rename = "updatedAt")]
     pub updated_at: i64,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
 /// Owner information
@@ -141,6 +146,45 @@ pub struct LockEntry {
     pub content_hash: String,
     #[serde(rename = "localPath")]
     pub local_path: String,
+    /// Where the skill came from; `local` for offline ZIP installs, unset for the registry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Digest of the extracted files, used by `skill verify` to detect drift
+    #[serde(default, rename = "filesHash", skip_serializing_if = "Option::is_none")]
+    pub files_hash: Option<String>,
+    /// Installed at an explicit version; `skill update` leaves it alone unless forced
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Skills this one needed when it was installed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    /// `dependency` when pulled in for another skill rather than requested directly
+    #[serde(
+        default,
+        rename = "installedAs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub installed_as: Option<String>,
+}
+
+#[cfg(test)]
+impl LockEntry {
+    /// Registry-installed `slug` at 1.0.0 under `/tmp/<slug>`; override
+    /// fields with struct update syntax where a test cares.
+    pub(crate) fn for_test(slug: &str) -> Self {
+        LockEntry {
+            slug: slug.to_string(),
+            installed_version: "1.0.0".into(),
+            installed_at: "2026-02-18T00:00:00Z".into(),
+            content_hash: "sha256:abc".into(),
+            local_path: format!("/tmp/{}", slug),
+            source: None,
+            files_hash: None,
+            pinned: false,
+            dependencies: Vec::new(),
+            installed_as: None,
+        }
+    }
 }
 
 /// Skill metadata from ClawHub API
@@ -154,6 +198,9 @@ pub struct SkillMeta {
     pub virustotal: Option<VirusTotal>,
     #[serde(default)]
     pub metadata: SkillMetadata,
+    /// Slugs of other skills that must be installed first
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,6 +208,12 @@ pub struct SkillVersion {
     pub version: String,
     #[serde(default)]
     pub latest: bool,
+    /// Hex SHA-256 of the published ZIP, when the registry provides one
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Slugs this version needs installed first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -170,6 +223,12 @@ pub struct VirusTotal {
     #[serde(rename = "pendingScan")]
     pub pending_scan: bool,
     pub status: String,
+    /// Engines flagging the skill as malicious, when the registry breaks it down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub malicious: Option<i32>,
+    /// Engines flagging the skill as suspicious, when the registry breaks it down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspicious: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -239,9 +298,16 @@ impl From<GetSkillResponse> for SkillMeta {
             versions: vec![SkillVersion {
                 version: resp.latest_version.version,
                 latest: true,
+                sha256: resp.latest_version.sha256,
+                dependencies: resp.skill.dependencies.clone(),
             }],
-            virustotal: None, // Not available in this response
+            virustotal: resp
+                .moderation
+                .as_ref()
+                .and_then(|m| m.get("virustotal"))
+                .and_then(|vt| serde_json::from_value(vt.clone()).ok()),
             metadata: SkillMetadata::default(),
+            dependencies: resp.skill.dependencies,
         }
     }
 }
@@ -268,6 +334,33 @@ mod tests {
             "description": "A test skill",
             "versions": [{"version": "1.0.0", "latest": true}]
         }"#;
-        let _meta: SkillMeta = serde_json::from_str(json).unwrap();
+        let meta: SkillMeta = serde_json::from_str(json).unwrap();
+        assert!(meta.versions[0].sha256.is_none());
+    }
+
+    #[test]
+    fn test_get_skill_response_carries_virustotal_scan() {
+        let json = r#"{
+            "skill": {
+                "slug": "pdf", "displayName": "PDF", "summary": "", "tags": {},
+                "stats": {"comments": 0, "downloads": 0, "installsAllTime": 0,
+                          "installsCurrent": 0, "stars": 0, "versions": 1},
+                "createdAt": 0, "updatedAt": 0
+            },
+            "latestVersion": {"version": "1.0.0", "createdAt": 0, "changelog": ""},
+            "owner": {"handle": "o", "userId": "u", "displayName": "O", "image": ""},
+            "moderation": {"virustotal": {"reportCount": 2, "pendingScan": true, "status": "pending"}}
+        }"#;
+        let resp: GetSkillResponse = serde_json::from_str(json).unwrap();
+        let vt = SkillMeta::from(resp).virustotal.unwrap();
+        assert_eq!(vt.report_count, 2);
+        assert!(vt.pending_scan);
+    }
+
+    #[test]
+    fn test_skill_version_captures_sha256() {
+        let json = r#"{"version": "1.0.0", "latest": true, "sha256": "abc123"}"#;
+        let version: SkillVersion = serde_json::from_str(json).unwrap();
+        assert_eq!(version.sha256.as_deref(), Some("abc123"));
     }
 }
@@ -0,0 +1,208 @@
+use crate::types::LockFile;
+use microclaw_core::error::MicroClawError;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// A mismatch between the lockfile and the skills directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyIssue {
+    /// Lockfile entry whose skill directory no longer exists
+    Missing { slug: String },
+    /// Installed files differ from what was recorded at install time
+    Drift {
+        slug: String,
+        expected: String,
+        actual: String,
+    },
+    /// Skill directory that no lockfile entry accounts for
+    Untracked { name: String },
+}
+
+/// Result of checking installed skills against the lockfile
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Entries whose files match the recorded hash
+    pub verified: Vec<String>,
+    /// Entries installed before file hashes were recorded
+    pub unverifiable: Vec<String>,
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Hash every file under `dir` (relative path and contents, in sorted order)
+/// into a single `sha256:` digest.
+pub fn hash_skill_dir(dir: &Path) -> Result<String, MicroClawError> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for rel in files {
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        let content = std::fs::read(dir.join(&rel))?;
+        hasher.update(rel_str.as_bytes());
+        hasher.update([0u8]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), MicroClawError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if file_type.is_file() {
+            if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_path_buf());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compare lockfile entries with the skill directories under `skills_dir`.
+///
+/// Directories named in `ignore` (e.g. built-in skills) are never reported
+/// as untracked.
+pub fn verify_skills(
+    skills_dir: &Path,
+    lock: &LockFile,
+    ignore: &[String],
+) -> Result<VerifyReport, MicroClawError> {
+    let mut report = VerifyReport::default();
+
+    let mut slugs: Vec<&String> = lock.skills.keys().collect();
+    slugs.sort();
+    for slug in slugs {
+        let entry = &lock.skills[slug];
+        let skill_path = skills_dir.join(slug);
+        if !skill_path.is_dir() {
+            report
+                .issues
+                .push(VerifyIssue::Missing { slug: slug.clone() });
+            continue;
+        }
+        let Some(expected) = entry.files_hash.as_deref() else {
+            report.unverifiable.push(slug.clone());
+            continue;
+        };
+        let actual = hash_skill_dir(&skill_path)?;
+        if actual == expected {
+            report.verified.push(slug.clone());
+        } else {
+            report.issues.push(VerifyIssue::Drift {
+                slug: slug.clone(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+
+    if skills_dir.is_dir() {
+        let mut untracked = Vec::new();
+        for entry in std::fs::read_dir(skills_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || lock.skills.contains_key(&name) || ignore.contains(&name) {
+                continue;
+            }
+            untracked.push(name);
+        }
+        untracked.sort();
+        report.issues.extend(
+            untracked
+                .into_iter()
+                .map(|name| VerifyIssue::Untracked { name }),
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LockEntry;
+    use std::collections::HashMap;
+
+    fn entry(slug: &str, files_hash: Option<String>) -> LockEntry {
+        LockEntry {
+            files_hash,
+            ..LockEntry::for_test(slug)
+        }
+    }
+
+    #[test]
+    fn test_verify_skills_detects_drift_missing_and_untracked() {
+        let root = std::env::temp_dir().join(format!("clawhub_verify_{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        for name in ["good", "mutated", "manual", "builtin"] {
+            std::fs::create_dir_all(skills_dir.join(name).join("scripts")).unwrap();
+            std::fs::write(skills_dir.join(name).join("SKILL.md"), "# skill").unwrap();
+            std::fs::write(skills_dir.join(name).join("scripts").join("run.sh"), "echo").unwrap();
+        }
+
+        let mut lock = LockFile {
+            version: 1,
+            skills: HashMap::new(),
+        };
+        for slug in ["good", "mutated"] {
+            let hash = hash_skill_dir(&skills_dir.join(slug)).unwrap();
+            lock.skills.insert(slug.into(), entry(slug, Some(hash)));
+        }
+        lock.skills.insert("gone".into(), entry("gone", None));
+
+        std::fs::write(
+            skills_dir.join("mutated").join("scripts").join("run.sh"),
+            "rm -rf /",
+        )
+        .unwrap();
+
+        let report = verify_skills(&skills_dir, &lock, &["builtin".to_string()]).unwrap();
+        assert_eq!(report.verified, vec!["good".to_string()]);
+        assert!(!report.is_clean());
+        assert_eq!(report.issues.len(), 3);
+        assert!(matches!(
+            &report.issues[0],
+            VerifyIssue::Missing { slug } if slug == "gone"
+        ));
+        assert!(matches!(
+            &report.issues[1],
+            VerifyIssue::Drift { slug, .. } if slug == "mutated"
+        ));
+        assert_eq!(
+            report.issues[2],
+            VerifyIssue::Untracked {
+                name: "manual".into()
+            }
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_hash_skill_dir_is_stable_and_path_sensitive() {
+        let root = std::env::temp_dir().join(format!("clawhub_verify_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.md"), "same").unwrap();
+        let first = hash_skill_dir(&root).unwrap();
+        assert_eq!(first, hash_skill_dir(&root).unwrap());
+
+        std::fs::rename(root.join("a.md"), root.join("b.md")).unwrap();
+        assert_ne!(first, hash_skill_dir(&root).unwrap());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -15,6 +15,9 @@ pub enum MicroClawError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("{message}")]
+    HttpStatus { status: u16, message: String },
+
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -51,6 +54,12 @@ mod tests {
 
         let e = MicroClawError::MaxIterations(25);
         assert_eq!(e.to_string(), "Max tool iterations reached (25)");
+
+        let e = MicroClawError::HttpStatus {
+            status: 503,
+            message: "server error (HTTP 503)".into(),
+        };
+        assert_eq!(e.to_string(), "server error (HTTP 503)");
     }
 
     #[test]
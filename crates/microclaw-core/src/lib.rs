@@ -1,5 +1,6 @@
 //! Shared foundational types and helpers for MicroClaw.
 
 pub mod error;
+pub mod http;
 pub mod llm_types;
 pub mod text;
@@ -68,6 +68,16 @@ pub struct MessagesResponse {
     pub content: Vec<ResponseContentBlock>,
     pub stop_reason: Option<String>,
     pub usage: Option<Usage>,
+    /// Set when a fallback provider, not the configured one, answered.
+    #[serde(skip)]
+    pub served_by: Option<ServedBy>,
+}
+
+/// Provider and model that actually produced a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServedBy {
+    pub provider: String,
+    pub model: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
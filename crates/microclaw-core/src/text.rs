@@ -33,3 +33,169 @@ pub fn split_text(text: &str, max_len: usize) -> Vec<String> {
     }
     chunks
 }
+
+/// Like [`split_text`], but never leaves a fenced code block open across a
+/// chunk boundary: a chunk that ends inside a fence is closed with ` ``` ` and
+/// the next chunk re-opens it with the same info string (e.g. ` ```rust `).
+/// Lines longer than a whole chunk are still hard-split.
+pub fn split_text_preserving_code(text: &str, max_len: usize) -> Vec<String> {
+    const CLOSE_FENCE: &str = "\n```";
+
+    if text.len() <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut lines_in_chunk = 0usize;
+    let mut open_fence: Option<String> = None;
+
+    for line in text.split('\n') {
+        let toggles_fence = line.trim_start().starts_with("```");
+        // Longest piece that fits in a fresh chunk, leaving room to re-open
+        // and close the fence when this line sits inside one.
+        let piece_limit = match &open_fence {
+            Some(fence) if !toggles_fence => {
+                max_len.saturating_sub(fence.len() + 1 + CLOSE_FENCE.len())
+            }
+            _ => max_len,
+        }
+        .max(1);
+
+        for piece in hard_split(line, piece_limit) {
+            let fence_after = if toggles_fence {
+                match &open_fence {
+                    Some(_) => None,
+                    None => Some(line.trim().to_string()),
+                }
+            } else {
+                open_fence.clone()
+            };
+            let reserve = if fence_after.is_some() {
+                CLOSE_FENCE.len()
+            } else {
+                0
+            };
+            let separator = usize::from(lines_in_chunk > 0);
+            if lines_in_chunk > 0 && current.len() + separator + piece.len() + reserve > max_len {
+                if open_fence.is_some() {
+                    current.push_str(CLOSE_FENCE);
+                }
+                chunks.push(std::mem::take(&mut current));
+                lines_in_chunk = 0;
+                if let Some(fence) = &open_fence {
+                    if toggles_fence {
+                        // The closing fence was already emitted above.
+                        continue;
+                    }
+                    current.push_str(fence);
+                    lines_in_chunk = 1;
+                }
+            }
+            if lines_in_chunk > 0 {
+                current.push('\n');
+            }
+            current.push_str(piece);
+            lines_in_chunk += 1;
+        }
+
+        if toggles_fence {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some(line.trim().to_string()),
+            };
+        }
+    }
+    if lines_in_chunk > 0 {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Splits one line into pieces of at most `max_len` bytes on char boundaries.
+fn hard_split(line: &str, max_len: usize) -> Vec<&str> {
+    if line.len() <= max_len {
+        return vec![line];
+    }
+    let mut pieces = Vec::new();
+    let mut remaining = line;
+    while !remaining.is_empty() {
+        let mut boundary = floor_char_boundary(remaining, max_len);
+        if boundary == 0 {
+            boundary = remaining
+                .chars()
+                .next()
+                .map_or(remaining.len(), char::len_utf8);
+        }
+        pieces.push(&remaining[..boundary]);
+        remaining = &remaining[boundary..];
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fence_count(chunk: &str) -> usize {
+        chunk
+            .lines()
+            .filter(|line| line.trim_start().starts_with("```"))
+            .count()
+    }
+
+    #[test]
+    fn test_short_text_is_single_chunk() {
+        assert_eq!(split_text_preserving_code("hello", 100), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_plain_text_splits_on_newlines() {
+        let text = format!("{}\n{}", "a".repeat(60), "b".repeat(60));
+        let chunks = split_text_preserving_code(&text, 100);
+        assert_eq!(chunks, vec!["a".repeat(60), "b".repeat(60)]);
+    }
+
+    #[test]
+    fn test_code_fence_is_closed_and_reopened() {
+        let mut text = String::from("Here is the script:\n```bash\n");
+        for i in 0..20 {
+            text.push_str(&format!("echo line number {i}\n"));
+        }
+        text.push_str("```\nDone.");
+
+        let chunks = split_text_preserving_code(&text, 120);
+        assert!(chunks.len() > 1);
+        for (idx, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.len() <= 120, "chunk {idx} too long: {}", chunk.len());
+            assert_eq!(fence_count(chunk) % 2, 0, "unbalanced chunk: {chunk}");
+            if idx > 0 && chunk.contains("echo") {
+                assert!(
+                    chunk.starts_with("```bash\n"),
+                    "fence not reopened: {chunk}"
+                );
+            }
+        }
+        let joined = chunks.join("\n");
+        for i in 0..20 {
+            assert!(joined.contains(&format!("echo line number {i}\n")));
+        }
+        assert!(chunks.last().unwrap().ends_with("Done."));
+    }
+
+    #[test]
+    fn test_long_single_line_is_hard_split() {
+        let text = "x".repeat(250);
+        let chunks = split_text_preserving_code(&text, 100);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.len() <= 100));
+        assert_eq!(chunks.concat(), text);
+
+        let fenced = format!("```\n{}\n```", "é".repeat(150));
+        let chunks = split_text_preserving_code(&fenced, 100);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 100);
+            assert_eq!(fence_count(chunk) % 2, 0);
+        }
+    }
+}
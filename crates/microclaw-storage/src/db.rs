@@ -9,6 +9,9 @@ use microclaw_core::error::MicroClawError;
 
 pub struct Database {
     conn: Mutex<Connection>,
+    /// Receives every newly stored message once a vector indexer subscribes.
+    #[cfg(feature = "sqlite-vec")]
+    message_index_tx: Mutex<Option<tokio::sync::mpsc::UnboundedSender<StoredMessage>>>,
 }
 
 #[cfg(feature = "sqlite-vec")]
@@ -81,6 +84,25 @@ pub struct LlmModelUsageSummary {
     pub total_tokens: i64,
 }
 
+/// Token usage for one UTC calendar day (`YYYY-MM-DD`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LlmDailyUsage {
+    pub day: String,
+    pub requests: i64,
+    pub total_tokens: i64,
+}
+
+/// Usage attributed to whoever sent the most recent message before each call.
+#[derive(Debug, Clone)]
+pub struct LlmSenderUsageSummary {
+    pub sender_name: String,
+    pub is_bot: bool,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct Memory {
     pub id: i64,
@@ -183,7 +205,7 @@ pub struct AuditLogRecord {
 pub type SessionMetaRow = (String, String, Option<String>, Option<i64>);
 pub type SessionTreeRow = (i64, Option<String>, Option<i64>, String);
 
-const SCHEMA_VERSION_CURRENT: i64 = 10;
+const SCHEMA_VERSION_CURRENT: i64 = 12;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -233,6 +255,38 @@ fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool
     Ok(false)
 }
 
+/// Drop the embedding stored for one message, if the vector index has been
+/// prepared. A no-op without the `sqlite-vec` feature.
+fn delete_message_vec(
+    conn: &Connection,
+    chat_id: i64,
+    message_id: &str,
+) -> Result<(), MicroClawError> {
+    #[cfg(feature = "sqlite-vec")]
+    {
+        if !table_has_column(conn, "message_vec_keys", "vec_id")? {
+            return Ok(());
+        }
+        let vec_id: Option<i64> = conn
+            .query_row(
+                "SELECT vec_id FROM message_vec_keys WHERE message_id = ?1 AND chat_id = ?2",
+                params![message_id, chat_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(vec_id) = vec_id {
+            conn.execute("DELETE FROM messages_vec WHERE rowid = ?1", params![vec_id])?;
+            conn.execute(
+                "DELETE FROM message_vec_keys WHERE vec_id = ?1",
+                params![vec_id],
+            )?;
+        }
+    }
+    #[cfg(not(feature = "sqlite-vec"))]
+    let _ = (conn, chat_id, message_id);
+    Ok(())
+}
+
 fn ensure_memory_schema(conn: &Connection) -> Result<(), MicroClawError> {
     if !table_has_column(conn, "memories", "embedding_model")? {
         conn.execute("ALTER TABLE memories ADD COLUMN embedding_model TEXT", [])?;
@@ -608,12 +662,62 @@ fn apply_schema_migrations(conn: &Connection) -> Result<(), MicroClawError> {
         set_schema_version(conn, 10)?;
         version = 10;
     }
+    if version < 11 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_model_overrides (
+                chat_id INTEGER PRIMARY KEY,
+                model TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );",
+        )?;
+        set_schema_version(conn, 11)?;
+        version = 11;
+    }
+    if version < 12 {
+        if !table_has_column(conn, "messages", "sender_id")? {
+            conn.execute("ALTER TABLE messages ADD COLUMN sender_id TEXT", [])?;
+        }
+        set_schema_version(conn, 12)?;
+        version = 12;
+    }
     if version != SCHEMA_VERSION_CURRENT {
         set_schema_version(conn, SCHEMA_VERSION_CURRENT)?;
     }
     Ok(())
 }
 
+/// Build a `WHERE` clause (with numbered placeholders) for `llm_usage_logs`
+/// limited to a chat and a `[since, until)` time range. `prefix` qualifies the
+/// columns when the table is aliased (e.g. `"u."`).
+fn llm_usage_filter(
+    prefix: &str,
+    chat_id: Option<i64>,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> (String, Vec<rusqlite::types::Value>) {
+    use rusqlite::types::Value;
+
+    let mut clauses = Vec::new();
+    let mut values = Vec::new();
+    if let Some(id) = chat_id {
+        values.push(Value::Integer(id));
+        clauses.push(format!("{prefix}chat_id = ?{}", values.len()));
+    }
+    if let Some(since) = since {
+        values.push(Value::Text(since.to_string()));
+        clauses.push(format!("{prefix}created_at >= ?{}", values.len()));
+    }
+    if let Some(until) = until {
+        values.push(Value::Text(until.to_string()));
+        clauses.push(format!("{prefix}created_at < ?{}", values.len()));
+    }
+    if clauses.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!(" WHERE {}", clauses.join(" AND ")), values)
+    }
+}
+
 impl Database {
     fn lock_conn(&self) -> MutexGuard<'_, Connection> {
         match self.conn.lock() {
@@ -847,6 +951,12 @@ impl Database {
                 active_sessions INTEGER NOT NULL DEFAULT 0
             );
             CREATE INDEX IF NOT EXISTS idx_metrics_history_ts ON metrics_history(timestamp_ms);
+
+            CREATE TABLE IF NOT EXISTS chat_model_overrides (
+                chat_id INTEGER PRIMARY KEY,
+                model TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
             ",
         )?;
 
@@ -869,9 +979,41 @@ impl Database {
 
         Ok(Database {
             conn: Mutex::new(conn),
+            #[cfg(feature = "sqlite-vec")]
+            message_index_tx: Mutex::new(None),
         })
     }
 
+    /// Route messages stored from now on to the returned receiver so they can
+    /// be embedded in the background. A later subscription replaces this one.
+    #[cfg(feature = "sqlite-vec")]
+    pub fn subscribe_message_index(&self) -> tokio::sync::mpsc::UnboundedReceiver<StoredMessage> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        *self
+            .message_index_tx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(tx);
+        rx
+    }
+
+    fn enqueue_for_index(&self, msg: &StoredMessage) {
+        #[cfg(feature = "sqlite-vec")]
+        {
+            let mut guard = self
+                .message_index_tx
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(tx) = guard.as_ref() {
+                if tx.send(msg.clone()).is_err() {
+                    // Indexer went away; stop queueing until someone resubscribes.
+                    *guard = None;
+                }
+            }
+        }
+        #[cfg(not(feature = "sqlite-vec"))]
+        let _ = msg;
+    }
+
     pub fn upsert_chat(
         &self,
         chat_id: i64,
@@ -972,14 +1114,35 @@ impl Database {
                 msg.timestamp,
             ],
         )?;
+        drop(conn);
+        self.enqueue_for_index(msg);
         Ok(())
     }
 
     pub fn store_message_if_new(&self, msg: &StoredMessage) -> Result<bool, MicroClawError> {
+        self.insert_message_if_new(msg, None)
+    }
+
+    /// Like [`Self::store_message_if_new`], also recording the platform's stable
+    /// sender id (e.g. a Matrix MXID), which unlike `sender_name` cannot be
+    /// chosen by another user.
+    pub fn store_message_if_new_with_sender_id(
+        &self,
+        msg: &StoredMessage,
+        sender_id: &str,
+    ) -> Result<bool, MicroClawError> {
+        self.insert_message_if_new(msg, Some(sender_id))
+    }
+
+    fn insert_message_if_new(
+        &self,
+        msg: &StoredMessage,
+        sender_id: Option<&str>,
+    ) -> Result<bool, MicroClawError> {
         let conn = self.lock_conn();
         let affected = conn.execute(
-            "INSERT OR IGNORE INTO messages (id, chat_id, sender_name, content, is_from_bot, timestamp)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT OR IGNORE INTO messages (id, chat_id, sender_name, content, is_from_bot, timestamp, sender_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 msg.id,
                 msg.chat_id,
@@ -987,11 +1150,106 @@ impl Database {
                 msg.content,
                 msg.is_from_bot as i32,
                 msg.timestamp,
+                sender_id,
             ],
         )?;
+        drop(conn);
+        if affected > 0 {
+            self.enqueue_for_index(msg);
+        }
         Ok(affected > 0)
     }
 
+    /// Replace the content of an already-stored message (e.g. a platform-side edit).
+    /// Returns false when no message with that id exists in the chat. The old
+    /// embedding is dropped and the message is queued for indexing again.
+    pub fn update_message_content(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+        content: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let tx = conn.unchecked_transaction()?;
+        let rows = tx.execute(
+            "UPDATE messages SET content = ?3 WHERE chat_id = ?1 AND id = ?2",
+            params![chat_id, message_id, content],
+        )?;
+        if rows > 0 {
+            delete_message_vec(&tx, chat_id, message_id)?;
+        }
+        tx.commit()?;
+        drop(conn);
+        if rows > 0 {
+            if let Some(msg) = self.get_message(chat_id, message_id)? {
+                self.enqueue_for_index(&msg);
+            }
+        }
+        Ok(rows > 0)
+    }
+
+    /// Remove a single stored message (e.g. one the sender deleted on the
+    /// platform), along with its embedding.
+    pub fn delete_message_by_id(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let tx = conn.unchecked_transaction()?;
+        let rows = tx.execute(
+            "DELETE FROM messages WHERE chat_id = ?1 AND id = ?2",
+            params![chat_id, message_id],
+        )?;
+        delete_message_vec(&tx, chat_id, message_id)?;
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Stable sender id recorded with a message, if any.
+    pub fn get_message_sender_id(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+    ) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let sender_id = conn
+            .query_row(
+                "SELECT sender_id FROM messages WHERE chat_id = ?1 AND id = ?2",
+                params![chat_id, message_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?;
+        Ok(sender_id.flatten())
+    }
+
+    pub fn get_message(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+    ) -> Result<Option<StoredMessage>, MicroClawError> {
+        let conn = self.lock_conn();
+        let message = conn
+            .query_row(
+                "SELECT id, chat_id, sender_name, content, is_from_bot, timestamp
+                 FROM messages
+                 WHERE chat_id = ?1 AND id = ?2",
+                params![chat_id, message_id],
+                |row| {
+                    Ok(StoredMessage {
+                        id: row.get(0)?,
+                        chat_id: row.get(1)?,
+                        sender_name: row.get(2)?,
+                        content: row.get(3)?,
+                        is_from_bot: row.get::<_, i32>(4)? != 0,
+                        timestamp: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(message)
+    }
+
     pub fn message_exists(&self, chat_id: i64, message_id: &str) -> Result<bool, MicroClawError> {
         let conn = self.lock_conn();
         let exists = conn
@@ -1720,6 +1978,68 @@ impl Database {
         Ok(rows)
     }
 
+    /// Model pinned for one chat with `/model <name>`, overriding the global model.
+    pub fn get_chat_model_override(&self, chat_id: i64) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let model = conn
+            .query_row(
+                "SELECT model FROM chat_model_overrides WHERE chat_id = ?1",
+                params![chat_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(model)
+    }
+
+    pub fn set_chat_model_override(&self, chat_id: i64, model: &str) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        conn.execute(
+            "INSERT INTO chat_model_overrides (chat_id, model, updated_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(chat_id) DO UPDATE SET model = excluded.model, updated_at = excluded.updated_at",
+            params![chat_id, model, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_chat_model_override(&self, chat_id: i64) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "DELETE FROM chat_model_overrides WHERE chat_id = ?1",
+            params![chat_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Free-form runtime state kept in `db_meta`, such as channel sync cursors.
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let value = conn
+            .query_row(
+                "SELECT value FROM db_meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        conn.execute(
+            "INSERT INTO db_meta(key, value) VALUES(?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_meta(&self, key: &str) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute("DELETE FROM db_meta WHERE key = ?1", params![key])?;
+        Ok(rows > 0)
+    }
+
     pub fn delete_session(&self, chat_id: i64) -> Result<bool, MicroClawError> {
         let conn = self.lock_conn();
         let rows = conn.execute("DELETE FROM sessions WHERE chat_id = ?1", params![chat_id])?;
@@ -2449,6 +2769,175 @@ impl Database {
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
+    /// Usage within `[since, until)`; either bound may be open.
+    pub fn get_llm_usage_summary_between(
+        &self,
+        chat_id: Option<i64>,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<LlmUsageSummary, MicroClawError> {
+        let conn = self.lock_conn();
+        let (filter, values) = llm_usage_filter("", chat_id, since, until);
+        let query = format!(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(total_tokens), 0),
+                MAX(created_at)
+             FROM llm_usage_logs{filter}"
+        );
+        let summary = conn.query_row(&query, rusqlite::params_from_iter(values), |row| {
+            Ok(LlmUsageSummary {
+                requests: row.get(0)?,
+                input_tokens: row.get(1)?,
+                output_tokens: row.get(2)?,
+                total_tokens: row.get(3)?,
+                last_request_at: row.get(4)?,
+            })
+        })?;
+        Ok(summary)
+    }
+
+    /// Per-model usage within `[since, until)`, largest first.
+    pub fn get_llm_usage_by_model_between(
+        &self,
+        chat_id: Option<i64>,
+        since: Option<&str>,
+        until: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<LlmModelUsageSummary>, MicroClawError> {
+        let conn = self.lock_conn();
+        let (filter, mut values) = llm_usage_filter("", chat_id, since, until);
+        let mut query = format!(
+            "SELECT
+                model,
+                COUNT(*) AS requests,
+                COALESCE(SUM(input_tokens), 0) AS input_tokens,
+                COALESCE(SUM(output_tokens), 0) AS output_tokens,
+                COALESCE(SUM(total_tokens), 0) AS total_tokens
+             FROM llm_usage_logs{filter}
+             GROUP BY model ORDER BY total_tokens DESC"
+        );
+        if let Some(limit_n) = limit {
+            values.push(rusqlite::types::Value::Integer(limit_n as i64));
+            query.push_str(&format!(" LIMIT ?{}", values.len()));
+        }
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok(LlmModelUsageSummary {
+                model: row.get(0)?,
+                requests: row.get(1)?,
+                input_tokens: row.get(2)?,
+                output_tokens: row.get(3)?,
+                total_tokens: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Per-day totals for the last `days` UTC days (today included), oldest
+    /// first. Days without usage are returned with zero counts.
+    pub fn get_llm_usage_daily(
+        &self,
+        chat_id: Option<i64>,
+        days: usize,
+    ) -> Result<Vec<LlmDailyUsage>, MicroClawError> {
+        if days == 0 {
+            return Ok(Vec::new());
+        }
+        let today = chrono::Utc::now().date_naive();
+        let first_day = today - chrono::Duration::days(days as i64 - 1);
+        let since = first_day
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc()
+            .to_rfc3339();
+
+        let conn = self.lock_conn();
+        let (filter, values) = llm_usage_filter("", chat_id, Some(&since), None);
+        let query = format!(
+            "SELECT substr(created_at, 1, 10) AS day, COUNT(*), COALESCE(SUM(total_tokens), 0)
+             FROM llm_usage_logs{filter}
+             GROUP BY day"
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        let mut by_day = std::collections::HashMap::new();
+        for row in rows {
+            let (day, requests, total_tokens) = row?;
+            by_day.insert(day, (requests, total_tokens));
+        }
+
+        Ok((0..days)
+            .map(|offset| {
+                let day = (first_day + chrono::Duration::days(offset as i64))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                let (requests, total_tokens) = by_day.get(&day).copied().unwrap_or((0, 0));
+                LlmDailyUsage {
+                    day,
+                    requests,
+                    total_tokens,
+                }
+            })
+            .collect())
+    }
+
+    /// Per-sender usage for a chat, largest first. Each usage row is credited
+    /// to the latest message stored at or before it, so agent runs land on the
+    /// user who triggered them and bot-initiated calls (scheduled tasks,
+    /// follow-ups) on the bot's own username with `is_bot` set.
+    pub fn get_llm_usage_by_sender(
+        &self,
+        chat_id: i64,
+        since: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<LlmSenderUsageSummary>, MicroClawError> {
+        let conn = self.lock_conn();
+        let (filter, mut values) = llm_usage_filter("u.", Some(chat_id), since, None);
+        let mut query = format!(
+            "SELECT
+                COALESCE(m.sender_name, '(unknown)') AS sender,
+                COALESCE(m.is_from_bot, 0) AS is_bot,
+                COUNT(*) AS requests,
+                COALESCE(SUM(u.input_tokens), 0) AS input_tokens,
+                COALESCE(SUM(u.output_tokens), 0) AS output_tokens,
+                COALESCE(SUM(u.total_tokens), 0) AS total_tokens
+             FROM llm_usage_logs u
+             LEFT JOIN messages m ON m.rowid = (
+                SELECT m2.rowid FROM messages m2
+                WHERE m2.chat_id = u.chat_id AND m2.timestamp <= u.created_at
+                ORDER BY m2.timestamp DESC
+                LIMIT 1
+             ){filter}
+             GROUP BY sender, is_bot
+             ORDER BY total_tokens DESC"
+        );
+        if let Some(limit_n) = limit {
+            values.push(rusqlite::types::Value::Integer(limit_n as i64));
+            query.push_str(&format!(" LIMIT ?{}", values.len()));
+        }
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok(LlmSenderUsageSummary {
+                sender_name: row.get(0)?,
+                is_bot: row.get::<_, i64>(1)? != 0,
+                requests: row.get(2)?,
+                input_tokens: row.get(3)?,
+                output_tokens: row.get(4)?,
+                total_tokens: row.get(5)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     // --- Memories ---
 
     pub fn insert_memory(
@@ -2786,6 +3275,8 @@ impl Database {
             if existing != dimension.to_string() {
                 conn.execute("DROP TABLE IF EXISTS memories_vec", [])?;
                 conn.execute("UPDATE memories SET embedding_model = NULL", [])?;
+                conn.execute("DROP TABLE IF EXISTS messages_vec", [])?;
+                conn.execute("DROP TABLE IF EXISTS message_vec_keys", [])?;
             }
         }
 
@@ -2797,6 +3288,51 @@ impl Database {
             ),
             [],
         )?;
+        // Messages are keyed by (id, chat_id) text pairs, so vectors hang off a
+        // stable integer key rather than the messages table's implicit rowid.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_vec_keys (
+                vec_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                message_id TEXT NOT NULL,
+                chat_id INTEGER NOT NULL,
+                UNIQUE(message_id, chat_id)
+            )",
+            [],
+        )?;
+        // chat_id is a partition key so per-chat searches run the KNN inside
+        // that chat instead of filtering a global top-k afterwards.
+        let create_messages_vec = |table: &str| {
+            format!(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS {table} USING vec0(
+                    chat_id integer partition key,
+                    embedding float[{dimension}] distance_metric=cosine
+                )"
+            )
+        };
+        let existing_sql: Option<String> = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE name = 'messages_vec'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if existing_sql.is_some_and(|sql| !sql.contains("chat_id")) {
+            // Older layout without the partition key: carry the vectors over.
+            conn.execute_batch(
+                "CREATE TEMP TABLE messages_vec_upgrade AS
+                    SELECT v.rowid AS vec_id, keys.chat_id AS chat_id, v.embedding AS embedding
+                    FROM messages_vec v
+                    JOIN message_vec_keys keys ON keys.vec_id = v.rowid;
+                 DROP TABLE messages_vec;",
+            )?;
+            conn.execute(&create_messages_vec("messages_vec"), [])?;
+            conn.execute_batch(
+                "INSERT INTO messages_vec(rowid, chat_id, embedding)
+                    SELECT vec_id, chat_id, embedding FROM messages_vec_upgrade;
+                 DROP TABLE messages_vec_upgrade;",
+            )?;
+        }
+        conn.execute(&create_messages_vec("messages_vec"), [])?;
         conn.execute(
             "INSERT INTO db_meta(key, value) VALUES('embedding_dim', ?1)
              ON CONFLICT(key) DO UPDATE SET value = excluded.value",
@@ -2856,10 +3392,85 @@ impl Database {
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
-    /// Get a single memory by id.
-    pub fn get_memory_by_id(&self, id: i64) -> Result<Option<Memory>, MicroClawError> {
+    #[cfg(feature = "sqlite-vec")]
+    pub fn upsert_message_vec(
+        &self,
+        message_id: &str,
+        chat_id: i64,
+        embedding: &[f32],
+    ) -> Result<(), MicroClawError> {
         let conn = self.lock_conn();
-        let result = conn.query_row(
+        let vector_json = serde_json::to_string(embedding)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO message_vec_keys(message_id, chat_id) VALUES(?1, ?2)",
+            params![message_id, chat_id],
+        )?;
+        let vec_id: i64 = conn.query_row(
+            "SELECT vec_id FROM message_vec_keys WHERE message_id = ?1 AND chat_id = ?2",
+            params![message_id, chat_id],
+            |row| row.get(0),
+        )?;
+        // vec0 tables reject INSERT OR REPLACE on an existing rowid.
+        conn.execute("DELETE FROM messages_vec WHERE rowid = ?1", params![vec_id])?;
+        conn.execute(
+            "INSERT INTO messages_vec(rowid, chat_id, embedding) VALUES(?1, ?2, vec_f32(?3))",
+            params![vec_id, chat_id, vector_json],
+        )?;
+        Ok(())
+    }
+
+    /// Nearest stored messages to `query_vec`, closest first. With `chat_id`
+    /// set, the KNN search runs within that chat's partition only.
+    #[cfg(feature = "sqlite-vec")]
+    pub fn knn_messages(
+        &self,
+        query_vec: &[f32],
+        k: usize,
+        chat_id: Option<i64>,
+    ) -> Result<Vec<(StoredMessage, f32)>, MicroClawError> {
+        let conn = self.lock_conn();
+        let vector_json = serde_json::to_string(query_vec)?;
+        let chat_filter = if chat_id.is_some() {
+            " AND chat_id = ?3"
+        } else {
+            ""
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT m.id, m.chat_id, m.sender_name, m.content, m.is_from_bot, m.timestamp,
+                    v.distance
+             FROM (
+                SELECT rowid, distance
+                FROM messages_vec
+                WHERE embedding MATCH vec_f32(?1) AND k = ?2{chat_filter}
+             ) v
+             JOIN message_vec_keys keys ON keys.vec_id = v.rowid
+             JOIN messages m ON m.id = keys.message_id AND m.chat_id = keys.chat_id
+             ORDER BY v.distance ASC"
+        ))?;
+        let mapper = |row: &rusqlite::Row<'_>| {
+            Ok((
+                StoredMessage {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    sender_name: row.get(2)?,
+                    content: row.get(3)?,
+                    is_from_bot: row.get::<_, i32>(4)? != 0,
+                    timestamp: row.get(5)?,
+                },
+                row.get::<_, f32>(6)?,
+            ))
+        };
+        let rows = match chat_id {
+            Some(cid) => stmt.query_map(params![vector_json, k as i64, cid], mapper)?,
+            None => stmt.query_map(params![vector_json, k as i64], mapper)?,
+        };
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Get a single memory by id.
+    pub fn get_memory_by_id(&self, id: i64) -> Result<Option<Memory>, MicroClawError> {
+        let conn = self.lock_conn();
+        let result = conn.query_row(
             "SELECT id, chat_id, content, category, created_at, updated_at, embedding_model,
                     confidence, source, last_seen_at, is_archived, archived_at
              FROM memories WHERE id = ?1",
@@ -3695,6 +4306,53 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_update_message_content() {
+        let (db, dir) = test_db();
+        assert!(!db.update_message_content(100, "msg1", "edited").unwrap());
+
+        db.store_message(&StoredMessage {
+            id: "msg1".into(),
+            chat_id: 100,
+            sender_name: "alice".into(),
+            content: "hello".into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        })
+        .unwrap();
+
+        assert!(db.update_message_content(100, "msg1", "edited").unwrap());
+        assert!(!db.update_message_content(200, "msg1", "edited").unwrap());
+        let msgs = db.get_recent_messages(100, 10).unwrap();
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "edited");
+        assert_eq!(msgs[0].timestamp, "2024-01-01T00:00:00Z");
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_delete_message_by_id() {
+        let (db, dir) = test_db();
+        for id in ["msg1", "msg2"] {
+            db.store_message(&StoredMessage {
+                id: id.into(),
+                chat_id: 100,
+                sender_name: "alice".into(),
+                content: format!("content of {id}"),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            })
+            .unwrap();
+        }
+
+        assert!(!db.delete_message_by_id(200, "msg1").unwrap());
+        assert!(db.delete_message_by_id(100, "msg1").unwrap());
+        assert!(!db.delete_message_by_id(100, "msg1").unwrap());
+        assert!(!db.message_exists(100, "msg1").unwrap());
+        assert!(db.message_exists(100, "msg2").unwrap());
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_store_message_if_new() {
         let (db, dir) = test_db();
@@ -3711,6 +4369,31 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_store_message_records_sender_id() {
+        let (db, dir) = test_db();
+        let msg = |id: &str| StoredMessage {
+            id: id.into(),
+            chat_id: 100,
+            sender_name: "Alice".into(),
+            content: "hello".into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        };
+        assert!(db
+            .store_message_if_new_with_sender_id(&msg("with-id"), "@alice:example.org")
+            .unwrap());
+        assert!(db.store_message_if_new(&msg("without-id")).unwrap());
+
+        assert_eq!(
+            db.get_message_sender_id(100, "with-id").unwrap().as_deref(),
+            Some("@alice:example.org")
+        );
+        assert_eq!(db.get_message_sender_id(100, "without-id").unwrap(), None);
+        assert_eq!(db.get_message_sender_id(100, "missing").unwrap(), None);
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_get_recent_messages_ordering_and_limit() {
         let (db, dir) = test_db();
@@ -4203,6 +4886,45 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_chat_model_override_persists_across_reopen() {
+        let (db, dir) = test_db();
+        assert!(db.get_chat_model_override(100).unwrap().is_none());
+        db.set_chat_model_override(100, "gpt-5-mini").unwrap();
+        db.set_chat_model_override(100, "gpt-5").unwrap();
+        assert_eq!(
+            db.get_chat_model_override(100).unwrap().as_deref(),
+            Some("gpt-5")
+        );
+        assert!(db.get_chat_model_override(200).unwrap().is_none());
+        drop(db);
+
+        let db = Database::new(dir.to_str().unwrap()).unwrap();
+        assert_eq!(
+            db.get_chat_model_override(100).unwrap().as_deref(),
+            Some("gpt-5")
+        );
+        assert!(db.clear_chat_model_override(100).unwrap());
+        assert!(!db.clear_chat_model_override(100).unwrap());
+        assert!(db.get_chat_model_override(100).unwrap().is_none());
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_meta_roundtrip() {
+        let (db, dir) = test_db();
+        assert!(db.get_meta("matrix_sync_token:matrix").unwrap().is_none());
+        db.set_meta("matrix_sync_token:matrix", "s1").unwrap();
+        db.set_meta("matrix_sync_token:matrix", "s2").unwrap();
+        assert_eq!(
+            db.get_meta("matrix_sync_token:matrix").unwrap().as_deref(),
+            Some("s2")
+        );
+        assert!(db.delete_meta("matrix_sync_token:matrix").unwrap());
+        assert!(db.get_meta("matrix_sync_token:matrix").unwrap().is_none());
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_clear_chat_context_removes_session_and_messages_only() {
         let (db, dir) = test_db();
@@ -4645,6 +5367,153 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_get_llm_usage_between_bounds_range() {
+        let (db, dir) = test_db();
+        db.log_llm_usage(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-a",
+            10,
+            5,
+            "agent_loop",
+        )
+        .unwrap();
+        db.log_llm_usage(200, "telegram", "anthropic", "claude-b", 3, 7, "agent_loop")
+            .unwrap();
+
+        let open = db.get_llm_usage_summary_between(None, None, None).unwrap();
+        assert_eq!(open.requests, 2);
+
+        let chat = db
+            .get_llm_usage_summary_between(
+                Some(100),
+                Some("2000-01-01T00:00:00Z"),
+                Some("2100-01-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert_eq!(chat.requests, 1);
+        assert_eq!(chat.total_tokens, 15);
+
+        let past = db
+            .get_llm_usage_summary_between(None, None, Some("2000-01-01T00:00:00Z"))
+            .unwrap();
+        assert_eq!(past.requests, 0);
+
+        let by_model = db
+            .get_llm_usage_by_model_between(
+                None,
+                Some("2000-01-01T00:00:00Z"),
+                Some("2100-01-01T00:00:00Z"),
+                Some(1),
+            )
+            .unwrap();
+        assert_eq!(by_model.len(), 1);
+        assert_eq!(by_model[0].model, "claude-a");
+
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_get_llm_usage_by_sender_attributes_to_latest_message() {
+        let (db, dir) = test_db();
+        let conn = db.lock_conn();
+        for (id, sender, is_bot, ts) in [
+            ("m1", "alice", 0, "2024-01-01T00:00:00+00:00"),
+            ("m2", "microclaw_bot", 1, "2024-01-01T00:01:00+00:00"),
+            ("m3", "bob", 0, "2024-01-01T00:02:00+00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO messages (id, chat_id, sender_name, content, is_from_bot, timestamp)
+                 VALUES (?1, 100, ?2, 'hi', ?3, ?4)",
+                params![id, sender, is_bot, ts],
+            )
+            .unwrap();
+        }
+        for (tokens, ts) in [
+            (10, "2023-12-31T23:59:00+00:00"),
+            (100, "2024-01-01T00:00:30+00:00"),
+            (50, "2024-01-01T00:00:40+00:00"),
+            (7, "2024-01-01T00:01:30+00:00"),
+            (20, "2024-01-01T00:02:30+00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO llm_usage_logs
+                    (chat_id, caller_channel, provider, model, input_tokens, output_tokens,
+                     total_tokens, request_kind, created_at)
+                 VALUES (100, 'telegram', 'anthropic', 'claude', ?1, 0, ?1, 'agent_loop', ?2)",
+                params![tokens, ts],
+            )
+            .unwrap();
+        }
+        drop(conn);
+
+        let rows = db.get_llm_usage_by_sender(100, None, None).unwrap();
+        let summary: Vec<(&str, bool, i64, i64)> = rows
+            .iter()
+            .map(|r| (r.sender_name.as_str(), r.is_bot, r.requests, r.total_tokens))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("alice", false, 2, 150),
+                ("bob", false, 1, 20),
+                ("(unknown)", false, 1, 10),
+                ("microclaw_bot", true, 1, 7),
+            ]
+        );
+
+        let recent = db
+            .get_llm_usage_by_sender(100, Some("2024-01-01T00:02:00+00:00"), Some(5))
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].sender_name, "bob");
+
+        let top = db.get_llm_usage_by_sender(100, None, Some(1)).unwrap();
+        assert_eq!(top.len(), 1);
+
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_get_llm_usage_daily_fills_missing_days() {
+        let (db, dir) = test_db();
+        db.log_llm_usage(100, "telegram", "anthropic", "claude", 10, 5, "agent_loop")
+            .unwrap();
+        db.log_llm_usage(100, "telegram", "anthropic", "claude", 1, 1, "agent_loop")
+            .unwrap();
+        let three_days_ago = (chrono::Utc::now() - chrono::Duration::days(3)).to_rfc3339();
+        db.lock_conn()
+            .execute(
+                "INSERT INTO llm_usage_logs
+                    (chat_id, caller_channel, provider, model, input_tokens, output_tokens,
+                     total_tokens, request_kind, created_at)
+                 VALUES (200, 'telegram', 'anthropic', 'claude', 4, 0, 4, 'agent_loop', ?1)",
+                params![three_days_ago],
+            )
+            .unwrap();
+
+        let days = db.get_llm_usage_daily(None, 5).unwrap();
+        let totals: Vec<i64> = days.iter().map(|d| d.total_tokens).collect();
+        assert_eq!(totals, vec![0, 4, 0, 0, 17]);
+        assert_eq!(days[4].requests, 2);
+        assert_eq!(
+            days[4].day,
+            chrono::Utc::now()
+                .date_naive()
+                .format("%Y-%m-%d")
+                .to_string()
+        );
+
+        let chat = db.get_llm_usage_daily(Some(200), 5).unwrap();
+        assert_eq!(chat[1].total_tokens, 4);
+        assert_eq!(chat[4].total_tokens, 0);
+        assert!(db.get_llm_usage_daily(None, 0).unwrap().is_empty());
+
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_insert_and_get_memories_for_context() {
         let (db, dir) = test_db();
@@ -5030,4 +5899,211 @@ mod tests {
 
         cleanup(&dir);
     }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[test]
+    fn test_storing_message_enqueues_it_for_indexing() {
+        let (db, dir) = test_db();
+        let msg = StoredMessage {
+            id: "m1".into(),
+            chat_id: 100,
+            sender_name: "alice".into(),
+            content: "remember the staging deploy".into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        };
+        // Nothing is queued before an indexer subscribes.
+        db.store_message(&msg).unwrap();
+        let mut rx = db.subscribe_message_index();
+        assert!(rx.try_recv().is_err());
+
+        db.store_message(&msg).unwrap();
+        assert_eq!(rx.try_recv().unwrap().id, "m1");
+
+        // Duplicates skipped by store_message_if_new are not re-queued.
+        assert!(!db.store_message_if_new(&msg).unwrap());
+        assert!(rx.try_recv().is_err());
+        let fresh = StoredMessage {
+            id: "m2".into(),
+            ..msg
+        };
+        assert!(db.store_message_if_new(&fresh).unwrap());
+        assert_eq!(rx.try_recv().unwrap().id, "m2");
+
+        cleanup(&dir);
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[test]
+    fn test_sqlite_vec_knn_messages_filters_by_chat() {
+        let (db, dir) = test_db();
+        db.prepare_vector_index(3).unwrap();
+        let fixtures = [
+            (
+                "m1",
+                100,
+                "we deployed the staging cluster",
+                [1.0, 0.0, 0.0],
+            ),
+            ("m2", 100, "lunch is at noon", [0.0, 1.0, 0.0]),
+            ("m3", 200, "staging deploy failed again", [0.9, 0.1, 0.0]),
+        ];
+        for (id, chat_id, content, vector) in fixtures {
+            db.store_message(&StoredMessage {
+                id: id.into(),
+                chat_id,
+                sender_name: "alice".into(),
+                content: content.into(),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            })
+            .unwrap();
+            db.upsert_message_vec(id, chat_id, &vector).unwrap();
+        }
+
+        let all = db.knn_messages(&[1.0, 0.05, 0.0], 2, None).unwrap();
+        let ids: Vec<&str> = all.iter().map(|(m, _)| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m1", "m3"]);
+
+        let scoped = db.knn_messages(&[1.0, 0.05, 0.0], 2, Some(200)).unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].0.content, "staging deploy failed again");
+
+        // Re-embedding a message replaces its vector instead of duplicating it.
+        db.upsert_message_vec("m2", 100, &[1.0, 0.0, 0.0]).unwrap();
+        let top = db.knn_messages(&[0.0, 1.0, 0.0], 3, Some(100)).unwrap();
+        assert_eq!(top.len(), 2);
+
+        cleanup(&dir);
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[test]
+    fn test_edit_and_delete_keep_message_vectors_in_sync() {
+        let (db, dir) = test_db();
+        db.prepare_vector_index(3).unwrap();
+        for (id, vector) in [("m1", [1.0, 0.0, 0.0]), ("m2", [0.0, 1.0, 0.0])] {
+            db.store_message(&StoredMessage {
+                id: id.into(),
+                chat_id: 100,
+                sender_name: "alice".into(),
+                content: format!("original {id}"),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            })
+            .unwrap();
+            db.upsert_message_vec(id, 100, &vector).unwrap();
+        }
+        let indexed = |db: &Database| -> Vec<String> {
+            db.knn_messages(&[1.0, 1.0, 0.0], 10, Some(100))
+                .unwrap()
+                .into_iter()
+                .map(|(m, _)| m.id)
+                .collect()
+        };
+        let mut rx = db.subscribe_message_index();
+
+        // An edit drops the stale vector and queues the new text for embedding.
+        assert!(db.update_message_content(100, "m1", "edited m1").unwrap());
+        let queued = rx.try_recv().unwrap();
+        assert_eq!(
+            (queued.id.as_str(), queued.content.as_str()),
+            ("m1", "edited m1")
+        );
+        assert_eq!(indexed(&db), vec!["m2"]);
+        assert!(!db.update_message_content(100, "missing", "x").unwrap());
+        assert!(rx.try_recv().is_err());
+
+        // A delete removes the vector and its key along with the message.
+        assert!(db.delete_message_by_id(100, "m2").unwrap());
+        assert!(indexed(&db).is_empty());
+        let keys: i64 = db
+            .lock_conn()
+            .query_row(
+                "SELECT COUNT(*) FROM message_vec_keys WHERE message_id = 'm2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(keys, 0);
+
+        cleanup(&dir);
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[test]
+    fn test_sqlite_vec_knn_messages_searches_within_chat() {
+        let (db, dir) = test_db();
+        db.prepare_vector_index(3).unwrap();
+        let store = |id: &str, chat_id: i64, vector: [f32; 3]| {
+            db.store_message(&StoredMessage {
+                id: id.into(),
+                chat_id,
+                sender_name: "alice".into(),
+                content: format!("message {id}"),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            })
+            .unwrap();
+            db.upsert_message_vec(id, chat_id, &vector).unwrap();
+        };
+        // Far more off-chat messages than any oversampled global top-k,
+        // all closer to the query than the only match in chat 100.
+        for i in 0..200 {
+            store(
+                &format!("other{i}"),
+                200 + i % 5,
+                [1.0, 0.001 * i as f32, 0.0],
+            );
+        }
+        store("mine", 100, [0.2, 1.0, 0.0]);
+
+        let hits = db.knn_messages(&[1.0, 0.0, 0.0], 3, Some(100)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, "mine");
+
+        cleanup(&dir);
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[test]
+    fn test_prepare_vector_index_upgrades_unpartitioned_messages_vec() {
+        let (db, dir) = test_db();
+        db.store_message(&StoredMessage {
+            id: "m1".into(),
+            chat_id: 100,
+            sender_name: "alice".into(),
+            content: "kept across the upgrade".into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        })
+        .unwrap();
+        {
+            let conn = db.lock_conn();
+            conn.execute_batch(
+                "CREATE TABLE message_vec_keys (
+                    vec_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    message_id TEXT NOT NULL,
+                    chat_id INTEGER NOT NULL,
+                    UNIQUE(message_id, chat_id)
+                 );
+                 CREATE VIRTUAL TABLE messages_vec USING vec0(
+                    embedding float[3] distance_metric=cosine
+                 );
+                 INSERT INTO message_vec_keys(vec_id, message_id, chat_id) VALUES(1, 'm1', 100);
+                 INSERT INTO messages_vec(rowid, embedding) VALUES(1, vec_f32('[1.0, 0.0, 0.0]'));
+                 INSERT INTO db_meta(key, value) VALUES('embedding_dim', '3')
+                    ON CONFLICT(key) DO UPDATE SET value = excluded.value;",
+            )
+            .unwrap();
+        }
+
+        db.prepare_vector_index(3).unwrap();
+
+        let hits = db.knn_messages(&[1.0, 0.0, 0.0], 1, Some(100)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, "m1");
+
+        cleanup(&dir);
+    }
 }
@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use chrono::SecondsFormat;
+use serde::Serialize;
 
 use crate::db::{
-    call_blocking, Database, LlmModelUsageSummary, LlmUsageSummary, MemoryObservabilitySummary,
+    call_blocking, Database, LlmDailyUsage, LlmModelUsageSummary, LlmSenderUsageSummary,
+    LlmUsageSummary, MemoryObservabilitySummary,
 };
 
+const SPARKLINE_DAYS: usize = 14;
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
 fn fmt_int(v: i64) -> String {
     let neg = v < 0;
     let mut n = v.unsigned_abs();
@@ -58,6 +63,56 @@ fn format_model_rows(rows: &[LlmModelUsageSummary], max_rows: usize) -> Vec<Stri
         .collect()
 }
 
+/// One-line block sparkline scaled to the largest value. Zero maps to the
+/// lowest block, so an all-zero series renders as a flat baseline.
+pub fn render_sparkline(values: &[i64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if max <= 0 || v <= 0 {
+                return SPARK_BLOCKS[0];
+            }
+            let top = SPARK_BLOCKS.len() - 1;
+            let level = ((v as f64 / max as f64) * top as f64).ceil() as usize;
+            SPARK_BLOCKS[level.clamp(1, top)]
+        })
+        .collect()
+}
+
+fn sparkline_line(days: &[LlmDailyUsage]) -> String {
+    let values: Vec<i64> = days.iter().map(|d| d.total_tokens).collect();
+    let peak = values.iter().copied().max().unwrap_or(0);
+    format!(
+        "  📈 {}d   {}  peak {}/day",
+        days.len(),
+        render_sparkline(&values),
+        fmt_int(peak)
+    )
+}
+
+fn format_sender_rows(rows: &[LlmSenderUsageSummary]) -> Vec<String> {
+    if rows.is_empty() {
+        return vec!["    - (no data)".to_string()];
+    }
+
+    rows.iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            format!(
+                "    {}. {}{}  tok={}  req={}  in {} / out {}",
+                idx + 1,
+                row.sender_name,
+                if row.is_bot { " (bot)" } else { "" },
+                fmt_int(row.total_tokens),
+                fmt_int(row.requests),
+                fmt_int(row.input_tokens),
+                fmt_int(row.output_tokens)
+            )
+        })
+        .collect()
+}
+
 fn block_lines(
     title: &str,
     all: &LlmUsageSummary,
@@ -65,6 +120,7 @@ fn block_lines(
     d7: &LlmUsageSummary,
     models_24h: &[LlmModelUsageSummary],
     models_7d: &[LlmModelUsageSummary],
+    daily: &[LlmDailyUsage],
 ) -> Vec<String> {
     let mut lines = vec![
         title.to_string(),
@@ -72,6 +128,7 @@ fn block_lines(
         format!("  🧮 {}", fmt_summary_line("All-time", all)),
         format!("  🕓 {}", fmt_summary_line("Last 24h", d24)),
         format!("  📆 {}", fmt_summary_line("Last 7d", d7)),
+        sparkline_line(daily),
         "".to_string(),
         "  🤖 Top models (24h)".to_string(),
     ];
@@ -87,9 +144,18 @@ async fn query_summary(
     db: Arc<Database>,
     chat_id: Option<i64>,
     since: Option<String>,
+) -> Result<LlmUsageSummary, String> {
+    query_summary_between(db, chat_id, since, None).await
+}
+
+async fn query_summary_between(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    since: Option<String>,
+    until: Option<String>,
 ) -> Result<LlmUsageSummary, String> {
     call_blocking(db, move |d| {
-        d.get_llm_usage_summary_since(chat_id, since.as_deref())
+        d.get_llm_usage_summary_between(chat_id, since.as_deref(), until.as_deref())
     })
     .await
     .map_err(|e| e.to_string())
@@ -99,9 +165,41 @@ async fn query_by_model(
     db: Arc<Database>,
     chat_id: Option<i64>,
     since: Option<String>,
+) -> Result<Vec<LlmModelUsageSummary>, String> {
+    query_by_model_between(db, chat_id, since, None).await
+}
+
+async fn query_by_model_between(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    since: Option<String>,
+    until: Option<String>,
 ) -> Result<Vec<LlmModelUsageSummary>, String> {
     call_blocking(db, move |d| {
-        d.get_llm_usage_by_model(chat_id, since.as_deref(), None)
+        d.get_llm_usage_by_model_between(chat_id, since.as_deref(), until.as_deref(), None)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn query_daily(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    days: usize,
+) -> Result<Vec<LlmDailyUsage>, String> {
+    call_blocking(db, move |d| d.get_llm_usage_daily(chat_id, days))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn query_by_sender(
+    db: Arc<Database>,
+    chat_id: i64,
+    since: Option<String>,
+    limit: usize,
+) -> Result<Vec<LlmSenderUsageSummary>, String> {
+    call_blocking(db, move |d| {
+        d.get_llm_usage_by_sender(chat_id, since.as_deref(), Some(limit))
     })
     .await
     .map_err(|e| e.to_string())
@@ -116,7 +214,13 @@ async fn query_memory_summary(
         .map_err(|e| e.to_string())
 }
 
-pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<String, String> {
+/// Human-readable usage report for `/usage`. `top_senders` caps the per-sender
+/// section for this chat; 0 hides it.
+pub async fn build_usage_report(
+    db: Arc<Database>,
+    chat_id: i64,
+    top_senders: usize,
+) -> Result<String, String> {
     let now = chrono::Utc::now();
     let since_24h = (now - chrono::Duration::hours(24)).to_rfc3339();
     let since_7d = (now - chrono::Duration::days(7)).to_rfc3339();
@@ -125,7 +229,13 @@ pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<Strin
     let chat_24h = query_summary(db.clone(), Some(chat_id), Some(since_24h.clone())).await?;
     let chat_7d = query_summary(db.clone(), Some(chat_id), Some(since_7d.clone())).await?;
     let chat_models_24h = query_by_model(db.clone(), Some(chat_id), Some(since_24h)).await?;
-    let chat_models_7d = query_by_model(db.clone(), Some(chat_id), Some(since_7d)).await?;
+    let chat_models_7d = query_by_model(db.clone(), Some(chat_id), Some(since_7d.clone())).await?;
+    let chat_daily = query_daily(db.clone(), Some(chat_id), SPARKLINE_DAYS).await?;
+    let chat_senders_7d = if top_senders > 0 {
+        Some(query_by_sender(db.clone(), chat_id, Some(since_7d), top_senders).await?)
+    } else {
+        None
+    };
 
     let global_all = query_summary(db.clone(), None, None).await?;
     let global_24h = query_summary(
@@ -152,6 +262,7 @@ pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<Strin
         Some((now - chrono::Duration::days(7)).to_rfc3339()),
     )
     .await?;
+    let global_daily = query_daily(db.clone(), None, SPARKLINE_DAYS).await?;
     let chat_mem = query_memory_summary(db.clone(), Some(chat_id)).await?;
     let global_mem = query_memory_summary(db.clone(), None).await?;
 
@@ -171,7 +282,13 @@ pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<Strin
         &chat_7d,
         &chat_models_24h,
         &chat_models_7d,
+        &chat_daily,
     ));
+    if let Some(senders) = &chat_senders_7d {
+        lines.push("".to_string());
+        lines.push("  👥 Top senders (7d)".to_string());
+        lines.extend(format_sender_rows(senders));
+    }
 
     lines.push("".to_string());
 
@@ -182,6 +299,7 @@ pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<Strin
         &global_7d,
         &global_models_24h,
         &global_models_7d,
+        &global_daily,
     ));
 
     lines.push("".to_string());
@@ -233,3 +351,494 @@ pub async fn build_usage_report(db: Arc<Database>, chat_id: i64) -> Result<Strin
 
     Ok(lines.join("\n"))
 }
+
+/// A user-chosen `[since, until)` window that replaces the default
+/// all-time / 24h / 7d windows. Either bound may be open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageRange {
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Parse an RFC3339 timestamp or a relative offset into the past such as
+/// `30m`, `12h`, `30d` or `2w`.
+pub fn parse_usage_time(
+    input: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let input = input.trim();
+    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(input) {
+        return Ok(ts.with_timezone(&chrono::Utc));
+    }
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid time '{input}': expected RFC3339 or e.g. 30d"))?;
+    let offset = match unit {
+        "s" => chrono::Duration::try_seconds(amount),
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        "w" => chrono::Duration::try_weeks(amount),
+        _ => {
+            return Err(format!(
+                "invalid time unit in '{input}': use s, m, h, d or w"
+            ))
+        }
+    };
+    offset
+        .and_then(|offset| now.checked_sub_signed(offset))
+        .ok_or_else(|| format!("time '{input}' is too far in the past"))
+}
+
+impl UsageRange {
+    /// Build a range from CLI-style bounds. Returns `None` when neither bound
+    /// is given, so callers fall back to the default windows.
+    pub fn parse(
+        since: Option<&str>,
+        until: Option<&str>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Self>, String> {
+        if since.is_none() && until.is_none() {
+            return Ok(None);
+        }
+        let since = since.map(|v| parse_usage_time(v, now)).transpose()?;
+        let until = until.map(|v| parse_usage_time(v, now)).transpose()?;
+        if let (Some(s), Some(u)) = (since, until) {
+            if s >= u {
+                return Err(format!(
+                    "--since ({}) must be earlier than --until ({})",
+                    s.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    u.to_rfc3339_opts(SecondsFormat::Secs, true)
+                ));
+            }
+        }
+        Ok(Some(Self { since, until }))
+    }
+
+    fn since_rfc3339(&self) -> Option<String> {
+        self.since.map(|t| t.to_rfc3339())
+    }
+
+    fn until_rfc3339(&self) -> Option<String> {
+        self.until.map(|t| t.to_rfc3339())
+    }
+
+    /// Window label such as `2024-01-01T00:00:00Z..now`.
+    pub fn label(&self) -> String {
+        let fmt = |t: Option<chrono::DateTime<chrono::Utc>>, open: &str| {
+            t.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_else(|| open.to_string())
+        };
+        format!("{}..{}", fmt(self.since, "start"), fmt(self.until, "now"))
+    }
+}
+
+/// Text report for a custom range; the default windows are covered by
+/// [`build_usage_report`].
+pub async fn build_usage_report_for_range(
+    db: Arc<Database>,
+    chat_id: i64,
+    range: &UsageRange,
+) -> Result<String, String> {
+    let now = chrono::Utc::now();
+    let mut lines = vec![
+        "📊 Token Usage".to_string(),
+        format!(
+            "🕒 Updated: {}",
+            now.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ),
+        format!("📅 Range: {}", range.label()),
+    ];
+    for (title, scope) in [("🔹 This chat", Some(chat_id)), ("🌍 Global", None)] {
+        let summary = query_summary_between(
+            db.clone(),
+            scope,
+            range.since_rfc3339(),
+            range.until_rfc3339(),
+        )
+        .await?;
+        let models = query_by_model_between(
+            db.clone(),
+            scope,
+            range.since_rfc3339(),
+            range.until_rfc3339(),
+        )
+        .await?;
+        lines.push("".to_string());
+        lines.push(title.to_string());
+        lines.push("".to_string());
+        lines.push(format!("  🧮 {}", fmt_summary_line("Range", &summary)));
+        lines.push("".to_string());
+        lines.push("  🤖 Top models".to_string());
+        lines.extend(format_model_rows(&models, 4));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Token totals for one scope and window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageTotals {
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+}
+
+impl From<&LlmUsageSummary> for UsageTotals {
+    fn from(s: &LlmUsageSummary) -> Self {
+        Self {
+            requests: s.requests,
+            input_tokens: s.input_tokens,
+            output_tokens: s.output_tokens,
+            total_tokens: s.total_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageModelRow {
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageWindowReport {
+    /// `all`, `24h`, `7d`, or `<since>..<until>` for a custom range.
+    pub window: String,
+    pub totals: UsageTotals,
+    pub models: Vec<UsageModelRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageScopeReport {
+    /// `chat:<id>` or `global`.
+    pub scope: String,
+    pub windows: Vec<UsageWindowReport>,
+}
+
+/// Machine-readable counterpart of [`build_usage_report`].
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub generated_at: String,
+    pub scopes: Vec<UsageScopeReport>,
+}
+
+/// `(label, since, until)` for each window in a report.
+fn report_windows(
+    now: chrono::DateTime<chrono::Utc>,
+    range: Option<&UsageRange>,
+) -> Vec<(String, Option<String>, Option<String>)> {
+    match range {
+        Some(range) => vec![(range.label(), range.since_rfc3339(), range.until_rfc3339())],
+        None => vec![
+            ("all".to_string(), None, None),
+            (
+                "24h".to_string(),
+                Some((now - chrono::Duration::hours(24)).to_rfc3339()),
+                None,
+            ),
+            (
+                "7d".to_string(),
+                Some((now - chrono::Duration::days(7)).to_rfc3339()),
+                None,
+            ),
+        ],
+    }
+}
+
+async fn build_scope_report(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    windows: &[(String, Option<String>, Option<String>)],
+) -> Result<UsageScopeReport, String> {
+    let mut out = Vec::with_capacity(windows.len());
+    for (window, since, until) in windows {
+        let totals =
+            query_summary_between(db.clone(), chat_id, since.clone(), until.clone()).await?;
+        let models =
+            query_by_model_between(db.clone(), chat_id, since.clone(), until.clone()).await?;
+        out.push(UsageWindowReport {
+            window: window.clone(),
+            totals: UsageTotals::from(&totals),
+            models: models
+                .into_iter()
+                .map(|row| UsageModelRow {
+                    model: row.model,
+                    totals: UsageTotals {
+                        requests: row.requests,
+                        input_tokens: row.input_tokens,
+                        output_tokens: row.output_tokens,
+                        total_tokens: row.total_tokens,
+                    },
+                })
+                .collect(),
+        });
+    }
+    Ok(UsageScopeReport {
+        scope: match chat_id {
+            Some(id) => format!("chat:{id}"),
+            None => "global".to_string(),
+        },
+        windows: out,
+    })
+}
+
+/// Usage for the same windows as the text report, or for `range` when given.
+/// Includes a chat scope when `chat_id` is given, followed by the global scope.
+pub async fn build_usage_report_json(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    range: Option<&UsageRange>,
+) -> Result<UsageReport, String> {
+    let now = chrono::Utc::now();
+    let windows = report_windows(now, range);
+    let mut scopes = Vec::new();
+    if let Some(id) = chat_id {
+        scopes.push(build_scope_report(db.clone(), Some(id), &windows).await?);
+    }
+    scopes.push(build_scope_report(db, None, &windows).await?);
+    Ok(UsageReport {
+        generated_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        scopes,
+    })
+}
+
+pub const USAGE_CSV_HEADER: &str = "window,scope,model,requests,input,output,total";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(window: &str, scope: &str, model: &str, t: &UsageTotals) -> String {
+    format!(
+        "{},{},{},{},{},{},{}",
+        csv_field(window),
+        csv_field(scope),
+        csv_field(model),
+        t.requests,
+        t.input_tokens,
+        t.output_tokens,
+        t.total_tokens
+    )
+}
+
+/// Flatten a [`UsageReport`] into CSV. Window totals use `*` as the model.
+pub fn usage_report_to_csv(report: &UsageReport) -> String {
+    let mut lines = vec![USAGE_CSV_HEADER.to_string()];
+    for scope in &report.scopes {
+        for window in &scope.windows {
+            lines.push(csv_row(&window.window, &scope.scope, "*", &window.totals));
+            for row in &window.models {
+                lines.push(csv_row(
+                    &window.window,
+                    &scope.scope,
+                    &row.model,
+                    &row.totals,
+                ));
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+pub async fn build_usage_report_csv(
+    db: Arc<Database>,
+    chat_id: Option<i64>,
+    range: Option<&UsageRange>,
+) -> Result<String, String> {
+    let report = build_usage_report_json(db, chat_id, range).await?;
+    Ok(usage_report_to_csv(&report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> (Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("microclaw_usage_{}", uuid::Uuid::new_v4()));
+        let db = Database::new(dir.to_str().unwrap()).unwrap();
+        (Arc::new(db), dir)
+    }
+
+    #[tokio::test]
+    async fn test_build_usage_report_json_has_scopes_windows_and_models() {
+        let (db, dir) = test_db();
+        db.log_llm_usage(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-a",
+            10,
+            5,
+            "agent_loop",
+        )
+        .unwrap();
+        db.log_llm_usage(200, "telegram", "anthropic", "claude-b", 1, 2, "agent_loop")
+            .unwrap();
+
+        let report = build_usage_report_json(db.clone(), Some(100), None)
+            .await
+            .unwrap();
+        let scopes: Vec<&str> = report.scopes.iter().map(|s| s.scope.as_str()).collect();
+        assert_eq!(scopes, vec!["chat:100", "global"]);
+        let chat_all = &report.scopes[0].windows[0];
+        assert_eq!(chat_all.window, "all");
+        assert_eq!(chat_all.totals.total_tokens, 15);
+        assert_eq!(chat_all.models[0].model, "claude-a");
+        assert_eq!(report.scopes[1].windows[0].totals.requests, 2);
+
+        let value = serde_json::to_value(&report).unwrap();
+        assert!(value["generated_at"].is_string());
+        let model = &value["scopes"][0]["windows"][0]["models"][0];
+        assert_eq!(model["model"], "claude-a");
+        assert_eq!(model["input_tokens"], 10);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_build_usage_report_csv_header_and_rows() {
+        let (db, dir) = test_db();
+        db.log_llm_usage(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-a",
+            10,
+            5,
+            "agent_loop",
+        )
+        .unwrap();
+
+        let csv = build_usage_report_csv(db.clone(), None, None)
+            .await
+            .unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(USAGE_CSV_HEADER));
+        assert_eq!(lines.next(), Some("all,global,*,1,10,5,15"));
+        assert_eq!(lines.next(), Some("all,global,claude-a,1,10,5,15"));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_usage_time_relative_and_rfc3339() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-03-31T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            parse_usage_time("30d", now).unwrap(),
+            now - chrono::Duration::days(30)
+        );
+        assert_eq!(
+            parse_usage_time("12h", now).unwrap(),
+            now - chrono::Duration::hours(12)
+        );
+        assert_eq!(
+            parse_usage_time("2024-03-01T00:00:00Z", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2024-03-01T00:00:00+00:00"
+        );
+        assert!(parse_usage_time("30x", now).is_err());
+        assert!(parse_usage_time("d", now).is_err());
+        for huge in ["99999999999w", "9223372036854775807s", "300000000d"] {
+            let err = parse_usage_time(huge, now).unwrap_err();
+            assert!(err.contains("too far in the past"), "{huge}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_usage_range_requires_since_before_until() {
+        let now = chrono::Utc::now();
+        assert_eq!(UsageRange::parse(None, None, now), Ok(None));
+        let range = UsageRange::parse(Some("7d"), Some("1d"), now)
+            .unwrap()
+            .unwrap();
+        assert!(range.since < range.until);
+        let err = UsageRange::parse(Some("1d"), Some("7d"), now).unwrap_err();
+        assert!(err.contains("must be earlier"));
+    }
+
+    #[tokio::test]
+    async fn test_build_usage_report_json_custom_range() {
+        let (db, dir) = test_db();
+        db.log_llm_usage(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-a",
+            10,
+            5,
+            "agent_loop",
+        )
+        .unwrap();
+        let now = chrono::Utc::now();
+
+        let range = UsageRange::parse(Some("1h"), None, now).unwrap().unwrap();
+        let report = build_usage_report_json(db.clone(), None, Some(&range))
+            .await
+            .unwrap();
+        let windows = &report.scopes[0].windows;
+        assert_eq!(windows.len(), 1);
+        assert!(windows[0].window.ends_with("..now"));
+        assert_eq!(windows[0].totals.requests, 1);
+
+        let past = UsageRange::parse(Some("2d"), Some("1d"), now)
+            .unwrap()
+            .unwrap();
+        let report = build_usage_report_json(db.clone(), None, Some(&past))
+            .await
+            .unwrap();
+        assert_eq!(report.scopes[0].windows[0].totals.requests, 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_format_sender_rows_marks_bot() {
+        let rows = vec![
+            LlmSenderUsageSummary {
+                sender_name: "alice".into(),
+                is_bot: false,
+                requests: 2,
+                input_tokens: 1000,
+                output_tokens: 500,
+                total_tokens: 1500,
+            },
+            LlmSenderUsageSummary {
+                sender_name: "microclaw_bot".into(),
+                is_bot: true,
+                requests: 1,
+                input_tokens: 5,
+                output_tokens: 5,
+                total_tokens: 10,
+            },
+        ];
+        let lines = format_sender_rows(&rows);
+        assert_eq!(
+            lines[0],
+            "    1. alice  tok=1,500  req=2  in 1,000 / out 500"
+        );
+        assert!(lines[1].starts_with("    2. microclaw_bot (bot)"));
+        assert_eq!(format_sender_rows(&[]), vec!["    - (no data)".to_string()]);
+    }
+
+    #[test]
+    fn test_render_sparkline_scales_to_max() {
+        assert_eq!(render_sparkline(&[0, 1, 2, 4, 8]), "▁▂▃▅█");
+        assert_eq!(render_sparkline(&[5, 5]), "██");
+        assert_eq!(render_sparkline(&[0, 0, 0]), "▁▁▁");
+        assert_eq!(render_sparkline(&[]), "");
+    }
+}
@@ -14,6 +14,8 @@ pub struct ToolResult {
     pub bytes: usize,
     pub duration_ms: Option<u128>,
     pub error_type: Option<String>,
+    /// File produced by the tool that should be delivered to the chat as an attachment.
+    pub attachment_path: Option<PathBuf>,
 }
 
 impl ToolResult {
@@ -26,6 +28,7 @@ impl ToolResult {
             bytes,
             duration_ms: None,
             error_type: None,
+            attachment_path: None,
         }
     }
 
@@ -38,6 +41,7 @@ impl ToolResult {
             bytes,
             duration_ms: None,
             error_type: Some("tool_error".to_string()),
+            attachment_path: None,
         }
     }
 
@@ -50,6 +54,11 @@ impl ToolResult {
         self.error_type = Some(error_type.into());
         self
     }
+
+    pub fn with_attachment_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.attachment_path = Some(path.into());
+        self
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -93,6 +102,9 @@ pub fn tool_risk(name: &str) -> ToolRisk {
         | "edit_file"
         | "write_memory"
         | "send_message"
+        | "react"
+        | "edit_message"
+        | "fetch_url"
         | "sync_skills"
         | "schedule_task"
         | "pause_scheduled_task"
@@ -228,6 +240,69 @@ pub fn resolve_tool_path(working_dir: &Path, path: &str) -> PathBuf {
     }
 }
 
+/// Canonical form of `path` with symlinks resolved through its longest
+/// existing prefix; components that do not exist yet are appended as-is.
+/// A dangling symlink is an error, since writing through it would create
+/// its target wherever that points.
+fn canonicalize_existing_prefix(path: &Path) -> std::io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        match current.canonicalize() {
+            Ok(real) => {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(real, |acc: PathBuf, part| acc.join(part)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if current.symlink_metadata().is_ok() {
+                    return Err(std::io::Error::other(format!(
+                        "'{}' is a dangling symlink",
+                        current.display()
+                    )));
+                }
+                let (Some(name), Some(parent)) = (current.file_name(), current.parent()) else {
+                    return Err(e);
+                };
+                missing.push(name.to_os_string());
+                current = parent;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Resolve `path` strictly inside `working_dir`: absolute paths and `..`
+/// components are rejected instead of resolved, and so is a path that
+/// leaves the directory through a symlink.
+pub fn resolve_confined_tool_path(working_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    let candidate = Path::new(path);
+    let escapes = candidate.has_root()
+        || candidate.components().any(|c| {
+            matches!(
+                c,
+                std::path::Component::ParentDir | std::path::Component::Prefix(_)
+            )
+        });
+    if escapes {
+        return Err(format!(
+            "Path '{path}' is outside the working directory: use a relative path without '..'"
+        ));
+    }
+    let joined = working_dir.join(candidate);
+    let (root, target) = canonicalize_existing_prefix(working_dir)
+        .and_then(|root| Ok((root, canonicalize_existing_prefix(&joined)?)))
+        .map_err(|e| format!("Cannot resolve path '{path}': {e}"))?;
+    if !target.starts_with(&root) {
+        return Err(format!(
+            "Path '{path}' is outside the working directory: it resolves through a symlink to {}",
+            target.display()
+        ));
+    }
+    Ok(joined)
+}
+
 fn sanitize_channel_segment(channel: &str) -> String {
     let mut out = String::with_capacity(channel.len());
     for c in channel.chars() {
@@ -336,6 +411,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_confined_tool_path_rejects_escapes() {
+        let work = Path::new("/work/chat/telegram/1");
+        assert_eq!(
+            resolve_confined_tool_path(work, "notes/a.txt").unwrap(),
+            work.join("notes/a.txt")
+        );
+        for path in ["/etc/passwd", "../other/a.txt", "notes/../../a.txt"] {
+            let err = resolve_confined_tool_path(work, path).unwrap_err();
+            assert!(err.contains("outside the working directory"), "{path}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_confined_tool_path_rejects_symlink_escape() {
+        let root = std::env::temp_dir().join(format!("mc_confine_{}", uuid::Uuid::new_v4()));
+        let work = root.join("work");
+        let outside = root.join("outside");
+        std::fs::create_dir_all(&work).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, work.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("missing"), work.join("dangling")).unwrap();
+        std::fs::create_dir_all(work.join("inner")).unwrap();
+        std::os::unix::fs::symlink(work.join("inner"), work.join("alias")).unwrap();
+
+        for path in ["link/secret.txt", "link/new/file.txt", "dangling"] {
+            assert!(
+                resolve_confined_tool_path(&work, path).is_err(),
+                "{path} should be rejected"
+            );
+        }
+        assert_eq!(
+            resolve_confined_tool_path(&work, "alias/new.txt").unwrap(),
+            work.join("alias/new.txt")
+        );
+        assert_eq!(
+            resolve_confined_tool_path(&work, "fresh/dir/file.txt").unwrap(),
+            work.join("fresh/dir/file.txt")
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn test_validate_execution_policy_dual_allows_fallback() {
         let ok = validate_execution_policy("bash", SandboxMode::All, false);
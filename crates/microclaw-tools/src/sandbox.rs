@@ -7,6 +7,7 @@ use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 
 use crate::command_runner::{build_command, shell_command};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 fn default_sandbox_mode() -> SandboxMode {
@@ -45,14 +46,14 @@ fn default_sandbox_cap_add() -> Vec<String> {
     Vec::new()
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SandboxMode {
     Off,
     All,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SandboxBackend {
     Auto,
@@ -64,7 +65,7 @@ pub enum SandboxBackend {
 /// - `Hardened`: `--cap-drop ALL --security-opt no-new-privileges` (most restrictive; apt/chown/su will fail)
 /// - `Standard`: Docker default capabilities (apt/chown/su work normally)
 /// - `Privileged`: `--privileged` flag (full host-level access; use for debugging only)
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SecurityProfile {
     Hardened,
@@ -82,7 +83,7 @@ impl std::fmt::Display for SecurityProfile {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
     #[serde(default = "default_sandbox_mode")]
     pub mode: SandboxMode,
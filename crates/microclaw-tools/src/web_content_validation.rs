@@ -1,4 +1,5 @@
 use regex::Regex;
+use schemars::JsonSchema;
 use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Copy)]
@@ -41,7 +42,7 @@ const RULES: &[ValidationRule] = &[
     },
 ];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct WebContentValidationConfig {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
@@ -43,21 +44,21 @@ fn http_client_no_redirect(timeout_secs: u64) -> reqwest::Client {
     client
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WebFetchFeedMode {
     Allowlist,
     Denylist,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WebFetchFeedFormat {
     Lines,
     CsvFirstColumn,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct WebFetchFeedSource {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
@@ -72,7 +73,7 @@ pub struct WebFetchFeedSource {
     pub timeout_secs: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct WebFetchFeedSyncConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -84,7 +85,7 @@ pub struct WebFetchFeedSyncConfig {
     pub sources: Vec<WebFetchFeedSource>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct WebFetchUrlValidationConfig {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
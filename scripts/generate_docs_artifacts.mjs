@@ -113,20 +113,28 @@ function parseConfigDefaults() {
 }
 
 function parseProviderPresets() {
-  const text = read('src/setup.rs');
-  const arrMatch = text.match(/const\s+PROVIDER_PRESETS:\s*&\[ProviderPreset\]\s*=\s*&\[([\s\S]*?)\n\];/);
+  const text = read('src/llm.rs');
+  const arrMatch = text.match(/const\s+PROVIDERS:\s*&\[ProviderSpec\]\s*=\s*&\[([\s\S]*?)\n\];/);
   if (!arrMatch) {
-    throw new Error('Failed to parse PROVIDER_PRESETS');
+    throw new Error('Failed to parse PROVIDERS');
   }
   const body = arrMatch[1];
-  const blockRe = /ProviderPreset\s*\{([\s\S]*?)\n\s*\},/g;
+  // Entries are inline `ProviderSpec { .. }` literals or names of standalone consts.
+  const entryRe = /ProviderSpec\s*\{([\s\S]*?)\n\s*\},|^\s*([A-Z][A-Z0-9_]*),\s*$/gm;
   const rows = [];
   let m;
-  while ((m = blockRe.exec(body)) !== null) {
-    const block = m[1];
+  while ((m = entryRe.exec(body)) !== null) {
+    let block = m[1];
+    if (block === undefined) {
+      const constRe = new RegExp(`const\\s+${m[2]}:\\s*ProviderSpec\\s*=\\s*ProviderSpec\\s*\\{([\\s\\S]*?)\\n\\};`);
+      block = text.match(constRe)?.[1];
+      if (!block) {
+        throw new Error(`Failed to resolve provider const ${m[2]}`);
+      }
+    }
     const id = block.match(/id:\s*"([^"]+)"/)?.[1] ?? '';
     const label = block.match(/label:\s*"([^"]+)"/)?.[1] ?? '';
-    const protocolRaw = block.match(/protocol:\s*ProviderProtocol::([A-Za-z]+)/)?.[1] ?? 'OpenAiCompat';
+    const backend = block.match(/backend:\s*LlmBackend::([A-Za-z]+)/)?.[1] ?? 'OpenAiCompat';
     const base = block.match(/default_base_url:\s*"([^"]*)"/)?.[1] ?? '';
     const modelsRaw = block.match(/models:\s*&\[([\s\S]*?)\]/)?.[1] ?? '';
     const firstModel = modelsRaw.match(/"([^"]+)"/)?.[1] ?? '';
@@ -135,7 +143,7 @@ function parseProviderPresets() {
     rows.push({
       id,
       label,
-      protocol: protocolRaw === 'Anthropic' ? 'native_anthropic' : 'openai_compatible',
+      protocol: backend === 'Anthropic' ? 'native_anthropic' : 'openai_compatible',
       defaultBaseUrl: base || '(provider default)',
       defaultModel: firstModel || '(none)',
     });
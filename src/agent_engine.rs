@@ -1,12 +1,16 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::{info, warn};
+use tracing::{info, warn, Instrument};
 
+use crate::budget::{self, BudgetStatus};
+use crate::config::Config;
 use crate::embedding::EmbeddingProvider;
 use crate::hooks::HookOutcome;
 use crate::run_control;
 use crate::runtime::AppState;
 use crate::tools::ToolAuthContext;
+use microclaw_core::error::MicroClawError;
 use microclaw_core::llm_types::{
     ContentBlock, ImageSource, Message, MessageContent, ResponseContentBlock,
 };
@@ -108,6 +112,22 @@ pub async fn process_with_agent_with_events(
     image_data: Option<(String, String)>,
     event_tx: Option<&UnboundedSender<AgentEvent>>,
 ) -> anyhow::Result<String> {
+    let budget_notice = match budget::check_budget(state, context.chat_id).await {
+        BudgetStatus::Exceeded { message } => {
+            info!(
+                "Usage budget exceeded; skipping LLM call for chat {}",
+                context.chat_id
+            );
+            if let Some(tx) = event_tx {
+                let _ = tx.send(AgentEvent::FinalResponse {
+                    text: message.clone(),
+                });
+            }
+            return Ok(message);
+        }
+        BudgetStatus::Warning { notice } => Some(notice),
+        BudgetStatus::WithinBudget => None,
+    };
     let source_message_id = call_blocking(state.db.clone(), move |db| {
         db.get_recent_messages(context.chat_id, 20)
     })
@@ -122,6 +142,7 @@ pub async fn process_with_agent_with_events(
     });
     let (run_id, cancelled, notify) =
         run_control::register_run(context.caller_channel, context.chat_id, source_message_id).await;
+    let started = std::time::Instant::now();
     let engine = DefaultAgentEngine;
     let result = tokio::select! {
         _ = async {
@@ -135,10 +156,47 @@ pub async fn process_with_agent_with_events(
             }
             Ok(run_control::STOPPED_TEXT.to_string())
         }
-        out = engine.process_with_events(state, context, override_prompt, image_data, event_tx) => out,
+        out = engine
+            .process_with_events(state, context, override_prompt, image_data, event_tx)
+            .instrument(tracing::info_span!(
+                "agent_turn",
+                channel = context.caller_channel,
+                chat_id = context.chat_id
+            )) => out,
     };
     run_control::unregister_run(context.caller_channel, context.chat_id, run_id).await;
-    result
+    crate::metrics::observe_agent_turn(context.caller_channel, started.elapsed());
+    if result.is_err() {
+        crate::metrics::record_error("agent");
+    }
+    match (result, budget_notice) {
+        (Ok(text), Some(notice)) if !text.is_empty() => Ok(format!("{notice}\n\n{text}")),
+        (result, _) => result,
+    }
+}
+
+/// Send a file a tool produced (e.g. a browser screenshot) to the current chat.
+async fn deliver_tool_attachment(
+    state: &AppState,
+    chat_id: i64,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    let routing = microclaw_channels::channel::get_required_chat_routing(
+        &state.channel_registry,
+        state.db.clone(),
+        chat_id,
+    )
+    .await?;
+    let bot_username = state.config.bot_username_for_channel(&routing.channel_name);
+    microclaw_channels::channel::deliver_and_store_bot_attachment(
+        &state.channel_registry,
+        state.db.clone(),
+        &bot_username,
+        chat_id,
+        path,
+        None,
+    )
+    .await
 }
 
 pub fn should_suppress_user_error(err: &anyhow::Error) -> bool {
@@ -502,20 +560,38 @@ pub(crate) async fn process_with_agent_impl(
         return Ok("I didn't receive any message to process.".into());
     }
 
-    // Compact if messages exceed threshold
-    if messages.len() > state.config.max_session_messages {
-        archive_conversation(
+    let live_config = state.live_config.load_full();
+
+    // Archive the older part and compact once the session outgrows its limit.
+    // A failed archive is logged but does not block compaction, or the
+    // session would grow without bound.
+    if let Some(split) = session_archive_split(
+        messages.len(),
+        live_config.max_session_messages,
+        live_config.compact_keep_recent,
+    ) {
+        let archived = archive_conversation(
             &state.config.data_dir,
             context.caller_channel,
             chat_id,
-            &messages,
+            &messages[..split],
         );
+        if archived.is_some() {
+            info!(
+                "Session for chat {} exceeded {} messages; archived {} older messages",
+                chat_id, live_config.max_session_messages, split
+            );
+        } else {
+            warn!(
+                "Archiving chat {chat_id} failed; compacting anyway, so {split} older messages survive only in the summary"
+            );
+        }
         messages = compact_messages(
             state,
             context.caller_channel,
             chat_id,
             &messages,
-            state.config.compact_keep_recent,
+            live_config.compact_keep_recent,
         )
         .await;
     }
@@ -530,12 +606,9 @@ pub(crate) async fn process_with_agent_impl(
     // Agentic tool-use loop
     let mut failed_tools: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
     let mut empty_visible_reply_retry_attempted = false;
-    let effective_model = state
-        .llm_model_overrides
-        .get(context.caller_channel)
-        .cloned()
-        .unwrap_or_else(|| state.config.model.clone());
-    for iteration in 0..state.config.max_tool_iterations {
+    let effective_model =
+        resolve_effective_model(state, &live_config, context.caller_channel, chat_id).await;
+    for iteration in 0..live_config.max_tool_iterations {
         if let Some(tx) = event_tx {
             let _ = tx.send(AgentEvent::Iteration {
                 iteration: iteration + 1,
@@ -591,7 +664,8 @@ pub(crate) async fn process_with_agent_impl(
                     Some(&llm_tx),
                     Some(&effective_model),
                 )
-                .await?;
+                .await
+                .inspect_err(|_| crate::metrics::record_error("llm"))?;
             drop(llm_tx);
             let _ = forward_handle.await;
             response
@@ -604,13 +678,17 @@ pub(crate) async fn process_with_agent_impl(
                     Some(tool_defs.clone()),
                     Some(&effective_model),
                 )
-                .await?
+                .await
+                .inspect_err(|_| crate::metrics::record_error("llm"))?
         };
 
         if let Some(usage) = &response.usage {
+            crate::metrics::record_llm_tokens(usage.input_tokens, usage.output_tokens);
             let channel = context.caller_channel.to_string();
-            let provider = state.config.llm_provider.clone();
-            let model = effective_model.clone();
+            let (provider, model) = match &response.served_by {
+                Some(served) => (served.provider.clone(), served.model.clone()),
+                None => (state.config.llm_provider.clone(), effective_model.clone()),
+            };
             let input_tokens = i64::from(usage.input_tokens);
             let output_tokens = i64::from(usage.output_tokens);
             let _ = call_blocking(state.db.clone(), move |db| {
@@ -839,6 +917,20 @@ pub(crate) async fn process_with_agent_impl(
                             }
                         }
                     }
+                    if !result.is_error {
+                        if let Some(path) = result.attachment_path.clone() {
+                            let note = match deliver_tool_attachment(state, chat_id, &path).await {
+                                Ok(()) => format!(
+                                    "\n(Delivered {} to this chat as an attachment.)",
+                                    path.display()
+                                ),
+                                Err(e) => format!(
+                                    "\n(Automatic attachment delivery failed: {e}. Use send_message with attachment_path if the user needs the file.)"
+                                ),
+                            };
+                            result.content.push_str(&note);
+                        }
+                    }
                     if result.is_error && result.error_type.as_deref() != Some("approval_required")
                     {
                         failed_tools.insert(name.clone());
@@ -855,6 +947,11 @@ pub(crate) async fn process_with_agent_impl(
                             preview
                         );
                     }
+                    crate::metrics::record_tool_invocation(
+                        name,
+                        result.is_error,
+                        result.error_type.as_deref(),
+                    );
                     if let Some(tx) = event_tx {
                         let preview = if result.content.chars().count() > 160 {
                             let clipped = result.content.chars().take(160).collect::<String>();
@@ -950,7 +1047,7 @@ pub(crate) async fn load_messages_from_db(
     chat_type: &str,
     caller_channel: &str,
 ) -> Result<Vec<Message>, anyhow::Error> {
-    let max_history = state.config.max_history_messages;
+    let max_history = state.live_config.load().max_history_messages;
     let history = if chat_type == "group" {
         call_blocking(state.db.clone(), move |db| {
             db.get_messages_since_last_bot_response(chat_id, max_history, max_history)
@@ -1243,9 +1340,10 @@ You have access to the following capabilities:
 - Send messages mid-conversation (`send_message`) — use this to send intermediate updates
 - Schedule tasks (`schedule_task`, `list_scheduled_tasks`, `pause/resume/cancel_scheduled_task`, `get_task_history`)
 - Export chat history to markdown (`export_chat`)
+- Search past messages by meaning (`search_history`, when embeddings are configured)
 - Understand images sent by users (they appear as image content blocks)
 - Delegate self-contained sub-tasks to a parallel agent (`sub_agent`)
-- Activate agent skills (`activate_skill`) for specialized tasks
+- List installed skills (`list_skills`) and activate them (`activate_skill`) for specialized tasks
 - Install skills from repos (`sync_skills`, `clawhub_install`, `clawhub_search`) — use these instead of manually writing SKILL.md files. Skills go in ~/.microclaw/skills/ (or configured skills dir).
 - Plan and track tasks with a todo list (`todo_read`, `todo_write`) — use this to break down complex tasks into steps, track progress, and stay organized
 
@@ -1485,10 +1583,44 @@ pub(crate) fn strip_images_for_session(messages: &mut [Message]) {
     }
 }
 
-/// Archive the full conversation to a markdown file before compaction.
-/// Saved to `<data_dir>/groups/<channel>/<chat_id>/conversations/<timestamp>.md`.
-pub fn archive_conversation(data_dir: &str, channel: &str, chat_id: i64, messages: &[Message]) {
-    let now = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+/// Where to split a session of `len` messages once it exceeds `max_messages`:
+/// everything before the returned index is archived and compacted, the last
+/// `keep_recent` messages stay live. `None` while the session is within bounds
+/// or when nothing would be archived.
+pub(crate) fn session_archive_split(
+    len: usize,
+    max_messages: usize,
+    keep_recent: usize,
+) -> Option<usize> {
+    if len <= max_messages {
+        return None;
+    }
+    let split = len.saturating_sub(keep_recent);
+    (split > 0).then_some(split)
+}
+
+/// First record of a conversation archive; each following line is one message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+    pub channel: String,
+    pub chat_id: i64,
+    /// RFC 3339 timestamp of when the archive was written
+    pub archived_at: String,
+    pub message_count: usize,
+}
+
+/// Archive the full conversation as JSONL before compaction: an
+/// [`ArchiveHeader`] line followed by one message per line, with images
+/// replaced by placeholders.
+/// Saved to `<data_dir>/groups/<channel>/<chat_id>/conversations/<timestamp>.jsonl`.
+/// Returns the archive path, or `None` if it could not be written.
+pub fn archive_conversation(
+    data_dir: &str,
+    channel: &str,
+    chat_id: i64,
+    messages: &[Message],
+) -> Option<std::path::PathBuf> {
+    let now = chrono::Utc::now();
     let channel_dir = if channel.trim().is_empty() {
         "unknown"
     } else {
@@ -1502,26 +1634,64 @@ pub fn archive_conversation(data_dir: &str, channel: &str, chat_id: i64, message
 
     if let Err(e) = std::fs::create_dir_all(&dir) {
         tracing::warn!("Failed to create conversations dir: {e}");
-        return;
+        return None;
     }
 
-    let path = dir.join(format!("{now}.md"));
-    let mut content = String::new();
-    for msg in messages {
-        let role = &msg.role;
-        let text = message_to_text(msg);
-        content.push_str(&format!("## {role}\n\n{text}\n\n---\n\n"));
+    let path = dir.join(format!("{}.jsonl", now.format("%Y%m%d-%H%M%S")));
+    let header = ArchiveHeader {
+        channel: channel_dir.to_string(),
+        chat_id,
+        archived_at: now.to_rfc3339(),
+        message_count: messages.len(),
+    };
+    let mut messages = messages.to_vec();
+    strip_images_for_session(&mut messages);
+
+    match write_archive(&path, &header, &messages) {
+        Ok(()) => {
+            info!(
+                "Archived conversation ({} messages) to {}",
+                messages.len(),
+                path.display()
+            );
+            Some(path)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to archive conversation to {}: {e}", path.display());
+            None
+        }
     }
+}
 
-    if let Err(e) = std::fs::write(&path, &content) {
-        tracing::warn!("Failed to archive conversation to {}: {e}", path.display());
-    } else {
-        info!(
-            "Archived conversation ({} messages) to {}",
-            messages.len(),
-            path.display()
-        );
+fn write_archive(
+    path: &std::path::Path,
+    header: &ArchiveHeader,
+    messages: &[Message],
+) -> std::io::Result<()> {
+    let mut content = serde_json::to_string(header)?;
+    content.push('\n');
+    for msg in messages {
+        content.push_str(&serde_json::to_string(msg)?);
+        content.push('\n');
     }
+    std::fs::write(path, content)
+}
+
+/// Read a JSONL conversation archive back into its header and messages,
+/// e.g. to re-import a session.
+pub fn read_archived_conversation(
+    path: &std::path::Path,
+) -> Result<(ArchiveHeader, Vec<Message>), MicroClawError> {
+    let content = std::fs::read_to_string(path)?;
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header_line = lines.next().ok_or_else(|| {
+        MicroClawError::Config(format!("Empty conversation archive: {}", path.display()))
+    })?;
+    let header: ArchiveHeader = serde_json::from_str(header_line)?;
+    let messages = lines
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Message>, _>>()?;
+    Ok((header, messages))
 }
 
 /// Compact old messages by summarizing them via LLM, keeping recent messages verbatim.
@@ -1562,11 +1732,8 @@ async fn compact_messages(
         role: "user".into(),
         content: MessageContent::Text(format!("{summarize_prompt}\n\n---\n\n{summary_input}")),
     }];
-    let effective_model = state
-        .llm_model_overrides
-        .get(caller_channel)
-        .cloned()
-        .unwrap_or_else(|| state.config.model.clone());
+    let effective_model =
+        resolve_effective_model(state, &state.config, caller_channel, chat_id).await;
 
     let timeout_secs = state.config.compaction_timeout_secs;
     let summary = match tokio::time::timeout(
@@ -1582,9 +1749,12 @@ async fn compact_messages(
     {
         Ok(Ok(response)) => {
             if let Some(usage) = &response.usage {
+                crate::metrics::record_llm_tokens(usage.input_tokens, usage.output_tokens);
                 let channel = caller_channel.to_string();
-                let provider = state.config.llm_provider.clone();
-                let model = effective_model.clone();
+                let (provider, model) = match &response.served_by {
+                    Some(served) => (served.provider.clone(), served.model.clone()),
+                    None => (state.config.llm_provider.clone(), effective_model.clone()),
+                };
                 let input_tokens = i64::from(usage.input_tokens);
                 let output_tokens = i64::from(usage.output_tokens);
                 let _ = call_blocking(state.db.clone(), move |db| {
@@ -1612,6 +1782,7 @@ async fn compact_messages(
                 .join("")
         }
         Ok(Err(e)) => {
+            crate::metrics::record_error("llm");
             tracing::warn!("Compaction summarization failed: {e}, falling back to truncation");
             return recent_messages.to_vec();
         }
@@ -1663,11 +1834,33 @@ async fn compact_messages(
     compacted
 }
 
+/// Model for a chat: its `/model` override, then the channel's configured
+/// model, then the global model.
+pub async fn resolve_effective_model(
+    state: &AppState,
+    config: &Config,
+    caller_channel: &str,
+    chat_id: i64,
+) -> String {
+    if let Ok(Some(model)) = call_blocking(state.db.clone(), move |db| {
+        db.get_chat_model_override(chat_id)
+    })
+    .await
+    {
+        return model;
+    }
+    state
+        .llm_model_overrides
+        .get(caller_channel)
+        .cloned()
+        .unwrap_or_else(|| config.model.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        build_db_memory_context, history_to_claude_messages, process_with_agent,
-        AgentRequestContext,
+        archive_conversation, build_db_memory_context, history_to_claude_messages,
+        process_with_agent, read_archived_conversation, session_archive_split, AgentRequestContext,
     };
     use crate::config::{Config, WorkingDirIsolation};
     use crate::llm::LlmProvider;
@@ -1701,6 +1894,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".to_string()),
                 usage: None,
+                served_by: None,
             })
         }
     }
@@ -1725,6 +1919,7 @@ mod tests {
                     }],
                     stop_reason: Some("end_turn".to_string()),
                     usage: None,
+                    served_by: None,
                 });
             }
             let saw_guard = messages.iter().any(|m| match &m.content {
@@ -1742,6 +1937,7 @@ mod tests {
                 content: vec![ResponseContentBlock::Text { text }],
                 stop_reason: Some("end_turn".to_string()),
                 usage: None,
+                served_by: None,
             })
         }
     }
@@ -1772,6 +1968,7 @@ mod tests {
         let channel_registry = Arc::new(registry);
         Arc::new(AppState {
             config: cfg.clone(),
+            live_config: Arc::new(arc_swap::ArcSwap::from_pointee(cfg.clone())),
             channel_registry: channel_registry.clone(),
             db: db.clone(),
             memory: MemoryManager::new(runtime_dir.to_str().unwrap()),
@@ -1782,6 +1979,9 @@ mod tests {
             embedding: None,
             memory_backend: memory_backend.clone(),
             tools: ToolRegistry::new(&cfg, channel_registry, db, memory_backend),
+            shutdown: tokio_util::sync::CancellationToken::new(),
+            tasks: tokio_util::task::TaskTracker::new(),
+            rate_limiter: crate::rate_limit::SenderRateLimiter::new(),
         })
     }
 
@@ -2240,4 +2440,69 @@ timeout_ms: 1000
 
         let _ = std::fs::remove_dir_all(&base_dir);
     }
+
+    #[test]
+    fn test_archive_conversation_roundtrip() {
+        use microclaw_core::llm_types::{ContentBlock, ImageSource, MessageContent};
+
+        let base_dir =
+            std::env::temp_dir().join(format!("microclaw_archive_test_{}", uuid::Uuid::new_v4()));
+        let messages = vec![
+            Message {
+                role: "user".into(),
+                content: MessageContent::Blocks(vec![
+                    ContentBlock::Image {
+                        source: ImageSource {
+                            source_type: "base64".into(),
+                            media_type: "image/png".into(),
+                            data: "AAAA".into(),
+                        },
+                    },
+                    ContentBlock::Text {
+                        text: "what is this?".into(),
+                    },
+                ]),
+            },
+            Message {
+                role: "assistant".into(),
+                content: MessageContent::Text("a cat".into()),
+            },
+        ];
+
+        let path =
+            archive_conversation(base_dir.to_str().unwrap(), "matrix", 42, &messages).unwrap();
+        assert_eq!(path.extension().unwrap(), "jsonl");
+        assert!(path.starts_with(base_dir.join("groups").join("matrix").join("42")));
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(raw.lines().count(), 3);
+
+        let (header, restored) = read_archived_conversation(&path).unwrap();
+        assert_eq!(header.channel, "matrix");
+        assert_eq!(header.chat_id, 42);
+        assert_eq!(header.message_count, 2);
+        assert!(chrono::DateTime::parse_from_rfc3339(&header.archived_at).is_ok());
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1].role, "assistant");
+        assert!(matches!(&restored[1].content, MessageContent::Text(t) if t == "a cat"));
+        let MessageContent::Blocks(blocks) = &restored[0].content else {
+            panic!("expected blocks");
+        };
+        assert!(matches!(&blocks[0], ContentBlock::Text { text } if text == "[image was sent]"));
+        assert!(!raw.contains("AAAA"));
+
+        let _ = std::fs::remove_dir_all(&base_dir);
+    }
+
+    #[test]
+    fn test_session_archive_split_boundaries() {
+        // Within the limit: nothing to archive
+        assert_eq!(session_archive_split(40, 40, 20), None);
+        assert_eq!(session_archive_split(0, 40, 20), None);
+        // One past the limit archives everything but the recent window
+        assert_eq!(session_archive_split(41, 40, 20), Some(21));
+        assert_eq!(session_archive_split(100, 40, 20), Some(80));
+        // Keeping more than the session holds leaves nothing to archive
+        assert_eq!(session_archive_split(41, 40, 50), None);
+        assert_eq!(session_archive_split(5, 4, 0), Some(5));
+    }
 }
@@ -0,0 +1,283 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use microclaw_core::error::MicroClawError;
+use microclaw_storage::db::{call_blocking, Database};
+use tracing::warn;
+
+use crate::config::{BudgetPeriod, BudgetScope, Config, UsageBudget};
+use crate::runtime::AppState;
+
+/// Tokens and estimated cost spent in the current budget period.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetStatus {
+    WithinBudget,
+    /// Past the warning threshold; the notice is prepended to the reply.
+    Warning {
+        notice: String,
+    },
+    /// At or over a limit; the message replaces the LLM call entirely.
+    Exceeded {
+        message: String,
+    },
+}
+
+fn period_name(period: BudgetPeriod) -> &'static str {
+    match period {
+        BudgetPeriod::Daily => "daily",
+        BudgetPeriod::Weekly => "weekly",
+        BudgetPeriod::Monthly => "monthly",
+    }
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+}
+
+/// Start of the calendar period containing `now` (UTC; weeks start Monday).
+pub fn period_start(period: BudgetPeriod, now: DateTime<Utc>) -> DateTime<Utc> {
+    let today = now.date_naive();
+    match period {
+        BudgetPeriod::Daily => midnight(today),
+        BudgetPeriod::Weekly => {
+            midnight(today - Duration::days(today.weekday().num_days_from_monday() as i64))
+        }
+        BudgetPeriod::Monthly => midnight(today.with_day(1).expect("day 1 exists")),
+    }
+}
+
+/// Start of the period after the one containing `now`.
+pub fn period_end(period: BudgetPeriod, now: DateTime<Utc>) -> DateTime<Utc> {
+    let start = period_start(period, now);
+    match period {
+        BudgetPeriod::Daily => start + Duration::days(1),
+        BudgetPeriod::Weekly => start + Duration::weeks(1),
+        BudgetPeriod::Monthly => {
+            let date = start.date_naive();
+            let (year, month) = if date.month() == 12 {
+                (date.year() + 1, 1)
+            } else {
+                (date.year(), date.month() + 1)
+            };
+            midnight(NaiveDate::from_ymd_opt(year, month, 1).expect("valid month start"))
+        }
+    }
+}
+
+/// Compare `usage` against the configured limits. The most-consumed limit
+/// decides the outcome.
+pub fn evaluate_budget(
+    budget: &UsageBudget,
+    usage: BudgetUsage,
+    now: DateTime<Utc>,
+) -> BudgetStatus {
+    let mut ratios = Vec::new();
+    if let Some(max) = budget.max_tokens.filter(|v| *v > 0) {
+        ratios.push((
+            usage.tokens as f64 / max as f64,
+            format!("{} / {} tokens", usage.tokens, max),
+        ));
+    }
+    if let Some(max) = budget.max_cost_usd.filter(|v| *v > 0.0) {
+        ratios.push((
+            usage.cost_usd / max,
+            format!("${:.2} / ${:.2}", usage.cost_usd, max),
+        ));
+    }
+    let Some((ratio, detail)) = ratios.into_iter().max_by(|a, b| a.0.total_cmp(&b.0)) else {
+        return BudgetStatus::WithinBudget;
+    };
+
+    let period = period_name(budget.period);
+    if ratio >= 1.0 {
+        let resets = period_end(budget.period, now).format("%Y-%m-%d %H:%M UTC");
+        BudgetStatus::Exceeded {
+            message: format!(
+                "⚠️ The {period} usage budget has been reached ({detail}). I'll be able to reply again after it resets at {resets}."
+            ),
+        }
+    } else if ratio >= budget.warn_ratio {
+        BudgetStatus::Warning {
+            notice: format!(
+                "⚠️ {:.0}% of the {period} usage budget used ({detail}).",
+                ratio * 100.0
+            ),
+        }
+    } else {
+        BudgetStatus::WithinBudget
+    }
+}
+
+/// Usage counted against `budget` for `chat_id` since the current period began.
+pub fn current_usage(
+    db: &Database,
+    config: &Config,
+    budget: &UsageBudget,
+    chat_id: i64,
+    now: DateTime<Utc>,
+) -> Result<BudgetUsage, MicroClawError> {
+    let scope = match budget.scope {
+        BudgetScope::Global => None,
+        BudgetScope::PerChat => Some(chat_id),
+    };
+    let since = period_start(budget.period, now).to_rfc3339();
+    let rows = db.get_llm_usage_by_model_between(scope, Some(&since), None, None)?;
+    Ok(rows
+        .iter()
+        .fold(BudgetUsage::default(), |acc, row| BudgetUsage {
+            tokens: acc.tokens + row.total_tokens,
+            cost_usd: acc.cost_usd
+                + config
+                    .estimate_cost_usd(&row.model, row.input_tokens, row.output_tokens)
+                    .unwrap_or(0.0),
+        }))
+}
+
+/// Budget check for the agent path. Lookup failures are logged and treated as
+/// within budget so a storage hiccup never blocks replies.
+pub async fn check_budget(state: &AppState, chat_id: i64) -> BudgetStatus {
+    let Some(budget) = state.config.usage_budget.clone() else {
+        return BudgetStatus::WithinBudget;
+    };
+    let now = Utc::now();
+    let config = state.config.clone();
+    let budget_for_query = budget.clone();
+    match call_blocking(state.db.clone(), move |db| {
+        current_usage(db, &config, &budget_for_query, chat_id, now)
+    })
+    .await
+    {
+        Ok(usage) => evaluate_budget(&budget, usage, now),
+        Err(e) => {
+            warn!("Usage budget check failed for chat {chat_id}: {e}");
+            BudgetStatus::WithinBudget
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ModelPrice;
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn budget(max_tokens: Option<i64>, max_cost_usd: Option<f64>) -> UsageBudget {
+        UsageBudget {
+            period: BudgetPeriod::Monthly,
+            scope: BudgetScope::Global,
+            max_tokens,
+            max_cost_usd,
+            warn_ratio: 0.8,
+        }
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let now = at("2024-12-18T15:30:00Z");
+        assert_eq!(
+            period_start(BudgetPeriod::Daily, now),
+            at("2024-12-18T00:00:00Z")
+        );
+        assert_eq!(
+            period_start(BudgetPeriod::Weekly, now),
+            at("2024-12-16T00:00:00Z")
+        );
+        assert_eq!(
+            period_start(BudgetPeriod::Monthly, now),
+            at("2024-12-01T00:00:00Z")
+        );
+        assert_eq!(
+            period_end(BudgetPeriod::Monthly, now),
+            at("2025-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_evaluate_budget_under_warn_and_over() {
+        let now = at("2024-12-18T15:30:00Z");
+        let b = budget(Some(1000), None);
+        let usage = |tokens| BudgetUsage {
+            tokens,
+            cost_usd: 0.0,
+        };
+        assert_eq!(
+            evaluate_budget(&b, usage(100), now),
+            BudgetStatus::WithinBudget
+        );
+        assert!(matches!(
+            evaluate_budget(&b, usage(850), now),
+            BudgetStatus::Warning { notice } if notice.contains("85%")
+        ));
+        assert!(matches!(
+            evaluate_budget(&b, usage(1000), now),
+            BudgetStatus::Exceeded { message } if message.contains("2025-01-01")
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_budget_uses_most_consumed_limit() {
+        let now = at("2024-12-18T15:30:00Z");
+        let b = budget(Some(1_000_000), Some(1.0));
+        let usage = BudgetUsage {
+            tokens: 10,
+            cost_usd: 1.5,
+        };
+        assert!(matches!(
+            evaluate_budget(&b, usage, now),
+            BudgetStatus::Exceeded { message } if message.contains("$1.50 / $1.00")
+        ));
+    }
+
+    #[test]
+    fn test_current_usage_from_fixture_rows() {
+        let dir = std::env::temp_dir().join(format!("microclaw_budget_{}", uuid::Uuid::new_v4()));
+        let db = Database::new(dir.to_str().unwrap()).unwrap();
+        db.log_llm_usage(
+            1,
+            "telegram",
+            "anthropic",
+            "m",
+            600_000,
+            400_000,
+            "agent_loop",
+        )
+        .unwrap();
+        db.log_llm_usage(2, "telegram", "anthropic", "m", 100, 100, "agent_loop")
+            .unwrap();
+
+        let mut config = Config::test_defaults();
+        config.model_prices = vec![ModelPrice {
+            model: "m".into(),
+            input_per_million_usd: 1.0,
+            output_per_million_usd: 5.0,
+        }];
+        let now = Utc::now();
+
+        let mut b = budget(Some(1_000_000), Some(10.0));
+        let global = current_usage(&db, &config, &b, 2, now).unwrap();
+        assert_eq!(global.tokens, 1_000_200);
+        assert!((global.cost_usd - 2.6006).abs() < 1e-9);
+        assert!(matches!(
+            evaluate_budget(&b, global, now),
+            BudgetStatus::Exceeded { .. }
+        ));
+
+        b.scope = BudgetScope::PerChat;
+        let chat = current_usage(&db, &config, &b, 2, now).unwrap();
+        assert_eq!(chat.tokens, 200);
+        assert_eq!(evaluate_budget(&b, chat, now), BudgetStatus::WithinBudget);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use axum::http::HeaderMap;
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
@@ -13,6 +14,7 @@ use crate::channels::startup_guard::{
     should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
@@ -76,7 +78,7 @@ fn default_webhook_path() -> String {
     "/dingtalk/events".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct DingTalkAccountConfig {
     #[serde(default)]
     pub robot_webhook_url: String,
@@ -92,7 +94,7 @@ pub struct DingTalkAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct DingTalkChannelConfig {
     #[serde(default)]
     pub robot_webhook_url: String,
@@ -290,6 +292,7 @@ impl ChannelAdapter for DingTalkAdapter {
 pub async fn start_dingtalk_bot(_app_state: Arc<AppState>, runtime: DingTalkRuntimeContext) {
     mark_channel_started(&runtime.channel_name);
     info!("DingTalk adapter '{}' is ready", runtime.channel_name);
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
 
 pub fn register_dingtalk_webhook(router: Router, app_state: Arc<AppState>) -> Router {
@@ -421,6 +424,7 @@ async fn process_dingtalk_webhook_message(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_message_id) {
         return;
     }
+    crate::metrics::record_message_received(&runtime_ctx.channel_name);
     if is_slash_command(&text) {
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime_ctx.channel_name, &text).await
@@ -461,6 +465,17 @@ async fn process_dingtalk_webhook_message(
         );
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime_ctx.channel_name,
+        chat_id,
+        &payload.sender_id,
+        &external_chat_id,
+    )
+    .await
+    {
+        return;
+    }
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
     match process_with_agent_with_events(
         &app_state,
@@ -3,12 +3,18 @@ use std::error::Error;
 use std::path::Path;
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::json;
 use serenity::async_trait;
+use serenity::builder::{
+    CreateCommand, CreateInteractionResponse, CreateInteractionResponseFollowup,
+    CreateInteractionResponseMessage,
+};
+use serenity::model::application::{Command, CommandInteraction, Interaction};
 use serenity::model::channel::Message as DiscordMessage;
 use serenity::model::gateway::Ready;
-use serenity::model::id::ChannelId;
+use serenity::model::id::{ChannelId, GuildId};
 use serenity::prelude::*;
 use tracing::{error, info, warn};
 
@@ -21,14 +27,15 @@ use crate::channels::startup_guard::{
 };
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
-use microclaw_core::text::{floor_char_boundary, split_text};
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
+use microclaw_core::text::floor_char_boundary;
 use microclaw_storage::db::call_blocking;
 use microclaw_storage::db::StoredMessage;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct DiscordAccountConfig {
     pub bot_token: String,
     #[serde(default)]
@@ -47,7 +54,7 @@ fn default_enabled() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct DiscordChannelConfig {
     #[serde(default)]
     pub bot_token: String,
@@ -234,6 +241,16 @@ impl ChannelAdapter for DiscordAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            reactions: true,
+            edits: true,
+            max_message_len: 2000,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![("discord", ConversationKind::Private)]
     }
@@ -245,7 +262,7 @@ impl ChannelAdapter for DiscordAdapter {
 
         let url = format!("https://discord.com/api/v10/channels/{discord_chat_id}/messages");
 
-        for chunk in split_text(text, 2000) {
+        for chunk in self.capabilities().split_text(text) {
             let body = json!({ "content": chunk });
             let resp = self
                 .http_client
@@ -326,6 +343,150 @@ impl ChannelAdapter for DiscordAdapter {
             None => format!("[attachment:{}]", file_path.display()),
         })
     }
+
+    async fn send_reaction(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), String> {
+        let discord_chat_id = external_chat_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord external_chat_id '{}'", external_chat_id))?;
+        let discord_message_id = message_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord message id '{message_id}'"))?;
+        let url = format!(
+            "https://discord.com/api/v10/channels/{discord_chat_id}/messages/{discord_message_id}/reactions/{}/@me",
+            urlencoding::encode(emoji)
+        );
+        let resp = self
+            .http_client
+            .put(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bot {}", self.token),
+            )
+            .header(reqwest::header::CONTENT_LENGTH, "0")
+            .send()
+            .await
+            .map_err(|e| format_reqwest_error("Failed to send Discord reaction", &e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!(
+                "Failed to send Discord reaction: HTTP {status} {}",
+                body.chars().take(300).collect::<String>()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn send_text_with_id(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        if text.chars().count() > 2000 {
+            self.send_text(external_chat_id, text).await?;
+            return Ok(None);
+        }
+        let discord_chat_id = external_chat_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord external_chat_id '{}'", external_chat_id))?;
+        let url = format!("https://discord.com/api/v10/channels/{discord_chat_id}/messages");
+        let resp = self
+            .http_client
+            .post(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bot {}", self.token),
+            )
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .json(&json!({ "content": text }))
+            .send()
+            .await
+            .map_err(|e| format_reqwest_error("Failed to send Discord message", &e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!(
+                "Failed to send Discord message: HTTP {status} {}",
+                body.chars().take(300).collect::<String>()
+            ));
+        }
+        let sent: serde_json::Value = resp.json().await.unwrap_or_default();
+        Ok(sent.get("id").and_then(|v| v.as_str()).map(str::to_string))
+    }
+
+    async fn edit_text(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), String> {
+        let discord_chat_id = external_chat_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord external_chat_id '{}'", external_chat_id))?;
+        let discord_message_id = message_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord message id '{message_id}'"))?;
+        let url = format!(
+            "https://discord.com/api/v10/channels/{discord_chat_id}/messages/{discord_message_id}"
+        );
+        let resp = self
+            .http_client
+            .patch(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bot {}", self.token),
+            )
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .json(&json!({ "content": text }))
+            .send()
+            .await
+            .map_err(|e| format_reqwest_error("Failed to edit Discord message", &e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!(
+                "Failed to edit Discord message: HTTP {status} {}",
+                body.chars().take(300).collect::<String>()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn delete_message(&self, external_chat_id: &str, message_id: &str) -> Result<(), String> {
+        let discord_chat_id = external_chat_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord external_chat_id '{}'", external_chat_id))?;
+        let discord_message_id = message_id
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid Discord message id '{message_id}'"))?;
+        let url = format!(
+            "https://discord.com/api/v10/channels/{discord_chat_id}/messages/{discord_message_id}"
+        );
+        let resp = self
+            .http_client
+            .delete(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bot {}", self.token),
+            )
+            .send()
+            .await
+            .map_err(|e| format_reqwest_error("Failed to delete Discord message", &e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!(
+                "Failed to delete Discord message: HTTP {status} {}",
+                body.chars().take(300).collect::<String>()
+            ));
+        }
+        Ok(())
+    }
 }
 
 struct Handler {
@@ -352,30 +513,11 @@ impl EventHandler for Handler {
 
         let text = msg.content.clone();
         let external_channel_id = msg.channel_id.get();
-        let channel_id = {
-            let external_chat_id = external_channel_id.to_string();
-            let chat_type = "discord".to_string();
-            let title = format!("discord-{external_channel_id}");
-            let channel_name = self.runtime.channel_name.clone();
-            call_blocking(self.app_state.db.clone(), move |db| {
-                db.resolve_or_create_chat_id(
-                    &channel_name,
-                    &external_chat_id,
-                    Some(&title),
-                    &chat_type,
-                )
-            })
-            .await
-            .unwrap_or(external_channel_id as i64)
-        };
-        let sender_name = msg.author.name.clone();
-
-        // Check allowed channels (empty = all)
-        if !self.runtime.allowed_channels.is_empty()
-            && !self.runtime.allowed_channels.contains(&external_channel_id)
-        {
+        if !is_discord_channel_allowed(&self.runtime.allowed_channels, external_channel_id) {
             return;
         }
+        let channel_id = self.resolve_chat_id(external_channel_id).await;
+        let sender_name = msg.author.name.clone();
 
         let should_respond = if msg.guild_id.is_some() {
             if self.runtime.no_mention {
@@ -402,6 +544,7 @@ impl EventHandler for Handler {
             return;
         }
 
+        crate::metrics::record_message_received(&self.runtime.channel_name);
         if is_slash_command(&text) {
             if !should_respond && !self.app_state.config.allow_group_slash_without_mention {
                 return;
@@ -476,6 +619,17 @@ impl EventHandler for Handler {
         if !should_respond {
             return;
         }
+        if !allow_sender_message(
+            &self.app_state,
+            &self.runtime.channel_name,
+            channel_id,
+            &msg.author.id.get().to_string(),
+            &external_channel_id.to_string(),
+        )
+        .await
+        {
+            return;
+        }
 
         info!(
             "Discord message from {} in channel {}: {}",
@@ -569,37 +723,227 @@ impl EventHandler for Handler {
         }
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!("Discord bot connected as {}", ready.user.name);
+        crate::health::report_channel_connected(&self.runtime.channel_name, true);
+        register_discord_slash_commands(&ctx, &self.runtime.allowed_channels).await;
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::Command(command) = interaction {
+            self.handle_slash_interaction(&ctx, &command).await;
+        }
     }
 }
 
-/// Split and send long messages (Discord limit is 2000 chars).
-async fn send_discord_response(ctx: &Context, channel_id: ChannelId, text: &str) {
-    const MAX_LEN: usize = 2000;
+impl Handler {
+    async fn resolve_chat_id(&self, external_channel_id: u64) -> i64 {
+        let external_chat_id = external_channel_id.to_string();
+        let chat_type = "discord".to_string();
+        let title = format!("discord-{external_channel_id}");
+        let channel_name = self.runtime.channel_name.clone();
+        call_blocking(self.app_state.db.clone(), move |db| {
+            db.resolve_or_create_chat_id(&channel_name, &external_chat_id, Some(&title), &chat_type)
+        })
+        .await
+        .unwrap_or(external_channel_id as i64)
+    }
+
+    async fn handle_slash_interaction(&self, ctx: &Context, command: &CommandInteraction) {
+        let Some(slash) = DiscordSlashCommand::from_name(&command.data.name) else {
+            return;
+        };
+        let external_channel_id = command.channel_id.get();
+        if !is_discord_channel_allowed(&self.runtime.allowed_channels, external_channel_id) {
+            let response = CreateInteractionResponseMessage::new()
+                .content("This channel is not enabled for the bot.")
+                .ephemeral(true);
+            let _ = command
+                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+                .await;
+            return;
+        }
+
+        // Usage reports can take a moment; acknowledge first and deliver as follow-ups.
+        if slash.deferred() {
+            let _ = command
+                .create_response(
+                    &ctx.http,
+                    CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()),
+                )
+                .await;
+        }
+
+        let chat_id = self.resolve_chat_id(external_channel_id).await;
+        let reply = handle_chat_command(
+            &self.app_state,
+            chat_id,
+            &self.runtime.channel_name,
+            slash.command_text(),
+        )
+        .await
+        .unwrap_or_else(unknown_command_response);
+
+        let mut chunks = split_discord_message(&reply).into_iter();
+        if !slash.deferred() {
+            let first = chunks.next().unwrap_or_default();
+            let response = CreateInteractionResponseMessage::new()
+                .content(first)
+                .ephemeral(slash.ephemeral());
+            if let Err(e) = command
+                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+                .await
+            {
+                warn!("Discord: failed to respond to /{}: {e}", slash.name());
+                return;
+            }
+        }
+        for chunk in chunks {
+            let followup = CreateInteractionResponseFollowup::new()
+                .content(chunk)
+                .ephemeral(slash.ephemeral());
+            if let Err(e) = command.create_followup(&ctx.http, followup).await {
+                warn!("Discord: failed to send /{} follow-up: {e}", slash.name());
+                return;
+            }
+        }
+    }
+}
+
+/// Application commands registered with Discord; each maps onto the shared chat command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiscordSlashCommand {
+    Reset,
+    Skills,
+    Usage,
+}
+
+impl DiscordSlashCommand {
+    const ALL: [DiscordSlashCommand; 3] = [Self::Reset, Self::Skills, Self::Usage];
 
-    if text.len() <= MAX_LEN {
-        let _ = channel_id.say(&ctx.http, text).await;
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Reset => "reset",
+            Self::Skills => "skills",
+            Self::Usage => "usage",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Reset => "Clear the conversation context for this channel",
+            Self::Skills => "List available skills",
+            Self::Usage => "Show token usage for this channel",
+        }
+    }
+
+    fn command_text(self) -> &'static str {
+        match self {
+            Self::Reset => "/reset",
+            Self::Skills => "/skills",
+            Self::Usage => "/usage",
+        }
+    }
+
+    fn ephemeral(self) -> bool {
+        matches!(self, Self::Reset)
+    }
+
+    fn deferred(self) -> bool {
+        matches!(self, Self::Usage)
+    }
+}
+
+fn discord_slash_command_definitions() -> Vec<CreateCommand> {
+    DiscordSlashCommand::ALL
+        .into_iter()
+        .map(|c| CreateCommand::new(c.name()).description(c.description()))
+        .collect()
+}
+
+/// Registers slash commands per guild when the allowed channels resolve to guilds,
+/// otherwise globally.
+async fn register_discord_slash_commands(ctx: &Context, allowed_channels: &[u64]) {
+    let mut guild_ids: Vec<GuildId> = Vec::new();
+    for channel_id in allowed_channels {
+        match ChannelId::new(*channel_id).to_channel(&ctx.http).await {
+            Ok(channel) => {
+                if let Some(guild_channel) = channel.guild() {
+                    if !guild_ids.contains(&guild_channel.guild_id) {
+                        guild_ids.push(guild_channel.guild_id);
+                    }
+                }
+            }
+            Err(e) => warn!("Discord: could not resolve allowed channel {channel_id}: {e}"),
+        }
+    }
+
+    if guild_ids.is_empty() {
+        match Command::set_global_commands(&ctx.http, discord_slash_command_definitions()).await {
+            Ok(commands) => info!(
+                "Discord: registered {} global slash commands",
+                commands.len()
+            ),
+            Err(e) => warn!("Discord: failed to register global slash commands: {e}"),
+        }
         return;
     }
+    for guild_id in guild_ids {
+        match guild_id
+            .set_commands(&ctx.http, discord_slash_command_definitions())
+            .await
+        {
+            Ok(commands) => info!(
+                "Discord: registered {} slash commands in guild {}",
+                commands.len(),
+                guild_id
+            ),
+            Err(e) => warn!("Discord: failed to register slash commands in guild {guild_id}: {e}"),
+        }
+    }
+}
 
+/// Allowed-channel filter (empty list = all channels).
+fn is_discord_channel_allowed(allowed_channels: &[u64], channel_id: u64) -> bool {
+    allowed_channels.is_empty() || allowed_channels.contains(&channel_id)
+}
+
+const DISCORD_MAX_MESSAGE_LEN: usize = 2000;
+
+/// Split text into Discord-sized chunks, preferring newline boundaries.
+fn split_discord_message(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
     let mut remaining = text;
     while !remaining.is_empty() {
-        let chunk_len = if remaining.len() <= MAX_LEN {
+        let chunk_len = if remaining.len() <= DISCORD_MAX_MESSAGE_LEN {
             remaining.len()
         } else {
-            let boundary = floor_char_boundary(remaining, MAX_LEN.min(remaining.len()));
-            remaining[..boundary].rfind('\n').unwrap_or(boundary)
+            let boundary = floor_char_boundary(remaining, DISCORD_MAX_MESSAGE_LEN);
+            match remaining[..boundary].rfind('\n') {
+                Some(pos) if pos > 0 => pos,
+                _ => boundary,
+            }
         };
 
-        let chunk = &remaining[..chunk_len];
-        let _ = channel_id.say(&ctx.http, chunk).await;
+        chunks.push(&remaining[..chunk_len]);
         remaining = &remaining[chunk_len..];
 
         if remaining.starts_with('\n') {
             remaining = &remaining[1..];
         }
     }
+    chunks
+}
+
+/// Split and send long messages (Discord limit is 2000 chars).
+async fn send_discord_response(ctx: &Context, channel_id: ChannelId, text: &str) {
+    for chunk in split_discord_message(text) {
+        let _ = channel_id.say(&ctx.http, chunk).await;
+    }
 }
 
 async fn run_discord_client(
@@ -629,6 +973,7 @@ pub async fn start_discord_bot(
     token: &str,
 ) {
     mark_channel_started(&runtime.channel_name);
+    let channel_name = runtime.channel_name.clone();
     let base_intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::DIRECT_MESSAGES;
     let full_intents = base_intents | GatewayIntents::MESSAGE_CONTENT;
 
@@ -647,6 +992,7 @@ pub async fn start_discord_bot(
             error!("Discord bot error: {e}");
         }
     }
+    crate::health::report_channel_connected(&channel_name, false);
 }
 
 #[cfg(test)]
@@ -677,4 +1023,49 @@ commands:
         assert_eq!(out.as_deref(), Some("discord-ok"));
         let _ = std::fs::remove_dir_all(root);
     }
+
+    #[test]
+    fn test_is_discord_channel_allowed() {
+        assert!(is_discord_channel_allowed(&[], 42));
+        assert!(is_discord_channel_allowed(&[111, 222], 222));
+        assert!(!is_discord_channel_allowed(&[111, 222], 333));
+    }
+
+    #[test]
+    fn test_split_discord_message_respects_limit() {
+        assert_eq!(split_discord_message("hello"), vec!["hello"]);
+        assert!(split_discord_message("").is_empty());
+
+        let line = "a".repeat(1500);
+        let text = format!("{line}\n{line}");
+        let chunks = split_discord_message(&text);
+        assert_eq!(chunks, vec![line.as_str(), line.as_str()]);
+
+        let long = "é".repeat(1500);
+        let chunks = split_discord_message(&long);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= DISCORD_MAX_MESSAGE_LEN));
+        assert_eq!(chunks.concat(), long);
+    }
+
+    #[test]
+    fn test_discord_slash_command_mapping() {
+        assert_eq!(
+            DiscordSlashCommand::from_name("reset"),
+            Some(DiscordSlashCommand::Reset)
+        );
+        assert_eq!(DiscordSlashCommand::from_name("unknown"), None);
+        for command in DiscordSlashCommand::ALL {
+            assert_eq!(
+                DiscordSlashCommand::from_name(command.name()),
+                Some(command)
+            );
+            assert_eq!(command.command_text(), format!("/{}", command.name()));
+            assert!(is_slash_command(command.command_text()));
+        }
+        assert!(DiscordSlashCommand::Reset.ephemeral());
+        assert!(!DiscordSlashCommand::Usage.ephemeral());
+        assert!(DiscordSlashCommand::Usage.deferred());
+        assert_eq!(discord_slash_command_definitions().len(), 3);
+    }
 }
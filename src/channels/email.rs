@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use axum::response::IntoResponse;
 use axum::{http::HeaderMap, Json, Router};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
@@ -15,10 +16,11 @@ use crate::channels::startup_guard::{
     should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
 use microclaw_core::text::split_text;
 use microclaw_storage::db::{call_blocking, StoredMessage};
 
@@ -90,7 +92,7 @@ fn default_webhook_path() -> String {
     "/email/webhook".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct EmailAccountConfig {
     pub from_address: String,
     #[serde(default = "default_sendmail_path")]
@@ -107,7 +109,7 @@ pub struct EmailAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct EmailChannelConfig {
     #[serde(default)]
     pub from_address: String,
@@ -288,6 +290,13 @@ impl ChannelAdapter for EmailAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_len: 8_000,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![("email_dm", ConversationKind::Private)]
     }
@@ -356,6 +365,7 @@ pub async fn start_email_bot(_app_state: Arc<AppState>, runtime: EmailRuntimeCon
         "Email adapter '{}' is ready (webhook ingress + sendmail egress from={})",
         runtime.channel_name, runtime.from_address
     );
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
 
 pub fn register_email_webhook(router: Router, app_state: Arc<AppState>) -> Router {
@@ -508,6 +518,7 @@ async fn process_email_webhook_message(
     }
 
     let trimmed = trimmed_text.trim();
+    crate::metrics::record_message_received(&runtime_ctx.channel_name);
     if is_slash_command(trimmed) {
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime_ctx.channel_name, trimmed).await
@@ -560,6 +571,17 @@ async fn process_email_webhook_message(
         );
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime_ctx.channel_name,
+        chat_id,
+        &external_chat_id,
+        &external_chat_id,
+    )
+    .await
+    {
+        return;
+    }
 
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
     match process_with_agent_with_events(
@@ -4,6 +4,7 @@ use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
@@ -16,10 +17,11 @@ use crate::agent_engine::AgentRequestContext;
 use crate::channels::startup_guard::should_drop_recent_duplicate_message;
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
 use microclaw_storage::db::call_blocking;
 use microclaw_storage::db::StoredMessage;
 
@@ -33,7 +35,7 @@ type WsSink = Arc<
         >,
     >,
 >;
-use microclaw_core::text::split_text;
+use microclaw_core::text::split_text_preserving_code;
 
 pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
     name: "feishu",
@@ -91,7 +93,7 @@ fn default_webhook_path() -> String {
     "/feishu/events".into()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct FeishuAccountConfig {
     pub app_id: String,
     pub app_secret: String,
@@ -119,7 +121,7 @@ fn default_enabled() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct FeishuChannelConfig {
     #[serde(default)]
     pub app_id: String,
@@ -418,6 +420,14 @@ impl ChannelAdapter for FeishuAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            max_message_len: 4000,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("feishu_group", ConversationKind::Group),
@@ -427,7 +437,7 @@ impl ChannelAdapter for FeishuAdapter {
 
     async fn send_text(&self, external_chat_id: &str, text: &str) -> Result<(), String> {
         let token = self.ensure_token().await?;
-        for chunk in split_text(text, 4000) {
+        for chunk in self.capabilities().split_text(text) {
             let content = serde_json::json!({ "text": chunk }).to_string();
             let body = serde_json::json!({
                 "receive_id": external_chat_id,
@@ -910,7 +920,7 @@ async fn send_feishu_response(
     chat_id: &str,
     text: &str,
 ) -> Result<(), String> {
-    for chunk in split_text(text, 4000) {
+    for chunk in split_text_preserving_code(text, 4000) {
         let content = serde_json::json!({ "text": chunk }).to_string();
         let body = serde_json::json!({
             "receive_id": chat_id,
@@ -1162,6 +1172,7 @@ pub async fn start_feishu_bot(app_state: Arc<AppState>, runtime: FeishuRuntimeCo
         Ok(t) => t,
         Err(e) => {
             error!("Feishu: failed to get initial token: {e}");
+            crate::health::report_channel_connected(&runtime.channel_name, false);
             return;
         }
     };
@@ -1174,6 +1185,7 @@ pub async fn start_feishu_bot(app_state: Arc<AppState>, runtime: FeishuRuntimeCo
         }
         Err(e) => {
             error!("Feishu: failed to resolve bot open_id: {e}");
+            crate::health::report_channel_connected(&runtime.channel_name, false);
             return;
         }
     };
@@ -1186,6 +1198,7 @@ pub async fn start_feishu_bot(app_state: Arc<AppState>, runtime: FeishuRuntimeCo
         // In webhook mode the web server handles events; we just keep running.
         // The webhook route is registered separately via register_feishu_webhook().
         // Park this task forever.
+        crate::health::report_channel_connected(&runtime.channel_name, true);
         std::future::pending::<()>().await;
         return;
     }
@@ -1205,6 +1218,7 @@ pub async fn start_feishu_bot(app_state: Arc<AppState>, runtime: FeishuRuntimeCo
         {
             warn!("Feishu WebSocket disconnected: {e}");
         }
+        crate::health::report_channel_connected(&runtime.channel_name, false);
         info!("Feishu: reconnecting in 5 seconds...");
         tokio::time::sleep(Duration::from_secs(5)).await;
     }
@@ -1236,6 +1250,7 @@ async fn run_ws_connection(
         .map_err(|e| format!("WebSocket connect failed: {e}"))?;
 
     info!("Feishu WS: connected");
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 
     let (write, mut read) = ws_stream.split();
     let write = Arc::new(tokio::sync::Mutex::new(write));
@@ -1591,6 +1606,7 @@ async fn handle_feishu_message(
         return;
     }
 
+    crate::metrics::record_message_received(&runtime.channel_name);
     if is_slash_command(trimmed) {
         if !should_respond && !app_state.config.allow_group_slash_without_mention {
             return;
@@ -1634,6 +1650,17 @@ async fn handle_feishu_message(
     if !should_respond {
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime.channel_name,
+        chat_id,
+        user,
+        external_chat_id,
+    )
+    .await
+    {
+        return;
+    }
 
     info!(
         "Feishu message from {} in {}: {}",
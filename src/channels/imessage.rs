@@ -2,14 +2,14 @@ use std::collections::HashMap;
 use std::process::Command;
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
-use microclaw_core::text::split_text;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
 
 pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
     name: "imessage",
@@ -47,7 +47,7 @@ fn default_service() -> String {
     "iMessage".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct IMessageAccountConfig {
     #[serde(default = "default_service")]
     pub service: String,
@@ -59,7 +59,7 @@ pub struct IMessageAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct IMessageChannelConfig {
     #[serde(default = "default_service")]
     pub service: String,
@@ -194,6 +194,13 @@ impl ChannelAdapter for IMessageAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_len: 1500,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![("imessage_dm", ConversationKind::Private)]
     }
@@ -204,7 +211,7 @@ impl ChannelAdapter for IMessageAdapter {
             return Err("iMessage target is empty".to_string());
         }
 
-        for chunk in split_text(text, 1500) {
+        for chunk in self.capabilities().split_text(text) {
             let script = r#"on run argv
 set targetBuddy to item 1 of argv
 set targetMessage to item 2 of argv
@@ -241,4 +248,5 @@ pub async fn start_imessage_bot(_app_state: Arc<AppState>, runtime: IMessageRunt
     if std::env::consts::OS != "macos" {
         error!("iMessage channel is enabled but current OS is not macOS; outbound will fail");
     }
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
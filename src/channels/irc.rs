@@ -2,6 +2,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use native_tls::TlsConnector as NativeTlsConnector;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tokio::io::{split, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
@@ -15,10 +16,11 @@ use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
 use microclaw_core::text::floor_char_boundary;
 use microclaw_storage::db::call_blocking;
 use microclaw_storage::db::StoredMessage;
@@ -128,7 +130,7 @@ fn default_irc_tls_danger_accept_invalid_certs() -> String {
     "false".into()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct IrcChannelConfig {
     pub server: String,
     #[serde(default = "default_irc_port")]
@@ -245,6 +247,13 @@ impl ChannelAdapter for IrcAdapter {
         "irc"
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_len: self.message_max_len.max(32),
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("irc_group", ConversationKind::Group),
@@ -276,6 +285,7 @@ pub async fn start_irc_bot(app_state: Arc<AppState>, adapter: Arc<IrcAdapter>) {
         Some(c) => c,
         None => {
             error!("IRC channel not configured");
+            crate::health::report_channel_connected(adapter.name(), false);
             return;
         }
     };
@@ -284,6 +294,7 @@ pub async fn start_irc_bot(app_state: Arc<AppState>, adapter: Arc<IrcAdapter>) {
     let nick = cfg.nick.trim().to_string();
     if server.is_empty() || nick.is_empty() {
         error!("IRC channel requires non-empty server and nick");
+        crate::health::report_channel_connected(adapter.name(), false);
         return;
     }
 
@@ -292,6 +303,7 @@ pub async fn start_irc_bot(app_state: Arc<AppState>, adapter: Arc<IrcAdapter>) {
         if let Err(e) = run_irc_connection(app_state.clone(), adapter.clone(), cfg.clone()).await {
             warn!("IRC connection ended: {e}");
         }
+        crate::health::report_channel_connected(adapter.name(), false);
         adapter.clear_command_tx().await;
         tokio::time::sleep(reconnect_delay).await;
     }
@@ -379,6 +391,7 @@ async fn run_irc_connection(
 
         if msg.command == "001" && !joined_channels {
             joined_channels = true;
+            crate::health::report_channel_connected(adapter.name(), true);
             for channel in cfg.channel_list() {
                 let _ = tx.send(format!("JOIN {channel}"));
             }
@@ -475,6 +488,7 @@ async fn handle_irc_message(
     let trimmed = text.trim();
     let should_respond =
         !is_group || !cfg.mention_required_bool() || is_irc_mention(&text, cfg.nick.trim());
+    crate::metrics::record_message_received(adapter.name());
     if is_slash_command(trimmed) {
         if !should_respond && !app_state.config.allow_group_slash_without_mention {
             return;
@@ -508,6 +522,17 @@ async fn handle_irc_message(
     if !should_respond {
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        adapter.name(),
+        chat_id,
+        &sender_nick,
+        &external_chat_id,
+    )
+    .await
+    {
+        return;
+    }
 
     info!(
         "IRC message from {} in {}: {}",
@@ -1,40 +1,55 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 
+use lru::LruCache;
 use matrix_sdk::attachment::AttachmentConfig;
 use matrix_sdk::authentication::matrix::MatrixSession;
 use matrix_sdk::config::SyncSettings as MatrixSyncSettings;
 use matrix_sdk::ruma::events::reaction::{ReactionEventContent, SyncReactionEvent};
 use matrix_sdk::ruma::events::relation::Annotation;
-use matrix_sdk::ruma::events::room::member::{MembershipState, StrippedRoomMemberEvent};
+use matrix_sdk::ruma::events::room::member::{
+    MembershipState, StrippedRoomMemberEvent, SyncRoomMemberEvent,
+};
 use matrix_sdk::ruma::events::room::message::{
-    MessageType, RoomMessageEventContent, SyncRoomMessageEvent,
+    MessageType, Relation, RoomMessageEventContent, SyncRoomMessageEvent,
 };
+use matrix_sdk::ruma::events::room::redaction::SyncRoomRedactionEvent;
+use matrix_sdk::ruma::events::room::MediaSource;
 use matrix_sdk::ruma::events::Mentions;
 use matrix_sdk::ruma::{OwnedDeviceId, OwnedEventId, OwnedRoomId, OwnedUserId};
 use matrix_sdk::{Client as MatrixSdkClient, Room as MatrixSdkRoom, SessionMeta, SessionTokens};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::Value;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tokio::sync::{RwLock, Semaphore};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::should_suppress_user_error;
 use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
+use crate::channels::media::guess_mime_from_extension;
 use crate::channels::startup_guard::{
     mark_channel_started, should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
-use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::channels::streaming::StreamingReply;
+use crate::channels::tool_progress::ToolProgress;
+use crate::chat_commands::{
+    handle_chat_command, is_mutating_command, is_slash_command, unknown_command_response,
+};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
-use microclaw_core::text::split_text;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
+use microclaw_core::error::MicroClawError;
+use microclaw_core::text::split_text_preserving_code;
 use microclaw_storage::db::call_blocking;
+use microclaw_storage::db::Database;
 use microclaw_storage::db::StoredMessage;
 
 pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
@@ -81,11 +96,41 @@ fn matrix_sdk_clients() -> &'static RwLock<HashMap<String, Arc<MatrixSdkClient>>
     CLIENTS.get_or_init(|| RwLock::new(HashMap::new()))
 }
 
+fn matrix_resolved_homeservers() -> &'static Mutex<HashMap<String, String>> {
+    static HOMESERVERS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    HOMESERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Homeserver URL discovered for `channel_name` via `.well-known`, if any.
+fn resolved_matrix_homeserver(channel_name: &str) -> Option<String> {
+    matrix_resolved_homeservers()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(channel_name).cloned())
+}
+
 fn matrix_chat_locks() -> &'static Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>> {
     static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
     LOCKS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+fn matrix_agent_semaphores() -> &'static Mutex<HashMap<String, Arc<Semaphore>>> {
+    static SEMAPHORES: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+    SEMAPHORES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Caps concurrent agent runs per channel so a burst of messages queues
+/// instead of firing every LLM call at once. Sized on first use.
+fn matrix_agent_semaphore(channel_name: &str, permits: usize) -> Arc<Semaphore> {
+    let Ok(mut guard) = matrix_agent_semaphores().lock() else {
+        return Arc::new(Semaphore::new(permits.max(1)));
+    };
+    guard
+        .entry(channel_name.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(permits.max(1))))
+        .clone()
+}
+
 fn matrix_chat_lock(channel_name: &str, room_id: &str) -> Arc<tokio::sync::Mutex<()>> {
     let key = format!("{channel_name}:{room_id}");
     let Ok(mut guard) = matrix_chat_locks().lock() else {
@@ -105,7 +150,36 @@ fn default_matrix_sync_timeout_ms() -> u64 {
     30_000
 }
 
-#[derive(Debug, Clone, Deserialize)]
+fn default_matrix_sync_timeout_margin_secs() -> u64 {
+    15
+}
+
+fn default_matrix_sync_timeline_limit() -> u32 {
+    50
+}
+
+fn default_matrix_sync_backfill_limit() -> u32 {
+    200
+}
+
+fn default_matrix_sync_backoff_base_ms() -> u64 {
+    1_000
+}
+
+fn default_matrix_sync_backoff_max_ms() -> u64 {
+    60_000
+}
+
+/// Per-room settings that take precedence over the account-level defaults.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct MatrixRoomOverride {
+    #[serde(default)]
+    pub mention_required: Option<bool>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct MatrixAccountConfig {
     pub access_token: String,
     pub homeserver_url: String,
@@ -114,19 +188,58 @@ pub struct MatrixAccountConfig {
     pub allowed_room_ids: Vec<String>,
     #[serde(default)]
     pub allowed_user_ids: Vec<String>,
+    /// MXIDs allowed to run mutating commands such as `/reload-skills`.
+    /// Empty leaves every command open.
+    #[serde(default, alias = "matrix_admins")]
+    pub admins: Vec<String>,
+    /// Proxy for this channel's Matrix traffic (`http://`, `https://` or
+    /// `socks5://`, optionally with `user:pass@`), overriding `http_proxy`.
+    #[serde(default, alias = "matrix_proxy")]
+    pub proxy: Option<String>,
     #[serde(default)]
     pub bot_username: String,
     #[serde(default = "default_matrix_mention_required")]
     pub mention_required: bool,
     #[serde(default = "default_matrix_sync_timeout_ms")]
     pub sync_timeout_ms: u64,
+    #[serde(
+        default = "default_matrix_sync_timeout_margin_secs",
+        alias = "matrix_sync_timeout_margin_secs"
+    )]
+    pub sync_timeout_margin_secs: u64,
+    #[serde(default = "default_matrix_sync_timeline_limit")]
+    pub sync_timeline_limit: u32,
+    #[serde(default = "default_matrix_sync_backfill_limit")]
+    pub sync_backfill_limit: u32,
+    #[serde(default = "default_matrix_sync_backoff_base_ms")]
+    pub sync_backoff_base_ms: u64,
+    #[serde(default = "default_matrix_sync_backoff_max_ms")]
+    pub sync_backoff_max_ms: u64,
     #[serde(default)]
     pub backup_key: String,
     #[serde(default = "default_enabled")]
+    pub auto_join: bool,
+    #[serde(default = "default_enabled", alias = "matrix_markdown")]
+    pub markdown: bool,
+    #[serde(default = "default_enabled", alias = "matrix_download_media")]
+    pub download_media: bool,
+    #[serde(default = "default_enabled", alias = "matrix_typing_indicator")]
+    pub typing_indicator: bool,
+    #[serde(default, alias = "matrix_reply_to_sender")]
+    pub reply_to_sender: bool,
+    #[serde(default, alias = "matrix_stream_responses")]
+    pub stream_responses: bool,
+    #[serde(default, alias = "matrix_show_tool_progress")]
+    pub show_tool_progress: bool,
+    #[serde(default = "default_enabled", alias = "matrix_encrypted_room_notice")]
+    pub encrypted_room_notice: bool,
+    #[serde(default)]
+    pub rooms: HashMap<String, MatrixRoomOverride>,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct MatrixChannelConfig {
     #[serde(default)]
     pub access_token: String,
@@ -138,20 +251,72 @@ pub struct MatrixChannelConfig {
     pub allowed_room_ids: Vec<String>,
     #[serde(default)]
     pub allowed_user_ids: Vec<String>,
+    /// MXIDs allowed to run mutating commands such as `/reload-skills`.
+    /// Empty leaves every command open.
+    #[serde(default, alias = "matrix_admins")]
+    pub admins: Vec<String>,
+    /// Proxy for this channel's Matrix traffic (`http://`, `https://` or
+    /// `socks5://`, optionally with `user:pass@`), overriding `http_proxy`.
+    #[serde(default, alias = "matrix_proxy")]
+    pub proxy: Option<String>,
     #[serde(default)]
     pub bot_username: String,
     #[serde(default = "default_matrix_mention_required")]
     pub mention_required: bool,
     #[serde(default = "default_matrix_sync_timeout_ms")]
     pub sync_timeout_ms: u64,
+    #[serde(
+        default = "default_matrix_sync_timeout_margin_secs",
+        alias = "matrix_sync_timeout_margin_secs"
+    )]
+    pub sync_timeout_margin_secs: u64,
+    #[serde(default = "default_matrix_sync_timeline_limit")]
+    pub sync_timeline_limit: u32,
+    #[serde(default = "default_matrix_sync_backfill_limit")]
+    pub sync_backfill_limit: u32,
+    #[serde(default = "default_matrix_sync_backoff_base_ms")]
+    pub sync_backoff_base_ms: u64,
+    #[serde(default = "default_matrix_sync_backoff_max_ms")]
+    pub sync_backoff_max_ms: u64,
     #[serde(default)]
     pub backup_key: String,
+    #[serde(default = "default_enabled")]
+    pub auto_join: bool,
+    #[serde(default = "default_enabled", alias = "matrix_markdown")]
+    pub markdown: bool,
+    #[serde(default = "default_enabled", alias = "matrix_download_media")]
+    pub download_media: bool,
+    #[serde(default = "default_enabled", alias = "matrix_typing_indicator")]
+    pub typing_indicator: bool,
+    #[serde(default, alias = "matrix_reply_to_sender")]
+    pub reply_to_sender: bool,
+    #[serde(default, alias = "matrix_stream_responses")]
+    pub stream_responses: bool,
+    #[serde(default, alias = "matrix_show_tool_progress")]
+    pub show_tool_progress: bool,
+    #[serde(default = "default_enabled", alias = "matrix_encrypted_room_notice")]
+    pub encrypted_room_notice: bool,
+    #[serde(default)]
+    pub rooms: HashMap<String, MatrixRoomOverride>,
     #[serde(default)]
     pub accounts: HashMap<String, MatrixAccountConfig>,
     #[serde(default)]
     pub default_account: Option<String>,
 }
 
+/// HTTP client for Matrix sync and sends, routed through `proxy` when set.
+fn build_matrix_http_client(
+    config: &crate::config::Config,
+    proxy: Option<&str>,
+) -> Result<reqwest::Client, String> {
+    let mut settings = config.http_client_settings();
+    settings.all_proxy = proxy
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(ToOwned::to_owned);
+    microclaw_core::http::build_http_client(&settings)
+}
+
 fn pick_default_account_id(
     configured: Option<&str>,
     accounts: &HashMap<String, MatrixAccountConfig>,
@@ -180,9 +345,26 @@ pub struct MatrixRuntimeContext {
     pub bot_username: String,
     pub allowed_room_ids: Vec<String>,
     pub allowed_user_ids: Vec<String>,
+    pub admins: Vec<String>,
     pub mention_required: bool,
     pub sync_timeout_ms: u64,
+    pub sync_timeout_margin_secs: u64,
+    pub sync_timeline_limit: u32,
+    pub sync_backfill_limit: u32,
+    pub sync_backoff_base_ms: u64,
+    pub sync_backoff_max_ms: u64,
     pub backup_key: String,
+    pub auto_join: bool,
+    pub markdown: bool,
+    pub download_media: bool,
+    pub typing_indicator: bool,
+    pub reply_to_sender: bool,
+    pub stream_responses: bool,
+    pub show_tool_progress: bool,
+    pub encrypted_room_notice: bool,
+    pub rooms: HashMap<String, MatrixRoomOverride>,
+    pub member_names: MatrixMemberNames,
+    pub http_client: reqwest::Client,
     pub sdk_client: Option<Arc<RwLock<Option<Arc<MatrixSdkClient>>>>>,
 }
 
@@ -199,10 +381,43 @@ impl MatrixRuntimeContext {
         }
     }
 
+    /// Client-side timeout for a `/sync` long-poll: the server-side wait plus a
+    /// margin, so a healthy long-poll is never cut off but a stalled one is.
+    fn sync_request_timeout(&self) -> Duration {
+        Duration::from_millis(self.sync_timeout_ms_or_default())
+            + Duration::from_secs(self.sync_timeout_margin_secs.max(1))
+    }
+
+    fn sync_backoff(&self) -> MatrixSyncBackoff {
+        let base_ms = if self.sync_backoff_base_ms == 0 {
+            default_matrix_sync_backoff_base_ms()
+        } else {
+            self.sync_backoff_base_ms
+        };
+        let max_ms = if self.sync_backoff_max_ms == 0 {
+            default_matrix_sync_backoff_max_ms()
+        } else {
+            self.sync_backoff_max_ms
+        };
+        MatrixSyncBackoff::new(base_ms, max_ms)
+    }
+
+    /// A room override's `enabled` flag wins over `allowed_room_ids`; rooms
+    /// without an override fall back to the allowlist.
     fn should_process_group_room(&self, room_id: &str) -> bool {
+        if let Some(room) = self.rooms.get(room_id) {
+            return room.enabled;
+        }
         self.allowed_room_ids.is_empty() || self.allowed_room_ids.iter().any(|v| v == room_id)
     }
 
+    fn mention_required_for_room(&self, room_id: &str) -> bool {
+        self.rooms
+            .get(room_id)
+            .and_then(|room| room.mention_required)
+            .unwrap_or(self.mention_required)
+    }
+
     fn should_process_dm_sender(&self, sender_user_id: &str) -> bool {
         self.allowed_user_ids.is_empty()
             || self
@@ -211,6 +426,12 @@ impl MatrixRuntimeContext {
                 .any(|v| v.eq_ignore_ascii_case(sender_user_id))
     }
 
+    /// Whether `sender` may run mutating commands. With no `admins`
+    /// configured everyone may, as before the list existed.
+    fn is_admin(&self, sender: &str) -> bool {
+        self.admins.is_empty() || self.admins.iter().any(|v| v.eq_ignore_ascii_case(sender))
+    }
+
     fn bot_localpart(&self) -> String {
         let user = self.bot_user_id.trim();
         if let Some(rest) = user.strip_prefix('@') {
@@ -219,12 +440,12 @@ impl MatrixRuntimeContext {
         user.to_string()
     }
 
-    fn should_respond(&self, text: &str, mentioned: bool, is_direct: bool) -> bool {
+    fn should_respond(&self, room_id: &str, text: &str, mentioned: bool, is_direct: bool) -> bool {
         if is_direct {
             return true;
         }
 
-        if !self.mention_required {
+        if !self.mention_required_for_room(room_id) {
             return true;
         }
 
@@ -247,6 +468,159 @@ impl MatrixRuntimeContext {
     }
 }
 
+/// Per-room MXID -> display name map, shared by all clones of a runtime.
+#[derive(Clone, Default)]
+pub struct MatrixMemberNames(Arc<Mutex<HashMap<String, HashMap<String, String>>>>);
+
+impl MatrixMemberNames {
+    fn set(&self, room_id: &str, user_id: &str, display_name: Option<&str>) {
+        let Ok(mut rooms) = self.0.lock() else {
+            return;
+        };
+        let members = rooms.entry(room_id.to_string()).or_default();
+        match display_name.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(name) => {
+                members.insert(user_id.to_string(), name.to_string());
+            }
+            None => {
+                members.remove(user_id);
+            }
+        }
+    }
+
+    fn contains(&self, room_id: &str, user_id: &str) -> bool {
+        self.0
+            .lock()
+            .map(|rooms| rooms.get(room_id).is_some_and(|m| m.contains_key(user_id)))
+            .unwrap_or(false)
+    }
+
+    /// Friendly sender name for stored messages; falls back to the raw MXID.
+    fn sender_name(&self, room_id: &str, user_id: &str) -> String {
+        match self.0.lock() {
+            Ok(rooms) => resolve_matrix_display_name(rooms.get(room_id), user_id),
+            Err(_) => user_id.to_string(),
+        }
+    }
+
+    /// Applies an `m.room.member` state/timeline event to the map.
+    fn apply_member_event(&self, room_id: &str, event: &Value) {
+        if event.get("type").and_then(|v| v.as_str()) != Some("m.room.member") {
+            return;
+        }
+        let Some(user_id) = event.get("state_key").and_then(|v| v.as_str()) else {
+            return;
+        };
+        let membership = event
+            .pointer("/content/membership")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let display_name = if membership == "join" {
+            event
+                .pointer("/content/displayname")
+                .and_then(|v| v.as_str())
+        } else {
+            None
+        };
+        self.set(room_id, user_id, display_name);
+    }
+}
+
+/// Resolves an MXID to its display name, appending the localpart when another
+/// member of the room shares the same display name.
+fn resolve_matrix_display_name(members: Option<&HashMap<String, String>>, user_id: &str) -> String {
+    let Some(name) = members.and_then(|m| m.get(user_id)) else {
+        return user_id.to_string();
+    };
+    let ambiguous = members.is_some_and(|m| {
+        m.iter()
+            .any(|(other_id, other_name)| other_id != user_id && other_name == name)
+    });
+    if !ambiguous {
+        return name.clone();
+    }
+    let localpart = user_id
+        .strip_prefix('@')
+        .and_then(|rest| rest.split(':').next())
+        .unwrap_or(user_id);
+    format!("{name} (@{localpart})")
+}
+
+/// Exponential backoff state for a single Matrix sync loop.
+struct MatrixSyncBackoff {
+    base_ms: u64,
+    max_ms: u64,
+    failures: u32,
+}
+
+impl MatrixSyncBackoff {
+    fn new(base_ms: u64, max_ms: u64) -> Self {
+        Self {
+            base_ms,
+            max_ms: max_ms.max(base_ms),
+            failures: 0,
+        }
+    }
+
+    /// Returns the un-jittered delay for the current failure and advances the state.
+    fn next_delay_ms(&mut self) -> u64 {
+        let factor = 2u64.saturating_pow(self.failures.min(32));
+        self.failures = self.failures.saturating_add(1);
+        self.base_ms.saturating_mul(factor).min(self.max_ms)
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_ms)
+    }
+
+    fn next_delay_with_jitter(&mut self) -> Duration {
+        let delay_ms = self.next_delay_ms();
+        Duration::from_millis(delay_ms.saturating_add(matrix_backoff_jitter_ms(delay_ms)))
+    }
+}
+
+/// Random jitter of up to a quarter of `delay_ms`.
+fn matrix_backoff_jitter_ms(delay_ms: u64) -> u64 {
+    let span = delay_ms / 4;
+    if span == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() as u64) % (span + 1)
+}
+
+/// Canonical form of a configured homeserver URL: `https://` is assumed when
+/// no scheme is given, the scheme is lowercased and trailing slashes are
+/// dropped, so `matrix.org/` becomes `https://matrix.org`. Values that cannot
+/// be a homeserver URL (other schemes, no host, embedded spaces) are rejected,
+/// and the account is skipped at startup with the reason logged.
+pub fn normalize_homeserver_url(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("homeserver_url is empty".to_string());
+    }
+    let (scheme, rest) = match raw.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+        None => ("https".to_string(), raw),
+    };
+    if scheme != "https" && scheme != "http" {
+        return Err(format!(
+            "homeserver_url '{raw}' must use http:// or https://, not {scheme}://"
+        ));
+    }
+    let rest = rest.trim_end_matches('/');
+    let normalized = format!("{scheme}://{rest}");
+    match reqwest::Url::parse(&normalized) {
+        Ok(url) if url.host_str().is_some_and(|h| !h.is_empty()) && !rest.contains(' ') => {
+            Ok(normalized)
+        }
+        _ => Err(format!("homeserver_url '{raw}' is not a valid URL")),
+    }
+}
+
 pub fn build_matrix_runtime_contexts(config: &crate::config::Config) -> Vec<MatrixRuntimeContext> {
     let Some(matrix_cfg) = config.channel_config::<MatrixChannelConfig>("matrix") else {
         return Vec::new();
@@ -271,6 +645,22 @@ pub fn build_matrix_runtime_contexts(config: &crate::config::Config) -> Vec<Matr
             continue;
         }
 
+        let homeserver_url = match normalize_homeserver_url(&account_cfg.homeserver_url) {
+            Ok(url) => url,
+            Err(e) => {
+                error!("Matrix account '{account_id}' skipped: invalid config: {e}");
+                continue;
+            }
+        };
+        let proxy = account_cfg.proxy.as_deref().or(matrix_cfg.proxy.as_deref());
+        let http_client = match build_matrix_http_client(config, proxy) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("Matrix account '{account_id}' skipped: invalid config: {e}");
+                continue;
+            }
+        };
+
         let is_default = default_account
             .as_deref()
             .map(|v| v == account_id.as_str())
@@ -290,14 +680,31 @@ pub fn build_matrix_runtime_contexts(config: &crate::config::Config) -> Vec<Matr
         runtimes.push(MatrixRuntimeContext {
             channel_name,
             access_token: account_cfg.access_token.clone(),
-            homeserver_url: account_cfg.homeserver_url.clone(),
+            homeserver_url,
             bot_user_id: account_cfg.bot_user_id.clone(),
             bot_username,
             allowed_room_ids: account_cfg.allowed_room_ids.clone(),
             allowed_user_ids: account_cfg.allowed_user_ids.clone(),
+            admins: account_cfg.admins.clone(),
             mention_required: account_cfg.mention_required,
             sync_timeout_ms: account_cfg.sync_timeout_ms,
+            sync_timeout_margin_secs: account_cfg.sync_timeout_margin_secs,
+            sync_timeline_limit: account_cfg.sync_timeline_limit,
+            sync_backfill_limit: account_cfg.sync_backfill_limit,
+            sync_backoff_base_ms: account_cfg.sync_backoff_base_ms,
+            sync_backoff_max_ms: account_cfg.sync_backoff_max_ms,
             backup_key: account_cfg.backup_key.clone(),
+            auto_join: account_cfg.auto_join,
+            markdown: account_cfg.markdown,
+            download_media: account_cfg.download_media,
+            typing_indicator: account_cfg.typing_indicator,
+            reply_to_sender: account_cfg.reply_to_sender,
+            stream_responses: account_cfg.stream_responses,
+            show_tool_progress: account_cfg.show_tool_progress,
+            encrypted_room_notice: account_cfg.encrypted_room_notice,
+            rooms: account_cfg.rooms.clone(),
+            member_names: MatrixMemberNames::default(),
+            http_client,
             sdk_client: None,
         });
     }
@@ -307,23 +714,46 @@ pub fn build_matrix_runtime_contexts(config: &crate::config::Config) -> Vec<Matr
         && !matrix_cfg.homeserver_url.trim().is_empty()
         && !matrix_cfg.bot_user_id.trim().is_empty()
     {
-        runtimes.push(MatrixRuntimeContext {
-            channel_name: "matrix".to_string(),
-            access_token: matrix_cfg.access_token,
-            homeserver_url: matrix_cfg.homeserver_url,
-            bot_user_id: matrix_cfg.bot_user_id,
-            bot_username: if matrix_cfg.bot_username.trim().is_empty() {
-                config.bot_username_for_channel("matrix")
-            } else {
-                matrix_cfg.bot_username.trim().to_string()
-            },
-            allowed_room_ids: matrix_cfg.allowed_room_ids,
-            allowed_user_ids: matrix_cfg.allowed_user_ids,
-            mention_required: matrix_cfg.mention_required,
-            sync_timeout_ms: matrix_cfg.sync_timeout_ms,
-            backup_key: matrix_cfg.backup_key,
-            sdk_client: None,
-        });
+        match normalize_homeserver_url(&matrix_cfg.homeserver_url).and_then(|url| {
+            build_matrix_http_client(config, matrix_cfg.proxy.as_deref())
+                .map(|client| (url, client))
+        }) {
+            Err(e) => error!("Matrix channel skipped: invalid config: {e}"),
+            Ok((homeserver_url, http_client)) => runtimes.push(MatrixRuntimeContext {
+                channel_name: "matrix".to_string(),
+                access_token: matrix_cfg.access_token,
+                homeserver_url,
+                bot_user_id: matrix_cfg.bot_user_id,
+                bot_username: if matrix_cfg.bot_username.trim().is_empty() {
+                    config.bot_username_for_channel("matrix")
+                } else {
+                    matrix_cfg.bot_username.trim().to_string()
+                },
+                allowed_room_ids: matrix_cfg.allowed_room_ids,
+                allowed_user_ids: matrix_cfg.allowed_user_ids,
+                admins: matrix_cfg.admins,
+                mention_required: matrix_cfg.mention_required,
+                sync_timeout_ms: matrix_cfg.sync_timeout_ms,
+                sync_timeout_margin_secs: matrix_cfg.sync_timeout_margin_secs,
+                sync_timeline_limit: matrix_cfg.sync_timeline_limit,
+                sync_backfill_limit: matrix_cfg.sync_backfill_limit,
+                sync_backoff_base_ms: matrix_cfg.sync_backoff_base_ms,
+                sync_backoff_max_ms: matrix_cfg.sync_backoff_max_ms,
+                backup_key: matrix_cfg.backup_key,
+                auto_join: matrix_cfg.auto_join,
+                markdown: matrix_cfg.markdown,
+                download_media: matrix_cfg.download_media,
+                typing_indicator: matrix_cfg.typing_indicator,
+                reply_to_sender: matrix_cfg.reply_to_sender,
+                stream_responses: matrix_cfg.stream_responses,
+                show_tool_progress: matrix_cfg.show_tool_progress,
+                encrypted_room_notice: matrix_cfg.encrypted_room_notice,
+                rooms: matrix_cfg.rooms,
+                member_names: MatrixMemberNames::default(),
+                http_client,
+                sdk_client: None,
+            }),
+        }
     }
 
     runtimes
@@ -333,16 +763,29 @@ pub struct MatrixAdapter {
     name: String,
     homeserver_url: String,
     access_token: String,
+    markdown: bool,
     http_client: reqwest::Client,
 }
 
 impl MatrixAdapter {
-    pub fn new(name: String, homeserver_url: String, access_token: String) -> Self {
+    fn homeserver_url(&self) -> String {
+        resolved_matrix_homeserver(&self.name).unwrap_or_else(|| self.homeserver_url.clone())
+    }
+
+    pub fn new(
+        name: String,
+        homeserver_url: String,
+        access_token: String,
+        markdown: bool,
+        http_client: reqwest::Client,
+    ) -> Self {
         Self {
             name,
-            homeserver_url: homeserver_url.trim_end_matches('/').to_string(),
+            homeserver_url: normalize_homeserver_url(&homeserver_url)
+                .unwrap_or_else(|_| homeserver_url.trim_end_matches('/').to_string()),
             access_token,
-            http_client: reqwest::Client::new(),
+            markdown,
+            http_client,
         }
     }
 }
@@ -357,6 +800,16 @@ impl ChannelAdapter for MatrixAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            reactions: true,
+            edits: true,
+            max_message_len: MATRIX_MAX_MESSAGE_LEN,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("matrix", ConversationKind::Group),
@@ -369,10 +822,14 @@ impl ChannelAdapter for MatrixAdapter {
         send_matrix_text_with_sdk(
             sdk_client,
             &self.http_client,
-            &self.homeserver_url,
+            &self.homeserver_url(),
             &self.access_token,
             external_chat_id,
             text,
+            MatrixTextOptions {
+                markdown: self.markdown,
+                reply_to: None,
+            },
         )
         .await
     }
@@ -387,7 +844,7 @@ impl ChannelAdapter for MatrixAdapter {
         send_matrix_attachment_with_sdk(
             sdk_client,
             &self.http_client,
-            &self.homeserver_url,
+            &self.homeserver_url(),
             &self.access_token,
             external_chat_id,
             file_path,
@@ -395,6 +852,97 @@ impl ChannelAdapter for MatrixAdapter {
         )
         .await
     }
+
+    async fn send_reaction(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), String> {
+        let event_id = send_matrix_reaction(
+            &self.http_client,
+            &self.homeserver_url(),
+            &self.access_token,
+            external_chat_id,
+            message_id,
+            emoji,
+        )
+        .await?;
+        record_matrix_reaction(&self.name, external_chat_id, message_id, emoji, &event_id);
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), String> {
+        remove_matrix_reaction(
+            &self.http_client,
+            &self.name,
+            &self.homeserver_url(),
+            &self.access_token,
+            external_chat_id,
+            message_id,
+            emoji,
+        )
+        .await
+    }
+
+    async fn send_text_with_id(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        if text.len() > MATRIX_MAX_MESSAGE_LEN {
+            self.send_text(external_chat_id, text).await?;
+            return Ok(None);
+        }
+        let sdk_client = get_registered_matrix_sdk_client(&self.name).await;
+        let payload = matrix_message_payload_for_text(text, self.markdown);
+        let event_id = send_matrix_payload_with_sdk(
+            sdk_client,
+            &self.http_client,
+            &self.homeserver_url(),
+            &self.access_token,
+            external_chat_id,
+            payload,
+        )
+        .await?;
+        Ok(Some(event_id).filter(|id| !id.is_empty()))
+    }
+
+    async fn edit_text(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), String> {
+        let sdk_client = get_registered_matrix_sdk_client(&self.name).await;
+        let payload = matrix_edit_payload(message_id, text, self.markdown);
+        send_matrix_payload_with_sdk(
+            sdk_client,
+            &self.http_client,
+            &self.homeserver_url(),
+            &self.access_token,
+            external_chat_id,
+            payload,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn delete_message(&self, external_chat_id: &str, message_id: &str) -> Result<(), String> {
+        redact_matrix_event(
+            &self.http_client,
+            &self.homeserver_url(),
+            &self.access_token,
+            external_chat_id,
+            message_id,
+        )
+        .await
+    }
 }
 
 enum MatrixIncomingEvent {
@@ -405,6 +953,7 @@ enum MatrixIncomingEvent {
         event_id: String,
         body: String,
         mentioned_bot: bool,
+        media: Option<MatrixMediaRef>,
         event_time_ms: Option<i64>,
     },
     Reaction {
@@ -416,11 +965,118 @@ enum MatrixIncomingEvent {
         key: String,
         event_time_ms: Option<i64>,
     },
+    Edit {
+        room_id: String,
+        is_direct: bool,
+        sender: String,
+        event_id: String,
+        original_event_id: String,
+        body: String,
+    },
+    Redaction {
+        room_id: String,
+        is_direct: bool,
+        redacts: String,
+    },
+    Encrypted {
+        room_id: String,
+    },
+}
+
+/// A URL like `https://example.org`, with no path or port, is likely a server
+/// name rather than the client API host and may delegate via `.well-known`.
+fn matrix_well_known_url(homeserver_url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(homeserver_url).ok()?;
+    if url.scheme() != "https" || url.port().is_some() || !matches!(url.path(), "" | "/") {
+        return None;
+    }
+    Some(format!(
+        "https://{}/.well-known/matrix/client",
+        url.host_str()?
+    ))
+}
+
+/// Extracts `m.homeserver.base_url` from a `.well-known/matrix/client` body.
+fn parse_matrix_well_known(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let base_url = value
+        .get("m.homeserver")
+        .and_then(|v| v.get("base_url"))
+        .and_then(|v| v.as_str())?;
+    normalize_homeserver_url(base_url).ok()
+}
+
+/// Resolves the client API URL for a bare server name through
+/// `.well-known/matrix/client`, keeping the configured URL on any failure.
+/// Runs once when the adapter starts; URLs with a port or path are taken as
+/// the client API already and are not looked up.
+async fn discover_matrix_homeserver(runtime: &MatrixRuntimeContext) -> String {
+    let configured = runtime.normalized_homeserver_url();
+    let Some(well_known_url) = matrix_well_known_url(&configured) else {
+        return configured;
+    };
+    let response = match runtime
+        .http_client
+        .get(&well_known_url)
+        .timeout(MATRIX_WELL_KNOWN_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            debug!(
+                "Matrix adapter '{}' has no .well-known delegation ({}), using {configured}",
+                runtime.channel_name,
+                response.status()
+            );
+            return configured;
+        }
+        Err(e) => {
+            debug!(
+                "Matrix adapter '{}' .well-known lookup failed, using {configured}: {e}",
+                runtime.channel_name
+            );
+            return configured;
+        }
+    };
+    let body = response.text().await.unwrap_or_default();
+    match parse_matrix_well_known(&body) {
+        Some(base_url) => {
+            if base_url != configured {
+                info!(
+                    "Matrix adapter '{}' resolved homeserver {configured} to {base_url} via .well-known",
+                    runtime.channel_name
+                );
+            }
+            base_url
+        }
+        None => {
+            warn!(
+                "Matrix adapter '{}' ignored malformed .well-known response, using {configured}",
+                runtime.channel_name
+            );
+            configured
+        }
+    }
 }
 
-pub async fn start_matrix_bot(app_state: Arc<AppState>, runtime: MatrixRuntimeContext) {
+pub async fn start_matrix_bot(app_state: Arc<AppState>, mut runtime: MatrixRuntimeContext) {
     mark_channel_started(&runtime.channel_name);
-    if let Some(client) = build_matrix_sdk_client(app_state.clone(), &runtime).await {
+    runtime.homeserver_url = discover_matrix_homeserver(&runtime).await;
+    if let Ok(mut guard) = matrix_resolved_homeservers().lock() {
+        guard.insert(runtime.channel_name.clone(), runtime.homeserver_url.clone());
+    }
+    let whoami = match verify_matrix_identity(&runtime).await {
+        Ok(whoami) => whoami,
+        Err(e) => {
+            error!(
+                "Matrix adapter '{}' not started: {e}. Check access_token and bot_user_id.",
+                runtime.channel_name
+            );
+            return;
+        }
+    };
+    if let Some(client) = build_matrix_sdk_client(app_state.clone(), &runtime, whoami).await {
         let client = Arc::new(client);
         matrix_sdk_clients()
             .write()
@@ -447,55 +1103,79 @@ pub async fn start_matrix_bot(app_state: Arc<AppState>, runtime: MatrixRuntimeCo
         return;
     }
 
-    let mut since: Option<String> = None;
-    let mut bootstrapped = false;
+    // Resume from the last processed batch so a restart neither replays nor
+    // skips events; without a stored token the first sync only bootstraps.
+    let sync_token_key = matrix_sync_token_key(&runtime.channel_name);
+    let mut since: Option<String> = call_blocking(app_state.db.clone(), {
+        let key = sync_token_key.clone();
+        move |db| db.get_meta(&key)
+    })
+    .await
+    .ok()
+    .flatten();
+    let mut bootstrapped = since.is_some();
+    let mut resumed_unconfirmed = since.is_some();
+    let mut backoff = runtime.sync_backoff();
+    let sync_filter = match create_matrix_sync_filter(&runtime).await {
+        Ok(filter_id) => filter_id,
+        Err(e) => {
+            warn!(
+                "Matrix adapter '{}' could not upload sync filter, sending it inline: {e}",
+                runtime.channel_name.as_str()
+            );
+            matrix_sync_filter(runtime.sync_timeline_limit).to_string()
+        }
+    };
 
     loop {
-        match sync_matrix_messages(&runtime, since.as_deref()).await {
+        let result = tokio::select! {
+            _ = app_state.shutdown.cancelled() => {
+                info!(
+                    "Matrix adapter '{}' stopped syncing for shutdown",
+                    runtime.channel_name.as_str()
+                );
+                return;
+            }
+            result = sync_matrix_messages(&runtime, since.as_deref(), &sync_filter) => result,
+        };
+        match result {
             Ok((next_batch, events)) => {
-                since = Some(next_batch);
-
-                if !bootstrapped {
-                    bootstrapped = true;
-                    continue;
-                }
-
-                for event in events {
-                    let state = app_state.clone();
-                    let runtime_ctx = runtime.clone();
-                    tokio::spawn(async move {
-                        match event {
-                            MatrixIncomingEvent::Message {
-                                room_id,
-                                is_direct,
-                                sender,
-                                event_id,
-                                body,
-                                mentioned_bot,
-                                event_time_ms,
-                            } => {
-                                let msg = MatrixIncomingMessage {
+                since = Some(next_batch.clone());
+                resumed_unconfirmed = false;
+                backoff.reset();
+                crate::health::report_channel_connected(&runtime.channel_name, true);
+
+                // The first sync without a token only establishes a position.
+                if bootstrapped {
+                    for event in events {
+                        let state = app_state.clone();
+                        let runtime_ctx = runtime.clone();
+                        app_state.tasks.spawn(async move {
+                            match event {
+                                MatrixIncomingEvent::Message {
                                     room_id,
                                     is_direct,
                                     sender,
                                     event_id,
                                     body,
                                     mentioned_bot,
-                                    prefer_sdk_send: false,
+                                    media,
                                     event_time_ms,
-                                };
-                                handle_matrix_message(state, runtime_ctx, msg).await;
-                            }
-                            MatrixIncomingEvent::Reaction {
-                                room_id,
-                                is_direct,
-                                sender,
-                                event_id,
-                                relates_to_event_id,
-                                key,
-                                event_time_ms,
-                            } => {
-                                let reaction = MatrixIncomingReaction {
+                                } => {
+                                    let msg = MatrixIncomingMessage {
+                                        room_id,
+                                        is_direct,
+                                        sender,
+                                        event_id,
+                                        body,
+                                        mentioned_bot,
+                                        media,
+                                        prefer_sdk_send: false,
+                                        event_time_ms,
+                                    };
+                                    handle_matrix_message(state, runtime_ctx, msg).await;
+                                }
+                                MatrixIncomingEvent::Reaction {
                                     room_id,
                                     is_direct,
                                     sender,
@@ -503,34 +1183,188 @@ pub async fn start_matrix_bot(app_state: Arc<AppState>, runtime: MatrixRuntimeCo
                                     relates_to_event_id,
                                     key,
                                     event_time_ms,
-                                };
-                                handle_matrix_reaction(state, runtime_ctx, reaction).await;
+                                } => {
+                                    let reaction = MatrixIncomingReaction {
+                                        room_id,
+                                        is_direct,
+                                        sender,
+                                        event_id,
+                                        relates_to_event_id,
+                                        key,
+                                        event_time_ms,
+                                    };
+                                    handle_matrix_reaction(state, runtime_ctx, reaction).await;
+                                }
+                                MatrixIncomingEvent::Edit {
+                                    room_id,
+                                    is_direct,
+                                    sender,
+                                    event_id,
+                                    original_event_id,
+                                    body,
+                                } => {
+                                    let edit = MatrixIncomingEdit {
+                                        room_id,
+                                        is_direct,
+                                        sender,
+                                        event_id,
+                                        original_event_id,
+                                        body,
+                                    };
+                                    handle_matrix_edit(state, runtime_ctx, edit).await;
+                                }
+                                MatrixIncomingEvent::Redaction {
+                                    room_id,
+                                    is_direct,
+                                    redacts,
+                                } => {
+                                    handle_matrix_redaction(
+                                        state,
+                                        runtime_ctx,
+                                        &room_id,
+                                        is_direct,
+                                        &redacts,
+                                    )
+                                    .await;
+                                }
+                                MatrixIncomingEvent::Encrypted { room_id } => {
+                                    handle_matrix_encrypted_event(&runtime_ctx, &room_id).await;
+                                }
                             }
-                        }
-                    });
+                        });
+                    }
+                } else {
+                    bootstrapped = true;
+                }
+
+                // Persist only once the batch has been handed to handlers, so
+                // a crash before then replays these events instead of losing
+                // them; the handlers' duplicate guard absorbs any overlap.
+                if let Err(e) = call_blocking(app_state.db.clone(), {
+                    let key = sync_token_key.clone();
+                    move |db| db.set_meta(&key, &next_batch)
+                })
+                .await
+                {
+                    warn!(
+                        "Matrix adapter '{}' could not persist sync token: {e}",
+                        runtime.channel_name.as_str()
+                    );
                 }
             }
             Err(e) => {
+                crate::health::report_channel_connected(&runtime.channel_name, false);
+                if resumed_unconfirmed && matches!(e, MatrixError::Http { .. }) {
+                    // The stored token may have been rejected; start over
+                    // with a fresh bootstrap sync instead of retrying it.
+                    warn!(
+                        "Matrix adapter '{}' discarding stored sync token after error: {e}",
+                        runtime.channel_name.as_str()
+                    );
+                    since = None;
+                    bootstrapped = false;
+                    resumed_unconfirmed = false;
+                    let key = sync_token_key.clone();
+                    let _ =
+                        call_blocking(app_state.db.clone(), move |db| db.delete_meta(&key)).await;
+                }
+                let delay = match &e {
+                    // Honour the server's delay instead of our own schedule.
+                    MatrixError::RateLimited {
+                        retry_after: Some(delay),
+                    } => *delay,
+                    // Retrying quickly won't fix a bad token.
+                    MatrixError::Auth(_) => {
+                        error!(
+                            "Matrix adapter '{}' access token rejected; check channels.matrix.access_token",
+                            runtime.channel_name.as_str()
+                        );
+                        backoff.max_delay()
+                    }
+                    _ => backoff.next_delay_with_jitter(),
+                };
                 warn!(
-                    "Matrix adapter '{}' sync error: {e}",
-                    runtime.channel_name.as_str()
+                    "Matrix adapter '{}' sync error (retrying in {}ms): {e}",
+                    runtime.channel_name.as_str(),
+                    delay.as_millis()
                 );
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                tokio::time::sleep(delay).await;
             }
         }
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct MatrixWhoAmIResponse {
     user_id: String,
     #[serde(default)]
     device_id: Option<String>,
 }
 
+fn parse_matrix_whoami(body: &str) -> Result<MatrixWhoAmIResponse, MatrixError> {
+    serde_json::from_str(body).map_err(|e| MatrixError::Parse(format!("Matrix whoami: {e}")))
+}
+
+async fn fetch_matrix_whoami(
+    runtime: &MatrixRuntimeContext,
+) -> Result<MatrixWhoAmIResponse, MatrixError> {
+    let url = format!(
+        "{}/_matrix/client/v3/account/whoami",
+        runtime.normalized_homeserver_url()
+    );
+    let response = send_matrix_request_with_retry("Matrix whoami", || {
+        runtime
+            .http_client
+            .get(&url)
+            .bearer_auth(runtime.access_token.trim())
+    })
+    .await?;
+    let body = response
+        .text()
+        .await
+        .map_err(|e| MatrixError::Network(format!("Matrix whoami: {e}")))?;
+    parse_matrix_whoami(&body)
+}
+
+/// Error when the access token belongs to a different user than `bot_user_id`.
+fn matrix_whoami_mismatch(bot_user_id: &str, whoami: &MatrixWhoAmIResponse) -> Option<String> {
+    let expected = bot_user_id.trim();
+    if whoami.user_id.trim() == expected {
+        None
+    } else {
+        Some(format!(
+            "access_token belongs to {} but bot_user_id is {expected}",
+            whoami.user_id
+        ))
+    }
+}
+
+/// Confirms the access token is valid and belongs to `bot_user_id` before the
+/// account starts syncing. `Err` means this account must not start; transient
+/// failures only warn and return `Ok(None)`.
+async fn verify_matrix_identity(
+    runtime: &MatrixRuntimeContext,
+) -> Result<Option<MatrixWhoAmIResponse>, String> {
+    match fetch_matrix_whoami(runtime).await {
+        Ok(whoami) => match matrix_whoami_mismatch(&runtime.bot_user_id, &whoami) {
+            Some(mismatch) => Err(mismatch),
+            None => Ok(Some(whoami)),
+        },
+        Err(e @ MatrixError::Auth(_)) => Err(format!("access_token was rejected: {e}")),
+        Err(e) => {
+            warn!(
+                "Matrix adapter '{}' could not verify access_token, starting anyway: {e}",
+                runtime.channel_name
+            );
+            Ok(None)
+        }
+    }
+}
+
 async fn build_matrix_sdk_client(
     app_state: Arc<AppState>,
     runtime: &MatrixRuntimeContext,
+    whoami: Option<MatrixWhoAmIResponse>,
 ) -> Option<MatrixSdkClient> {
     let store_dir = matrix_sdk_store_dir(&app_state, runtime);
     if let Err(e) = std::fs::create_dir_all(&store_dir) {
@@ -543,6 +1377,7 @@ async fn build_matrix_sdk_client(
 
     let sdk_client = match MatrixSdkClient::builder()
         .homeserver_url(runtime.homeserver_url.clone())
+        .http_client(runtime.http_client.clone())
         .sqlite_store(&store_dir, None)
         .build()
         .await
@@ -554,27 +1389,15 @@ async fn build_matrix_sdk_client(
         }
     };
 
-    let whoami_url = format!(
-        "{}/_matrix/client/v3/account/whoami",
-        runtime.homeserver_url.trim_end_matches('/')
-    );
-    let whoami = match reqwest::Client::new()
-        .get(&whoami_url)
-        .bearer_auth(runtime.access_token.trim())
-        .send()
-        .await
-    {
-        Ok(resp) => match resp.json::<MatrixWhoAmIResponse>().await {
-            Ok(v) => v,
+    let whoami = match whoami {
+        Some(whoami) => whoami,
+        None => match fetch_matrix_whoami(runtime).await {
+            Ok(whoami) => whoami,
             Err(e) => {
-                warn!("Matrix SDK whoami parse failed: {e}");
+                warn!("Matrix SDK whoami failed: {e}");
                 return None;
             }
         },
-        Err(e) => {
-            warn!("Matrix SDK whoami failed: {e}");
-            return None;
-        }
     };
 
     let user_id: OwnedUserId = match whoami.user_id.parse() {
@@ -711,6 +1534,22 @@ async fn auto_join_invited_rooms(client: &MatrixSdkClient) {
     }
 }
 
+async fn seed_matrix_member_name(
+    runtime: &MatrixRuntimeContext,
+    room: &MatrixSdkRoom,
+    user_id: &matrix_sdk::ruma::UserId,
+) {
+    let room_id = room.room_id().as_str();
+    if runtime.member_names.contains(room_id, user_id.as_str()) {
+        return;
+    }
+    if let Ok(Some(member)) = room.get_member_no_sync(user_id).await {
+        runtime
+            .member_names
+            .set(room_id, user_id.as_str(), member.display_name());
+    }
+}
+
 async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntimeContext) {
     let Some(slot) = runtime.sdk_client.as_ref() else {
         return;
@@ -732,6 +1571,9 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
     client.add_event_handler(move |ev: StrippedRoomMemberEvent, room: MatrixSdkRoom| {
         let runtime = invite_runtime.clone();
         async move {
+            if !runtime.auto_join {
+                return;
+            }
             if ev.content.membership != MembershipState::Invite {
                 return;
             }
@@ -765,6 +1607,25 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
             {
                 return;
             }
+            if let Some(Relation::Replacement(replacement)) = ev.content.relates_to.as_ref() {
+                let Some(body) =
+                    normalize_matrix_sdk_message_type(&replacement.new_content.msgtype)
+                else {
+                    return;
+                };
+                let room_id = room.room_id().to_string();
+                let is_direct = room.is_direct().await.unwrap_or(false);
+                let edit = MatrixIncomingEdit {
+                    room_id,
+                    is_direct,
+                    sender: ev.sender.to_string(),
+                    event_id: ev.event_id.to_string(),
+                    original_event_id: replacement.event_id.to_string(),
+                    body,
+                };
+                handle_matrix_edit(app_state, runtime, edit).await;
+                return;
+            }
             let Some(body) = normalize_matrix_sdk_message_type(&ev.content.msgtype) else {
                 return;
             };
@@ -787,6 +1648,7 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
             if is_direct && !runtime.should_process_dm_sender(ev.sender.as_str()) {
                 return;
             }
+            seed_matrix_member_name(&runtime, &room, &ev.sender).await;
             let msg = MatrixIncomingMessage {
                 room_id,
                 is_direct,
@@ -794,10 +1656,14 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
                 event_id: ev.event_id.to_string(),
                 body,
                 mentioned_bot,
+                media: matrix_sdk_media_ref(&ev.content.msgtype),
                 prefer_sdk_send: true,
                 event_time_ms: None,
             };
-            handle_matrix_message(app_state, runtime, msg).await;
+            let tasks = app_state.tasks.clone();
+            tasks
+                .track_future(handle_matrix_message(app_state, runtime, msg))
+                .await;
         }
     });
 
@@ -836,6 +1702,7 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
             if is_direct && !runtime.should_process_dm_sender(ev.sender.as_str()) {
                 return;
             }
+            seed_matrix_member_name(&runtime, &room, &ev.sender).await;
             let reaction = MatrixIncomingReaction {
                 room_id,
                 is_direct,
@@ -849,7 +1716,54 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
         }
     });
 
+    let member_runtime = runtime.clone();
+    client.add_event_handler(move |ev: SyncRoomMemberEvent, room: MatrixSdkRoom| {
+        let runtime = member_runtime.clone();
+        async move {
+            let SyncRoomMemberEvent::Original(ev) = ev else {
+                return;
+            };
+            let display_name = if ev.content.membership == MembershipState::Join {
+                ev.content.displayname.as_deref()
+            } else {
+                None
+            };
+            runtime
+                .member_names
+                .set(room.room_id().as_str(), ev.state_key.as_str(), display_name);
+        }
+    });
+
+    let redaction_state = app_state.clone();
+    let redaction_runtime = runtime.clone();
+    let redaction_boot = bootstrapped.clone();
+    client.add_event_handler(move |ev: SyncRoomRedactionEvent, room: MatrixSdkRoom| {
+        let app_state = redaction_state.clone();
+        let runtime = redaction_runtime.clone();
+        let bootstrapped = redaction_boot.clone();
+        async move {
+            if !bootstrapped.load(std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+            let SyncRoomRedactionEvent::Original(ev) = ev else {
+                return;
+            };
+            let Some(redacts) = ev.content.redacts.clone().or(ev.redacts.clone()) else {
+                return;
+            };
+            let room_id = room.room_id().to_string();
+            let is_direct = room.is_direct().await.unwrap_or(false);
+            handle_matrix_redaction(app_state, runtime, &room_id, is_direct, redacts.as_str())
+                .await;
+        }
+    });
+
+    let mut backoff = runtime.sync_backoff();
+    let shutdown = app_state.shutdown.clone();
     loop {
+        if shutdown.is_cancelled() {
+            return;
+        }
         let settings = || {
             MatrixSyncSettings::default()
                 .timeout(Duration::from_millis(runtime.sync_timeout_ms_or_default()))
@@ -857,28 +1771,183 @@ async fn start_matrix_e2ee_sync(app_state: Arc<AppState>, runtime: MatrixRuntime
         if !bootstrapped.load(std::sync::atomic::Ordering::SeqCst) {
             match client.sync_once(settings()).await {
                 Ok(_) => {
-                    auto_join_invited_rooms(&client).await;
+                    backoff.reset();
+                    if runtime.auto_join {
+                        auto_join_invited_rooms(&client).await;
+                    }
                     bootstrapped.store(true, std::sync::atomic::Ordering::SeqCst);
                 }
                 Err(e) => {
+                    crate::health::report_channel_connected(&runtime.channel_name, false);
                     warn!("Matrix SDK initial sync failed: {e}");
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    tokio::time::sleep(backoff.next_delay_with_jitter()).await;
                     continue;
                 }
             }
         }
+        // `sync` only returns on failure, so entering it counts as connected.
+        crate::health::report_channel_connected(&runtime.channel_name, true);
+
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!(
+                    "Matrix adapter '{}' stopped syncing for shutdown",
+                    runtime.channel_name.as_str()
+                );
+                return;
+            }
+            result = client.sync(settings()) => {
+                if let Err(e) = result {
+                    crate::health::report_channel_connected(&runtime.channel_name, false);
+                    warn!("Matrix SDK sync loop ended: {e}");
+                    tokio::time::sleep(backoff.next_delay_with_jitter()).await;
+                }
+            }
+        }
+    }
+}
+
+/// Sync filter limited to the event types the adapter handles. Presence and
+/// room account data are dropped; global account data keeps only `m.direct`.
+fn matrix_sync_filter(timeline_limit: u32) -> Value {
+    let timeline_limit = if timeline_limit == 0 {
+        default_matrix_sync_timeline_limit()
+    } else {
+        timeline_limit
+    };
+    serde_json::json!({
+        "presence": { "not_types": ["*"] },
+        "account_data": { "types": ["m.direct"] },
+        "room": {
+            "timeline": {
+                "types": [
+                    "m.room.message",
+                    "m.room.encrypted",
+                    "m.reaction",
+                    "m.room.redaction",
+                    "m.room.member",
+                ],
+                "limit": timeline_limit,
+            },
+            "state": {
+                "types": ["m.room.member"],
+                "lazy_load_members": true,
+            },
+            "ephemeral": { "not_types": ["*"] },
+            "account_data": { "not_types": ["*"] },
+        }
+    })
+}
+
+/// Uploads the sync filter once and returns the server-assigned filter id.
+async fn create_matrix_sync_filter(runtime: &MatrixRuntimeContext) -> Result<String, MatrixError> {
+    let url = format!(
+        "{}/_matrix/client/v3/user/{}/filter",
+        runtime.normalized_homeserver_url(),
+        urlencoding::encode(runtime.bot_user_id.trim())
+    );
+    let filter = matrix_sync_filter(runtime.sync_timeline_limit);
+    let client = &runtime.http_client;
+    let response = send_matrix_request_with_retry("Matrix filter upload", || {
+        client
+            .post(&url)
+            .bearer_auth(runtime.access_token.trim())
+            .json(&filter)
+    })
+    .await?;
+    let json = read_matrix_json("Matrix filter response", response).await?;
+    json.get("filter_id")
+        .and_then(|v| v.as_str())
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| MatrixError::Parse("Matrix filter response missing filter_id".into()))
+}
+
+/// Returns the `prev_batch` token when the homeserver truncated a room's timeline.
+fn matrix_timeline_gap(room_data: &Value) -> Option<&str> {
+    let limited = room_data
+        .pointer("/timeline/limited")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !limited {
+        return None;
+    }
+    room_data
+        .pointer("/timeline/prev_batch")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+}
 
-        if let Err(e) = client.sync(settings()).await {
-            warn!("Matrix SDK sync loop ended: {e}");
-            tokio::time::sleep(Duration::from_secs(5)).await;
+/// Pages backwards from `prev_batch` to the previous sync token, returning the
+/// missed events oldest-first. Stops after `sync_backfill_limit` events.
+async fn backfill_matrix_room(
+    client: &reqwest::Client,
+    runtime: &MatrixRuntimeContext,
+    room_id: &str,
+    prev_batch: &str,
+    since: &str,
+) -> Result<Vec<Value>, MatrixError> {
+    let url = format!(
+        "{}/_matrix/client/v3/rooms/{}/messages",
+        runtime.normalized_homeserver_url(),
+        urlencoding::encode(room_id)
+    );
+    let room_filter = matrix_sync_filter(runtime.sync_timeline_limit)
+        .pointer("/room/timeline")
+        .cloned()
+        .unwrap_or(Value::Null)
+        .to_string();
+    let max_events = runtime.sync_backfill_limit as usize;
+    let mut from = prev_batch.to_string();
+    let mut collected = Vec::new();
+
+    while collected.len() < max_events {
+        let limit = (max_events - collected.len()).min(100).to_string();
+        let response = send_matrix_request_with_retry("Matrix backfill", || {
+            client
+                .get(&url)
+                .bearer_auth(runtime.access_token.trim())
+                .query(&[
+                    ("dir", "b"),
+                    ("from", from.as_str()),
+                    ("to", since),
+                    ("limit", limit.as_str()),
+                    ("filter", room_filter.as_str()),
+                ])
+        })
+        .await?;
+        let page = read_matrix_json("Matrix backfill response", response).await?;
+        let chunk = page
+            .get("chunk")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        if chunk.is_empty() {
+            break;
+        }
+        collected.extend(chunk);
+        match page.get("end").and_then(|v| v.as_str()) {
+            Some(end) if end != from => from = end.to_string(),
+            _ => break,
         }
     }
+
+    collected.truncate(max_events);
+    collected.reverse();
+    Ok(collected)
+}
+
+const MATRIX_WELL_KNOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn matrix_sync_token_key(channel: &str) -> String {
+    format!("matrix_sync_token:{channel}")
 }
 
+/// `filter` is either a server filter id or an inline JSON filter definition.
 async fn sync_matrix_messages(
     runtime: &MatrixRuntimeContext,
     since: Option<&str>,
-) -> Result<(String, Vec<MatrixIncomingEvent>), String> {
+    filter: &str,
+) -> Result<(String, Vec<MatrixIncomingEvent>), MatrixError> {
     let homeserver_url = runtime.normalized_homeserver_url();
     let url = format!("{homeserver_url}/_matrix/client/v3/sync");
 
@@ -888,11 +1957,13 @@ async fn sync_matrix_messages(
         0
     };
 
-    let client = reqwest::Client::new();
+    let client = &runtime.http_client;
     let mut request = client
         .get(&url)
         .bearer_auth(runtime.access_token.trim())
-        .query(&[("timeout", timeout_ms)]);
+        .query(&[("timeout", timeout_ms)])
+        .query(&[("filter", filter)])
+        .timeout(runtime.sync_request_timeout());
 
     if let Some(since_token) = since {
         request = request.query(&[("since", since_token)]);
@@ -901,31 +1972,33 @@ async fn sync_matrix_messages(
     let response = request
         .send()
         .await
-        .map_err(|e| format!("Matrix /sync request failed: {e}"))?;
+        .map_err(|e| MatrixError::Network(format!("/sync: {e}")))?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Matrix /sync failed: HTTP {status} {}",
-            body.chars().take(300).collect::<String>()
-        ));
+        return Err(MatrixError::from_failed_response(response).await);
     }
 
-    let payload: Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Matrix /sync response parse failed: {e}"))?;
+    let payload = read_matrix_json("Matrix /sync response", response).await?;
 
     let next_batch = payload
         .get("next_batch")
         .and_then(|v| v.as_str())
         .map(ToOwned::to_owned)
-        .ok_or_else(|| "Matrix /sync response missing next_batch".to_string())?;
+        .ok_or_else(|| MatrixError::Parse("Matrix /sync response missing next_batch".into()))?;
 
     let mut incoming = Vec::new();
     let direct_rooms = extract_direct_room_ids(&payload);
 
+    if runtime.auto_join {
+        for room_id in extract_invited_room_ids(&payload, runtime) {
+            if let Err(e) = join_matrix_room(client, runtime, &room_id).await {
+                warn!("Matrix failed to auto-join invited room {}: {e}", room_id);
+            } else {
+                info!("Matrix auto-joined invited room {}", room_id);
+            }
+        }
+    }
+
     let joined_rooms = payload
         .pointer("/rooms/join")
         .and_then(|v| v.as_object())
@@ -938,14 +2011,40 @@ async fn sync_matrix_messages(
             continue;
         }
 
-        let Some(events) = room_data
+        for pointer in ["/state/events", "/timeline/events"] {
+            if let Some(events) = room_data.pointer(pointer).and_then(|v| v.as_array()) {
+                for event in events {
+                    runtime.member_names.apply_member_event(&room_id, event);
+                }
+            }
+        }
+
+        let Some(timeline_events) = room_data
             .pointer("/timeline/events")
             .and_then(|v| v.as_array())
         else {
             continue;
         };
 
-        for event in events {
+        let mut events = Vec::new();
+        if let (Some(since_token), Some(prev_batch)) = (since, matrix_timeline_gap(&room_data)) {
+            match backfill_matrix_room(client, runtime, &room_id, prev_batch, since_token).await {
+                Ok(backfilled) => {
+                    if !backfilled.is_empty() {
+                        info!(
+                            "Matrix: backfilled {} events for gappy timeline in {}",
+                            backfilled.len(),
+                            room_id
+                        );
+                    }
+                    events.extend(backfilled);
+                }
+                Err(e) => warn!("Matrix: backfill failed for {}: {e}", room_id),
+            }
+        }
+        events.extend(timeline_events.iter().cloned());
+
+        for event in &events {
             let sender = event
                 .get("sender")
                 .and_then(|v| v.as_str())
@@ -967,8 +2066,33 @@ async fn sync_matrix_messages(
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
+            if event_type == "m.room.encrypted" {
+                incoming.push(MatrixIncomingEvent::Encrypted {
+                    room_id: room_id.clone(),
+                });
+            } else if event_type == "m.room.redaction" {
+                if let Some(redacts) = matrix_redaction_target(event) {
+                    incoming.push(MatrixIncomingEvent::Redaction {
+                        room_id: room_id.clone(),
+                        is_direct,
+                        redacts,
+                    });
+                }
+            } else if event_type == "m.room.message" {
+                if let Some((original_event_id, body)) = matrix_replacement_edit(event) {
+                    if !body.trim().is_empty() {
+                        incoming.push(MatrixIncomingEvent::Edit {
+                            room_id: room_id.clone(),
+                            is_direct,
+                            sender,
+                            event_id,
+                            original_event_id,
+                            body,
+                        });
+                    }
+                    continue;
+                }
 
-            if event_type == "m.room.message" {
                 let body = normalize_matrix_message_body(event);
                 if body.trim().is_empty() {
                     continue;
@@ -991,6 +2115,7 @@ async fn sync_matrix_messages(
                     event_id,
                     body,
                     mentioned_bot,
+                    media: matrix_media_ref(event),
                     event_time_ms: event.get("origin_server_ts").and_then(|v| v.as_i64()),
                 });
             } else if event_type == "m.reaction" {
@@ -1025,6 +2150,70 @@ async fn sync_matrix_messages(
     Ok((next_batch, incoming))
 }
 
+/// Invited rooms from a `/sync` payload that pass the room/sender allowlists and
+/// are not already joined.
+fn extract_invited_room_ids(payload: &Value, runtime: &MatrixRuntimeContext) -> Vec<String> {
+    let Some(invited) = payload.pointer("/rooms/invite").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    let joined = payload.pointer("/rooms/join").and_then(|v| v.as_object());
+
+    let mut out = Vec::new();
+    for (room_id, room_data) in invited {
+        if joined.is_some_and(|j| j.contains_key(room_id)) {
+            continue;
+        }
+        let invite_event = room_data
+            .pointer("/invite_state/events")
+            .and_then(|v| v.as_array())
+            .and_then(|events| {
+                events.iter().find(|ev| {
+                    ev.get("type").and_then(|v| v.as_str()) == Some("m.room.member")
+                        && ev.get("state_key").and_then(|v| v.as_str())
+                            == Some(runtime.bot_user_id.as_str())
+                })
+            });
+        let is_direct = invite_event
+            .and_then(|ev| ev.pointer("/content/is_direct"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let allowed = if is_direct {
+            let inviter = invite_event
+                .and_then(|ev| ev.get("sender"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            runtime.should_process_dm_sender(inviter)
+        } else {
+            runtime.should_process_group_room(room_id)
+        };
+        if allowed {
+            out.push(room_id.clone());
+        }
+    }
+    out.sort();
+    out
+}
+
+async fn join_matrix_room(
+    client: &reqwest::Client,
+    runtime: &MatrixRuntimeContext,
+    room_id: &str,
+) -> Result<(), MatrixError> {
+    let url = format!(
+        "{}/_matrix/client/v3/join/{}",
+        runtime.normalized_homeserver_url(),
+        urlencoding::encode(room_id)
+    );
+    send_matrix_request_with_retry("Matrix join", || {
+        client
+            .post(&url)
+            .bearer_auth(runtime.access_token.trim())
+            .json(&serde_json::json!({}))
+    })
+    .await?;
+    Ok(())
+}
+
 fn normalize_matrix_message_body(event: &Value) -> String {
     let msgtype = event
         .pointer("/content/msgtype")
@@ -1052,6 +2241,206 @@ fn normalize_matrix_message_body(event: &Value) -> String {
     }
 }
 
+/// Event id removed by an `m.room.redaction` event. Room versions before v11
+/// carry it at the top level, v11+ moved it into `content`.
+fn matrix_redaction_target(event: &Value) -> Option<String> {
+    event
+        .pointer("/content/redacts")
+        .or_else(|| event.get("redacts"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Detects an `m.replace` edit and returns `(original_event_id, replacement_body)`.
+/// The replacement body comes from `m.new_content`, not the `* fallback` body.
+fn matrix_replacement_edit(event: &Value) -> Option<(String, String)> {
+    let relates_to = event.pointer("/content/m.relates_to")?;
+    if relates_to.get("rel_type").and_then(|v| v.as_str()) != Some("m.replace") {
+        return None;
+    }
+    let original_event_id = relates_to
+        .get("event_id")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())?
+        .to_string();
+    let new_content = event.pointer("/content/m.new_content")?;
+    let body = normalize_matrix_message_body(&serde_json::json!({ "content": new_content }));
+    Some((original_event_id, body))
+}
+
+/// Unencrypted media attached to an incoming message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MatrixMediaRef {
+    msgtype: String,
+    body: String,
+    mxc_url: String,
+    size: Option<u64>,
+}
+
+fn matrix_media_ref(event: &Value) -> Option<MatrixMediaRef> {
+    let msgtype = event.pointer("/content/msgtype").and_then(|v| v.as_str())?;
+    if !matches!(msgtype, "m.image" | "m.file" | "m.audio" | "m.video") {
+        return None;
+    }
+    let mxc_url = event
+        .pointer("/content/url")
+        .and_then(|v| v.as_str())
+        .filter(|v| v.starts_with("mxc://"))?;
+    Some(MatrixMediaRef {
+        msgtype: msgtype.to_string(),
+        body: event
+            .pointer("/content/body")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        mxc_url: mxc_url.to_string(),
+        size: event.pointer("/content/info/size").and_then(|v| v.as_u64()),
+    })
+}
+
+fn matrix_sdk_media_ref(msgtype: &MessageType) -> Option<MatrixMediaRef> {
+    let (kind, body, source, size) = match msgtype {
+        MessageType::Image(c) => (
+            "m.image",
+            &c.body,
+            &c.source,
+            c.info.as_ref().and_then(|i| i.size),
+        ),
+        MessageType::File(c) => (
+            "m.file",
+            &c.body,
+            &c.source,
+            c.info.as_ref().and_then(|i| i.size),
+        ),
+        MessageType::Audio(c) => (
+            "m.audio",
+            &c.body,
+            &c.source,
+            c.info.as_ref().and_then(|i| i.size),
+        ),
+        MessageType::Video(c) => (
+            "m.video",
+            &c.body,
+            &c.source,
+            c.info.as_ref().and_then(|i| i.size),
+        ),
+        _ => return None,
+    };
+    let MediaSource::Plain(uri) = source else {
+        return None;
+    };
+    Some(MatrixMediaRef {
+        msgtype: kind.to_string(),
+        body: body.clone(),
+        mxc_url: uri.to_string(),
+        size: size.map(u64::from),
+    })
+}
+
+/// Maps `mxc://server/media_id` to the homeserver's media download endpoint.
+fn matrix_mxc_download_url(homeserver_url: &str, mxc_url: &str) -> Option<String> {
+    let rest = mxc_url.strip_prefix("mxc://")?;
+    let (server, media_id) = rest.split_once('/')?;
+    if server.is_empty() || media_id.is_empty() || media_id.contains('/') {
+        return None;
+    }
+    Some(format!(
+        "{}/_matrix/media/v3/download/{}/{}",
+        homeserver_url.trim_end_matches('/'),
+        urlencoding::encode(server),
+        urlencoding::encode(media_id)
+    ))
+}
+
+/// Downloads an incoming attachment into the chat's upload directory. Returns
+/// `Ok(None)` when the file exceeds `max_document_size_mb`.
+async fn download_matrix_media(
+    app_state: &AppState,
+    runtime: &MatrixRuntimeContext,
+    room_id: &str,
+    media: &MatrixMediaRef,
+) -> Result<Option<PathBuf>, String> {
+    let max_bytes = app_state
+        .config
+        .max_document_size_mb
+        .saturating_mul(1024 * 1024);
+    if media.size.is_some_and(|size| size > max_bytes) {
+        return Ok(None);
+    }
+    let url = matrix_mxc_download_url(&runtime.homeserver_url, &media.mxc_url)
+        .ok_or_else(|| format!("Invalid Matrix content URI '{}'", media.mxc_url))?;
+
+    let response = runtime
+        .http_client
+        .get(&url)
+        .bearer_auth(runtime.access_token.trim())
+        .send()
+        .await
+        .map_err(|e| format!("Matrix media download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Matrix media download failed: HTTP {}",
+            response.status()
+        ));
+    }
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Ok(None);
+    }
+    let Some(bytes) = read_capped_body(response, max_bytes).await? else {
+        return Ok(None);
+    };
+
+    let safe_name = media
+        .body
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    let safe_name = if safe_name.trim_matches(['.', '_']).is_empty() {
+        "matrix-attachment.bin".to_string()
+    } else {
+        safe_name
+    };
+    let dir = Path::new(&app_state.config.working_dir)
+        .join("uploads")
+        .join(matrix_channel_slug(&runtime.channel_name))
+        .join(matrix_channel_slug(room_id));
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create upload dir {}: {e}", dir.display()))?;
+    let ts = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("{ts}-{safe_name}"));
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to save Matrix attachment {}: {e}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// Read a response body, giving up with `None` once it exceeds `max_bytes`.
+/// Content-Length may be absent, so the limit is enforced while reading.
+async fn read_capped_body(
+    mut response: reqwest::Response,
+    max_bytes: u64,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Matrix media download failed: {e}"))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > max_bytes {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(Some(bytes))
+}
+
 fn normalize_matrix_sdk_message_type(msgtype: &MessageType) -> Option<String> {
     match msgtype {
         MessageType::Text(text) => Some(text.body.clone()),
@@ -1166,41 +2555,404 @@ fn extract_matrix_user_ids(text: &str) -> Vec<String> {
     out
 }
 
-fn matrix_message_payload_for_text(chunk: &str) -> Value {
-    let user_ids = extract_matrix_user_ids(chunk);
-    if user_ids.is_empty() {
-        return serde_json::json!({
-            "msgtype": "m.text",
-            "body": chunk,
-        });
-    }
-
-    let mut formatted = html_escape(chunk);
-    for uid in &user_ids {
+fn inject_matrix_mention_pills(escaped: &str, user_ids: &[String]) -> String {
+    let mut formatted = escaped.to_string();
+    for uid in user_ids {
         let escaped_uid = html_escape(uid);
         let href = format!("https://matrix.to/#/{}", uid);
         let pill = format!("<a href=\"{}\">{}</a>", html_escape(&href), escaped_uid);
         formatted = formatted.replace(&escaped_uid, &pill);
     }
+    formatted
+}
 
-    serde_json::json!({
-        "msgtype": "m.text",
+fn render_matrix_inline_text(segment: &str, user_ids: &[String]) -> String {
+    let mut out = String::new();
+    let mut rest = segment;
+
+    while let Some(start) = rest.find("**") {
+        let (before, after_start) = rest.split_at(start);
+        let after_start = &after_start[2..];
+        let Some(end) = after_start.find("**") else {
+            break;
+        };
+        out.push_str(&inject_matrix_mention_pills(&html_escape(before), user_ids));
+        out.push_str("<strong>");
+        out.push_str(&inject_matrix_mention_pills(
+            &html_escape(&after_start[..end]),
+            user_ids,
+        ));
+        out.push_str("</strong>");
+        rest = &after_start[end + 2..];
+    }
+
+    out.push_str(&inject_matrix_mention_pills(&html_escape(rest), user_ids));
+    out
+}
+
+fn render_matrix_inline_markdown(line: &str, user_ids: &[String]) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find('`') {
+        let (before, after_start) = rest.split_at(start);
+        let after_start = &after_start[1..];
+        let Some(end) = after_start.find('`') else {
+            break;
+        };
+        out.push_str(&render_matrix_inline_text(before, user_ids));
+        out.push_str("<code>");
+        out.push_str(&html_escape(&after_start[..end]));
+        out.push_str("</code>");
+        rest = &after_start[end + 1..];
+    }
+
+    out.push_str(&render_matrix_inline_text(rest, user_ids));
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MatrixMarkdownList {
+    Unordered,
+    Ordered,
+}
+
+fn matrix_markdown_list_item(line: &str) -> Option<(MatrixMarkdownList, &str)> {
+    let trimmed = line.trim_start();
+    if let Some(item) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+    {
+        return Some((MatrixMarkdownList::Unordered, item));
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        if let Some(item) = trimmed[digits..].strip_prefix(". ") {
+            return Some((MatrixMarkdownList::Ordered, item));
+        }
+    }
+    None
+}
+
+fn matrix_markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        Some((level, trimmed[level + 1..].trim()))
+    } else {
+        None
+    }
+}
+
+/// Renders the subset of Markdown agents commonly produce (fenced code, headings,
+/// lists, inline code, bold) to Matrix HTML, turning MXIDs into matrix.to pills.
+fn render_matrix_markdown_html(text: &str, user_ids: &[String]) -> String {
+    let mut out = String::new();
+    let mut list: Option<MatrixMarkdownList> = None;
+    let mut code_block: Option<String> = None;
+    let mut previous_was_text = false;
+
+    let close_list = |out: &mut String, list: &mut Option<MatrixMarkdownList>| match list.take() {
+        Some(MatrixMarkdownList::Unordered) => out.push_str("</ul>"),
+        Some(MatrixMarkdownList::Ordered) => out.push_str("</ol>"),
+        None => {}
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if let Some(code) = code_block.as_mut() {
+            if trimmed.starts_with("```") {
+                out.push_str(code);
+                out.push_str("</code></pre>");
+                code_block = None;
+            } else {
+                code.push_str(&html_escape(line));
+                code.push('\n');
+            }
+            continue;
+        }
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            close_list(&mut out, &mut list);
+            previous_was_text = false;
+            let lang = lang.trim();
+            if lang.is_empty() {
+                out.push_str("<pre><code>");
+            } else {
+                out.push_str(&format!(
+                    "<pre><code class=\"language-{}\">",
+                    html_escape(lang)
+                ));
+            }
+            code_block = Some(String::new());
+            continue;
+        }
+
+        if let Some((kind, item)) = matrix_markdown_list_item(line) {
+            if list != Some(kind) {
+                close_list(&mut out, &mut list);
+                out.push_str(match kind {
+                    MatrixMarkdownList::Unordered => "<ul>",
+                    MatrixMarkdownList::Ordered => "<ol>",
+                });
+                list = Some(kind);
+            }
+            out.push_str("<li>");
+            out.push_str(&render_matrix_inline_markdown(item, user_ids));
+            out.push_str("</li>");
+            previous_was_text = false;
+            continue;
+        }
+        close_list(&mut out, &mut list);
+
+        if trimmed.is_empty() {
+            if previous_was_text {
+                out.push_str("<br>");
+            }
+            previous_was_text = false;
+            continue;
+        }
+
+        if let Some((level, title)) = matrix_markdown_heading(line) {
+            out.push_str(&format!(
+                "<h{level}>{}</h{level}>",
+                render_matrix_inline_markdown(title, user_ids)
+            ));
+            previous_was_text = false;
+            continue;
+        }
+
+        if previous_was_text {
+            out.push_str("<br>");
+        }
+        out.push_str(&render_matrix_inline_markdown(line, user_ids));
+        previous_was_text = true;
+    }
+
+    if let Some(code) = code_block {
+        out.push_str(&code);
+        out.push_str("</code></pre>");
+    }
+    close_list(&mut out, &mut list);
+    out
+}
+
+fn matrix_formatted_body_for_text(
+    chunk: &str,
+    user_ids: &[String],
+    markdown: bool,
+) -> Option<String> {
+    if markdown {
+        let rendered = render_matrix_markdown_html(chunk, user_ids);
+        let plain = inject_matrix_mention_pills(&html_escape(chunk), user_ids);
+        if rendered != plain || !user_ids.is_empty() {
+            return Some(rendered);
+        }
+        return None;
+    }
+    if user_ids.is_empty() {
+        None
+    } else {
+        Some(inject_matrix_mention_pills(&html_escape(chunk), user_ids))
+    }
+}
+
+fn matrix_message_payload_for_text(chunk: &str, markdown: bool) -> Value {
+    let user_ids = extract_matrix_user_ids(chunk);
+    let mut payload = serde_json::json!({
+        "msgtype": "m.text",
         "body": chunk,
-        "format": "org.matrix.custom.html",
-        "formatted_body": formatted,
-        "m.mentions": {
-            "user_ids": user_ids,
+    });
+    if let Some(formatted) = matrix_formatted_body_for_text(chunk, &user_ids, markdown) {
+        payload["format"] = Value::String("org.matrix.custom.html".to_string());
+        payload["formatted_body"] = Value::String(formatted);
+    }
+    if !user_ids.is_empty() {
+        payload["m.mentions"] = serde_json::json!({ "user_ids": user_ids });
+    }
+    payload
+}
+
+/// Builds an `m.replace` edit of `event_id`. Clients without edit support show
+/// the `* `-prefixed fallback body; others render `m.new_content`.
+fn matrix_edit_payload(event_id: &str, text: &str, markdown: bool) -> Value {
+    let new_content = matrix_message_payload_for_text(text, markdown);
+    let mut payload = new_content.clone();
+    if let Some(obj) = payload.as_object_mut() {
+        obj.remove("m.mentions");
+    }
+    payload["body"] = Value::String(format!("* {text}"));
+    if let Some(formatted) = new_content.get("formatted_body").and_then(|v| v.as_str()) {
+        payload["formatted_body"] = Value::String(format!("* {formatted}"));
+    }
+    payload["m.new_content"] = new_content;
+    payload["m.relates_to"] = serde_json::json!({
+        "rel_type": "m.replace",
+        "event_id": event_id,
+    });
+    payload
+}
+
+const MATRIX_SEND_MAX_ATTEMPTS: u32 = 3;
+const MATRIX_MAX_MESSAGE_LEN: usize = 3800;
+
+/// A failed Matrix client-server API call, classified so callers can choose
+/// how to back off instead of treating every failure the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MatrixError {
+    /// No HTTP response: DNS, connect, TLS or timeout failure.
+    Network(String),
+    /// Any other non-success response.
+    Http { status: u16, body: String },
+    /// HTTP 429 or `M_LIMIT_EXCEEDED`, with the server's delay when given.
+    RateLimited { retry_after: Option<Duration> },
+    /// The response body was not what the endpoint returns.
+    Parse(String),
+    /// HTTP 401 or a token errcode: the access token is missing or expired.
+    Auth(String),
+    /// HTTP 403 with a valid token: the bot may not act here, typically
+    /// because it has not joined the room or lacks the power level.
+    Forbidden(String),
+}
+
+impl MatrixError {
+    fn from_response(status: u16, retry_after_header: Option<&str>, body: &str) -> Self {
+        let errcode = serde_json::from_str::<Value>(body)
+            .ok()
+            .and_then(|v| {
+                v.get("errcode")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+        if status == 429 || errcode == "M_LIMIT_EXCEEDED" {
+            return MatrixError::RateLimited {
+                retry_after: matrix_retry_after_ms(retry_after_header, body)
+                    .map(Duration::from_millis),
+            };
+        }
+        let body: String = body.chars().take(300).collect();
+        if status == 401 || matches!(errcode.as_str(), "M_UNKNOWN_TOKEN" | "M_MISSING_TOKEN") {
+            return MatrixError::Auth(format!("HTTP {status} {body}"));
+        }
+        if status == 403 {
+            return MatrixError::Forbidden(body);
+        }
+        MatrixError::Http { status, body }
+    }
+
+    /// Read a non-success response into an error.
+    async fn from_failed_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let retry_after_header = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(ToOwned::to_owned);
+        let body = response.text().await.unwrap_or_default();
+        Self::from_response(status, retry_after_header.as_deref(), &body)
+    }
+}
+
+impl std::fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixError::Network(e) => write!(f, "Matrix request failed: {e}"),
+            MatrixError::Http { status, body } => {
+                write!(f, "Matrix request failed: HTTP {status} {body}")
+            }
+            MatrixError::RateLimited {
+                retry_after: Some(delay),
+            } => write!(
+                f,
+                "Matrix rate limited, retry after {}ms",
+                delay.as_millis()
+            ),
+            MatrixError::RateLimited { retry_after: None } => write!(f, "Matrix rate limited"),
+            MatrixError::Parse(e) => write!(f, "Matrix response parse failed: {e}"),
+            MatrixError::Auth(e) => write!(f, "Matrix authentication failed: {e}"),
+            MatrixError::Forbidden(e) => write!(
+                f,
+                "Matrix request forbidden (bot not joined or not permitted): {e}"
+            ),
         }
+    }
+}
+
+impl From<MatrixError> for String {
+    fn from(e: MatrixError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Parse a JSON response body, reporting `what` on failure.
+fn parse_matrix_json(what: &str, body: &str) -> Result<Value, MatrixError> {
+    serde_json::from_str(body).map_err(|e| MatrixError::Parse(format!("{what}: {e}")))
+}
+
+async fn read_matrix_json(what: &str, response: reqwest::Response) -> Result<Value, MatrixError> {
+    let body = response
+        .text()
+        .await
+        .map_err(|e| MatrixError::Network(format!("{what}: {e}")))?;
+    parse_matrix_json(what, &body)
+}
+
+/// Extracts the rate-limit delay from a Matrix 429 response, preferring the
+/// `retry_after_ms` body field and falling back to a `Retry-After` seconds header.
+fn matrix_retry_after_ms(retry_after_header: Option<&str>, body: &str) -> Option<u64> {
+    let from_body = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("retry_after_ms").and_then(|v| v.as_u64()));
+    from_body.or_else(|| {
+        retry_after_header
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(|secs| secs.saturating_mul(1000))
     })
 }
 
+/// Sends a Matrix client request, retrying on HTTP 429 with the server-provided
+/// delay. The builder is re-invoked per attempt so the caller keeps the same
+/// transaction id and the send is not duplicated.
+async fn send_matrix_request_with_retry<F>(
+    label: &str,
+    mut build: F,
+) -> Result<reqwest::Response, MatrixError>
+where
+    F: FnMut() -> reqwest::RequestBuilder,
+{
+    let mut attempt = 1;
+    loop {
+        let response = build()
+            .send()
+            .await
+            .map_err(|e| MatrixError::Network(format!("{label}: {e}")))?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        match MatrixError::from_failed_response(response).await {
+            MatrixError::RateLimited { retry_after } if attempt < MATRIX_SEND_MAX_ATTEMPTS => {
+                let delay = retry_after.unwrap_or(Duration::from_secs(1));
+                warn!(
+                    "{label} rate limited (attempt {attempt}), retrying in {}ms",
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            e => return Err(e),
+        }
+    }
+}
+
 async fn send_matrix_message_payload(
     client: &reqwest::Client,
     homeserver_url: &str,
     access_token: &str,
     room_id: &str,
     payload: &Value,
-) -> Result<String, String> {
+) -> Result<String, MatrixError> {
     let homeserver = homeserver_url.trim_end_matches('/');
     let txn_id = uuid::Uuid::new_v4().to_string();
     let url = format!(
@@ -1208,28 +2960,16 @@ async fn send_matrix_message_payload(
         urlencoding::encode(room_id)
     );
 
-    let response = client
-        .put(&url)
-        .bearer_auth(access_token.trim())
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .json(payload)
-        .send()
-        .await
-        .map_err(|e| format!("Matrix send request failed: {e}"))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Matrix send failed: HTTP {status} {}",
-            body.chars().take(300).collect::<String>()
-        ));
-    }
+    let response = send_matrix_request_with_retry("Matrix send", || {
+        client
+            .put(&url)
+            .bearer_auth(access_token.trim())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .json(payload)
+    })
+    .await?;
 
-    let json: Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Matrix send response parse failed: {e}"))?;
+    let json = read_matrix_json("Matrix send response", response).await?;
 
     Ok(json
         .get("event_id")
@@ -1238,15 +2978,105 @@ async fn send_matrix_message_payload(
         .to_string())
 }
 
+/// Sends one raw `m.room.message` payload, through the SDK room when it is
+/// joined and over the client-server API otherwise. Returns the event id.
+async fn send_matrix_payload_with_sdk(
+    sdk_client: Option<Arc<MatrixSdkClient>>,
+    http_client: &reqwest::Client,
+    homeserver_url: &str,
+    access_token: &str,
+    room_id: &str,
+    payload: Value,
+) -> Result<String, String> {
+    if let Some(sdk_client) = sdk_client {
+        let parsed_room_id: OwnedRoomId = room_id
+            .parse()
+            .map_err(|e| format!("Invalid Matrix room id '{room_id}': {e}"))?;
+        if let Some(room) = sdk_client.get_room(&parsed_room_id) {
+            let response = room
+                .send_raw("m.room.message", payload)
+                .await
+                .map_err(|e| format!("Matrix SDK send failed: {e}"))?;
+            return Ok(response.event_id.to_string());
+        }
+    }
+    Ok(
+        send_matrix_message_payload(http_client, homeserver_url, access_token, room_id, &payload)
+            .await?,
+    )
+}
+
+/// Event the bot is answering, used to build an `m.in_reply_to` rich reply.
+struct MatrixReplyTarget {
+    event_id: String,
+    sender: String,
+    body: String,
+}
+
+#[derive(Clone, Copy, Default)]
+struct MatrixTextOptions<'a> {
+    markdown: bool,
+    reply_to: Option<&'a MatrixReplyTarget>,
+}
+
+/// Turns a message payload into a rich reply: adds the `m.in_reply_to` relation
+/// plus the quoted plain-text and `<mx-reply>` HTML fallbacks for older clients.
+fn apply_matrix_reply(payload: &mut Value, room_id: &str, reply: &MatrixReplyTarget) {
+    let body = payload["body"].as_str().unwrap_or_default().to_string();
+    let formatted = payload
+        .get("formatted_body")
+        .and_then(|v| v.as_str())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| html_escape(&body).replace('\n', "<br>"));
+
+    let mut quoted_lines = reply.body.lines();
+    let mut fallback_body = format!(
+        "> <{}> {}",
+        reply.sender,
+        quoted_lines.next().unwrap_or_default()
+    );
+    for line in quoted_lines {
+        fallback_body.push_str("\n> ");
+        fallback_body.push_str(line);
+    }
+    fallback_body.push_str("\n\n");
+    fallback_body.push_str(&body);
+
+    let event_href = format!("https://matrix.to/#/{}/{}", room_id, reply.event_id);
+    let sender_href = format!("https://matrix.to/#/{}", reply.sender);
+    let fallback_html = format!(
+        "<mx-reply><blockquote><a href=\"{}\">In reply to</a> <a href=\"{}\">{}</a><br>{}</blockquote></mx-reply>{}",
+        html_escape(&event_href),
+        html_escape(&sender_href),
+        html_escape(&reply.sender),
+        html_escape(&reply.body).replace('\n', "<br>"),
+        formatted
+    );
+
+    payload["body"] = Value::String(fallback_body);
+    payload["format"] = Value::String("org.matrix.custom.html".to_string());
+    payload["formatted_body"] = Value::String(fallback_html);
+    payload["m.relates_to"] = serde_json::json!({
+        "m.in_reply_to": { "event_id": reply.event_id }
+    });
+}
+
 async fn send_matrix_text(
     client: &reqwest::Client,
     homeserver_url: &str,
     access_token: &str,
     room_id: &str,
     text: &str,
-) -> Result<(), String> {
-    for chunk in split_text(text, 3800) {
-        let payload = matrix_message_payload_for_text(&chunk);
+    options: MatrixTextOptions<'_>,
+) -> Result<(), MatrixError> {
+    for (idx, chunk) in split_text_preserving_code(text, MATRIX_MAX_MESSAGE_LEN)
+        .into_iter()
+        .enumerate()
+    {
+        let mut payload = matrix_message_payload_for_text(&chunk, options.markdown);
+        if let Some(reply) = options.reply_to.filter(|_| idx == 0) {
+            apply_matrix_reply(&mut payload, room_id, reply);
+        }
         let _ =
             send_matrix_message_payload(client, homeserver_url, access_token, room_id, &payload)
                 .await?;
@@ -1274,14 +3104,34 @@ async fn send_matrix_text_with_sdk(
     access_token: &str,
     room_id: &str,
     text: &str,
+    options: MatrixTextOptions<'_>,
 ) -> Result<(), String> {
     if let Some(sdk_client) = sdk_client {
         let parsed_room_id: OwnedRoomId = room_id
             .parse()
             .map_err(|e| format!("Invalid Matrix room id '{room_id}': {e}"))?;
         if let Some(room) = sdk_client.get_room(&parsed_room_id) {
-            for chunk in split_text(text, 3800) {
-                let mut content = RoomMessageEventContent::text_plain(chunk.clone());
+            for (idx, chunk) in split_text_preserving_code(text, MATRIX_MAX_MESSAGE_LEN)
+                .into_iter()
+                .enumerate()
+            {
+                if let Some(reply) = options.reply_to.filter(|_| idx == 0) {
+                    let mut payload = matrix_message_payload_for_text(&chunk, options.markdown);
+                    apply_matrix_reply(&mut payload, room_id, reply);
+                    room.send_raw("m.room.message", payload)
+                        .await
+                        .map_err(|e| format!("Matrix SDK send failed: {e}"))?;
+                    continue;
+                }
+                let user_ids = extract_matrix_user_ids(&chunk);
+                let mut content = if options.markdown {
+                    match matrix_formatted_body_for_text(&chunk, &user_ids, true) {
+                        Some(html) => RoomMessageEventContent::text_html(chunk.clone(), html),
+                        None => RoomMessageEventContent::text_plain(chunk.clone()),
+                    }
+                } else {
+                    RoomMessageEventContent::text_plain(chunk.clone())
+                };
                 content.mentions = matrix_mentions_for_text(&chunk);
                 room.send(content)
                     .await
@@ -1291,7 +3141,15 @@ async fn send_matrix_text_with_sdk(
         }
     }
 
-    send_matrix_text(http_client, homeserver_url, access_token, room_id, text).await
+    Ok(send_matrix_text(
+        http_client,
+        homeserver_url,
+        access_token,
+        room_id,
+        text,
+        options,
+    )
+    .await?)
 }
 
 async fn send_matrix_text_runtime(
@@ -1299,6 +3157,16 @@ async fn send_matrix_text_runtime(
     room_id: &str,
     text: &str,
     prefer_sdk_send: bool,
+) -> Result<(), String> {
+    send_matrix_reply_runtime(runtime, room_id, text, prefer_sdk_send, None).await
+}
+
+async fn send_matrix_reply_runtime(
+    runtime: &MatrixRuntimeContext,
+    room_id: &str,
+    text: &str,
+    prefer_sdk_send: bool,
+    reply_to: Option<&MatrixReplyTarget>,
 ) -> Result<(), String> {
     let sdk_client = if prefer_sdk_send {
         match runtime.sdk_client.as_ref() {
@@ -1311,44 +3179,21 @@ async fn send_matrix_text_runtime(
     } else {
         None
     };
-    let http_client = reqwest::Client::new();
     send_matrix_text_with_sdk(
         sdk_client,
-        &http_client,
+        &runtime.http_client,
         &runtime.homeserver_url,
         &runtime.access_token,
         room_id,
         text,
+        MatrixTextOptions {
+            markdown: runtime.markdown,
+            reply_to,
+        },
     )
     .await
 }
 
-fn guess_mime_from_extension(path: &Path) -> &'static str {
-    match path
-        .extension()
-        .and_then(|v| v.to_str())
-        .map(|v| v.to_ascii_lowercase())
-        .as_deref()
-    {
-        Some("png") => "image/png",
-        Some("jpg") | Some("jpeg") => "image/jpeg",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        Some("svg") => "image/svg+xml",
-        Some("pdf") => "application/pdf",
-        Some("txt") => "text/plain",
-        Some("json") => "application/json",
-        Some("md") => "text/markdown",
-        Some("zip") => "application/zip",
-        Some("mp3") => "audio/mpeg",
-        Some("wav") => "audio/wav",
-        Some("ogg") => "audio/ogg",
-        Some("mp4") => "video/mp4",
-        Some("mov") => "video/quicktime",
-        _ => "application/octet-stream",
-    }
-}
-
 fn matrix_msgtype_for_mime(mime: &str) -> &'static str {
     if mime.starts_with("image/") {
         "m.image"
@@ -1361,6 +3206,35 @@ fn matrix_msgtype_for_mime(mime: &str) -> &'static str {
     }
 }
 
+/// Upload `bytes` to the media repository and return the `mxc://` URI.
+async fn upload_matrix_media(
+    client: &reqwest::Client,
+    homeserver_url: &str,
+    access_token: &str,
+    file_name: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> Result<String, MatrixError> {
+    let homeserver = homeserver_url.trim_end_matches('/');
+    let upload_url = format!(
+        "{homeserver}/_matrix/media/v3/upload?filename={}",
+        urlencoding::encode(file_name)
+    );
+    let response = send_matrix_request_with_retry("Matrix media upload", || {
+        client
+            .post(&upload_url)
+            .bearer_auth(access_token.trim())
+            .header(reqwest::header::CONTENT_TYPE, mime)
+            .body(bytes.to_vec())
+    })
+    .await?;
+    let json = read_matrix_json("Matrix media upload response", response).await?;
+    json.get("content_uri")
+        .and_then(|v| v.as_str())
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| MatrixError::Parse("Matrix media upload missing content_uri".into()))
+}
+
 async fn send_matrix_attachment(
     client: &reqwest::Client,
     homeserver_url: &str,
@@ -1379,38 +3253,16 @@ async fn send_matrix_attachment(
         .to_string();
 
     let mime = guess_mime_from_extension(file_path);
-    let homeserver = homeserver_url.trim_end_matches('/');
-    let upload_url = format!(
-        "{homeserver}/_matrix/media/v3/upload?filename={}",
-        urlencoding::encode(&file_name)
-    );
-
-    let upload_response = client
-        .post(&upload_url)
-        .bearer_auth(access_token.trim())
-        .header(reqwest::header::CONTENT_TYPE, mime)
-        .body(bytes.clone())
-        .send()
-        .await
-        .map_err(|e| format!("Matrix media upload failed: {e}"))?;
-
-    if !upload_response.status().is_success() {
-        let status = upload_response.status();
-        let body = upload_response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Matrix media upload failed: HTTP {status} {}",
-            body.chars().take(300).collect::<String>()
-        ));
-    }
-
-    let upload_json: Value = upload_response
-        .json()
-        .await
-        .map_err(|e| format!("Matrix media upload parse failed: {e}"))?;
 
-    let Some(content_uri) = upload_json.get("content_uri").and_then(|v| v.as_str()) else {
-        return Err("Matrix media upload missing content_uri".to_string());
-    };
+    let content_uri = upload_matrix_media(
+        client,
+        homeserver_url,
+        access_token,
+        &file_name,
+        mime,
+        &bytes,
+    )
+    .await?;
 
     let msgtype = matrix_msgtype_for_mime(mime);
     let mut payload = serde_json::json!({
@@ -1432,7 +3284,15 @@ async fn send_matrix_attachment(
         .await?;
 
     if let Some(c) = caption.map(str::trim).filter(|v| !v.is_empty()) {
-        send_matrix_text(client, homeserver_url, access_token, room_id, c).await?;
+        send_matrix_text(
+            client,
+            homeserver_url,
+            access_token,
+            room_id,
+            c,
+            MatrixTextOptions::default(),
+        )
+        .await?;
     }
 
     Ok(match caption {
@@ -1480,6 +3340,7 @@ async fn send_matrix_attachment_with_sdk(
                     access_token,
                     room_id,
                     c,
+                    MatrixTextOptions::default(),
                 )
                 .await?;
             }
@@ -1516,6 +3377,65 @@ fn looks_like_reaction_token(text: &str) -> Option<String> {
     Some(trimmed.to_string())
 }
 
+/// How many sent reactions the bot remembers for later removal.
+const MATRIX_REACTION_LOG_CAPACITY: usize = 1024;
+
+/// Event ids of reactions the bot sent, keyed by
+/// `(channel, room_id, target_event_id, key)`, so they can later be redacted.
+/// The oldest entries are evicted once the log is full.
+struct MatrixReactionLog {
+    entries: LruCache<(String, String, String, String), String>,
+}
+
+impl Default for MatrixReactionLog {
+    fn default() -> Self {
+        Self::with_capacity(MATRIX_REACTION_LOG_CAPACITY)
+    }
+}
+
+impl MatrixReactionLog {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::new(NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)),
+        }
+    }
+
+    fn record(&mut self, channel: &str, room_id: &str, target: &str, key: &str, event_id: &str) {
+        if event_id.trim().is_empty() {
+            return;
+        }
+        self.entries.put(
+            (
+                channel.to_string(),
+                room_id.to_string(),
+                target.to_string(),
+                key.to_string(),
+            ),
+            event_id.to_string(),
+        );
+    }
+
+    fn take(&mut self, channel: &str, room_id: &str, target: &str, key: &str) -> Option<String> {
+        self.entries.pop(&(
+            channel.to_string(),
+            room_id.to_string(),
+            target.to_string(),
+            key.to_string(),
+        ))
+    }
+}
+
+fn matrix_reaction_log() -> &'static Mutex<MatrixReactionLog> {
+    static LOG: OnceLock<Mutex<MatrixReactionLog>> = OnceLock::new();
+    LOG.get_or_init(|| Mutex::new(MatrixReactionLog::default()))
+}
+
+fn record_matrix_reaction(channel: &str, room_id: &str, target: &str, key: &str, event_id: &str) {
+    if let Ok(mut log) = matrix_reaction_log().lock() {
+        log.record(channel, room_id, target, key, event_id);
+    }
+}
+
 async fn send_matrix_reaction(
     client: &reqwest::Client,
     homeserver_url: &str,
@@ -1523,7 +3443,7 @@ async fn send_matrix_reaction(
     room_id: &str,
     target_event_id: &str,
     key: &str,
-) -> Result<(), String> {
+) -> Result<String, String> {
     let homeserver = homeserver_url.trim_end_matches('/');
     let txn_id = uuid::Uuid::new_v4().to_string();
     let url = format!(
@@ -1539,25 +3459,21 @@ async fn send_matrix_reaction(
         }
     });
 
-    let response = client
-        .put(&url)
-        .bearer_auth(access_token.trim())
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Matrix reaction send failed: {e}"))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Matrix reaction send failed: HTTP {status} {}",
-            body.chars().take(300).collect::<String>()
-        ));
-    }
+    let response = send_matrix_request_with_retry("Matrix reaction send", || {
+        client
+            .put(&url)
+            .bearer_auth(access_token.trim())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .json(&payload)
+    })
+    .await?;
 
-    Ok(())
+    let json: Value = response.json().await.unwrap_or(Value::Null);
+    Ok(json
+        .get("event_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string())
 }
 
 async fn send_matrix_reaction_runtime(
@@ -1585,24 +3501,169 @@ async fn send_matrix_reaction_runtime(
                         parsed_event_id,
                         key.to_string(),
                     ));
-                    room.send(content)
+                    let response = room
+                        .send(content)
                         .await
                         .map_err(|e| format!("Matrix SDK reaction send failed: {e}"))?;
+                    record_matrix_reaction(
+                        &runtime.channel_name,
+                        room_id,
+                        target_event_id,
+                        key,
+                        response.event_id.as_str(),
+                    );
                     return Ok(());
                 }
             }
         }
     }
 
-    send_matrix_reaction(
-        &reqwest::Client::new(),
+    let event_id = send_matrix_reaction(
+        &runtime.http_client,
         &runtime.homeserver_url,
         &runtime.access_token,
         room_id,
         target_event_id,
         key,
     )
-    .await
+    .await?;
+    record_matrix_reaction(
+        &runtime.channel_name,
+        room_id,
+        target_event_id,
+        key,
+        &event_id,
+    );
+    Ok(())
+}
+
+async fn redact_matrix_event(
+    client: &reqwest::Client,
+    homeserver_url: &str,
+    access_token: &str,
+    room_id: &str,
+    event_id: &str,
+) -> Result<(), String> {
+    let txn_id = uuid::Uuid::new_v4().to_string();
+    let url = format!(
+        "{}/_matrix/client/v3/rooms/{}/redact/{}/{txn_id}",
+        homeserver_url.trim_end_matches('/'),
+        urlencoding::encode(room_id),
+        urlencoding::encode(event_id)
+    );
+    send_matrix_request_with_retry("Matrix redact", || {
+        client
+            .put(&url)
+            .bearer_auth(access_token.trim())
+            .json(&serde_json::json!({}))
+    })
+    .await?;
+    Ok(())
+}
+
+/// Redacts a reaction the bot previously sent. Fails when no matching reaction
+/// was recorded for this channel.
+async fn remove_matrix_reaction(
+    client: &reqwest::Client,
+    channel_name: &str,
+    homeserver_url: &str,
+    access_token: &str,
+    room_id: &str,
+    target_event_id: &str,
+    key: &str,
+) -> Result<(), String> {
+    let event_id = matrix_reaction_log()
+        .lock()
+        .ok()
+        .and_then(|mut log| log.take(channel_name, room_id, target_event_id, key))
+        .ok_or_else(|| {
+            format!("No reaction '{key}' from the bot on {target_event_id} to remove")
+        })?;
+    if let Err(e) =
+        redact_matrix_event(client, homeserver_url, access_token, room_id, &event_id).await
+    {
+        record_matrix_reaction(channel_name, room_id, target_event_id, key, &event_id);
+        return Err(e);
+    }
+    Ok(())
+}
+
+const MATRIX_TYPING_TIMEOUT_MS: u64 = 30_000;
+const MATRIX_TYPING_REFRESH: Duration = Duration::from_secs(20);
+
+fn matrix_typing_url(homeserver_url: &str, room_id: &str, user_id: &str) -> String {
+    format!(
+        "{}/_matrix/client/v3/rooms/{}/typing/{}",
+        homeserver_url.trim_end_matches('/'),
+        urlencoding::encode(room_id),
+        urlencoding::encode(user_id)
+    )
+}
+
+fn matrix_typing_payload(typing: bool) -> Value {
+    if typing {
+        serde_json::json!({ "typing": true, "timeout": MATRIX_TYPING_TIMEOUT_MS })
+    } else {
+        serde_json::json!({ "typing": false })
+    }
+}
+
+async fn send_matrix_typing(client: &reqwest::Client, url: &str, access_token: &str, typing: bool) {
+    let result = client
+        .put(url)
+        .bearer_auth(access_token.trim())
+        .json(&matrix_typing_payload(typing))
+        .send()
+        .await;
+    if let Err(e) = result {
+        warn!("Matrix typing notification failed: {e}");
+    }
+}
+
+/// Keeps a typing notification alive while the agent runs. Dropping it aborts the
+/// refresh task and clears the indicator, so every exit path stops typing.
+struct MatrixTypingIndicator {
+    handle: tokio::task::JoinHandle<()>,
+    client: reqwest::Client,
+    url: String,
+    access_token: String,
+}
+
+impl MatrixTypingIndicator {
+    fn start(runtime: &MatrixRuntimeContext, room_id: &str) -> Self {
+        let url = matrix_typing_url(&runtime.homeserver_url, room_id, &runtime.bot_user_id);
+        let access_token = runtime.access_token.clone();
+        let client = runtime.http_client.clone();
+        let handle = tokio::spawn({
+            let client = client.clone();
+            let url = url.clone();
+            let access_token = access_token.clone();
+            async move {
+                loop {
+                    send_matrix_typing(&client, &url, &access_token, true).await;
+                    tokio::time::sleep(MATRIX_TYPING_REFRESH).await;
+                }
+            }
+        });
+        Self {
+            handle,
+            client,
+            url,
+            access_token,
+        }
+    }
+}
+
+impl Drop for MatrixTypingIndicator {
+    fn drop(&mut self) {
+        self.handle.abort();
+        let url = std::mem::take(&mut self.url);
+        let access_token = std::mem::take(&mut self.access_token);
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            send_matrix_typing(&client, &url, &access_token, false).await;
+        });
+    }
 }
 
 struct MatrixIncomingMessage {
@@ -1612,6 +3673,7 @@ struct MatrixIncomingMessage {
     event_id: String,
     body: String,
     mentioned_bot: bool,
+    media: Option<MatrixMediaRef>,
     prefer_sdk_send: bool,
     event_time_ms: Option<i64>,
 }
@@ -1626,6 +3688,15 @@ struct MatrixIncomingReaction {
     event_time_ms: Option<i64>,
 }
 
+struct MatrixIncomingEdit {
+    room_id: String,
+    is_direct: bool,
+    sender: String,
+    event_id: String,
+    original_event_id: String,
+    body: String,
+}
+
 async fn resolve_matrix_chat_id(
     app_state: Arc<AppState>,
     runtime: &MatrixRuntimeContext,
@@ -1652,6 +3723,9 @@ async fn handle_matrix_reaction(
     runtime: MatrixRuntimeContext,
     reaction: MatrixIncomingReaction,
 ) {
+    if should_drop_recent_duplicate_message(&runtime.channel_name, &reaction.event_id) {
+        return;
+    }
     let chat_lock = matrix_chat_lock(&runtime.channel_name, &reaction.room_id);
     let _guard = chat_lock.lock().await;
 
@@ -1682,20 +3756,24 @@ async fn handle_matrix_reaction(
     ) {
         return;
     }
+    let sender_name = runtime
+        .member_names
+        .sender_name(&reaction.room_id, &reaction.sender);
     let reaction_text = format!(
         "[reaction] {} reacted {} to {}",
-        reaction.sender, reaction.key, reaction.relates_to_event_id
+        sender_name, reaction.key, reaction.relates_to_event_id
     );
     let incoming = StoredMessage {
         id: inbound_event_id.clone(),
         chat_id,
-        sender_name: reaction.sender,
+        sender_name,
         content: reaction_text,
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
+    let sender_id = reaction.sender.clone();
     let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&incoming)
+        db.store_message_if_new_with_sender_id(&incoming, &sender_id)
     })
     .await
     .unwrap_or(false);
@@ -1707,10 +3785,246 @@ async fn handle_matrix_reaction(
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+enum MatrixEditOutcome {
+    Applied,
+    /// The editor did not send the original, or it is a bot reply.
+    Rejected,
+    OriginalMissing,
+}
+
+/// Apply an `m.replace` edit to the stored original. Matrix servers do not
+/// stop anyone from sending a replacement for someone else's event, so only
+/// the original sender may rewrite a message and bot replies never change.
+fn apply_matrix_edit(
+    db: &Database,
+    chat_id: i64,
+    original_event_id: &str,
+    editor_id: &str,
+    body: &str,
+) -> Result<MatrixEditOutcome, MicroClawError> {
+    let Some(original) = db.get_message(chat_id, original_event_id)? else {
+        return Ok(MatrixEditOutcome::OriginalMissing);
+    };
+    // Display names are user-chosen, so authorship is checked by MXID.
+    // Messages stored before MXIDs were recorded cannot be edited.
+    let author_id = db.get_message_sender_id(chat_id, original_event_id)?;
+    if original.is_from_bot || author_id.as_deref() != Some(editor_id) {
+        return Ok(MatrixEditOutcome::Rejected);
+    }
+    if db.update_message_content(chat_id, original_event_id, body)? {
+        Ok(MatrixEditOutcome::Applied)
+    } else {
+        Ok(MatrixEditOutcome::OriginalMissing)
+    }
+}
+
+async fn handle_matrix_edit(
+    app_state: Arc<AppState>,
+    runtime: MatrixRuntimeContext,
+    edit: MatrixIncomingEdit,
+) {
+    if should_drop_recent_duplicate_message(&runtime.channel_name, &edit.event_id) {
+        return;
+    }
+    let chat_lock = matrix_chat_lock(&runtime.channel_name, &edit.room_id);
+    let _guard = chat_lock.lock().await;
+
+    let chat_id =
+        resolve_matrix_chat_id(app_state.clone(), &runtime, &edit.room_id, edit.is_direct).await;
+    if chat_id == 0 {
+        error!(
+            "Matrix: failed to resolve chat ID for room {}",
+            edit.room_id
+        );
+        return;
+    }
+
+    let editor_name = runtime
+        .member_names
+        .sender_name(&edit.room_id, &edit.sender);
+    let outcome = call_blocking(app_state.db.clone(), {
+        let original_event_id = edit.original_event_id.clone();
+        let editor_id = edit.sender.clone();
+        let body = edit.body.clone();
+        move |db| apply_matrix_edit(db, chat_id, &original_event_id, &editor_id, &body)
+    })
+    .await;
+    match outcome {
+        Err(e) => {
+            warn!(
+                "Matrix: failed to apply edit of event {}: {e}",
+                edit.original_event_id
+            );
+            return;
+        }
+        Ok(MatrixEditOutcome::Applied) => {
+            info!(
+                "Matrix: applied edit chat_id={} original_event_id={}",
+                chat_id, edit.original_event_id
+            );
+            return;
+        }
+        Ok(MatrixEditOutcome::Rejected) => {
+            warn!(
+                "Matrix: ignoring edit by {} of event {} they did not send",
+                edit.sender, edit.original_event_id
+            );
+            return;
+        }
+        Ok(MatrixEditOutcome::OriginalMissing) => {}
+    }
+
+    // Original not stored (e.g. sent before the bot joined): keep the edit as a new message.
+    let id = if edit.event_id.trim().is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        edit.event_id
+    };
+    let incoming = StoredMessage {
+        id,
+        chat_id,
+        sender_name: editor_name,
+        content: edit.body,
+        is_from_bot: false,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    let _ = call_blocking(app_state.db.clone(), move |db| {
+        db.store_message_if_new_with_sender_id(&incoming, &edit.sender)
+    })
+    .await;
+}
+
+const MATRIX_ENCRYPTED_ROOM_NOTICE: &str =
+    "I can't read encrypted messages in this room. Please message me in an unencrypted room.";
+
+/// Rooms per channel that have already been warned about encrypted events.
+#[derive(Default)]
+struct MatrixEncryptedRoomWarnings {
+    warned: HashSet<(String, String)>,
+}
+
+impl MatrixEncryptedRoomWarnings {
+    /// Returns true only the first time a room is seen for a channel.
+    fn first_seen(&mut self, channel: &str, room_id: &str) -> bool {
+        self.warned
+            .insert((channel.to_string(), room_id.to_string()))
+    }
+}
+
+fn matrix_encrypted_room_warnings() -> &'static Mutex<MatrixEncryptedRoomWarnings> {
+    static WARNINGS: OnceLock<Mutex<MatrixEncryptedRoomWarnings>> = OnceLock::new();
+    WARNINGS.get_or_init(|| Mutex::new(MatrixEncryptedRoomWarnings::default()))
+}
+
+async fn handle_matrix_encrypted_event(runtime: &MatrixRuntimeContext, room_id: &str) {
+    let first_seen = matrix_encrypted_room_warnings()
+        .lock()
+        .map(|mut warnings| warnings.first_seen(&runtime.channel_name, room_id))
+        .unwrap_or(false);
+    if !first_seen {
+        return;
+    }
+
+    warn!(
+        "Matrix adapter '{}' received encrypted events in {} but E2EE is unavailable; skipping them",
+        runtime.channel_name.as_str(),
+        room_id
+    );
+    if !runtime.encrypted_room_notice {
+        return;
+    }
+    if let Err(e) = send_matrix_text(
+        &runtime.http_client,
+        &runtime.homeserver_url,
+        &runtime.access_token,
+        room_id,
+        MATRIX_ENCRYPTED_ROOM_NOTICE,
+        MatrixTextOptions::default(),
+    )
+    .await
+    {
+        warn!(
+            "Matrix: failed to send encrypted room notice to {}: {e}",
+            room_id
+        );
+    }
+}
+
+async fn handle_matrix_redaction(
+    app_state: Arc<AppState>,
+    runtime: MatrixRuntimeContext,
+    room_id: &str,
+    is_direct: bool,
+    redacts: &str,
+) {
+    let chat_lock = matrix_chat_lock(&runtime.channel_name, room_id);
+    let _guard = chat_lock.lock().await;
+
+    let chat_id = resolve_matrix_chat_id(app_state.clone(), &runtime, room_id, is_direct).await;
+    if chat_id == 0 {
+        error!("Matrix: failed to resolve chat ID for room {}", room_id);
+        return;
+    }
+
+    let deleted = call_blocking(app_state.db.clone(), {
+        let redacts = redacts.to_string();
+        move |db| db.delete_message_by_id(chat_id, &redacts)
+    })
+    .await
+    .unwrap_or(false);
+    if deleted {
+        info!(
+            "Matrix: removed redacted message chat_id={} event_id={}",
+            chat_id, redacts
+        );
+    }
+}
+
+const MATRIX_NOT_AUTHORIZED_REPLY: &str =
+    "Not authorized: this command is limited to Matrix admins.";
+
+const MATRIX_EMPTY_REPLY_FALLBACK: &str =
+    "I couldn't produce a visible reply after an automatic retry. Please try again.";
+
+/// Id shared by every log line about one inbound message: the Matrix event
+/// id when the server sent one, otherwise a fresh UUID.
+fn matrix_correlation_id(event_id: &str) -> String {
+    if event_id.trim().is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        event_id.to_string()
+    }
+}
+
+/// Span covering one inbound message from storage through the agent run to
+/// the reply; `chat_id` is recorded once the room has been resolved.
+fn matrix_message_span(channel_name: &str, correlation_id: &str) -> tracing::Span {
+    tracing::info_span!(
+        "matrix_message",
+        channel = channel_name,
+        event_id = correlation_id,
+        chat_id = tracing::field::Empty
+    )
+}
+
 async fn handle_matrix_message(
     app_state: Arc<AppState>,
     runtime: MatrixRuntimeContext,
     msg: MatrixIncomingMessage,
+) {
+    let correlation_id = matrix_correlation_id(&msg.event_id);
+    let span = matrix_message_span(&runtime.channel_name, &correlation_id);
+    process_matrix_message(app_state, runtime, msg, correlation_id)
+        .instrument(span)
+        .await
+}
+
+async fn process_matrix_message(
+    app_state: Arc<AppState>,
+    runtime: MatrixRuntimeContext,
+    msg: MatrixIncomingMessage,
+    inbound_event_id: String,
 ) {
     let chat_id =
         resolve_matrix_chat_id(app_state.clone(), &runtime, &msg.room_id, msg.is_direct).await;
@@ -1719,24 +4033,34 @@ async fn handle_matrix_message(
         error!("Matrix: failed to resolve chat ID for room {}", msg.room_id);
         return;
     }
+    tracing::Span::current().record("chat_id", chat_id);
 
-    let inbound_event_id = if msg.event_id.trim().is_empty() {
-        uuid::Uuid::new_v4().to_string()
-    } else {
-        msg.event_id.clone()
-    };
     if should_drop_pre_start_message(&runtime.channel_name, &inbound_event_id, msg.event_time_ms) {
         return;
     }
     if should_drop_recent_duplicate_message(&runtime.channel_name, &inbound_event_id) {
         return;
     }
-    let should_respond = runtime.should_respond(&msg.body, msg.mentioned_bot, msg.is_direct);
+    let should_respond =
+        runtime.should_respond(&msg.room_id, &msg.body, msg.mentioned_bot, msg.is_direct);
     let trimmed = msg.body.trim();
+    crate::metrics::record_message_received(&runtime.channel_name);
     if is_slash_command(trimmed) {
         if !should_respond && !app_state.config.allow_group_slash_without_mention {
             return;
         }
+        if !runtime.is_admin(&msg.sender)
+            && is_mutating_command(trimmed, app_state.config.reset_default_scope)
+        {
+            let _ = send_matrix_text_runtime(
+                &runtime,
+                &msg.room_id,
+                MATRIX_NOT_AUTHORIZED_REPLY,
+                msg.prefer_sdk_send,
+            )
+            .await;
+            return;
+        }
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime.channel_name, trimmed).await
         {
@@ -1757,16 +4081,38 @@ async fn handle_matrix_message(
     let chat_lock = matrix_chat_lock(&runtime.channel_name, &msg.room_id);
     let _guard = chat_lock.lock().await;
 
+    let mut msg = msg;
+    if runtime.download_media {
+        if let Some(media) = msg.media.as_ref() {
+            match download_matrix_media(&app_state, &runtime, &msg.room_id, media).await {
+                Ok(Some(path)) => {
+                    msg.body = format!(
+                        "[attachment:{}] {} saved_path={}",
+                        media.msgtype,
+                        media.body,
+                        path.display()
+                    );
+                }
+                Ok(None) => info!(
+                    "Matrix: skipping attachment download over size limit event_id={}",
+                    inbound_event_id
+                ),
+                Err(e) => warn!("Matrix: {e}"),
+            }
+        }
+    }
+
     let incoming = StoredMessage {
         id: inbound_event_id.clone(),
         chat_id,
-        sender_name: msg.sender.clone(),
+        sender_name: runtime.member_names.sender_name(&msg.room_id, &msg.sender),
         content: msg.body.clone(),
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
+    let sender_id = msg.sender.clone();
     let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&incoming)
+        db.store_message_if_new_with_sender_id(&incoming, &sender_id)
     })
     .await
     .unwrap_or(false);
@@ -1780,6 +4126,17 @@ async fn handle_matrix_message(
     if !should_respond {
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime.channel_name,
+        chat_id,
+        &msg.sender,
+        &msg.room_id,
+    )
+    .await
+    {
+        return;
+    }
 
     info!(
         "Matrix message from {} in {}: {}",
@@ -1789,8 +4146,61 @@ async fn handle_matrix_message(
     );
 
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
+    let mut streaming = if runtime.stream_responses {
+        app_state
+            .channel_registry
+            .get(&runtime.channel_name)
+            .cloned()
+            .and_then(|adapter| StreamingReply::new(adapter, &msg.room_id))
+    } else {
+        None
+    };
+    let mut tool_progress = if runtime.show_tool_progress {
+        app_state
+            .channel_registry
+            .get(&runtime.channel_name)
+            .cloned()
+            .and_then(|adapter| ToolProgress::new(adapter, &msg.room_id))
+    } else {
+        None
+    };
+    let event_consumer = tokio::spawn(
+        async move {
+            let mut used_send_message_tool = false;
+            while let Some(event) = event_rx.recv().await {
+                if let AgentEvent::ToolStart { name } = &event {
+                    if name == "send_message" {
+                        used_send_message_tool = true;
+                    }
+                }
+                if let Some(progress) = tool_progress.as_mut() {
+                    progress.handle_event(&event).await;
+                }
+                if let Some(reply) = streaming.as_mut() {
+                    reply.handle_event(&event).await;
+                }
+            }
+            (used_send_message_tool, streaming, tool_progress)
+        }
+        .in_current_span(),
+    );
 
-    match process_with_agent_with_events(
+    let agent_slots = matrix_agent_semaphore(
+        &runtime.channel_name,
+        app_state.config.max_concurrent_agents,
+    );
+    if agent_slots.available_permits() == 0 {
+        debug!(
+            "Matrix adapter '{}' at max_concurrent_agents; queueing message in {}",
+            runtime.channel_name.as_str(),
+            msg.room_id
+        );
+    }
+    let agent_slot = agent_slots.acquire_owned().await.ok();
+    let typing = runtime
+        .typing_indicator
+        .then(|| MatrixTypingIndicator::start(&runtime, &msg.room_id));
+    let result = process_with_agent_with_events(
         &app_state,
         AgentRequestContext {
             caller_channel: &runtime.channel_name,
@@ -1801,27 +4211,55 @@ async fn handle_matrix_message(
         None,
         Some(&event_tx),
     )
-    .await
-    {
+    .await;
+    drop(typing);
+    drop(agent_slot);
+    drop(event_tx);
+    let (used_send_message_tool, streaming, tool_progress) =
+        event_consumer.await.unwrap_or((false, None, None));
+    if let Some(progress) = tool_progress {
+        progress.finish().await;
+    }
+
+    match result {
         Ok(response) => {
-            drop(event_tx);
-            let mut used_send_message_tool = false;
-            while let Some(event) = event_rx.recv().await {
-                if let AgentEvent::ToolStart { name } = event {
-                    if name == "send_message" {
-                        used_send_message_tool = true;
-                    }
-                }
-            }
-
             if used_send_message_tool {
+                // The tool already delivered the answer; drop any partial stream.
+                if let Some(reply) = streaming {
+                    reply.discard().await;
+                }
                 if !response.is_empty() {
                     info!(
                         "Matrix: suppressing final response for chat {} because send_message already delivered output",
                         chat_id
                     );
                 }
-            } else if !response.is_empty() {
+                return;
+            }
+            if let Some(reply) = streaming {
+                let final_text = if response.is_empty() {
+                    MATRIX_EMPTY_REPLY_FALLBACK
+                } else {
+                    response.as_str()
+                };
+                // The streamed message already holds the reply; store only the final text.
+                if let Some(message_id) = reply.finish(final_text).await {
+                    let bot_msg = StoredMessage {
+                        id: message_id,
+                        chat_id,
+                        sender_name: runtime.bot_username.clone(),
+                        content: final_text.to_string(),
+                        is_from_bot: true,
+                        timestamp: chrono::Utc::now().to_rfc3339(),
+                    };
+                    let _ =
+                        call_blocking(app_state.db.clone(), move |db| db.store_message(&bot_msg))
+                            .await;
+                    return;
+                }
+            }
+
+            if !response.is_empty() {
                 if let Some(reaction_key) = looks_like_reaction_token(&response) {
                     if !msg.event_id.trim().is_empty() {
                         if let Err(e) = send_matrix_reaction_runtime(
@@ -1852,9 +4290,21 @@ async fn handle_matrix_message(
                     }
                 }
 
-                if let Err(e) =
-                    send_matrix_text_runtime(&runtime, &msg.room_id, &response, msg.prefer_sdk_send)
-                        .await
+                let reply_to =
+                    (runtime.reply_to_sender && !msg.is_direct && !msg.event_id.trim().is_empty())
+                        .then(|| MatrixReplyTarget {
+                            event_id: msg.event_id.clone(),
+                            sender: msg.sender.clone(),
+                            body: msg.body.clone(),
+                        });
+                if let Err(e) = send_matrix_reply_runtime(
+                    &runtime,
+                    &msg.room_id,
+                    &response,
+                    msg.prefer_sdk_send,
+                    reply_to.as_ref(),
+                )
+                .await
                 {
                     error!("Matrix: failed to send response: {e}");
                 }
@@ -1870,8 +4320,7 @@ async fn handle_matrix_message(
                 let _ =
                     call_blocking(app_state.db.clone(), move |db| db.store_message(&bot_msg)).await;
             } else {
-                let fallback =
-                    "I couldn't produce a visible reply after an automatic retry. Please try again.";
+                let fallback = MATRIX_EMPTY_REPLY_FALLBACK;
                 let _ =
                     send_matrix_text_runtime(&runtime, &msg.room_id, fallback, msg.prefer_sdk_send)
                         .await;
@@ -1890,6 +4339,11 @@ async fn handle_matrix_message(
         }
         Err(e) => {
             error!("Error processing Matrix message: {e}");
+            if let Some(reply) = streaming {
+                if reply.finish(&format!("Error: {e}")).await.is_some() {
+                    return;
+                }
+            }
             if !should_suppress_user_error(&e) {
                 let _ = send_matrix_text_runtime(
                     &runtime,
@@ -1906,19 +4360,34 @@ async fn handle_matrix_message(
 #[cfg(test)]
 mod tests {
     use super::{
-        extract_matrix_user_ids, is_bot_mentioned_in_mentions, looks_like_reaction_token,
-        matrix_backup_key_candidates, matrix_channel_slug, matrix_mentions_for_text,
-        matrix_message_payload_for_text, matrix_sdk_clients, normalize_matrix_message_body,
-        normalize_matrix_sdk_message_type, MatrixRuntimeContext, Mentions,
+        apply_matrix_edit, apply_matrix_reply, build_matrix_runtime_contexts,
+        default_matrix_sync_timeout_ms, extract_invited_room_ids, extract_matrix_user_ids,
+        handle_matrix_message, handle_matrix_redaction, is_bot_mentioned_in_mentions,
+        is_mutating_command, looks_like_reaction_token, matrix_agent_semaphore,
+        matrix_backoff_jitter_ms, matrix_backup_key_candidates, matrix_channel_slug,
+        matrix_correlation_id, matrix_edit_payload, matrix_media_ref, matrix_mentions_for_text,
+        matrix_message_payload_for_text, matrix_mxc_download_url, matrix_redaction_target,
+        matrix_replacement_edit, matrix_retry_after_ms, matrix_sdk_clients, matrix_sync_filter,
+        matrix_timeline_gap, matrix_typing_payload, matrix_typing_url, matrix_well_known_url,
+        matrix_whoami_mismatch, normalize_homeserver_url, normalize_matrix_message_body,
+        normalize_matrix_sdk_message_type, parse_matrix_well_known, parse_matrix_whoami,
+        read_capped_body, read_matrix_json, render_matrix_markdown_html, resolve_matrix_chat_id,
+        send_matrix_request_with_retry, sync_matrix_messages, MatrixEditOutcome,
+        MatrixEncryptedRoomWarnings, MatrixError, MatrixIncomingEvent, MatrixIncomingMessage,
+        MatrixMemberNames, MatrixReactionLog, MatrixReplyTarget, MatrixRoomOverride,
+        MatrixRuntimeContext, MatrixSyncBackoff, Mentions,
     };
     use matrix_sdk::ruma::events::room::message::{
         AudioMessageEventContent, FileMessageEventContent, ImageMessageEventContent, MessageType,
         TextMessageEventContent, VideoMessageEventContent,
     };
     use matrix_sdk::Client as MatrixSdkClient;
+    use microclaw_core::error::MicroClawError;
+    use microclaw_core::llm_types::{Message, MessagesResponse, ToolDefinition};
     use serde_json::json;
-    use std::collections::BTreeSet;
+    use std::collections::{BTreeSet, HashMap};
     use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn test_extract_matrix_user_ids() {
@@ -1928,7 +4397,7 @@ mod tests {
 
     #[test]
     fn test_message_payload_mentions() {
-        let payload = matrix_message_payload_for_text("hello @alice:example.org");
+        let payload = matrix_message_payload_for_text("hello @alice:example.org", false);
         let mentions = payload
             .pointer("/m.mentions/user_ids")
             .and_then(|v| v.as_array())
@@ -1937,6 +4406,298 @@ mod tests {
         assert_eq!(mentions[0].as_str(), Some("@alice:example.org"));
     }
 
+    fn test_runtime() -> MatrixRuntimeContext {
+        MatrixRuntimeContext {
+            channel_name: "matrix".to_string(),
+            access_token: "tok".to_string(),
+            homeserver_url: "http://localhost:8008".to_string(),
+            bot_user_id: "@bot:localhost".to_string(),
+            bot_username: "bot".to_string(),
+            allowed_room_ids: Vec::new(),
+            allowed_user_ids: Vec::new(),
+            admins: Vec::new(),
+            mention_required: true,
+            sync_timeout_ms: 30_000,
+            sync_timeout_margin_secs: 15,
+            sync_timeline_limit: 50,
+            sync_backfill_limit: 200,
+            sync_backoff_base_ms: 1_000,
+            sync_backoff_max_ms: 60_000,
+            backup_key: String::new(),
+            auto_join: true,
+            markdown: true,
+            download_media: true,
+            typing_indicator: true,
+            reply_to_sender: false,
+            stream_responses: false,
+            show_tool_progress: false,
+            encrypted_room_notice: true,
+            rooms: HashMap::new(),
+            member_names: MatrixMemberNames::default(),
+            http_client: reqwest::Client::new(),
+            sdk_client: None,
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingLlm;
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmProvider for FailingLlm {
+        async fn send_message(
+            &self,
+            _system: &str,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<ToolDefinition>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            Err(MicroClawError::LlmApi("provider unavailable".into()))
+        }
+    }
+
+    fn test_app_state(
+        base_dir: &std::path::Path,
+        llm: Box<dyn crate::llm::LlmProvider>,
+    ) -> Arc<crate::runtime::AppState> {
+        let runtime_dir = base_dir.join("runtime");
+        std::fs::create_dir_all(&runtime_dir).unwrap();
+        let mut cfg = crate::config::Config::test_defaults();
+        cfg.data_dir = base_dir.to_string_lossy().to_string();
+        cfg.working_dir = base_dir.join("tmp").to_string_lossy().to_string();
+        cfg.working_dir_isolation = crate::config::WorkingDirIsolation::Shared;
+        let db =
+            Arc::new(microclaw_storage::db::Database::new(runtime_dir.to_str().unwrap()).unwrap());
+        let memory_backend = Arc::new(crate::memory_backend::MemoryBackend::local_only(db.clone()));
+        let channel_registry =
+            Arc::new(microclaw_channels::channel_adapter::ChannelRegistry::new());
+        Arc::new(crate::runtime::AppState {
+            config: cfg.clone(),
+            live_config: Arc::new(arc_swap::ArcSwap::from_pointee(cfg.clone())),
+            channel_registry: channel_registry.clone(),
+            db: db.clone(),
+            memory: crate::memory::MemoryManager::new(runtime_dir.to_str().unwrap()),
+            skills: crate::skills::SkillManager::from_skills_dir(&cfg.skills_data_dir()),
+            hooks: Arc::new(crate::hooks::HookManager::from_config(&cfg)),
+            llm,
+            llm_model_overrides: HashMap::new(),
+            embedding: None,
+            memory_backend: memory_backend.clone(),
+            tools: crate::tools::ToolRegistry::new(&cfg, channel_registry, db, memory_backend),
+            shutdown: tokio_util::sync::CancellationToken::new(),
+            tasks: tokio_util::task::TaskTracker::new(),
+            rate_limiter: crate::rate_limit::SenderRateLimiter::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_matrix_message_span_carries_event_id_through_handler() {
+        let dir = std::env::temp_dir().join(format!("mc_matrix_span_{}", uuid::Uuid::new_v4()));
+        let app_state = test_app_state(&dir, Box::new(FailingLlm));
+        let runtime = MatrixRuntimeContext {
+            channel_name: "matrix_span_test".to_string(),
+            // Nothing listens here, so the error reply fails fast.
+            homeserver_url: "http://127.0.0.1:9".to_string(),
+            mention_required: false,
+            download_media: false,
+            typing_indicator: false,
+            ..test_runtime()
+        };
+        let msg = MatrixIncomingMessage {
+            room_id: "!room:localhost".to_string(),
+            is_direct: true,
+            sender: "@alice:localhost".to_string(),
+            event_id: "$ev1:localhost".to_string(),
+            body: "hello".to_string(),
+            mentioned_bot: false,
+            media: None,
+            prefer_sdk_send: false,
+            event_time_ms: None,
+        };
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        handle_matrix_message(app_state, runtime, msg).await;
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let error_line = output
+            .lines()
+            .find(|line| line.contains("Error processing Matrix message"))
+            .unwrap_or_else(|| panic!("agent failure not logged: {output}"));
+        assert!(error_line.contains("chat_id="), "{error_line}");
+        for line in output.lines() {
+            assert!(line.contains("event_id=\"$ev1:localhost\""), "{line}");
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_sync_redaction_removes_the_stored_message() {
+        use microclaw_storage::db::StoredMessage;
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let body = json!({
+                "next_batch": "s2",
+                "rooms": { "join": { "!room:localhost": { "timeline": { "events": [{
+                    "type": "m.room.redaction",
+                    "event_id": "$redaction:localhost",
+                    "sender": "@alice:localhost",
+                    "content": { "redacts": "$gone:localhost" }
+                }]}}}}
+            })
+            .to_string();
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        let runtime = MatrixRuntimeContext {
+            channel_name: "matrix_redaction_test".to_string(),
+            homeserver_url: format!("http://{addr}"),
+            ..test_runtime()
+        };
+
+        let (next_batch, events) = sync_matrix_messages(&runtime, None, "{}").await.unwrap();
+        server.join().unwrap();
+        assert_eq!(next_batch, "s2");
+        let [MatrixIncomingEvent::Redaction {
+            room_id,
+            is_direct,
+            redacts,
+        }] = events.as_slice()
+        else {
+            panic!("redaction was not routed");
+        };
+        assert_eq!(redacts, "$gone:localhost");
+
+        let dir = std::env::temp_dir().join(format!("mc_matrix_redact_{}", uuid::Uuid::new_v4()));
+        let app_state = test_app_state(&dir, Box::new(FailingLlm));
+        let chat_id =
+            resolve_matrix_chat_id(app_state.clone(), &runtime, room_id, *is_direct).await;
+        for id in ["$gone:localhost", "$kept:localhost"] {
+            app_state
+                .db
+                .store_message(&StoredMessage {
+                    id: id.into(),
+                    chat_id,
+                    sender_name: "@alice:localhost".into(),
+                    content: "hello".into(),
+                    is_from_bot: false,
+                    timestamp: "2024-01-01T00:00:00Z".into(),
+                })
+                .unwrap();
+        }
+
+        handle_matrix_redaction(app_state.clone(), runtime, room_id, *is_direct, redacts).await;
+
+        assert!(!app_state
+            .db
+            .message_exists(chat_id, "$gone:localhost")
+            .unwrap());
+        assert!(app_state
+            .db
+            .message_exists(chat_id, "$kept:localhost")
+            .unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_matrix_edit_only_applies_to_own_messages() {
+        use microclaw_storage::db::{Database, StoredMessage};
+
+        let dir = std::env::temp_dir().join(format!("mc_matrix_edit_{}", uuid::Uuid::new_v4()));
+        let db = Database::new(dir.to_str().unwrap()).unwrap();
+        for (id, sender, from_bot) in [
+            ("$alice", "@alice:localhost", false),
+            ("$bot", "@bot:localhost", true),
+        ] {
+            db.store_message_if_new_with_sender_id(
+                &StoredMessage {
+                    id: id.into(),
+                    chat_id: 1,
+                    sender_name: "Alice".into(),
+                    content: "original".into(),
+                    is_from_bot: from_bot,
+                    timestamp: "2024-01-01T00:00:00Z".into(),
+                },
+                sender,
+            )
+            .unwrap();
+        }
+        db.store_message(&StoredMessage {
+            id: "$legacy".into(),
+            chat_id: 1,
+            sender_name: "Alice".into(),
+            content: "original".into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        })
+        .unwrap();
+        let content = |id: &str| db.get_message(1, id).unwrap().unwrap().content;
+
+        // Mallory renamed to "Alice": same display name, different MXID.
+        assert_eq!(
+            apply_matrix_edit(&db, 1, "$alice", "@mallory:localhost", "rewritten").unwrap(),
+            MatrixEditOutcome::Rejected
+        );
+        assert_eq!(content("$alice"), "original");
+        assert_eq!(
+            apply_matrix_edit(&db, 1, "$bot", "@bot:localhost", "rewritten").unwrap(),
+            MatrixEditOutcome::Rejected
+        );
+        assert_eq!(content("$bot"), "original");
+        assert_eq!(
+            apply_matrix_edit(&db, 1, "$legacy", "@alice:localhost", "rewritten").unwrap(),
+            MatrixEditOutcome::Rejected
+        );
+        assert_eq!(
+            apply_matrix_edit(&db, 1, "$alice", "@alice:localhost", "fixed typo").unwrap(),
+            MatrixEditOutcome::Applied
+        );
+        assert_eq!(content("$alice"), "fixed typo");
+        assert_eq!(
+            apply_matrix_edit(&db, 1, "$unknown", "@alice:localhost", "late edit").unwrap(),
+            MatrixEditOutcome::OriginalMissing
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_matrix_correlation_id_falls_back_to_uuid() {
+        assert_eq!(matrix_correlation_id("$ev1:localhost"), "$ev1:localhost");
+        let generated = matrix_correlation_id("  ");
+        assert!(uuid::Uuid::parse_str(&generated).is_ok());
+        assert_ne!(generated, matrix_correlation_id(""));
+    }
+
     #[test]
     fn test_reaction_token_detection() {
         assert_eq!(looks_like_reaction_token("👍"), Some("👍".to_string()));
@@ -1959,59 +4720,85 @@ mod tests {
 
     #[test]
     fn test_should_respond_when_mentioned_metadata() {
-        let runtime = MatrixRuntimeContext {
-            channel_name: "matrix".to_string(),
-            access_token: "tok".to_string(),
-            homeserver_url: "http://localhost:8008".to_string(),
-            bot_user_id: "@bot:localhost".to_string(),
-            bot_username: "bot".to_string(),
-            allowed_room_ids: Vec::new(),
-            allowed_user_ids: Vec::new(),
-            mention_required: true,
-            sync_timeout_ms: 30_000,
-            backup_key: String::new(),
-            sdk_client: None,
-        };
+        let runtime = test_runtime();
 
-        assert!(runtime.should_respond("hello there", true, false));
-        assert!(!runtime.should_respond("hello there", false, false));
-        assert!(runtime.should_respond("hello there", false, true));
+        assert!(runtime.should_respond("!room:localhost", "hello there", true, false));
+        assert!(!runtime.should_respond("!room:localhost", "hello there", false, false));
+        assert!(runtime.should_respond("!room:localhost", "hello there", false, true));
     }
 
     #[test]
     fn test_should_process_dm_sender_allowlist() {
         let runtime = MatrixRuntimeContext {
-            channel_name: "matrix".to_string(),
-            access_token: "tok".to_string(),
-            homeserver_url: "http://localhost:8008".to_string(),
-            bot_user_id: "@bot:localhost".to_string(),
-            bot_username: "bot".to_string(),
-            allowed_room_ids: Vec::new(),
             allowed_user_ids: vec!["@alice:localhost".to_string()],
-            mention_required: true,
-            sync_timeout_ms: 30_000,
-            backup_key: String::new(),
-            sdk_client: None,
+            ..test_runtime()
         };
 
         assert!(runtime.should_process_dm_sender("@alice:localhost"));
         assert!(!runtime.should_process_dm_sender("@bob:localhost"));
     }
 
+    #[tokio::test]
+    async fn test_agent_semaphore_caps_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let active = active.clone();
+            let peak = peak.clone();
+            handles.push(tokio::spawn(async move {
+                let slots = matrix_agent_semaphore("matrix.test_cap", 2);
+                let _slot = slots.acquire_owned().await.unwrap();
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            matrix_agent_semaphore("matrix.test_cap", 10).available_permits(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_is_admin_gates_mutating_commands() {
+        let open = test_runtime();
+        assert!(open.is_admin("@anyone:localhost"));
+
+        let restricted = MatrixRuntimeContext {
+            admins: vec!["@alice:localhost".to_string()],
+            ..open
+        };
+        assert!(restricted.is_admin("@alice:localhost"));
+        assert!(restricted.is_admin("@Alice:localhost"));
+        assert!(!restricted.is_admin("@bob:localhost"));
+
+        let scope = crate::config::ResetScope::All;
+        assert!(is_mutating_command("/reload-skills", scope));
+        assert!(is_mutating_command("@bot /reset all", scope));
+        assert!(is_mutating_command("/reset", scope));
+        assert!(!is_mutating_command(
+            "/reset",
+            crate::config::ResetScope::Session
+        ));
+        assert!(is_mutating_command("/model gpt-5", scope));
+        assert!(!is_mutating_command("/model", scope));
+        assert!(!is_mutating_command("/status", scope));
+        assert!(!is_mutating_command("/help", scope));
+    }
+
     #[test]
     fn test_group_room_allowlist_does_not_imply_dm_blocklist() {
         let runtime = MatrixRuntimeContext {
-            channel_name: "matrix".to_string(),
-            access_token: "tok".to_string(),
-            homeserver_url: "http://localhost:8008".to_string(),
-            bot_user_id: "@bot:localhost".to_string(),
-            bot_username: "bot".to_string(),
             allowed_room_ids: vec!["!group:localhost".to_string()],
-            allowed_user_ids: Vec::new(),
-            mention_required: true,
-            sync_timeout_ms: 30_000,
-            backup_key: String::new(),
-            sdk_client: None,
+            ..test_runtime()
         };
 
         assert!(runtime.should_process_group_room("!group:localhost"));
@@ -2129,4 +4916,652 @@ mod tests {
         assert!(candidates.contains(&"C1E7 44EC DE73 7A4B".to_string()));
         assert!(candidates.contains(&"C1E744ECDE737A4B".to_string()));
     }
+
+    #[test]
+    fn test_matrix_sync_backoff_sequence_and_reset() {
+        let mut backoff = MatrixSyncBackoff::new(1_000, 60_000);
+        let delays: Vec<u64> = (0..8).map(|_| backoff.next_delay_ms()).collect();
+        assert_eq!(
+            delays,
+            vec![1_000, 2_000, 4_000, 8_000, 16_000, 32_000, 60_000, 60_000]
+        );
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay_ms(), 1_000);
+        assert_eq!(backoff.next_delay_ms(), 2_000);
+    }
+
+    #[test]
+    fn test_matrix_backoff_jitter_is_bounded() {
+        assert_eq!(matrix_backoff_jitter_ms(0), 0);
+        for _ in 0..32 {
+            assert!(matrix_backoff_jitter_ms(4_000) <= 1_000);
+        }
+    }
+
+    #[test]
+    fn test_matrix_retry_after_ms_from_429_body() {
+        let body =
+            r#"{"errcode":"M_LIMIT_EXCEEDED","error":"Too many requests","retry_after_ms":2500}"#;
+        assert_eq!(matrix_retry_after_ms(None, body), Some(2_500));
+        assert_eq!(matrix_retry_after_ms(Some("7"), body), Some(2_500));
+        assert_eq!(
+            matrix_retry_after_ms(Some("3"), r#"{"errcode":"M_LIMIT_EXCEEDED"}"#),
+            Some(3_000)
+        );
+        assert_eq!(matrix_retry_after_ms(None, "not json"), None);
+    }
+
+    fn mocked_response(status: u16, retry_after: Option<&str>, body: &str) -> reqwest::Response {
+        let mut builder = axum::http::Response::builder().status(status);
+        if let Some(value) = retry_after {
+            builder = builder.header("Retry-After", value);
+        }
+        reqwest::Response::from(builder.body(body.to_string()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_read_capped_body_stops_past_the_limit() {
+        let body = read_capped_body(mocked_response(200, None, "0123456789"), 10).await;
+        assert_eq!(body, Ok(Some(b"0123456789".to_vec())));
+        let body = read_capped_body(mocked_response(200, None, "0123456789"), 4).await;
+        assert_eq!(body, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_error_classifies_responses() {
+        let limited = MatrixError::from_failed_response(mocked_response(
+            429,
+            Some("2"),
+            r#"{"errcode":"M_LIMIT_EXCEEDED"}"#,
+        ))
+        .await;
+        assert_eq!(
+            limited,
+            MatrixError::RateLimited {
+                retry_after: Some(Duration::from_secs(2))
+            }
+        );
+
+        let limited_in_body = MatrixError::from_failed_response(mocked_response(
+            400,
+            None,
+            r#"{"errcode":"M_LIMIT_EXCEEDED","retry_after_ms":750}"#,
+        ))
+        .await;
+        assert_eq!(
+            limited_in_body,
+            MatrixError::RateLimited {
+                retry_after: Some(Duration::from_millis(750))
+            }
+        );
+
+        let auth = MatrixError::from_failed_response(mocked_response(
+            401,
+            None,
+            r#"{"errcode":"M_UNKNOWN_TOKEN"}"#,
+        ))
+        .await;
+        assert!(matches!(auth, MatrixError::Auth(ref m) if m.contains("M_UNKNOWN_TOKEN")));
+
+        let not_joined = MatrixError::from_failed_response(mocked_response(
+            403,
+            None,
+            r#"{"errcode":"M_FORBIDDEN","error":"User not in room"}"#,
+        ))
+        .await;
+        assert!(matches!(not_joined, MatrixError::Forbidden(ref m) if m.contains("M_FORBIDDEN")));
+        assert!(!not_joined.to_string().contains("authentication"));
+
+        let soft_logout = MatrixError::from_failed_response(mocked_response(
+            403,
+            None,
+            r#"{"errcode":"M_UNKNOWN_TOKEN"}"#,
+        ))
+        .await;
+        assert!(matches!(soft_logout, MatrixError::Auth(_)));
+
+        let http =
+            MatrixError::from_failed_response(mocked_response(502, None, "bad gateway")).await;
+        assert_eq!(
+            http,
+            MatrixError::Http {
+                status: 502,
+                body: "bad gateway".to_string()
+            }
+        );
+        assert_eq!(
+            http.to_string(),
+            "Matrix request failed: HTTP 502 bad gateway"
+        );
+
+        let parse = read_matrix_json("Matrix send response", mocked_response(200, None, "<html>"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(parse, MatrixError::Parse(ref m) if m.starts_with("Matrix send response"))
+        );
+        assert!(parse
+            .to_string()
+            .starts_with("Matrix response parse failed"));
+    }
+
+    #[test]
+    fn test_normalize_homeserver_url() {
+        assert_eq!(
+            normalize_homeserver_url("matrix.example.org").unwrap(),
+            "https://matrix.example.org"
+        );
+        assert_eq!(
+            normalize_homeserver_url("HTTPS://matrix.example.org").unwrap(),
+            "https://matrix.example.org"
+        );
+        assert_eq!(
+            normalize_homeserver_url(" http://localhost:8008// ").unwrap(),
+            "http://localhost:8008"
+        );
+        assert_eq!(
+            normalize_homeserver_url("https://example.org/matrix/").unwrap(),
+            "https://example.org/matrix"
+        );
+        assert!(normalize_homeserver_url("").is_err());
+        assert!(normalize_homeserver_url("ftp://matrix.example.org").is_err());
+        assert!(normalize_homeserver_url("https://").is_err());
+        assert!(normalize_homeserver_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_matrix_well_known() {
+        let body = r#"{
+            "m.homeserver": {"base_url": "https://matrix.example.org/"},
+            "m.identity_server": {"base_url": "https://id.example.org"}
+        }"#;
+        assert_eq!(
+            parse_matrix_well_known(body).as_deref(),
+            Some("https://matrix.example.org")
+        );
+        assert_eq!(parse_matrix_well_known(r#"{"m.homeserver": {}}"#), None);
+        assert_eq!(parse_matrix_well_known("<html>"), None);
+
+        assert_eq!(
+            matrix_well_known_url("https://example.org").as_deref(),
+            Some("https://example.org/.well-known/matrix/client")
+        );
+        assert_eq!(matrix_well_known_url("https://example.org:8448"), None);
+        assert_eq!(matrix_well_known_url("https://example.org/matrix"), None);
+        assert_eq!(matrix_well_known_url("http://localhost"), None);
+    }
+
+    #[test]
+    fn test_matrix_whoami_parsing_and_mismatch() {
+        let whoami =
+            parse_matrix_whoami(r#"{"user_id":"@bot:example.org","device_id":"ABCDEF"}"#).unwrap();
+        assert_eq!(whoami.user_id, "@bot:example.org");
+        assert_eq!(whoami.device_id.as_deref(), Some("ABCDEF"));
+        assert!(matrix_whoami_mismatch("@bot:example.org", &whoami).is_none());
+        assert!(matrix_whoami_mismatch(" @bot:example.org ", &whoami).is_none());
+
+        let mismatch = matrix_whoami_mismatch("@other:example.org", &whoami).unwrap();
+        assert!(mismatch.contains("@bot:example.org"));
+        assert!(mismatch.contains("@other:example.org"));
+
+        let guest = parse_matrix_whoami(r#"{"user_id":"@bot:example.org"}"#).unwrap();
+        assert_eq!(guest.device_id, None);
+        assert!(matches!(
+            parse_matrix_whoami(r#"{"errcode":"M_UNKNOWN_TOKEN"}"#),
+            Err(MatrixError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_matrix_runtime_contexts_applies_proxy() {
+        let mut cfg = crate::config::Config::test_defaults();
+        cfg.channels = serde_yaml::from_str(
+            r#"matrix: { enabled: true, matrix_proxy: "socks5://bot:pw@127.0.0.1:1080", default_account: "main", accounts: { main: { access_token: "a", homeserver_url: "https://matrix.example.org", bot_user_id: "@a:example.org" }, relay: { access_token: "b", homeserver_url: "https://matrix.example.org", bot_user_id: "@b:example.org", proxy: "http://proxy.example:3128" }, broken: { access_token: "c", homeserver_url: "https://matrix.example.org", bot_user_id: "@c:example.org", proxy: "ftp://proxy.example" } } }"#,
+        )
+        .unwrap();
+
+        let runtimes = build_matrix_runtime_contexts(&cfg);
+        let names: Vec<&str> = runtimes.iter().map(|r| r.channel_name.as_str()).collect();
+        assert_eq!(names, vec!["matrix", "matrix.relay"]);
+    }
+
+    #[tokio::test]
+    async fn test_matrix_error_network_failure() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let client = reqwest::Client::new();
+        let err = send_matrix_request_with_retry("Matrix join", || client.post(&url))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatrixError::Network(ref m) if m.starts_with("Matrix join")));
+        let message: String = err.into();
+        assert!(message.starts_with("Matrix request failed: Matrix join"));
+    }
+
+    #[test]
+    fn test_extract_invited_room_ids_filters_joined_and_disallowed() {
+        let runtime = MatrixRuntimeContext {
+            allowed_room_ids: vec!["!ok:localhost".to_string(), "!joined:localhost".to_string()],
+            allowed_user_ids: vec!["@alice:localhost".to_string()],
+            ..test_runtime()
+        };
+        let payload = json!({
+            "rooms": {
+                "join": { "!joined:localhost": {} },
+                "invite": {
+                    "!ok:localhost": {},
+                    "!joined:localhost": {},
+                    "!other:localhost": {},
+                    "!dm:localhost": {
+                        "invite_state": { "events": [{
+                            "type": "m.room.member",
+                            "state_key": "@bot:localhost",
+                            "sender": "@alice:localhost",
+                            "content": { "membership": "invite", "is_direct": true }
+                        }]}
+                    },
+                    "!spam-dm:localhost": {
+                        "invite_state": { "events": [{
+                            "type": "m.room.member",
+                            "state_key": "@bot:localhost",
+                            "sender": "@mallory:localhost",
+                            "content": { "membership": "invite", "is_direct": true }
+                        }]}
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            extract_invited_room_ids(&payload, &runtime),
+            vec!["!dm:localhost".to_string(), "!ok:localhost".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_matrix_replacement_edit_extracts_new_content() {
+        let event = json!({
+            "type": "m.room.message",
+            "content": {
+                "msgtype": "m.text",
+                "body": "* hello wrold",
+                "m.new_content": { "msgtype": "m.text", "body": "hello world" },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$orig" }
+            }
+        });
+        assert_eq!(
+            matrix_replacement_edit(&event),
+            Some(("$orig".to_string(), "hello world".to_string()))
+        );
+
+        let reply = json!({
+            "content": {
+                "msgtype": "m.text",
+                "body": "plain reply",
+                "m.relates_to": { "m.in_reply_to": { "event_id": "$other" } }
+            }
+        });
+        assert_eq!(matrix_replacement_edit(&reply), None);
+
+        let missing_new_content = json!({
+            "content": {
+                "body": "* edited",
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$orig" }
+            }
+        });
+        assert_eq!(matrix_replacement_edit(&missing_new_content), None);
+    }
+
+    #[test]
+    fn test_render_matrix_markdown_code_block_and_lists() {
+        let html = render_matrix_markdown_html(
+            "Steps:\n- first **bold**\n- use `cargo <build>`\n\n1. one\n2. two\n\n```rust\nfn main() { a < b }\n```",
+            &[],
+        );
+        assert_eq!(
+            html,
+            "Steps:<ul><li>first <strong>bold</strong></li><li>use <code>cargo &lt;build&gt;</code></li></ul>\
+             <ol><li>one</li><li>two</li></ol>\
+             <pre><code class=\"language-rust\">fn main() { a &lt; b }\n</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_markdown_payload_keeps_plain_body_and_mention_pills() {
+        let text = "## Hi @alice:example.org\n- item";
+        let payload = matrix_message_payload_for_text(text, true);
+        assert_eq!(payload["body"].as_str(), Some(text));
+        assert_eq!(payload["format"].as_str(), Some("org.matrix.custom.html"));
+        assert_eq!(
+            payload["formatted_body"].as_str(),
+            Some(
+                "<h2>Hi <a href=\"https://matrix.to/#/@alice:example.org\">@alice:example.org</a></h2><ul><li>item</li></ul>"
+            )
+        );
+        assert!(payload.pointer("/m.mentions/user_ids").is_some());
+
+        let plain = matrix_message_payload_for_text("just text", true);
+        assert!(plain.get("formatted_body").is_none());
+        let disabled = matrix_message_payload_for_text("- item", false);
+        assert!(disabled.get("formatted_body").is_none());
+    }
+
+    #[test]
+    fn test_matrix_edit_payload_uses_m_replace() {
+        let payload =
+            matrix_edit_payload("$orig:example.org", "**fixed** @alice:example.org", true);
+        assert_eq!(payload["msgtype"].as_str(), Some("m.text"));
+        assert_eq!(
+            payload["body"].as_str(),
+            Some("* **fixed** @alice:example.org")
+        );
+        assert!(payload["formatted_body"]
+            .as_str()
+            .is_some_and(|html| html.starts_with("* <strong>fixed</strong>")));
+        assert!(payload.get("m.mentions").is_none());
+        assert_eq!(
+            payload
+                .pointer("/m.relates_to/rel_type")
+                .and_then(|v| v.as_str()),
+            Some("m.replace")
+        );
+        assert_eq!(
+            payload
+                .pointer("/m.relates_to/event_id")
+                .and_then(|v| v.as_str()),
+            Some("$orig:example.org")
+        );
+        let new_content = &payload["m.new_content"];
+        assert_eq!(
+            new_content["body"].as_str(),
+            Some("**fixed** @alice:example.org")
+        );
+        assert!(new_content.pointer("/m.mentions/user_ids").is_some());
+        assert!(new_content.get("m.relates_to").is_none());
+
+        let plain = matrix_edit_payload("$orig:example.org", "typo fixed", false);
+        assert_eq!(plain["body"].as_str(), Some("* typo fixed"));
+        assert!(plain.get("formatted_body").is_none());
+    }
+
+    #[test]
+    fn test_matrix_redaction_target_parses_both_formats() {
+        let legacy = json!({
+            "type": "m.room.redaction",
+            "redacts": "$old",
+            "content": { "reason": "oops" }
+        });
+        assert_eq!(matrix_redaction_target(&legacy), Some("$old".to_string()));
+
+        let v11 = json!({
+            "type": "m.room.redaction",
+            "content": { "redacts": "$new" }
+        });
+        assert_eq!(matrix_redaction_target(&v11), Some("$new".to_string()));
+
+        let missing = json!({ "type": "m.room.redaction", "content": {} });
+        assert_eq!(matrix_redaction_target(&missing), None);
+    }
+
+    #[test]
+    fn test_matrix_mxc_download_url() {
+        assert_eq!(
+            matrix_mxc_download_url("https://matrix.example.org/", "mxc://example.org/AbC123"),
+            Some(
+                "https://matrix.example.org/_matrix/media/v3/download/example.org/AbC123"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            matrix_mxc_download_url("https://hs", "https://example.org/AbC123"),
+            None
+        );
+        assert_eq!(
+            matrix_mxc_download_url("https://hs", "mxc://example.org"),
+            None
+        );
+        assert_eq!(
+            matrix_mxc_download_url("https://hs", "mxc://example.org/a/../b"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_matrix_media_ref_from_event() {
+        let event = json!({
+            "content": {
+                "msgtype": "m.file",
+                "body": "report.pdf",
+                "url": "mxc://localhost/pdf",
+                "info": { "size": 1234 }
+            }
+        });
+        let media = matrix_media_ref(&event).expect("media");
+        assert_eq!(media.mxc_url, "mxc://localhost/pdf");
+        assert_eq!(media.size, Some(1234));
+
+        let text = json!({ "content": { "msgtype": "m.text", "body": "hi" } });
+        assert!(matrix_media_ref(&text).is_none());
+    }
+
+    #[test]
+    fn test_matrix_typing_url_and_payload() {
+        assert_eq!(
+            matrix_typing_url("https://hs/", "!room:hs", "@bot:hs"),
+            "https://hs/_matrix/client/v3/rooms/%21room%3Ahs/typing/%40bot%3Ahs"
+        );
+        assert_eq!(
+            matrix_typing_payload(true),
+            json!({ "typing": true, "timeout": 30_000 })
+        );
+        assert_eq!(matrix_typing_payload(false), json!({ "typing": false }));
+    }
+
+    #[test]
+    fn test_resolve_matrix_display_name() {
+        let names = MatrixMemberNames::default();
+        let room = "!room:hs";
+        names.apply_member_event(
+            room,
+            &json!({
+                "type": "m.room.member",
+                "state_key": "@alice:hs",
+                "content": { "membership": "join", "displayname": "Alice" }
+            }),
+        );
+        names.set(room, "@bob:hs", Some("Bob"));
+        assert_eq!(names.sender_name(room, "@alice:hs"), "Alice");
+        assert_eq!(names.sender_name(room, "@carol:hs"), "@carol:hs");
+        assert_eq!(names.sender_name("!other:hs", "@alice:hs"), "@alice:hs");
+
+        names.set(room, "@alice2:other", Some("Alice"));
+        assert_eq!(names.sender_name(room, "@alice:hs"), "Alice (@alice)");
+        assert_eq!(names.sender_name(room, "@alice2:other"), "Alice (@alice2)");
+
+        names.apply_member_event(
+            room,
+            &json!({
+                "type": "m.room.member",
+                "state_key": "@alice2:other",
+                "content": { "membership": "leave" }
+            }),
+        );
+        assert_eq!(names.sender_name(room, "@alice:hs"), "Alice");
+        assert_eq!(names.sender_name(room, "@alice2:other"), "@alice2:other");
+    }
+
+    #[test]
+    fn test_apply_matrix_reply_builds_relation_and_fallbacks() {
+        let reply = MatrixReplyTarget {
+            event_id: "$orig".to_string(),
+            sender: "@alice:hs".to_string(),
+            body: "what is <x>?\nsecond line".to_string(),
+        };
+        let mut payload = matrix_message_payload_for_text("it is **y**", true);
+        apply_matrix_reply(&mut payload, "!room:hs", &reply);
+
+        assert_eq!(
+            payload.pointer("/m.relates_to/m.in_reply_to/event_id"),
+            Some(&json!("$orig"))
+        );
+        assert_eq!(
+            payload["body"].as_str(),
+            Some("> <@alice:hs> what is <x>?\n> second line\n\nit is **y**")
+        );
+        assert_eq!(
+            payload["formatted_body"].as_str(),
+            Some(
+                "<mx-reply><blockquote><a href=\"https://matrix.to/#/!room:hs/$orig\">In reply to</a> \
+                 <a href=\"https://matrix.to/#/@alice:hs\">@alice:hs</a><br>what is &lt;x&gt;?<br>second line\
+                 </blockquote></mx-reply>it is <strong>y</strong>"
+            )
+        );
+        assert_eq!(payload["format"].as_str(), Some("org.matrix.custom.html"));
+    }
+
+    #[test]
+    fn test_matrix_room_override_precedence() {
+        let mut rooms = HashMap::new();
+        rooms.insert(
+            "!support:localhost".to_string(),
+            MatrixRoomOverride {
+                mention_required: Some(false),
+                enabled: true,
+            },
+        );
+        rooms.insert(
+            "!muted:localhost".to_string(),
+            MatrixRoomOverride {
+                mention_required: None,
+                enabled: false,
+            },
+        );
+        let runtime = MatrixRuntimeContext {
+            allowed_room_ids: vec![
+                "!general:localhost".to_string(),
+                "!muted:localhost".to_string(),
+            ],
+            rooms,
+            ..test_runtime()
+        };
+
+        assert!(runtime.should_respond("!support:localhost", "hello", false, false));
+        assert!(!runtime.should_respond("!general:localhost", "hello", false, false));
+        assert!(runtime.should_respond("!general:localhost", "hello", true, false));
+
+        assert!(runtime.should_process_group_room("!support:localhost"));
+        assert!(runtime.should_process_group_room("!general:localhost"));
+        assert!(!runtime.should_process_group_room("!muted:localhost"));
+        assert!(!runtime.should_process_group_room("!other:localhost"));
+    }
+
+    #[test]
+    fn test_matrix_sync_filter_definition() {
+        let filter = matrix_sync_filter(25);
+        assert_eq!(filter.pointer("/room/timeline/limit"), Some(&json!(25)));
+        assert_eq!(
+            filter.pointer("/room/timeline/types"),
+            Some(&json!([
+                "m.room.message",
+                "m.room.encrypted",
+                "m.reaction",
+                "m.room.redaction",
+                "m.room.member"
+            ]))
+        );
+        assert_eq!(filter.pointer("/presence/not_types"), Some(&json!(["*"])));
+        assert_eq!(
+            filter.pointer("/account_data/types"),
+            Some(&json!(["m.direct"]))
+        );
+        assert_eq!(
+            matrix_sync_filter(0).pointer("/room/timeline/limit"),
+            Some(&json!(50))
+        );
+    }
+
+    #[test]
+    fn test_matrix_timeline_gap_detection() {
+        let gappy = json!({
+            "timeline": { "limited": true, "prev_batch": "t123", "events": [] }
+        });
+        assert_eq!(matrix_timeline_gap(&gappy), Some("t123"));
+
+        let complete = json!({
+            "timeline": { "limited": false, "prev_batch": "t123", "events": [] }
+        });
+        assert_eq!(matrix_timeline_gap(&complete), None);
+
+        let no_token = json!({ "timeline": { "limited": true, "events": [] } });
+        assert_eq!(matrix_timeline_gap(&no_token), None);
+    }
+
+    #[test]
+    fn test_matrix_reaction_log_bookkeeping() {
+        let mut log = MatrixReactionLog::default();
+        log.record("matrix", "!r:hs", "$target", "👍", "$reaction1");
+        log.record("matrix", "!r:hs", "$target", "🎉", "$reaction2");
+        log.record("matrix.alt", "!r:hs", "$target", "👍", "$reaction3");
+        log.record("matrix", "!r:hs", "$target", "❤️", "");
+
+        assert_eq!(log.take("matrix", "!r:hs", "$other", "👍"), None);
+        assert_eq!(
+            log.take("matrix", "!r:hs", "$target", "👍"),
+            Some("$reaction1".to_string())
+        );
+        assert_eq!(log.take("matrix", "!r:hs", "$target", "👍"), None);
+        assert_eq!(
+            log.take("matrix.alt", "!r:hs", "$target", "👍"),
+            Some("$reaction3".to_string())
+        );
+        assert_eq!(
+            log.take("matrix", "!r:hs", "$target", "🎉"),
+            Some("$reaction2".to_string())
+        );
+        assert_eq!(log.take("matrix", "!r:hs", "$target", "❤️"), None);
+    }
+
+    #[test]
+    fn test_matrix_reaction_log_evicts_oldest() {
+        let mut log = MatrixReactionLog::with_capacity(2);
+        log.record("matrix", "!r:hs", "$a", "👍", "$reaction1");
+        log.record("matrix", "!r:hs", "$b", "👍", "$reaction2");
+        log.record("matrix", "!r:hs", "$c", "👍", "$reaction3");
+
+        assert_eq!(log.take("matrix", "!r:hs", "$a", "👍"), None);
+        assert_eq!(
+            log.take("matrix", "!r:hs", "$c", "👍"),
+            Some("$reaction3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_matrix_encrypted_room_warning_dedup() {
+        let mut warnings = MatrixEncryptedRoomWarnings::default();
+        assert!(warnings.first_seen("matrix", "!secret:hs"));
+        assert!(!warnings.first_seen("matrix", "!secret:hs"));
+        assert!(warnings.first_seen("matrix", "!other:hs"));
+        assert!(warnings.first_seen("matrix.alt", "!secret:hs"));
+        assert!(!warnings.first_seen("matrix.alt", "!secret:hs"));
+    }
+
+    #[test]
+    fn test_matrix_sync_request_timeout_exceeds_long_poll() {
+        let mut runtime = test_runtime();
+        runtime.sync_timeout_ms = 30_000;
+        runtime.sync_timeout_margin_secs = 15;
+        assert_eq!(runtime.sync_request_timeout(), Duration::from_secs(45));
+
+        runtime.sync_timeout_ms = 120_000;
+        assert!(runtime.sync_request_timeout() > Duration::from_millis(120_000));
+
+        runtime.sync_timeout_ms = 0;
+        runtime.sync_timeout_margin_secs = 0;
+        assert!(
+            runtime.sync_request_timeout()
+                > Duration::from_millis(default_matrix_sync_timeout_ms())
+        );
+    }
 }
@@ -0,0 +1,48 @@
+//! Media helpers shared by the channel adapters that upload attachments.
+
+use std::path::Path;
+
+/// Best-effort MIME type for an outgoing attachment, based on its extension.
+pub fn guess_mime_from_extension(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|v| v.to_str())
+        .map(|v| v.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("txt") => "text/plain",
+        Some("json") => "application/json",
+        Some("md") => "text/markdown",
+        Some("zip") => "application/zip",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("ogg") => "audio/ogg",
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_mime_from_extension() {
+        assert_eq!(guess_mime_from_extension(Path::new("a.PNG")), "image/png");
+        assert_eq!(
+            guess_mime_from_extension(Path::new("note.md")),
+            "text/markdown"
+        );
+        assert_eq!(
+            guess_mime_from_extension(Path::new("blob")),
+            "application/octet-stream"
+        );
+    }
+}
@@ -5,12 +5,15 @@ pub mod feishu;
 pub mod imessage;
 pub mod irc;
 pub mod matrix;
+pub mod media;
 pub mod nostr;
 pub mod qq;
 pub mod signal;
 pub mod slack;
 pub mod startup_guard;
+pub mod streaming;
 pub mod telegram;
+pub mod tool_progress;
 pub mod whatsapp;
 
 // Re-export adapter types
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use axum::http::HeaderMap;
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
@@ -14,6 +15,7 @@ use crate::channels::startup_guard::{
     should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
@@ -77,7 +79,7 @@ fn default_webhook_path() -> String {
     "/nostr/events".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct NostrAccountConfig {
     #[serde(default)]
     pub allowed_pubkeys: String,
@@ -93,7 +95,7 @@ pub struct NostrAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct NostrChannelConfig {
     #[serde(default)]
     pub allowed_pubkeys: String,
@@ -290,6 +292,7 @@ pub async fn start_nostr_bot(_app_state: Arc<AppState>, runtime: NostrRuntimeCon
         "Nostr adapter '{}' is ready (webhook ingress + publish command bridge)",
         runtime.channel_name
     );
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
 
 pub fn register_nostr_webhook(router: Router, app_state: Arc<AppState>) -> Router {
@@ -400,6 +403,7 @@ async fn nostr_webhook_handler(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_event_id) {
         return axum::http::StatusCode::OK;
     }
+    crate::metrics::record_message_received(&runtime_ctx.channel_name);
     if is_slash_command(content) {
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime_ctx.channel_name, content).await
@@ -438,6 +442,17 @@ async fn nostr_webhook_handler(
         );
         return axum::http::StatusCode::OK;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime_ctx.channel_name,
+        chat_id,
+        &external_chat_id,
+        &external_chat_id,
+    )
+    .await
+    {
+        return axum::http::StatusCode::OK;
+    }
 
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
     match process_with_agent_with_events(
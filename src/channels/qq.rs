@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use axum::http::HeaderMap;
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
@@ -14,6 +15,7 @@ use crate::channels::startup_guard::{
     should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
@@ -77,7 +79,7 @@ fn default_webhook_path() -> String {
     "/qq/events".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct QQAccountConfig {
     #[serde(default)]
     pub send_command: String,
@@ -93,7 +95,7 @@ pub struct QQAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct QQChannelConfig {
     #[serde(default)]
     pub send_command: String,
@@ -278,6 +280,7 @@ impl ChannelAdapter for QQAdapter {
 pub async fn start_qq_bot(_app_state: Arc<AppState>, runtime: QQRuntimeContext) {
     mark_channel_started(&runtime.channel_name);
     info!("QQ adapter '{}' is ready", runtime.channel_name);
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
 
 pub fn register_qq_webhook(router: Router, app_state: Arc<AppState>) -> Router {
@@ -373,6 +376,7 @@ async fn qq_webhook_handler(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_message_id) {
         return axum::http::StatusCode::OK;
     }
+    crate::metrics::record_message_received(&runtime_ctx.channel_name);
     if is_slash_command(text) {
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime_ctx.channel_name, text).await
@@ -413,6 +417,17 @@ async fn qq_webhook_handler(
         );
         return axum::http::StatusCode::OK;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime_ctx.channel_name,
+        chat_id,
+        &external_chat_id,
+        &external_chat_id,
+    )
+    .await
+    {
+        return axum::http::StatusCode::OK;
+    }
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
     match process_with_agent_with_events(
         &app_state,
@@ -3,6 +3,7 @@ use std::path::Path;
 use std::sync::{Arc, Mutex, OnceLock};
 
 use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{error, info, warn};
@@ -17,11 +18,12 @@ use crate::channels::startup_guard::{
 };
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
-use microclaw_core::text::split_text;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
+use microclaw_core::text::split_text_preserving_code;
 use microclaw_storage::db::call_blocking;
 use microclaw_storage::db::StoredMessage;
 
@@ -60,7 +62,7 @@ pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
     ],
 };
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct SlackAccountConfig {
     pub bot_token: String,
     pub app_token: String,
@@ -78,7 +80,7 @@ fn default_enabled() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct SlackChannelConfig {
     #[serde(default)]
     pub bot_token: String,
@@ -231,6 +233,14 @@ impl ChannelAdapter for SlackAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            max_message_len: 4000,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("slack", ConversationKind::Group),
@@ -239,7 +249,7 @@ impl ChannelAdapter for SlackAdapter {
     }
 
     async fn send_text(&self, external_chat_id: &str, text: &str) -> Result<(), String> {
-        for chunk in split_text(text, 4000) {
+        for chunk in self.capabilities().split_text(text) {
             let body = serde_json::json!({
                 "channel": external_chat_id,
                 "text": chunk,
@@ -413,7 +423,7 @@ async fn send_slack_response(bot_token: &str, channel: &str, text: &str) -> Resu
     let client = reqwest::Client::new();
     const MAX_LEN: usize = 4000;
 
-    let chunks = split_text(text, MAX_LEN);
+    let chunks = split_text_preserving_code(text, MAX_LEN);
     for chunk in chunks {
         let body = serde_json::json!({
             "channel": channel,
@@ -470,6 +480,7 @@ pub async fn start_slack_bot(app_state: Arc<AppState>, runtime: SlackRuntimeCont
         }
         Err(e) => {
             error!("Failed to resolve Slack bot user ID: {e}");
+            crate::health::report_channel_connected(&runtime.channel_name, false);
             return;
         }
     };
@@ -486,6 +497,7 @@ pub async fn start_slack_bot(app_state: Arc<AppState>, runtime: SlackRuntimeCont
         {
             warn!("Slack Socket Mode disconnected: {e}");
         }
+        crate::health::report_channel_connected(&runtime.channel_name, false);
         info!("Slack: reconnecting in 5 seconds...");
         tokio::time::sleep(std::time::Duration::from_secs(5)).await;
     }
@@ -506,6 +518,7 @@ async fn run_socket_mode(
         .map_err(|e| format!("WebSocket connect failed: {e}"))?;
 
     info!("Slack Socket Mode: connected");
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 
     let (mut write, mut read) = ws_stream.split();
 
@@ -682,6 +695,7 @@ async fn handle_slack_message(
     let trimmed = text.trim();
     let mention_tag = format!("<@{bot_user_id}>");
     let should_respond = is_dm || is_app_mention || text.contains(&mention_tag);
+    crate::metrics::record_message_received(&runtime.channel_name);
     if is_slash_command(trimmed) {
         if !should_respond && !app_state.config.allow_group_slash_without_mention {
             return;
@@ -732,6 +746,9 @@ async fn handle_slack_message(
     if !should_respond {
         return;
     }
+    if !allow_sender_message(&app_state, &runtime.channel_name, chat_id, user, channel).await {
+        return;
+    }
 
     info!(
         "Slack message from {} in {}: {}",
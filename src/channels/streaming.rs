@@ -0,0 +1,326 @@
+//! Progressive delivery of agent output: the reply is posted as a placeholder
+//! as soon as the model starts writing and then edited in place while text
+//! streams in, instead of appearing only once the whole run has finished.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use microclaw_channels::channel_adapter::ChannelAdapter;
+use microclaw_core::text::floor_char_boundary;
+use tracing::warn;
+
+use crate::agent_engine::AgentEvent;
+
+/// Minimum time between two edits of the streamed message.
+const STREAM_EDIT_INTERVAL: Duration = Duration::from_millis(1500);
+/// Minimum amount of new text before the streamed message is edited again.
+const STREAM_MIN_NEW_CHARS: usize = 32;
+
+/// Coalesces text deltas so the placeholder is edited at a bounded rate
+/// rather than once per token.
+#[derive(Debug)]
+pub struct StreamBuffer {
+    text: String,
+    flushed_len: usize,
+    last_flush: Option<Instant>,
+    min_interval: Duration,
+    min_new_chars: usize,
+}
+
+impl StreamBuffer {
+    pub fn new(min_interval: Duration, min_new_chars: usize) -> Self {
+        Self {
+            text: String::new(),
+            flushed_len: 0,
+            last_flush: None,
+            min_interval,
+            min_new_chars,
+        }
+    }
+
+    pub fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+    }
+
+    /// Start over for a new model turn; text written before a tool call is
+    /// not part of the final answer.
+    pub fn reset(&mut self) {
+        self.text.clear();
+        self.flushed_len = 0;
+    }
+
+    /// The full text to show if an update is due at `now`, otherwise `None`.
+    pub fn take_update(&mut self, now: Instant) -> Option<String> {
+        if self.text.trim().is_empty() || self.text.len() == self.flushed_len {
+            return None;
+        }
+        if let Some(last) = self.last_flush {
+            if now.duration_since(last) < self.min_interval {
+                return None;
+            }
+        }
+        if self.text.len().abs_diff(self.flushed_len) < self.min_new_chars {
+            return None;
+        }
+        self.flushed_len = self.text.len();
+        self.last_flush = Some(now);
+        Some(self.text.clone())
+    }
+}
+
+/// A reply that is streamed into one channel message through
+/// [`ChannelAdapter::edit_text`].
+pub struct StreamingReply {
+    adapter: Arc<dyn ChannelAdapter>,
+    external_chat_id: String,
+    buffer: StreamBuffer,
+    message_id: Option<String>,
+    stopped: bool,
+}
+
+impl StreamingReply {
+    /// `None` when the channel cannot edit messages; callers then deliver the
+    /// final response the usual way.
+    pub fn new(adapter: Arc<dyn ChannelAdapter>, external_chat_id: &str) -> Option<Self> {
+        if !adapter.capabilities().edits {
+            return None;
+        }
+        Some(Self {
+            adapter,
+            external_chat_id: external_chat_id.to_string(),
+            buffer: StreamBuffer::new(STREAM_EDIT_INTERVAL, STREAM_MIN_NEW_CHARS),
+            message_id: None,
+            stopped: false,
+        })
+    }
+
+    pub async fn handle_event(&mut self, event: &AgentEvent) {
+        match event {
+            AgentEvent::Iteration { .. } => self.buffer.reset(),
+            AgentEvent::TextDelta { delta } => {
+                self.buffer.push(delta);
+                if let Some(snapshot) = self.buffer.take_update(Instant::now()) {
+                    self.show(&snapshot).await;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn show(&mut self, snapshot: &str) {
+        if self.stopped {
+            return;
+        }
+        let preview = truncate_preview(snapshot, self.adapter.capabilities().max_message_len);
+        let result = match &self.message_id {
+            Some(id) => {
+                self.adapter
+                    .edit_text(&self.external_chat_id, id, &preview)
+                    .await
+            }
+            None => match self
+                .adapter
+                .send_text_with_id(&self.external_chat_id, &preview)
+                .await
+            {
+                Ok(Some(id)) => {
+                    self.message_id = Some(id);
+                    Ok(())
+                }
+                Ok(None) => Err("channel did not return a message id".to_string()),
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = result {
+            warn!(
+                "{}: streaming stopped, final reply will be sent normally: {e}",
+                self.adapter.name()
+            );
+            self.stopped = true;
+        }
+    }
+
+    /// Replace the streamed text with `final_text`. Returns the id of the
+    /// edited message, or `None` if nothing was streamed and the caller should
+    /// send the reply itself.
+    pub async fn finish(self, final_text: &str) -> Option<String> {
+        let id = self.message_id?;
+        let chunks = self.adapter.capabilities().split_text(final_text);
+        let mut chunks = chunks.into_iter();
+        let first = chunks.next().unwrap_or_default();
+        if let Err(e) = self
+            .adapter
+            .edit_text(&self.external_chat_id, &id, &first)
+            .await
+        {
+            warn!(
+                "{}: failed to finalize streamed reply: {e}",
+                self.adapter.name()
+            );
+        }
+        for chunk in chunks {
+            if let Err(e) = self.adapter.send_text(&self.external_chat_id, &chunk).await {
+                warn!("{}: failed to send reply chunk: {e}", self.adapter.name());
+            }
+        }
+        Some(id)
+    }
+
+    /// Remove the streamed message, for runs whose answer was already
+    /// delivered another way, such as the `send_message` tool.
+    pub async fn discard(self) {
+        let Some(id) = self.message_id else {
+            return;
+        };
+        if let Err(e) = self
+            .adapter
+            .delete_message(&self.external_chat_id, &id)
+            .await
+        {
+            warn!(
+                "{}: failed to remove streamed reply: {e}",
+                self.adapter.name()
+            );
+        }
+    }
+}
+
+/// Fit a partial reply into one message, marking it as cut off.
+fn truncate_preview(text: &str, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text.to_string();
+    }
+    let cut = floor_char_boundary(text, max_len.saturating_sub('…'.len_utf8()));
+    format!("{}…", &text[..cut])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use microclaw_channels::channel::ConversationKind;
+    use microclaw_channels::channel_adapter::ChannelCapabilities;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingAdapter {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ChannelAdapter for RecordingAdapter {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
+            Vec::new()
+        }
+
+        fn capabilities(&self) -> ChannelCapabilities {
+            ChannelCapabilities {
+                edits: true,
+                ..ChannelCapabilities::default()
+            }
+        }
+
+        async fn send_text(&self, _external_chat_id: &str, text: &str) -> Result<(), String> {
+            self.calls.lock().unwrap().push(format!("send {text}"));
+            Ok(())
+        }
+
+        async fn send_text_with_id(
+            &self,
+            _external_chat_id: &str,
+            text: &str,
+        ) -> Result<Option<String>, String> {
+            self.calls.lock().unwrap().push(format!("send {text}"));
+            Ok(Some("m1".to_string()))
+        }
+
+        async fn delete_message(
+            &self,
+            _external_chat_id: &str,
+            message_id: &str,
+        ) -> Result<(), String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("delete {message_id}"));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discard_removes_the_streamed_message() {
+        let adapter = Arc::new(RecordingAdapter::default());
+        let mut reply = StreamingReply::new(adapter.clone(), "room").unwrap();
+        reply
+            .handle_event(&AgentEvent::TextDelta {
+                delta: "Sending the report to the team channel now.".to_string(),
+            })
+            .await;
+        reply.discard().await;
+        assert_eq!(
+            *adapter.calls.lock().unwrap(),
+            vec![
+                "send Sending the report to the team channel now.".to_string(),
+                "delete m1".to_string()
+            ]
+        );
+
+        // Nothing was posted, so there is nothing to remove.
+        let adapter = Arc::new(RecordingAdapter::default());
+        StreamingReply::new(adapter.clone(), "room")
+            .unwrap()
+            .discard()
+            .await;
+        assert!(adapter.calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stream_buffer_coalesces_deltas() {
+        let start = Instant::now();
+        let mut buffer = StreamBuffer::new(Duration::from_secs(1), 10);
+
+        buffer.push("Hello");
+        assert_eq!(buffer.take_update(start), None);
+        buffer.push(", world!");
+        assert_eq!(buffer.take_update(start).as_deref(), Some("Hello, world!"));
+
+        buffer.push(" More text arriving quickly.");
+        assert_eq!(buffer.take_update(start + Duration::from_millis(200)), None);
+        assert_eq!(
+            buffer
+                .take_update(start + Duration::from_millis(1200))
+                .as_deref(),
+            Some("Hello, world! More text arriving quickly.")
+        );
+        assert_eq!(buffer.take_update(start + Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn test_stream_buffer_reset_starts_new_turn() {
+        let start = Instant::now();
+        let mut buffer = StreamBuffer::new(Duration::ZERO, 1);
+        buffer.push("Let me check that.");
+        assert!(buffer.take_update(start).is_some());
+
+        buffer.reset();
+        assert_eq!(buffer.take_update(start), None);
+        buffer.push("   ");
+        assert_eq!(buffer.take_update(start), None);
+        buffer.push("Here is the answer.");
+        assert_eq!(
+            buffer.take_update(start).as_deref(),
+            Some("   Here is the answer.")
+        );
+    }
+
+    #[test]
+    fn test_truncate_preview() {
+        assert_eq!(truncate_preview("short", 10), "short");
+        let cut = truncate_preview("ééééé", 6);
+        assert!(cut.len() <= 6);
+        assert_eq!(cut, "é…");
+    }
+}
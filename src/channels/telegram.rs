@@ -3,9 +3,10 @@ use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use teloxide::prelude::*;
-use teloxide::types::{ChatAction, InputFile, ParseMode, ThreadId};
+use teloxide::types::{ChatAction, InputFile, MessageId, ParseMode, ReactionType, ThreadId};
 use tracing::{error, info, warn};
 
 use crate::agent_engine::{
@@ -16,15 +17,16 @@ use crate::channels::startup_guard::{
 };
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
 #[cfg(test)]
 use microclaw_core::llm_types::{ContentBlock, ImageSource, MessageContent};
-use microclaw_core::text::floor_char_boundary;
+use microclaw_core::text::split_text_preserving_code;
 use microclaw_storage::db::{call_blocking, StoredMessage};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct TelegramAccountConfig {
     pub bot_token: String,
     #[serde(default)]
@@ -43,7 +45,7 @@ fn default_enabled() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct TelegramChannelConfig {
     #[serde(default)]
     pub bot_token: String,
@@ -115,6 +117,16 @@ impl ChannelAdapter for TelegramAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            reactions: true,
+            edits: true,
+            threads: true,
+            max_message_len: 4096,
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("telegram_private", ConversationKind::Private),
@@ -177,6 +189,102 @@ impl ChannelAdapter for TelegramAdapter {
             None => format!("[attachment:{}]", file_path.display()),
         })
     }
+
+    async fn send_reaction(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<(), String> {
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        let telegram_message_id = message_id
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid Telegram message id '{message_id}'"))?;
+        self.bot
+            .set_message_reaction(ChatId(telegram_chat_id), MessageId(telegram_message_id))
+            .reaction(vec![ReactionType::Emoji {
+                emoji: emoji.to_string(),
+            }])
+            .await
+            .map_err(|e| format!("Failed to send Telegram reaction: {e}"))?;
+        Ok(())
+    }
+
+    async fn send_text_with_id(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        if text.chars().count() > 4096 {
+            self.send_text(external_chat_id, text).await?;
+            return Ok(None);
+        }
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        let chat_id = ChatId(telegram_chat_id);
+        let sent = match self
+            .bot
+            .send_message(chat_id, render_markdown_v2_safe(text))
+            .parse_mode(ParseMode::MarkdownV2)
+            .await
+        {
+            Ok(msg) => msg,
+            Err(err) => {
+                warn!("Telegram MarkdownV2 send failed, falling back to plain text: {err}");
+                self.bot
+                    .send_message(chat_id, text)
+                    .await
+                    .map_err(|e| format!("Failed to send Telegram message: {e}"))?
+            }
+        };
+        Ok(Some(sent.id.0.to_string()))
+    }
+
+    async fn edit_text(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), String> {
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        let telegram_message_id = message_id
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid Telegram message id '{message_id}'"))?;
+        let chat_id = ChatId(telegram_chat_id);
+        let message_id = MessageId(telegram_message_id);
+        if let Err(err) = self
+            .bot
+            .edit_message_text(chat_id, message_id, render_markdown_v2_safe(text))
+            .parse_mode(ParseMode::MarkdownV2)
+            .await
+        {
+            warn!("Telegram MarkdownV2 edit failed, falling back to plain text: {err}");
+            self.bot
+                .edit_message_text(chat_id, message_id, text)
+                .await
+                .map_err(|e| format!("Failed to edit Telegram message: {e}"))?;
+        }
+        Ok(())
+    }
+
+    async fn delete_message(&self, external_chat_id: &str, message_id: &str) -> Result<(), String> {
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        let telegram_message_id = message_id
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid Telegram message id '{message_id}'"))?;
+        self.bot
+            .delete_message(ChatId(telegram_chat_id), MessageId(telegram_message_id))
+            .await
+            .map_err(|e| format!("Failed to delete Telegram message: {e}"))?;
+        Ok(())
+    }
 }
 
 /// Escape XML special characters in user-supplied content to prevent prompt injection.
@@ -335,6 +443,12 @@ pub async fn start_telegram_bot(
         format!("An error from the Telegram update listener ({channel_name})"),
     );
 
+    // Long polling has no handshake; a successful getMe shows the token works.
+    match bot.get_me().await {
+        Ok(_) => crate::health::report_channel_connected(&channel_name, true),
+        Err(e) => warn!("Telegram channel '{channel_name}': getMe failed: {e}"),
+    }
+
     let mut dispatcher = Dispatcher::builder(bot, handler)
         .default_handler(|_| async {})
         .dependencies(dptree::deps![state, ctx])
@@ -359,6 +473,7 @@ pub async fn start_telegram_bot(
             );
         }
     }
+    crate::health::report_channel_connected(&channel_name, false);
 
     Ok(())
 }
@@ -451,6 +566,7 @@ async fn handle_message(
         if should_drop_recent_duplicate_message(&tg_channel_name, &inbound_message_id) {
             return Ok(());
         }
+        crate::metrics::record_message_received(&tg_channel_name);
         if !should_respond && !state.config.allow_group_slash_without_mention {
             return Ok(());
         }
@@ -663,6 +779,7 @@ async fn handle_message(
     if should_drop_recent_duplicate_message(&tg_channel_name, &inbound_message_id) {
         return Ok(());
     }
+    crate::metrics::record_message_received(&tg_channel_name);
 
     // Check group allowlist
     if (db_chat_type == "telegram_group" || db_chat_type == "telegram_supergroup")
@@ -784,6 +901,20 @@ async fn handle_message(
     if !should_respond {
         return Ok(());
     }
+    let rate_limit_sender = sender_user_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| sender_name.clone());
+    if !allow_sender_message(
+        &state,
+        &tg_channel_name,
+        chat_id,
+        &rate_limit_sender,
+        &raw_chat_id.to_string(),
+    )
+    .await
+    {
+        return Ok(());
+    }
 
     info!(
         "Processing message from {} in chat {}: {}",
@@ -974,50 +1105,9 @@ fn guess_image_media_type(data: &[u8]) -> String {
 }
 
 fn split_response_text(text: &str) -> Vec<String> {
-    const MAX_LEN: usize = 4096;
-
-    if text.len() <= MAX_LEN {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text.to_string();
-
-    while !remaining.is_empty() {
-        let chunk_len = if remaining.len() <= MAX_LEN {
-            remaining.len()
-        } else {
-            let boundary = floor_char_boundary(&remaining, MAX_LEN.min(remaining.len()));
-            remaining[..boundary]
-                .rfind(char::from(10))
-                .unwrap_or(boundary)
-        };
-
-        let mut chunk = remaining[..chunk_len].to_string();
-        let mut next_remaining = remaining[chunk_len..].to_string();
-        if next_remaining.starts_with(char::from(10)) {
-            next_remaining = next_remaining[1..].to_string();
-        }
-
-        // Keep fenced code blocks balanced per chunk; otherwise Telegram rejects MarkdownV2
-        // and the whole chunk falls back to plain text.
-        let fence_count = chunk
-            .lines()
-            .filter(|line| line.trim_start().starts_with("```"))
-            .count();
-        if fence_count % 2 == 1 {
-            chunk.push(char::from(10));
-            chunk.push_str("```");
-            if !next_remaining.is_empty() {
-                next_remaining = format!("```{}{}", char::from(10), next_remaining);
-            }
-        }
-
-        chunks.push(chunk);
-        remaining = next_remaining;
-    }
-
-    chunks
+    // Keep fenced code blocks balanced per chunk; otherwise Telegram rejects MarkdownV2
+    // and the whole chunk falls back to plain text.
+    split_text_preserving_code(text, 4096)
 }
 
 fn escape_markdown_v2(text: &str) -> String {
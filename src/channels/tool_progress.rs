@@ -0,0 +1,220 @@
+//! A single status message ("🔧 running browser…") that follows the agent's
+//! tool calls and is removed once the final answer goes out.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use microclaw_channels::channel_adapter::ChannelAdapter;
+use tracing::warn;
+
+use crate::agent_engine::AgentEvent;
+
+/// Minimum time between two updates of the status message.
+const TOOL_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The status line for a tool event, or `None` for events that don't change it.
+pub fn tool_status_line(event: &AgentEvent) -> Option<String> {
+    match event {
+        AgentEvent::ToolStart { name } => Some(format!("🔧 running {name}…")),
+        AgentEvent::ToolResult {
+            name,
+            is_error: true,
+            ..
+        } => Some(format!("⚠️ {name} failed")),
+        AgentEvent::ToolResult { name, .. } => Some(format!("✅ {name} done")),
+        _ => None,
+    }
+}
+
+/// Rate-limits status updates. A line offered too soon after the last one is
+/// held back and replaced by whatever is offered next.
+#[derive(Debug)]
+pub struct StatusDebounce {
+    min_interval: Duration,
+    last_shown_at: Option<Instant>,
+    shown: Option<String>,
+    pending: Option<String>,
+}
+
+impl StatusDebounce {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_shown_at: None,
+            shown: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the line to display now, if any.
+    pub fn offer(&mut self, line: String, now: Instant) -> Option<String> {
+        self.pending = Some(line);
+        self.flush(now)
+    }
+
+    /// Returns the held-back line once the interval has passed.
+    pub fn flush(&mut self, now: Instant) -> Option<String> {
+        let line = self.pending.take()?;
+        if self.shown.as_deref() == Some(line.as_str()) {
+            return None;
+        }
+        if let Some(last) = self.last_shown_at {
+            if now.duration_since(last) < self.min_interval {
+                self.pending = Some(line);
+                return None;
+            }
+        }
+        self.last_shown_at = Some(now);
+        self.shown = Some(line.clone());
+        Some(line)
+    }
+}
+
+/// Posts and edits the status message through a channel adapter.
+pub struct ToolProgress {
+    adapter: Arc<dyn ChannelAdapter>,
+    external_chat_id: String,
+    debounce: StatusDebounce,
+    message_id: Option<String>,
+    stopped: bool,
+}
+
+impl ToolProgress {
+    /// `None` when the channel cannot edit messages, since every update would
+    /// otherwise be a new message.
+    pub fn new(adapter: Arc<dyn ChannelAdapter>, external_chat_id: &str) -> Option<Self> {
+        if !adapter.capabilities().edits {
+            return None;
+        }
+        Some(Self {
+            adapter,
+            external_chat_id: external_chat_id.to_string(),
+            debounce: StatusDebounce::new(TOOL_PROGRESS_INTERVAL),
+            message_id: None,
+            stopped: false,
+        })
+    }
+
+    pub async fn handle_event(&mut self, event: &AgentEvent) {
+        let now = Instant::now();
+        let due = match tool_status_line(event) {
+            Some(line) => self.debounce.offer(line, now),
+            None => self.debounce.flush(now),
+        };
+        if let Some(line) = due {
+            self.show(&line).await;
+        }
+    }
+
+    async fn show(&mut self, line: &str) {
+        if self.stopped {
+            return;
+        }
+        let result = match &self.message_id {
+            Some(id) => {
+                self.adapter
+                    .edit_text(&self.external_chat_id, id, line)
+                    .await
+            }
+            None => match self
+                .adapter
+                .send_text_with_id(&self.external_chat_id, line)
+                .await
+            {
+                Ok(id) => {
+                    self.message_id = id;
+                    self.stopped = self.message_id.is_none();
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = result {
+            warn!("{}: tool progress disabled: {e}", self.adapter.name());
+            self.stopped = true;
+        }
+    }
+
+    /// Remove the status message, or mark it finished where deletion fails.
+    pub async fn finish(self) {
+        let Some(id) = self.message_id else {
+            return;
+        };
+        if self
+            .adapter
+            .delete_message(&self.external_chat_id, &id)
+            .await
+            .is_err()
+        {
+            let _ = self
+                .adapter
+                .edit_text(&self.external_chat_id, &id, "✅ done")
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_result(name: &str, is_error: bool) -> AgentEvent {
+        AgentEvent::ToolResult {
+            name: name.into(),
+            is_error,
+            preview: String::new(),
+            duration_ms: 5,
+            status_code: None,
+            bytes: 0,
+            error_type: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_status_line_mapping() {
+        assert_eq!(
+            tool_status_line(&AgentEvent::ToolStart {
+                name: "browser".into()
+            })
+            .as_deref(),
+            Some("🔧 running browser…")
+        );
+        assert_eq!(
+            tool_status_line(&tool_result("bash", false)).as_deref(),
+            Some("✅ bash done")
+        );
+        assert_eq!(
+            tool_status_line(&tool_result("bash", true)).as_deref(),
+            Some("⚠️ bash failed")
+        );
+        assert_eq!(
+            tool_status_line(&AgentEvent::TextDelta { delta: "x".into() }),
+            None
+        );
+        assert_eq!(
+            tool_status_line(&AgentEvent::Iteration { iteration: 1 }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_status_debounce_drops_rapid_updates() {
+        let start = Instant::now();
+        let mut debounce = StatusDebounce::new(Duration::from_secs(2));
+
+        assert_eq!(
+            debounce.offer("🔧 running bash…".into(), start).as_deref(),
+            Some("🔧 running bash…")
+        );
+        let soon = start + Duration::from_millis(300);
+        assert_eq!(debounce.offer("✅ bash done".into(), soon), None);
+        assert_eq!(debounce.offer("🔧 running grep…".into(), soon), None);
+
+        let later = start + Duration::from_secs(3);
+        assert_eq!(debounce.flush(later).as_deref(), Some("🔧 running grep…"));
+        assert_eq!(debounce.flush(later), None);
+
+        let much_later = later + Duration::from_secs(5);
+        assert_eq!(debounce.offer("🔧 running grep…".into(), much_later), None);
+    }
+}
@@ -1,24 +1,28 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use axum::extract::Query;
 use axum::response::IntoResponse;
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tracing::{error, info};
 
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{should_suppress_user_error, AgentEvent, AgentRequestContext};
+use crate::channels::media::guess_mime_from_extension;
 use crate::channels::startup_guard::{
     mark_channel_started, parse_epoch_ms_from_seconds_str, should_drop_pre_start_message,
     should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::rate_limit::allow_sender_message;
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
-use microclaw_channels::channel_adapter::ChannelAdapter;
-use microclaw_core::text::split_text;
+use microclaw_channels::channel_adapter::{ChannelAdapter, ChannelCapabilities};
+use microclaw_core::text::split_text_preserving_code;
 use microclaw_storage::db::{call_blocking, StoredMessage};
 
 pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
@@ -96,7 +100,7 @@ fn default_api_version() -> String {
     "v21.0".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct WhatsAppAccountConfig {
     pub access_token: String,
     pub phone_number_id: String,
@@ -112,7 +116,7 @@ pub struct WhatsAppAccountConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct WhatsAppChannelConfig {
     #[serde(default)]
     pub access_token: String,
@@ -295,6 +299,14 @@ impl ChannelAdapter for WhatsAppAdapter {
         &self.name
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            attachments: true,
+            max_message_len: 3000,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
         vec![
             ("whatsapp_dm", ConversationKind::Private),
@@ -313,6 +325,161 @@ impl ChannelAdapter for WhatsAppAdapter {
         )
         .await
     }
+
+    async fn send_attachment(
+        &self,
+        external_chat_id: &str,
+        file_path: &Path,
+        caption: Option<&str>,
+    ) -> Result<String, String> {
+        send_whatsapp_attachment(
+            &self.http_client,
+            &self.access_token,
+            &self.phone_number_id,
+            &self.api_version,
+            external_chat_id,
+            file_path,
+            caption,
+        )
+        .await
+    }
+}
+
+/// WhatsApp Cloud API message type for a mime type. Only JPEG/PNG are accepted
+/// as images and only MP4/3GPP as video; everything else goes out as a document.
+fn whatsapp_media_type_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/jpeg" | "image/png" => "image",
+        "video/mp4" | "video/3gpp" => "video",
+        m if m.starts_with("audio/") => "audio",
+        _ => "document",
+    }
+}
+
+/// Upload size limit per WhatsApp media type, in bytes.
+fn whatsapp_media_size_limit(media_type: &str) -> u64 {
+    match media_type {
+        "image" => 5 * 1024 * 1024,
+        "audio" | "video" => 16 * 1024 * 1024,
+        _ => 100 * 1024 * 1024,
+    }
+}
+
+async fn send_whatsapp_attachment(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    phone_number_id: &str,
+    api_version: &str,
+    to: &str,
+    file_path: &Path,
+    caption: Option<&str>,
+) -> Result<String, String> {
+    let filename = file_path
+        .file_name()
+        .and_then(|v| v.to_str())
+        .unwrap_or("attachment.bin")
+        .to_string();
+    let mime = guess_mime_from_extension(file_path);
+    let media_type = whatsapp_media_type_for_mime(mime);
+    let limit = whatsapp_media_size_limit(media_type);
+    let size = tokio::fs::metadata(file_path)
+        .await
+        .map_err(|e| format!("Failed to read attachment file: {e}"))?
+        .len();
+    if size > limit {
+        return Err(format!(
+            "WhatsApp {media_type} attachments are limited to {} MB (file is {:.1} MB)",
+            limit / (1024 * 1024),
+            size as f64 / (1024.0 * 1024.0)
+        ));
+    }
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .map_err(|e| format!("Failed to read attachment file: {e}"))?;
+
+    let upload_url = format!(
+        "https://graph.facebook.com/{}/{}/media",
+        api_version.trim(),
+        phone_number_id.trim()
+    );
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(filename.clone())
+        .mime_str(mime)
+        .map_err(|e| format!("Invalid attachment mime type: {e}"))?;
+    let form = reqwest::multipart::Form::new()
+        .text("messaging_product", "whatsapp")
+        .text("type", mime.to_string())
+        .part("file", part);
+    let response = http_client
+        .post(&upload_url)
+        .bearer_auth(access_token.trim())
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("WhatsApp media upload failed: {e}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("WhatsApp media upload error {status}: {body}"));
+    }
+    let upload_json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("WhatsApp media upload parse failed: {e}"))?;
+    let media_id = upload_json
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "WhatsApp media upload response missing id".to_string())?;
+
+    let caption = caption.map(str::trim).filter(|v| !v.is_empty());
+    let mut media = serde_json::json!({ "id": media_id });
+    if media_type == "document" {
+        media["filename"] = serde_json::Value::String(filename);
+    }
+    // Audio messages cannot carry a caption; it is sent as a follow-up text instead.
+    if let Some(c) = caption.filter(|_| media_type != "audio") {
+        media["caption"] = serde_json::Value::String(c.to_string());
+    }
+    let body = serde_json::json!({
+        "messaging_product": "whatsapp",
+        "to": to,
+        "type": media_type,
+        media_type: media,
+    });
+    let messages_url = format!(
+        "https://graph.facebook.com/{}/{}/messages",
+        api_version.trim(),
+        phone_number_id.trim()
+    );
+    let response = http_client
+        .post(&messages_url)
+        .bearer_auth(access_token.trim())
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("WhatsApp API request failed: {e}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("WhatsApp API error {status}: {body}"));
+    }
+
+    if let Some(c) = caption.filter(|_| media_type == "audio") {
+        send_whatsapp_text(
+            http_client,
+            access_token,
+            phone_number_id,
+            api_version,
+            to,
+            c,
+        )
+        .await?;
+    }
+
+    Ok(match caption {
+        Some(c) => format!("[attachment:{}] {}", file_path.display(), c),
+        None => format!("[attachment:{}]", file_path.display()),
+    })
 }
 
 async fn send_whatsapp_text(
@@ -328,7 +495,7 @@ async fn send_whatsapp_text(
         api_version.trim(),
         phone_number_id.trim()
     );
-    for chunk in split_text(text, 3000) {
+    for chunk in split_text_preserving_code(text, 3000) {
         let body = serde_json::json!({
             "messaging_product": "whatsapp",
             "to": to,
@@ -359,6 +526,7 @@ pub async fn start_whatsapp_bot(_app_state: Arc<AppState>, runtime: WhatsAppRunt
         "WhatsApp adapter '{}' is ready (webhook ingress via web server, phone_number_id={})",
         runtime.channel_name, runtime.phone_number_id
     );
+    crate::health::report_channel_connected(&runtime.channel_name, true);
 }
 
 #[derive(Debug, Deserialize)]
@@ -598,6 +766,7 @@ async fn handle_whatsapp_message(
     }
 
     let trimmed = text.trim();
+    crate::metrics::record_message_received(&runtime.channel_name);
     if is_slash_command(trimmed) {
         if let Some(reply) =
             handle_chat_command(&app_state, chat_id, &runtime.channel_name, trimmed).await
@@ -644,6 +813,17 @@ async fn handle_whatsapp_message(
         );
         return;
     }
+    if !allow_sender_message(
+        &app_state,
+        &runtime.channel_name,
+        chat_id,
+        external_chat_id,
+        external_chat_id,
+    )
+    .await
+    {
+        return;
+    }
 
     info!(
         "WhatsApp message from {} in {}: {}",
@@ -749,3 +929,36 @@ async fn handle_whatsapp_message(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whatsapp_media_type_for_mime() {
+        let cases = [
+            ("photo.jpg", "image"),
+            ("photo.PNG", "image"),
+            ("anim.gif", "document"),
+            ("sticker.webp", "document"),
+            ("voice.ogg", "audio"),
+            ("song.mp3", "audio"),
+            ("clip.mp4", "video"),
+            ("clip.mov", "document"),
+            ("report.pdf", "document"),
+            ("blob", "document"),
+        ];
+        for (name, expected) in cases {
+            let mime = guess_mime_from_extension(Path::new(name));
+            assert_eq!(whatsapp_media_type_for_mime(mime), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_whatsapp_media_size_limits() {
+        assert_eq!(whatsapp_media_size_limit("image"), 5 * 1024 * 1024);
+        assert_eq!(whatsapp_media_size_limit("audio"), 16 * 1024 * 1024);
+        assert_eq!(whatsapp_media_size_limit("video"), 16 * 1024 * 1024);
+        assert_eq!(whatsapp_media_size_limit("document"), 100 * 1024 * 1024);
+    }
+}
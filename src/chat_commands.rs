@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
 use crate::agent_engine::archive_conversation;
-use crate::config::Config;
+use crate::config::{Config, ResetScope};
+use crate::embedding::EmbeddingProvider;
 use crate::run_control;
 use crate::runtime::AppState;
+use crate::tools::search_history::{format_history_hits, search_history, DEFAULT_SEARCH_LIMIT};
 use microclaw_core::llm_types::Message;
 use microclaw_storage::db::{call_blocking, Database};
 use microclaw_storage::usage::build_usage_report;
@@ -39,6 +41,111 @@ fn normalized_slash_command(text: &str) -> Option<&str> {
     }
 }
 
+/// Scope requested by a `/reset` command: `/reset session`, `/reset all`, or
+/// bare `/reset` for `default`. `None` for an unrecognised argument.
+pub fn parse_reset_command(command: &str, default: ResetScope) -> Option<ResetScope> {
+    let arg = command.trim().strip_prefix("/reset")?.trim();
+    match arg.to_ascii_lowercase().as_str() {
+        "" => Some(default),
+        "session" => Some(ResetScope::Session),
+        "all" => Some(ResetScope::All),
+        _ => None,
+    }
+}
+
+/// Clear what a `/reset` command asks for and describe what was cleared.
+pub async fn run_reset_command(
+    db: Arc<Database>,
+    chat_id: i64,
+    command: &str,
+    default: ResetScope,
+) -> String {
+    match parse_reset_command(command, default) {
+        Some(ResetScope::Session) => {
+            let _ = call_blocking(db, move |db| db.delete_session(chat_id)).await;
+            "Session cleared. Chat history was kept.".to_string()
+        }
+        Some(ResetScope::All) => {
+            let _ = call_blocking(db, move |db| db.clear_chat_context(chat_id)).await;
+            "Context cleared (session + chat history).".to_string()
+        }
+        None => "Usage: /reset [session|all]".to_string(),
+    }
+}
+
+/// Commands that change state beyond the current conversation's replies:
+/// `/reload-skills`, `/archive`, a `/reset` that clears chat history, and
+/// `/model` with an argument. Channels with an admin list gate these.
+pub fn is_mutating_command(command_text: &str, reset_default: ResetScope) -> bool {
+    let Some(command) = normalized_slash_command(command_text) else {
+        return false;
+    };
+    let command = command.trim();
+    if command == "/reload-skills" || command == "/archive" {
+        return true;
+    }
+    if parse_reset_command(command, reset_default) == Some(ResetScope::All) {
+        return true;
+    }
+    matches!(
+        parse_model_command(command),
+        Some(ModelCommand::Set(_) | ModelCommand::Reset)
+    )
+}
+
+/// Built-in commands and what they do, in the order `/help` lists them.
+pub const CHAT_COMMANDS: &[(&str, &str)] = &[
+    ("/help", "Show this list"),
+    (
+        "/reset [session|all]",
+        "Forget the live session, or the session and chat history",
+    ),
+    ("/stop", "Stop the run in progress"),
+    (
+        "/status",
+        "Show channel, model, session and scheduled tasks",
+    ),
+    (
+        "/model [<name>|default]",
+        "Show the model, or switch it for this chat (control chats)",
+    ),
+    ("/usage", "Show token usage and cost for this chat"),
+    (
+        "/archive",
+        "Save the current session to the conversation archive",
+    ),
+    (
+        "/search <query>",
+        "Find past messages in this chat by meaning",
+    ),
+    ("/skills", "List available skills"),
+    ("/reload-skills", "Reload skills from disk"),
+];
+
+/// Text for `/help`: the built-in commands plus any channel-specific notes.
+pub fn build_help_text(notes: &[String]) -> String {
+    let mut text = String::from("Commands\n");
+    for (command, description) in CHAT_COMMANDS {
+        text.push_str(&format!("{command} — {description}\n"));
+    }
+    text.push_str("\nSend /skills to see what else I can do.");
+    for note in notes {
+        text.push_str(&format!("\n{note}"));
+    }
+    text
+}
+
+fn channel_help_notes(config: &Config, caller_channel: &str) -> Vec<String> {
+    if caller_channel == "web" {
+        return Vec::new();
+    }
+    let mut notes = vec!["In group chats, mention me to get a reply.".to_string()];
+    if !config.allow_group_slash_without_mention {
+        notes.push("Commands in group chats need a mention as well.".to_string());
+    }
+    notes
+}
+
 pub fn unknown_command_response() -> String {
     "Unknown command.".to_string()
 }
@@ -51,9 +158,23 @@ pub async fn handle_chat_command(
 ) -> Option<String> {
     let trimmed = normalized_slash_command(command_text)?.trim();
 
-    if trimmed == "/reset" {
-        let _ = call_blocking(state.db.clone(), move |db| db.clear_chat_context(chat_id)).await;
-        return Some("Context cleared (session + chat history).".to_string());
+    if trimmed == "/reset" || trimmed.starts_with("/reset ") {
+        return Some(
+            run_reset_command(
+                state.db.clone(),
+                chat_id,
+                trimmed,
+                state.config.reset_default_scope,
+            )
+            .await,
+        );
+    }
+
+    if trimmed == "/help" {
+        return Some(build_help_text(&channel_help_notes(
+            &state.config,
+            caller_channel,
+        )));
     }
 
     if trimmed == "/stop" {
@@ -69,8 +190,7 @@ pub async fn handle_chat_command(
     }
 
     if trimmed == "/reload-skills" {
-        let count = state.skills.reload().len();
-        return Some(format!("Reloaded {count} skills from disk."));
+        return Some(state.skills.reload().summary());
     }
 
     if trimmed == "/archive" {
@@ -87,8 +207,20 @@ pub async fn handle_chat_command(
         return Some("No session to archive.".to_string());
     }
 
+    if trimmed == "/search" || trimmed.starts_with("/search ") {
+        return Some(
+            run_search_command(state.db.clone(), state.embedding.as_ref(), chat_id, trimmed).await,
+        );
+    }
+
     if trimmed == "/usage" {
-        let text = match build_usage_report(state.db.clone(), chat_id).await {
+        let text = match build_usage_report(
+            state.db.clone(),
+            chat_id,
+            state.config.usage_report_top_senders,
+        )
+        .await
+        {
             Ok(v) => v,
             Err(e) => format!("Failed to query usage statistics: {e}"),
         };
@@ -109,17 +241,39 @@ pub async fn handle_chat_command(
     }
 
     if trimmed == "/model" || trimmed.starts_with("/model ") {
-        return Some(build_model_response(
-            &state.config,
-            &state.llm_model_overrides,
-            caller_channel,
-            trimmed,
-        ));
+        return Some(
+            run_model_command(
+                state.db.clone(),
+                &state.config,
+                &state.llm_model_overrides,
+                caller_channel,
+                chat_id,
+                trimmed,
+            )
+            .await,
+        );
     }
 
     None
 }
 
+/// `/search <query>`: semantic lookup over the chat's stored messages.
+pub async fn run_search_command(
+    db: Arc<Database>,
+    embedding: Option<&Arc<dyn EmbeddingProvider>>,
+    chat_id: i64,
+    command: &str,
+) -> String {
+    let query = command.trim().strip_prefix("/search").unwrap_or("").trim();
+    if query.is_empty() {
+        return "Usage: /search <query>".to_string();
+    }
+    match search_history(db, embedding, query, Some(chat_id), DEFAULT_SEARCH_LIMIT).await {
+        Ok(hits) => format_history_hits(&hits),
+        Err(e) => e,
+    }
+}
+
 pub async fn build_status_response(
     db: Arc<Database>,
     config: &Config,
@@ -128,9 +282,13 @@ pub async fn build_status_response(
     caller_channel: &str,
 ) -> String {
     let provider = config.llm_provider.trim();
-    let model = llm_model_overrides
-        .get(caller_channel)
-        .map(String::as_str)
+    let chat_override = call_blocking(db.clone(), move |db| db.get_chat_model_override(chat_id))
+        .await
+        .ok()
+        .flatten();
+    let model = chat_override
+        .as_deref()
+        .or_else(|| llm_model_overrides.get(caller_channel).map(String::as_str))
         .unwrap_or(config.model.as_str())
         .trim();
 
@@ -185,30 +343,108 @@ pub async fn build_status_response(
     )
 }
 
-pub fn build_model_response(
+/// A parsed `/model` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelCommand {
+    Show,
+    Set(String),
+    /// `/model default`: drop this chat's override.
+    Reset,
+}
+
+pub fn parse_model_command(command: &str) -> Option<ModelCommand> {
+    let rest = command.trim().strip_prefix("/model")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(match rest.trim() {
+        "" => ModelCommand::Show,
+        "default" | "reset" => ModelCommand::Reset,
+        name => ModelCommand::Set(name.to_string()),
+    })
+}
+
+/// Models `/model <name>` accepts: the provider's preset models plus any model
+/// already named in config (global, per-channel, or in `model_prices`).
+pub fn known_models(
+    config: &Config,
+    llm_model_overrides: &std::collections::HashMap<String, String>,
+) -> Vec<String> {
+    let mut models: Vec<String> = crate::setup::preset_models_for_provider(&config.llm_provider)
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+    models.push(config.model.clone());
+    models.extend(llm_model_overrides.values().cloned());
+    models.extend(config.model_prices.iter().map(|p| p.model.clone()));
+    let mut seen = std::collections::HashSet::new();
+    models.retain(|m| !m.trim().is_empty() && seen.insert(m.clone()));
+    models
+}
+
+/// Only control chats may change the model.
+pub fn can_switch_model(config: &Config, chat_id: i64) -> bool {
+    config.control_chat_ids.contains(&chat_id)
+}
+
+pub async fn run_model_command(
+    db: Arc<Database>,
     config: &Config,
     llm_model_overrides: &std::collections::HashMap<String, String>,
     caller_channel: &str,
+    chat_id: i64,
     command_text: &str,
 ) -> String {
     let provider = config.llm_provider.trim();
-    let model = llm_model_overrides
-        .get(caller_channel)
-        .map(String::as_str)
-        .unwrap_or(config.model.as_str())
-        .trim();
-    let requested = command_text
-        .trim()
-        .strip_prefix("/model")
-        .map(str::trim)
-        .unwrap_or("");
-
-    if requested.is_empty() {
-        format!("Current provider/model: {provider} / {model}")
-    } else {
-        format!(
-            "Model switching is not supported yet. Current provider/model: {provider} / {model}"
-        )
+    let Some(command) = parse_model_command(command_text) else {
+        return "Usage: /model [<name>|default]".to_string();
+    };
+    match command {
+        ModelCommand::Show => {
+            let chat_override =
+                call_blocking(db.clone(), move |db| db.get_chat_model_override(chat_id))
+                    .await
+                    .ok()
+                    .flatten();
+            match chat_override {
+                Some(model) => {
+                    format!("Current provider/model: {provider} / {model} (set for this chat)")
+                }
+                None => {
+                    let model = llm_model_overrides
+                        .get(caller_channel)
+                        .map(String::as_str)
+                        .unwrap_or(config.model.as_str())
+                        .trim();
+                    format!("Current provider/model: {provider} / {model}")
+                }
+            }
+        }
+        _ if !can_switch_model(config, chat_id) => {
+            "Not authorized: only control chats can switch the model.".to_string()
+        }
+        ModelCommand::Reset => {
+            match call_blocking(db, move |db| db.clear_chat_model_override(chat_id)).await {
+                Ok(_) => format!(
+                    "Model override cleared. Using {provider} / {}.",
+                    llm_model_overrides
+                        .get(caller_channel)
+                        .unwrap_or(&config.model)
+                ),
+                Err(e) => format!("Failed to clear model override: {e}"),
+            }
+        }
+        ModelCommand::Set(name) => {
+            let known = known_models(config, llm_model_overrides);
+            if !known.iter().any(|m| m == &name) {
+                return format!("Unknown model '{name}'. Known models: {}", known.join(", "));
+            }
+            let model = name.clone();
+            match call_blocking(db, move |db| db.set_chat_model_override(chat_id, &model)).await {
+                Ok(()) => format!("Model for this chat set to {provider} / {name}."),
+                Err(e) => format!("Failed to set model: {e}"),
+            }
+        }
     }
 }
 
@@ -227,7 +463,14 @@ pub async fn maybe_handle_plugin_command(
 
 #[cfg(test)]
 mod tests {
-    use super::is_slash_command;
+    use super::{
+        build_help_text, is_slash_command, parse_model_command, parse_reset_command,
+        run_model_command, ModelCommand, CHAT_COMMANDS,
+    };
+    use crate::config::{Config, ResetScope};
+    use microclaw_storage::db::Database;
+    use std::collections::HashMap;
+    use std::sync::Arc;
 
     #[test]
     fn test_is_slash_command_with_leading_mentions() {
@@ -237,4 +480,138 @@ mod tests {
         assert!(is_slash_command(" <@U123>   @bot   /status"));
         assert!(!is_slash_command("@bot hello"));
     }
+
+    #[test]
+    fn test_parse_reset_command_scopes() {
+        assert_eq!(
+            parse_reset_command("/reset", ResetScope::Session),
+            Some(ResetScope::Session)
+        );
+        assert_eq!(
+            parse_reset_command("/reset", ResetScope::All),
+            Some(ResetScope::All)
+        );
+        assert_eq!(
+            parse_reset_command("/reset session", ResetScope::All),
+            Some(ResetScope::Session)
+        );
+        assert_eq!(
+            parse_reset_command("/reset  ALL ", ResetScope::Session),
+            Some(ResetScope::All)
+        );
+        assert_eq!(
+            parse_reset_command("/reset everything", ResetScope::All),
+            None
+        );
+    }
+
+    #[test]
+    fn test_help_text_lists_every_command() {
+        let notes = vec!["In group chats, mention me to get a reply.".to_string()];
+        let text = build_help_text(&notes);
+        for command in [
+            "/help",
+            "/reset",
+            "/stop",
+            "/status",
+            "/model",
+            "/usage",
+            "/archive",
+            "/search",
+            "/skills",
+            "/reload-skills",
+        ] {
+            assert!(text.contains(command), "missing {command}");
+        }
+        assert_eq!(
+            CHAT_COMMANDS.len(),
+            text.lines().filter(|l| l.starts_with('/')).count()
+        );
+        assert!(text.ends_with("mention me to get a reply."));
+        // Fits the smallest single-message limit among the channels (iMessage).
+        assert!(text.chars().count() <= 1500);
+    }
+
+    #[test]
+    fn test_parse_model_command() {
+        assert_eq!(parse_model_command("/model"), Some(ModelCommand::Show));
+        assert_eq!(parse_model_command("/model  "), Some(ModelCommand::Show));
+        assert_eq!(
+            parse_model_command("/model gpt-5-mini"),
+            Some(ModelCommand::Set("gpt-5-mini".into()))
+        );
+        assert_eq!(
+            parse_model_command("/model default"),
+            Some(ModelCommand::Reset)
+        );
+        assert_eq!(parse_model_command("/models"), None);
+    }
+
+    #[tokio::test]
+    async fn test_model_switch_is_gated_and_persisted() {
+        let dir = std::env::temp_dir().join(format!("microclaw_model_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let mut config = Config::test_defaults();
+        config.llm_provider = "openai".into();
+        config.model = "gpt-5.2".into();
+        config.control_chat_ids = vec![1];
+        let overrides = HashMap::new();
+
+        let denied = run_model_command(
+            db.clone(),
+            &config,
+            &overrides,
+            "telegram",
+            2,
+            "/model gpt-5",
+        )
+        .await;
+        assert!(denied.contains("Not authorized"));
+        assert!(db.get_chat_model_override(2).unwrap().is_none());
+
+        let unknown = run_model_command(
+            db.clone(),
+            &config,
+            &overrides,
+            "telegram",
+            1,
+            "/model nope",
+        )
+        .await;
+        assert!(unknown.contains("Unknown model 'nope'"));
+        assert!(unknown.contains("gpt-5-mini"));
+
+        let set = run_model_command(
+            db.clone(),
+            &config,
+            &overrides,
+            "telegram",
+            1,
+            "/model gpt-5",
+        )
+        .await;
+        assert!(set.contains("set to openai / gpt-5"), "{set}");
+        assert_eq!(
+            db.get_chat_model_override(1).unwrap().as_deref(),
+            Some("gpt-5")
+        );
+        let shown =
+            run_model_command(db.clone(), &config, &overrides, "telegram", 1, "/model").await;
+        assert!(shown.contains("gpt-5 (set for this chat)"));
+        let other =
+            run_model_command(db.clone(), &config, &overrides, "telegram", 3, "/model").await;
+        assert!(other.ends_with("openai / gpt-5.2"));
+
+        run_model_command(
+            db.clone(),
+            &config,
+            &overrides,
+            "telegram",
+            1,
+            "/model default",
+        )
+        .await;
+        assert!(db.get_chat_model_override(1).unwrap().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
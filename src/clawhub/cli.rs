@@ -1,36 +1,158 @@
-use crate::clawhub::service::{ClawHubGateway, RegistryClawHubGateway};
+use crate::clawhub::service::{
+    retry_with_backoff, ClawHubGateway, RegistryClawHubGateway, RetryPolicy,
+};
 use crate::config::Config;
 use crate::error::MicroClawError;
 use crate::skills::SkillManager;
 use clap::{Parser, Subcommand};
-use microclaw_clawhub::install::InstallOptions;
+use microclaw_clawhub::client::DownloadProgress;
+use microclaw_clawhub::install::{
+    decide_update, dependents_of, ensure_version_available, exceeds_virustotal_threshold,
+    latest_version, parse_skill_spec, InstallOptions, UpdateDecision, LOCAL_SOURCE,
+};
+use microclaw_clawhub::types::{LockFile, SkillMeta};
+use microclaw_clawhub::verify::{verify_skills, VerifyIssue};
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::time::{sleep, Duration};
-
-/// Retry an async operation up to 3 times with brief delays
-async fn retry_with_backoff<T, F, Fut>(mut operation: F) -> Result<T, MicroClawError>
-where
-    F: FnMut() -> Fut,
-    Fut: std::future::Future<Output = Result<T, MicroClawError>>,
-{
-    let mut last_error = None;
-    for attempt in 1..=3 {
-        match operation().await {
-            Ok(result) => return Ok(result),
-            Err(e) => {
-                last_error = Some(e);
-                if attempt < 3 {
-                    sleep(Duration::from_millis(500)).await;
-                }
+
+static PROGRESS_DRAWN: AtomicBool = AtomicBool::new(false);
+
+/// Single-line download progress bar on stderr
+fn cli_download_progress() -> Arc<DownloadProgress> {
+    Arc::new(|received, total| {
+        PROGRESS_DRAWN.store(true, Ordering::Relaxed);
+        eprint!("\r{}", render_download_progress(received, total));
+        let _ = std::io::stderr().flush();
+    })
+}
+
+/// End the progress line, if one was drawn, so later output starts cleanly.
+fn finish_download_progress() {
+    if PROGRESS_DRAWN.swap(false, Ordering::Relaxed) {
+        eprintln!();
+    }
+}
+
+fn render_download_progress(received: u64, total: Option<u64>) -> String {
+    const WIDTH: u64 = 30;
+    let kib = |bytes: u64| bytes as f64 / 1024.0;
+    match total {
+        Some(total) if total > 0 => {
+            let received = received.min(total);
+            let filled = (received * WIDTH / total) as usize;
+            format!(
+                "Downloading [{}{}] {:>3}% {:.1}/{:.1} KiB",
+                "#".repeat(filled),
+                "-".repeat(WIDTH as usize - filled),
+                received * 100 / total,
+                kib(received),
+                kib(total)
+            )
+        }
+        _ => format!("Downloading {:.1} KiB", kib(received)),
+    }
+}
+
+/// Clamp `--limit` to the registry maximum and turn a 1-based `--page` into an offset.
+fn search_page(limit: usize, page: usize) -> (usize, usize) {
+    let limit = limit.clamp(1, microclaw_clawhub::client::MAX_SEARCH_LIMIT);
+    (limit, page.max(1).saturating_sub(1).saturating_mul(limit))
+}
+
+fn search_range_label(offset: usize, count: usize) -> String {
+    match (offset, count) {
+        (0, 0) => "No skills found.".to_string(),
+        (_, 0) => format!("No skills found after the first {}.", offset),
+        _ => format!("Showing {}–{}:", offset + 1, offset + count),
+    }
+}
+
+/// Turn CLI slug arguments into `(slug, version)` specs, folding in `--version`.
+fn resolve_install_specs(
+    slugs: &[String],
+    version_flag: Option<&str>,
+) -> Result<Vec<(String, Option<String>)>, MicroClawError> {
+    let mut specs = slugs
+        .iter()
+        .map(|s| parse_skill_spec(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(flag) = version_flag {
+        let [(slug, version)] = specs.as_mut_slice() else {
+            return Err(MicroClawError::Config(
+                "--version requires exactly one slug".into(),
+            ));
+        };
+        match version {
+            Some(existing) if existing != flag => {
+                return Err(MicroClawError::Config(format!(
+                    "Conflicting versions for '{}': {}@{} vs --version {}",
+                    slug, slug, existing, flag
+                )));
             }
+            _ => *version = Some(flag.to_string()),
+        }
+    }
+    Ok(specs)
+}
+
+/// Installed skills that declare `slug` as a dependency, either in their
+/// lockfile entry or in their SKILL.md frontmatter.
+fn installed_dependents(lock: &LockFile, skills_dir: &str, slug: &str) -> Vec<String> {
+    let mut dependents = dependents_of(lock, slug);
+    for skill in SkillManager::from_skills_dir(skills_dir).discover_skills_with_status(true) {
+        let dir_name = skill
+            .meta
+            .dir_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if dir_name != slug && skill.meta.dependencies.iter().any(|d| d == slug) {
+            dependents.push(dir_name);
         }
     }
-    Err(last_error
-        .unwrap_or_else(|| MicroClawError::Config("Unexpected error during retry".to_string())))
+    dependents.sort();
+    dependents.dedup();
+    dependents
+}
+
+/// Latest registry version for `slug`, consulting the versions endpoint when
+/// the skill metadata does not flag one.
+async fn resolve_latest_version(
+    gateway: &Arc<dyn ClawHubGateway>,
+    slug: &str,
+    retry: RetryPolicy,
+) -> Result<Option<String>, MicroClawError> {
+    let meta = retry_with_backoff(retry, || {
+        let gateway = gateway.clone();
+        let slug = slug.to_string();
+        async move { gateway.get_skill(&slug).await }
+    })
+    .await?;
+    if let Some(latest) = latest_version(&meta.versions) {
+        return Ok(Some(latest));
+    }
+    let versions = retry_with_backoff(retry, || {
+        let gateway = gateway.clone();
+        let slug = slug.to_string();
+        async move { gateway.get_versions(&slug).await }
+    })
+    .await?;
+    Ok(latest_version(&versions))
 }
 
 pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), MicroClawError> {
+    let gateway: Arc<dyn ClawHubGateway> = Arc::new(RegistryClawHubGateway::from_config(config));
+    handle_skill_cli_with_gateway(args, config, gateway).await
+}
+
+/// Run a `skill` subcommand against the given gateway (tests pass a fake).
+pub async fn handle_skill_cli_with_gateway(
+    args: &[String],
+    config: &Config,
+    gateway: Arc<dyn ClawHubGateway>,
+) -> Result<(), MicroClawError> {
     let cli = match SkillCli::try_parse_from(
         std::iter::once("skill").chain(args.iter().map(std::string::String::as_str)),
     ) {
@@ -48,21 +170,27 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
         Err(err) => return Err(MicroClawError::Config(err.to_string())),
     };
     let subcommand = cli.command;
-
-    let gateway: Arc<dyn ClawHubGateway> = Arc::new(RegistryClawHubGateway::from_config(config));
+    let retry = RetryPolicy::from_config(config);
 
     match subcommand {
-        Some(SkillCommand::Search { query }) => {
+        Some(SkillCommand::Search {
+            query,
+            sort,
+            limit,
+            page,
+        }) => {
+            let sort = microclaw_clawhub::client::validate_search_sort(&sort)?;
+            let (limit, offset) = search_page(limit, page);
             let gateway = gateway.clone();
-            let results = retry_with_backoff(|| {
+            let results = retry_with_backoff(retry, || {
                 let gateway = gateway.clone();
                 let query = query.clone();
-                async move { gateway.search(&query, 10, "trending").await }
+                async move { gateway.search(&query, limit, offset, sort).await }
             })
             .await;
             match results {
                 Ok(results) => {
-                    println!("Found {} skills:\n", results.len());
+                    println!("{}\n", search_range_label(offset, results.len()));
                     for r in results {
                         println!("  {} - {}", r.slug, r.name);
                         println!("    {}", r.description);
@@ -77,7 +205,13 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
             }
             Ok(())
         }
-        Some(SkillCommand::Install { slug, force }) => {
+        Some(SkillCommand::Install {
+            slugs,
+            file,
+            version,
+            force,
+            skip_checksum,
+        }) => {
             let skills_dir = PathBuf::from(config.skills_data_dir());
             let lockfile_path = config.clawhub_lockfile_path();
 
@@ -86,20 +220,96 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
                 force,
                 skip_gates: false,
                 skip_security: config.clawhub.skip_security_warnings,
+                skip_checksum: skip_checksum || config.clawhub.skip_checksum,
+                progress: None,
+                pin: false,
+                block_on_virustotal_detections: config.clawhub.block_on_virustotal_detections,
+            };
+            if let Some(file) = file {
+                if slugs.len() > 1 {
+                    return Err(MicroClawError::Config(
+                        "skill install --file accepts at most one slug".into(),
+                    ));
+                }
+                match gateway.install_local(
+                    &file,
+                    slugs.first().map(String::as_str),
+                    &skills_dir,
+                    &lockfile_path,
+                    &options,
+                ) {
+                    Ok(result) => {
+                        println!("{}", result.message);
+                        if result.requires_restart {
+                            println!("Restart MicroClaw or run /reload-skills to activate.");
+                        }
+                    }
+                    Err(e) => eprintln!("Install failed: {}", e),
+                }
+                return Ok(());
+            }
+            let specs = resolve_install_specs(&slugs, version.as_deref())?;
+            for (slug, version) in &specs {
+                if let Some(version) = version {
+                    let available = retry_with_backoff(retry, || {
+                        let gateway = gateway.clone();
+                        let slug = slug.clone();
+                        async move { gateway.get_versions(&slug).await }
+                    })
+                    .await?;
+                    ensure_version_available(slug, version, &available)?;
+                }
+            }
+            if specs.len() > 1 {
+                let results = gateway
+                    .install_many(&specs, &skills_dir, &lockfile_path, &options)
+                    .await?;
+                let mut activated = false;
+                for (slug, result) in results {
+                    match result {
+                        Ok(result) => {
+                            activated |= result.requires_restart;
+                            println!("  {}: {}", slug, result.message);
+                        }
+                        Err(e) => eprintln!("  {}: install failed: {}", slug, e),
+                    }
+                }
+                if activated {
+                    println!("Restart MicroClaw or run /reload-skills to activate.");
+                }
+                return Ok(());
+            }
+            let Some((slug, version)) = specs.into_iter().next() else {
+                return Err(MicroClawError::Config(
+                    "skill install requires a <slug> or --file <path>".into(),
+                ));
+            };
+            let options = InstallOptions {
+                progress: Some(cli_download_progress()),
+                pin: version.is_some(),
+                ..options
             };
-            let result = retry_with_backoff(|| {
+            let result = retry_with_backoff(retry, || {
                 let gateway = gateway.clone();
                 let skills_dir = skills_dir.clone();
                 let lockfile_path = lockfile_path.clone();
                 let options = options.clone();
                 let slug = slug.clone();
+                let version = version.clone();
                 async move {
                     gateway
-                        .install(&slug, None, &skills_dir, &lockfile_path, &options)
+                        .install(
+                            &slug,
+                            version.as_deref(),
+                            &skills_dir,
+                            &lockfile_path,
+                            &options,
+                        )
                         .await
                 }
             })
             .await;
+            finish_download_progress();
             match result {
                 Ok(result) => {
                     println!("{}", result.message);
@@ -111,6 +321,181 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
             }
             Ok(())
         }
+        Some(SkillCommand::Uninstall { slug }) => {
+            let skills_dir = PathBuf::from(config.skills_data_dir());
+            let lockfile_path = config.clawhub_lockfile_path();
+            let lock = gateway.read_lockfile(&lockfile_path)?;
+            let dependents = installed_dependents(&lock, &config.skills_data_dir(), &slug);
+            if !dependents.is_empty() {
+                eprintln!(
+                    "Warning: {} depend{} on '{}' and may stop working once it is removed.",
+                    dependents.join(", "),
+                    if dependents.len() == 1 { "s" } else { "" },
+                    slug
+                );
+            }
+            match gateway.uninstall(&slug, &skills_dir, &lockfile_path) {
+                Ok(result) => {
+                    println!("{}", result.message);
+                    if result.requires_restart {
+                        println!("Restart MicroClaw or run /reload-skills to unload it.");
+                    }
+                }
+                Err(e) => eprintln!("Uninstall failed: {}", e),
+            }
+            Ok(())
+        }
+        Some(SkillCommand::Update { slug, force }) => {
+            let skills_dir = PathBuf::from(config.skills_data_dir());
+            let lockfile_path = config.clawhub_lockfile_path();
+            let lock = gateway.read_lockfile(&lockfile_path)?;
+
+            let mut targets: Vec<(String, String, bool)> = match &slug {
+                Some(slug) => match lock.skills.get(slug) {
+                    Some(entry) if entry.source.as_deref() == Some(LOCAL_SOURCE) => {
+                        println!(
+                            "Skill '{}' was installed from a local file; skipping.",
+                            slug
+                        );
+                        return Ok(());
+                    }
+                    Some(entry) => {
+                        vec![(slug.clone(), entry.installed_version.clone(), entry.pinned)]
+                    }
+                    None => {
+                        println!("Skill '{}' is not installed from ClawHub.", slug);
+                        return Ok(());
+                    }
+                },
+                None => lock
+                    .skills
+                    .iter()
+                    .filter(|(_, entry)| entry.source.as_deref() != Some(LOCAL_SOURCE))
+                    .map(|(slug, entry)| {
+                        (slug.clone(), entry.installed_version.clone(), entry.pinned)
+                    })
+                    .collect(),
+            };
+            if targets.is_empty() {
+                println!("No ClawHub skills installed.");
+                return Ok(());
+            }
+            targets.sort();
+
+            let options = InstallOptions {
+                force: true,
+                skip_gates: false,
+                skip_security: config.clawhub.skip_security_warnings,
+                skip_checksum: config.clawhub.skip_checksum,
+                progress: Some(cli_download_progress()),
+                pin: false,
+                block_on_virustotal_detections: config.clawhub.block_on_virustotal_detections,
+            };
+            let mut upgraded = 0usize;
+            for (slug, installed, pinned) in targets {
+                if pinned && !force {
+                    println!(
+                        "  {}: pinned at v{} (use --force to update)",
+                        slug, installed
+                    );
+                    continue;
+                }
+                let options = InstallOptions {
+                    pin: pinned,
+                    ..options.clone()
+                };
+                let latest = match resolve_latest_version(&gateway, &slug, retry).await {
+                    Ok(Some(latest)) => latest,
+                    Ok(None) => {
+                        println!("  {}: no published versions found", slug);
+                        continue;
+                    }
+                    Err(e) => {
+                        eprintln!("  {}: version check failed: {}", slug, e);
+                        continue;
+                    }
+                };
+                let (from, to) = match decide_update(&installed, &latest) {
+                    UpdateDecision::UpToDate => {
+                        println!("  {}: up to date (v{})", slug, installed);
+                        continue;
+                    }
+                    UpdateDecision::Upgrade { from, to } => (from, to),
+                };
+                let result = retry_with_backoff(retry, || {
+                    let gateway = gateway.clone();
+                    let skills_dir = skills_dir.clone();
+                    let lockfile_path = lockfile_path.clone();
+                    let options = options.clone();
+                    let slug = slug.clone();
+                    let to = to.clone();
+                    async move {
+                        gateway
+                            .install(&slug, Some(&to), &skills_dir, &lockfile_path, &options)
+                            .await
+                    }
+                })
+                .await;
+                finish_download_progress();
+                match result {
+                    Ok(result) if result.success => {
+                        upgraded += 1;
+                        println!("  {}: upgraded {} \u{2192} {}", slug, from, to);
+                    }
+                    Ok(result) => eprintln!("  {}: {}", slug, result.message),
+                    Err(e) => eprintln!("  {}: upgrade failed: {}", slug, e),
+                }
+            }
+            if upgraded > 0 {
+                println!("Restart MicroClaw or run /reload-skills to activate.");
+            }
+            Ok(())
+        }
+        Some(SkillCommand::Verify) => {
+            let skills_dir = PathBuf::from(config.skills_data_dir());
+            let lock = gateway.read_lockfile(&config.clawhub_lockfile_path())?;
+            let report = verify_skills(
+                &skills_dir,
+                &lock,
+                &crate::builtin_skills::builtin_skill_names(),
+            )?;
+            for slug in &report.verified {
+                println!("  ok        {}", slug);
+            }
+            for slug in &report.unverifiable {
+                println!(
+                    "  unknown   {} (installed before file hashes were recorded)",
+                    slug
+                );
+            }
+            for issue in &report.issues {
+                match issue {
+                    VerifyIssue::Missing { slug } => {
+                        println!("  missing   {} (in lockfile, directory not found)", slug)
+                    }
+                    VerifyIssue::Drift {
+                        slug,
+                        expected,
+                        actual,
+                    } => println!(
+                        "  modified  {} (expected {}, found {})",
+                        slug, expected, actual
+                    ),
+                    VerifyIssue::Untracked { name } => {
+                        println!("  untracked {} (directory not in lockfile)", name)
+                    }
+                }
+            }
+            if report.is_clean() {
+                println!("All ClawHub skills match the lockfile.");
+                Ok(())
+            } else {
+                Err(MicroClawError::Config(format!(
+                    "{} skill discrepancies found",
+                    report.issues.len()
+                )))
+            }
+        }
         Some(SkillCommand::List) => {
             let lockfile_path = config.clawhub_lockfile_path();
             let lock = gateway.read_lockfile(&lockfile_path)?;
@@ -127,24 +512,47 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
             }
             Ok(())
         }
-        Some(SkillCommand::Available { all }) => {
+        Some(SkillCommand::Lint { path }) => match SkillManager::validate_skill(&path) {
+            Ok(()) => {
+                println!("{}: ok", path.display());
+                Ok(())
+            }
+            Err(problems) => {
+                for problem in &problems {
+                    println!("{}: {}", path.display(), problem);
+                }
+                Err(MicroClawError::Config(format!(
+                    "{} problem(s) found in {}",
+                    problems.len(),
+                    path.display()
+                )))
+            }
+        },
+        Some(SkillCommand::Available { all, json }) => {
             let manager = SkillManager::from_skills_dir(&config.skills_data_dir());
-            if all {
+            if json {
+                let lock = gateway.read_lockfile(&config.clawhub_lockfile_path())?;
+                let clawhub: Vec<String> = lock.skills.keys().cloned().collect();
+                let entries = manager
+                    .list_skills_json(&crate::builtin_skills::builtin_skill_names(), &clawhub);
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            } else if all {
                 println!("{}", manager.list_skills_formatted_all());
             } else {
                 println!("{}", manager.list_skills_formatted());
             }
             Ok(())
         }
-        Some(SkillCommand::Inspect { slug }) => {
+        Some(SkillCommand::Inspect { slug, json }) => {
             let gateway = gateway.clone();
-            let meta = retry_with_backoff(|| {
+            let meta = retry_with_backoff(retry, || {
                 let gateway = gateway.clone();
                 let slug = slug.clone();
                 async move { gateway.get_skill(&slug).await }
             })
             .await;
             match meta {
+                Ok(meta) if json => println!("{}", skill_meta_json(&meta)?),
                 Ok(meta) => {
                     println!("Skill: {} ({})", meta.name, meta.slug);
                     println!("{}", meta.description);
@@ -155,6 +563,24 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
                     }
                     if let Some(vt) = meta.virustotal {
                         println!("\nVirusTotal: {} ({} reports)", vt.status, vt.report_count);
+                        if vt.malicious.is_some() || vt.suspicious.is_some() {
+                            println!(
+                                "  malicious: {}, suspicious: {}",
+                                vt.malicious.unwrap_or(0),
+                                vt.suspicious.unwrap_or(0)
+                            );
+                        }
+                        if vt.pending_scan {
+                            println!("  scan pending");
+                        }
+                        if let Some(threshold) = config.clawhub.block_on_virustotal_detections {
+                            if exceeds_virustotal_threshold(&vt, threshold) {
+                                println!(
+                                    "  install blocked: more than {} detections (use --force)",
+                                    threshold
+                                );
+                            }
+                        }
                     }
                 }
                 Err(e) => eprintln!("Inspect failed: {}", e),
@@ -165,10 +591,14 @@ pub async fn handle_skill_cli(args: &[String], config: &Config) -> Result<(), Mi
             println!("Usage: microclaw skill <command>");
             println!("\nCommands:");
             println!("  search <query>   Search for skills");
-            println!("  install <slug>    Install a skill");
+            println!("  install <slug>... Install skills (or --file <zip> for offline installs)");
+            println!("  uninstall <slug>  Remove an installed ClawHub skill");
+            println!("  update [slug]     Upgrade installed skills to the latest version");
+            println!("  verify            Check installed skill files against the lockfile");
             println!("  list              List installed skills");
-            println!("  available [--all] List local skills (with diagnostics when --all)");
-            println!("  inspect <slug>    Show skill details");
+            println!("  available [--all] List local skills (with diagnostics when --all; --json)");
+            println!("  lint <path>       Check a skill's SKILL.md frontmatter");
+            println!("  inspect <slug>    Show skill details (alias: info; --json for scripts)");
             Ok(())
         }
     }
@@ -188,20 +618,320 @@ struct SkillCli {
 #[derive(Debug, Subcommand)]
 enum SkillCommand {
     /// Search for skills
-    Search { query: String },
+    Search {
+        query: String,
+        /// Result order: trending, recent or installs
+        #[arg(long, default_value = microclaw_clawhub::client::DEFAULT_SEARCH_SORT)]
+        sort: String,
+        /// Results per page (max 50)
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Page of results to show, starting at 1
+        #[arg(long, default_value_t = 1)]
+        page: usize,
+    },
     /// Install a skill
     Install {
-        slug: String,
+        /// One or more skill slugs (`slug` or `slug@version`); several are installed concurrently
+        #[arg(required_unless_present = "file")]
+        slugs: Vec<String>,
+        /// Install (and pin) this exact version
+        #[arg(long)]
+        version: Option<String>,
+        /// Install from a local skill ZIP instead of the registry
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
         #[arg(long)]
         force: bool,
+        /// Skip SHA-256 verification of the downloaded ZIP
+        #[arg(long)]
+        skip_checksum: bool,
     },
+    /// Remove an installed ClawHub skill
+    Uninstall { slug: String },
+    /// Upgrade installed skills to the latest version
+    Update {
+        slug: Option<String>,
+        /// Also upgrade skills pinned to an explicit version
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check installed skill files against the lockfile
+    Verify,
     /// List installed skills
     List,
+    /// Check a skill directory's SKILL.md frontmatter
+    Lint { path: PathBuf },
     /// List local skills (with diagnostics when --all)
     Available {
         #[arg(long)]
         all: bool,
+        /// Print every local skill as JSON (slug, source, enabled, diagnostics)
+        #[arg(long)]
+        json: bool,
     },
     /// Show skill details
-    Inspect { slug: String },
+    #[command(visible_alias = "info")]
+    Inspect {
+        slug: String,
+        /// Print the registry metadata as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Registry metadata for `inspect --json`
+fn skill_meta_json(meta: &SkillMeta) -> Result<String, MicroClawError> {
+    Ok(serde_json::to_string_pretty(meta)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        handle_skill_cli_with_gateway, render_download_progress, resolve_install_specs,
+        search_page, search_range_label, skill_meta_json,
+    };
+    use crate::clawhub::fake::FakeClawHubGateway;
+    use crate::clawhub::service::{retry_with_backoff, ClawHubGateway, RetryPolicy};
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn test_config() -> (Config, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_skill_cli_{}", uuid::Uuid::new_v4()));
+        let mut config = Config::test_defaults();
+        config.data_dir = dir.to_string_lossy().to_string();
+        config.skills_dir = Some(dir.join("skills").to_string_lossy().to_string());
+        (config, dir)
+    }
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_render_download_progress() {
+        let half = render_download_progress(512, Some(1024));
+        assert!(half.contains(" 50%"), "{half}");
+        assert!(half.contains(&format!("[{}{}]", "#".repeat(15), "-".repeat(15))));
+        assert!(render_download_progress(4096, None).contains("4.0 KiB"));
+    }
+
+    #[test]
+    fn test_search_page() {
+        assert_eq!(search_page(10, 1), (10, 0));
+        assert_eq!(search_page(10, 3), (10, 20));
+        assert_eq!(search_page(10, 0), (10, 0));
+        assert_eq!(search_page(500, 2), (50, 50));
+        assert_eq!(search_page(0, 2), (1, 1));
+        assert_eq!(search_range_label(20, 10), "Showing 21–30:");
+        assert_eq!(search_range_label(0, 0), "No skills found.");
+        assert_eq!(
+            search_range_label(20, 0),
+            "No skills found after the first 20."
+        );
+    }
+
+    #[test]
+    fn test_resolve_install_specs() {
+        let specs = resolve_install_specs(&["a@1.0".into(), "b".into()], None).unwrap();
+        assert_eq!(
+            specs,
+            vec![
+                ("a".to_string(), Some("1.0".to_string())),
+                ("b".to_string(), None)
+            ]
+        );
+        assert_eq!(
+            resolve_install_specs(&["a".into()], Some("2.0")).unwrap(),
+            vec![("a".to_string(), Some("2.0".to_string()))]
+        );
+        assert!(resolve_install_specs(&["a@1.0".into()], Some("1.0")).is_ok());
+        assert!(resolve_install_specs(&["a@1.0".into()], Some("2.0")).is_err());
+        assert!(resolve_install_specs(&["a".into(), "b".into()], Some("2.0")).is_err());
+    }
+
+    #[test]
+    fn test_skill_meta_json_includes_versions() {
+        let meta: microclaw_clawhub::types::SkillMeta = serde_json::from_str(
+            r#"{
+                "slug": "pdf-tools",
+                "name": "PDF Tools",
+                "description": "PDF helpers",
+                "versions": [
+                    {"version": "1.0.0", "latest": false},
+                    {"version": "1.1.0", "latest": true}
+                ],
+                "virustotal": {"reportCount": 0, "pendingScan": false, "status": "clean"}
+            }"#,
+        )
+        .unwrap();
+        let value: serde_json::Value =
+            serde_json::from_str(&skill_meta_json(&meta).unwrap()).unwrap();
+        let versions = value["versions"].as_array().unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1]["version"], "1.1.0");
+        assert_eq!(versions[1]["latest"], true);
+        assert_eq!(value["virustotal"]["status"], "clean");
+    }
+
+    #[tokio::test]
+    async fn test_cli_search_against_fake_gateway() {
+        let (config, dir) = test_config();
+        let fake =
+            Arc::new(FakeClawHubGateway::new().with_skill("pdf-tools", "PDF helpers", "1.0.0"));
+        let gateway: Arc<dyn ClawHubGateway> = fake.clone();
+        handle_skill_cli_with_gateway(
+            &args(&[
+                "search", "pdf", "--limit", "5", "--page", "2", "--sort", "installs",
+            ]),
+            &config,
+            gateway,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fake.searches(),
+            vec![("pdf".to_string(), 5, 5, "installs".to_string())]
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_cli_install_and_list_against_fake_gateway() {
+        let (config, dir) = test_config();
+        let fake =
+            Arc::new(FakeClawHubGateway::new().with_skill("pdf-tools", "PDF helpers", "1.2.0"));
+        let gateway: Arc<dyn ClawHubGateway> = fake.clone();
+
+        handle_skill_cli_with_gateway(&args(&["install", "pdf-tools"]), &config, gateway.clone())
+            .await
+            .unwrap();
+        let skill_md = std::path::Path::new(&config.skills_data_dir())
+            .join("pdf-tools")
+            .join("SKILL.md");
+        assert!(skill_md.exists());
+        let lock = fake.read_lockfile(&config.clawhub_lockfile_path()).unwrap();
+        assert_eq!(lock.skills["pdf-tools"].installed_version, "1.2.0");
+
+        handle_skill_cli_with_gateway(&args(&["list"]), &config, gateway.clone())
+            .await
+            .unwrap();
+
+        // Unknown slugs report a failure but do not abort the CLI.
+        handle_skill_cli_with_gateway(&args(&["install", "missing"]), &config, gateway)
+            .await
+            .unwrap();
+        let lock = fake.read_lockfile(&config.clawhub_lockfile_path()).unwrap();
+        assert!(!lock.skills.contains_key("missing"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_cli_lint_reports_frontmatter_problems() {
+        let (config, dir) = test_config();
+        let skill = dir.join("draft");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\nname: draft\n---\nbody\n").unwrap();
+        let gateway: Arc<dyn ClawHubGateway> = Arc::new(FakeClawHubGateway::new());
+        let lint = |path: &std::path::Path| args(&["lint", path.to_str().unwrap()]);
+
+        let err = handle_skill_cli_with_gateway(&lint(&skill), &config, gateway.clone())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1 problem(s)"), "{err}");
+
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: draft\ndescription: Draft skill\n---\nbody\n",
+        )
+        .unwrap();
+        handle_skill_cli_with_gateway(&lint(&skill), &config, gateway)
+            .await
+            .unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_respects_attempt_count() {
+        use crate::error::MicroClawError;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let mut config = Config::test_defaults();
+        config.clawhub.max_retries = 4;
+        let policy = RetryPolicy {
+            delay: std::time::Duration::ZERO,
+            ..RetryPolicy::from_config(&config)
+        };
+        let calls = AtomicU32::new(0);
+        let result: Result<(), MicroClawError> = retry_with_backoff(policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async {
+                Err(MicroClawError::HttpStatus {
+                    status: 503,
+                    message: "down".into(),
+                })
+            }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+        config.clawhub.max_retries = 0;
+        let policy = RetryPolicy::from_config(&config);
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), MicroClawError> = retry_with_backoff(policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(MicroClawError::RateLimited) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_stops_on_permanent_errors() {
+        use crate::error::MicroClawError;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let policy = RetryPolicy {
+            attempts: 4,
+            delay: std::time::Duration::ZERO,
+        };
+        let permanent: [fn() -> MicroClawError; 3] = [
+            || MicroClawError::HttpStatus {
+                status: 404,
+                message: "Skill 'x' not found on ClawHub".into(),
+            },
+            || MicroClawError::HttpStatus {
+                status: 401,
+                message: "rejected".into(),
+            },
+            || MicroClawError::Config("Failed to parse skill metadata".into()),
+        ];
+        for err in permanent {
+            let calls = AtomicU32::new(0);
+            let result: Result<(), MicroClawError> = retry_with_backoff(policy, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move { Err(err()) }
+            })
+            .await;
+            assert!(result.is_err());
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        }
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), MicroClawError> = retry_with_backoff(policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async {
+                Err(MicroClawError::HttpStatus {
+                    status: 429,
+                    message: "slow down".into(),
+                })
+            }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,198 @@
+//! In-memory `ClawHubGateway` for exercising the skill CLI and tools offline.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use microclaw_clawhub::install::{
+    install_local_skill, uninstall_skill, InstallOptions, InstallResult,
+};
+use microclaw_clawhub::lockfile::{read_lockfile, write_lockfile};
+use microclaw_clawhub::types::{
+    LockEntry, LockFile, SearchResult, SkillMeta, SkillMetadata, SkillVersion,
+};
+
+use crate::clawhub::service::ClawHubGateway;
+use crate::error::MicroClawError;
+
+/// One recorded `search` call: query, limit, offset and sort
+pub type SearchCall = (String, usize, usize, String);
+
+#[derive(Default)]
+pub struct FakeClawHubGateway {
+    skills: BTreeMap<String, SkillMeta>,
+    searches: Mutex<Vec<SearchCall>>,
+}
+
+impl FakeClawHubGateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish `slug` at `version` (marked latest).
+    pub fn with_skill(mut self, slug: &str, description: &str, version: &str) -> Self {
+        self.skills.insert(
+            slug.to_string(),
+            SkillMeta {
+                slug: slug.to_string(),
+                name: slug.to_string(),
+                description: description.to_string(),
+                versions: vec![SkillVersion {
+                    version: version.to_string(),
+                    latest: true,
+                    sha256: None,
+                    dependencies: Vec::new(),
+                }],
+                virustotal: None,
+                metadata: SkillMetadata::default(),
+                dependencies: Vec::new(),
+            },
+        );
+        self
+    }
+
+    pub fn searches(&self) -> Vec<SearchCall> {
+        self.searches.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    fn skill(&self, slug: &str) -> Result<&SkillMeta, MicroClawError> {
+        self.skills
+            .get(slug)
+            .ok_or_else(|| MicroClawError::Config(format!("Skill '{}' not found on ClawHub", slug)))
+    }
+}
+
+#[async_trait]
+impl ClawHubGateway for FakeClawHubGateway {
+    async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+        sort: &str,
+    ) -> Result<Vec<SearchResult>, MicroClawError> {
+        if let Ok(mut searches) = self.searches.lock() {
+            searches.push((query.to_string(), limit, offset, sort.to_string()));
+        }
+        let query = query.to_ascii_lowercase();
+        Ok(self
+            .skills
+            .values()
+            .filter(|s| {
+                s.slug.to_ascii_lowercase().contains(&query)
+                    || s.description.to_ascii_lowercase().contains(&query)
+            })
+            .skip(offset)
+            .take(limit)
+            .map(|s| SearchResult {
+                slug: s.slug.clone(),
+                name: s.name.clone(),
+                description: s.description.clone(),
+                install_count: 0,
+                virustotal: None,
+            })
+            .collect())
+    }
+
+    async fn get_skill(&self, slug: &str) -> Result<SkillMeta, MicroClawError> {
+        self.skill(slug).cloned()
+    }
+
+    async fn get_versions(&self, slug: &str) -> Result<Vec<SkillVersion>, MicroClawError> {
+        Ok(self.skill(slug)?.versions.clone())
+    }
+
+    async fn install(
+        &self,
+        slug: &str,
+        version: Option<&str>,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<InstallResult, MicroClawError> {
+        let meta = self.skill(slug)?;
+        let version = match version {
+            Some(v) => v.to_string(),
+            None => meta
+                .versions
+                .iter()
+                .find(|v| v.latest)
+                .map(|v| v.version.clone())
+                .unwrap_or_else(|| "latest".to_string()),
+        };
+        let skill_path = skills_dir.join(slug);
+        std::fs::create_dir_all(&skill_path)?;
+        std::fs::write(
+            skill_path.join("SKILL.md"),
+            format!(
+                "---\nname: {}\ndescription: {}\nsource: clawhub\nversion: {}\n---\n",
+                slug, meta.description, version
+            ),
+        )?;
+
+        let mut lock = read_lockfile(lockfile_path)?;
+        lock.skills.insert(
+            slug.to_string(),
+            LockEntry {
+                slug: slug.to_string(),
+                installed_version: version.clone(),
+                installed_at: chrono::Utc::now().to_rfc3339(),
+                content_hash: "sha256:fake".to_string(),
+                local_path: skill_path.to_string_lossy().to_string(),
+                source: None,
+                files_hash: None,
+                pinned: options.pin,
+                dependencies: meta.dependencies.clone(),
+                installed_as: None,
+            },
+        );
+        write_lockfile(lockfile_path, &lock)?;
+        Ok(InstallResult {
+            success: true,
+            message: format!("Installed {} v{}", slug, version),
+            requires_restart: true,
+        })
+    }
+
+    async fn install_many(
+        &self,
+        specs: &[(String, Option<String>)],
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<Vec<(String, Result<InstallResult, MicroClawError>)>, MicroClawError> {
+        let mut results = Vec::with_capacity(specs.len());
+        for (slug, version) in specs {
+            let result = self
+                .install(slug, version.as_deref(), skills_dir, lockfile_path, options)
+                .await;
+            results.push((slug.clone(), result));
+        }
+        Ok(results)
+    }
+
+    fn install_local(
+        &self,
+        zip_path: &Path,
+        slug: Option<&str>,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<InstallResult, MicroClawError> {
+        install_local_skill(zip_path, slug, skills_dir, lockfile_path, options)
+    }
+
+    fn uninstall(
+        &self,
+        slug: &str,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+    ) -> Result<InstallResult, MicroClawError> {
+        uninstall_skill(slug, skills_dir, lockfile_path)
+    }
+
+    fn read_lockfile(&self, path: &Path) -> Result<LockFile, MicroClawError> {
+        read_lockfile(path)
+    }
+}
@@ -1,4 +1,6 @@
 pub mod cli;
+#[cfg(test)]
+pub mod fake;
 pub mod service;
 pub mod tools;
 
@@ -1,10 +1,14 @@
 use std::path::Path;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use microclaw_clawhub::client::ClawHubClient;
-use microclaw_clawhub::install::{install_skill, InstallOptions, InstallResult};
+use microclaw_clawhub::install::{
+    install_local_skill, install_skill_with_dependencies, install_skills, uninstall_skill,
+    InstallOptions, InstallResult,
+};
 use microclaw_clawhub::lockfile::read_lockfile;
-use microclaw_clawhub::types::{LockFile, SearchResult, SkillMeta};
+use microclaw_clawhub::types::{LockFile, SearchResult, SkillMeta, SkillVersion};
 
 use crate::config::Config;
 use crate::error::MicroClawError;
@@ -15,9 +19,11 @@ pub trait ClawHubGateway: Send + Sync {
         &self,
         query: &str,
         limit: usize,
+        offset: usize,
         sort: &str,
     ) -> Result<Vec<SearchResult>, MicroClawError>;
     async fn get_skill(&self, slug: &str) -> Result<SkillMeta, MicroClawError>;
+    async fn get_versions(&self, slug: &str) -> Result<Vec<SkillVersion>, MicroClawError>;
     async fn install(
         &self,
         slug: &str,
@@ -26,17 +32,142 @@ pub trait ClawHubGateway: Send + Sync {
         lockfile_path: &Path,
         options: &InstallOptions,
     ) -> Result<InstallResult, MicroClawError>;
+    async fn install_many(
+        &self,
+        specs: &[(String, Option<String>)],
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<Vec<(String, Result<InstallResult, MicroClawError>)>, MicroClawError>;
+    fn install_local(
+        &self,
+        zip_path: &Path,
+        slug: Option<&str>,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<InstallResult, MicroClawError>;
+    fn uninstall(
+        &self,
+        slug: &str,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+    ) -> Result<InstallResult, MicroClawError>;
     fn read_lockfile(&self, path: &Path) -> Result<LockFile, MicroClawError>;
 }
 
+/// Pause between failed registry calls
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// How many times a registry call is attempted, and how long to wait between tries
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RetryPolicy {
+    pub(crate) attempts: u32,
+    pub(crate) delay: Duration,
+}
+
+impl RetryPolicy {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            attempts: config.clawhub.max_retries.saturating_add(1),
+            delay: RETRY_DELAY,
+        }
+    }
+}
+
+/// Whether a failed registry call may succeed if repeated: transport
+/// failures, rate limiting and server errors. Other statuses and parse
+/// errors would only fail again.
+fn is_retryable(err: &MicroClawError) -> bool {
+    match err {
+        MicroClawError::Http(_) | MicroClawError::RateLimited => true,
+        MicroClawError::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+/// Retry an async operation per `policy` (always at least one attempt),
+/// giving up at once on errors that [`is_retryable`] rejects
+pub(crate) async fn retry_with_backoff<T, F, Fut>(
+    policy: RetryPolicy,
+    mut operation: F,
+) -> Result<T, MicroClawError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, MicroClawError>>,
+{
+    let attempts = policy.attempts.max(1);
+    let mut last_error = None;
+    for attempt in 1..=attempts {
+        match operation().await {
+            Ok(result) => return Ok(result),
+            Err(e) if !is_retryable(&e) => return Err(e),
+            Err(e) => {
+                last_error = Some(e);
+                if attempt < attempts {
+                    tokio::time::sleep(policy.delay).await;
+                }
+            }
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| MicroClawError::Config("Unexpected error during retry".to_string())))
+}
+
+/// Upper bound on parallel downloads for multi-skill installs
+const MAX_CONCURRENT_INSTALLS: usize = 4;
+
+/// Set to a truthy value to keep the registry gateway from touching the network
+pub const OFFLINE_ENV: &str = "MICROCLAW_CLAWHUB_OFFLINE";
+
+fn offline_flag(value: Option<&str>) -> bool {
+    value.is_some_and(|v| {
+        let v = v.trim().to_ascii_lowercase();
+        !v.is_empty() && !matches!(v.as_str(), "0" | "false" | "no" | "off")
+    })
+}
+
 pub struct RegistryClawHubGateway {
     client: ClawHubClient,
+    offline: bool,
 }
 
 impl RegistryClawHubGateway {
     pub fn from_config(config: &Config) -> Self {
-        let client = ClawHubClient::new(&config.clawhub.registry, config.clawhub.token.clone());
-        Self { client }
+        // The registry timeout replaces the general one and bounds stalls,
+        // not total time, so large skill downloads are not cut off.
+        let mut settings = config.http_client_settings();
+        settings.timeout = None;
+        if config.clawhub.timeout_secs > 0 {
+            let timeout = Duration::from_secs(config.clawhub.timeout_secs);
+            settings.connect_timeout = Some(timeout);
+            settings.read_timeout = Some(timeout);
+        }
+        // Downloads retry inside the client and surface as non-retryable
+        // errors, so `clawhub.max_retries` bounds them here, not again above.
+        let retry = RetryPolicy::from_config(config);
+        let client = ClawHubClient::new(&config.clawhub.registry, config.clawhub.token.clone())
+            .with_http_client(crate::http_client::build_http_client_with_settings(
+                &settings,
+            ))
+            .with_download_retry(retry.attempts, retry.delay);
+        let offline = offline_flag(std::env::var(OFFLINE_ENV).ok().as_deref());
+        Self { client, offline }
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    fn ensure_online(&self) -> Result<(), MicroClawError> {
+        if self.offline {
+            return Err(MicroClawError::Config(format!(
+                "ClawHub is offline ({OFFLINE_ENV} is set); registry search and installs are \
+                 unavailable, but local installs (--file), list and uninstall still work"
+            )));
+        }
+        Ok(())
     }
 }
 
@@ -46,15 +177,23 @@ impl ClawHubGateway for RegistryClawHubGateway {
         &self,
         query: &str,
         limit: usize,
+        offset: usize,
         sort: &str,
     ) -> Result<Vec<SearchResult>, MicroClawError> {
-        self.client.search(query, limit, sort).await
+        self.ensure_online()?;
+        self.client.search(query, limit, offset, sort).await
     }
 
     async fn get_skill(&self, slug: &str) -> Result<SkillMeta, MicroClawError> {
+        self.ensure_online()?;
         self.client.get_skill(slug).await
     }
 
+    async fn get_versions(&self, slug: &str) -> Result<Vec<SkillVersion>, MicroClawError> {
+        self.ensure_online()?;
+        self.client.get_versions(slug).await
+    }
+
     async fn install(
         &self,
         slug: &str,
@@ -63,7 +202,8 @@ impl ClawHubGateway for RegistryClawHubGateway {
         lockfile_path: &Path,
         options: &InstallOptions,
     ) -> Result<InstallResult, MicroClawError> {
-        install_skill(
+        self.ensure_online()?;
+        install_skill_with_dependencies(
             &self.client,
             slug,
             version,
@@ -74,7 +214,81 @@ impl ClawHubGateway for RegistryClawHubGateway {
         .await
     }
 
+    async fn install_many(
+        &self,
+        specs: &[(String, Option<String>)],
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<Vec<(String, Result<InstallResult, MicroClawError>)>, MicroClawError> {
+        self.ensure_online()?;
+        install_skills(
+            &self.client,
+            specs,
+            skills_dir,
+            lockfile_path,
+            options,
+            MAX_CONCURRENT_INSTALLS,
+        )
+        .await
+    }
+
+    fn install_local(
+        &self,
+        zip_path: &Path,
+        slug: Option<&str>,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+        options: &InstallOptions,
+    ) -> Result<InstallResult, MicroClawError> {
+        install_local_skill(zip_path, slug, skills_dir, lockfile_path, options)
+    }
+
+    fn uninstall(
+        &self,
+        slug: &str,
+        skills_dir: &Path,
+        lockfile_path: &Path,
+    ) -> Result<InstallResult, MicroClawError> {
+        uninstall_skill(slug, skills_dir, lockfile_path)
+    }
+
     fn read_lockfile(&self, path: &Path) -> Result<LockFile, MicroClawError> {
         read_lockfile(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{offline_flag, ClawHubGateway, RegistryClawHubGateway};
+    use crate::config::Config;
+
+    #[test]
+    fn test_offline_flag() {
+        assert!(!offline_flag(None));
+        assert!(!offline_flag(Some("")));
+        assert!(!offline_flag(Some("0")));
+        assert!(!offline_flag(Some("false")));
+        assert!(offline_flag(Some("1")));
+        assert!(offline_flag(Some("TRUE")));
+    }
+
+    #[tokio::test]
+    async fn test_offline_gateway_rejects_network_calls() {
+        let gateway =
+            RegistryClawHubGateway::from_config(&Config::test_defaults()).with_offline(true);
+        let err = gateway
+            .search("pdf", 10, 0, "trending")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("offline"), "{err}");
+        assert!(gateway.get_skill("pdf").await.is_err());
+
+        let dir = std::env::temp_dir().join(format!("microclaw_offline_{}", uuid::Uuid::new_v4()));
+        let lock = gateway
+            .read_lockfile(&dir.join("clawhub.lock.json"))
+            .unwrap();
+        assert!(lock.skills.is_empty());
+    }
+}
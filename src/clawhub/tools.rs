@@ -1,8 +1,11 @@
-use crate::clawhub::service::{ClawHubGateway, RegistryClawHubGateway};
+use crate::clawhub::service::{
+    retry_with_backoff, ClawHubGateway, RegistryClawHubGateway, RetryPolicy,
+};
 use crate::config::Config;
 use crate::llm_types::ToolDefinition;
 use crate::tools::{schema_object, Tool, ToolResult};
 use async_trait::async_trait;
+use microclaw_clawhub::client::{DEFAULT_SEARCH_SORT, MAX_SEARCH_LIMIT};
 use microclaw_clawhub::install::InstallOptions;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -16,6 +19,9 @@ pub struct ClawHubInstallTool {
     skills_dir: PathBuf,
     lockfile_path: PathBuf,
     skip_security: bool,
+    skip_checksum: bool,
+    block_on_virustotal_detections: Option<u32>,
+    retry: RetryPolicy,
 }
 
 impl ClawHubSearchTool {
@@ -48,7 +54,7 @@ impl Tool for ClawHubSearchTool {
                     },
                     "sort": {
                         "type": "string",
-                        "description": "Sort order: trending, installs, latest"
+                        "description": "Sort order: trending (default), recent, installs"
                     }
                 }),
                 &["query"],
@@ -65,10 +71,12 @@ impl Tool for ClawHubSearchTool {
         let sort = params
             .get("sort")
             .and_then(|v| v.as_str())
-            .unwrap_or("trending");
+            .unwrap_or(DEFAULT_SEARCH_SORT);
 
         let gateway = self.gateway.clone();
-        let results = gateway.search(query, limit.min(50), sort).await;
+        let results = gateway
+            .search(query, limit.min(MAX_SEARCH_LIMIT), 0, sort)
+            .await;
 
         match results {
             Ok(results) => {
@@ -107,6 +115,9 @@ impl ClawHubInstallTool {
             skills_dir,
             lockfile_path,
             skip_security: config.clawhub.skip_security_warnings,
+            skip_checksum: config.clawhub.skip_checksum,
+            block_on_virustotal_detections: config.clawhub.block_on_virustotal_detections,
+            retry: RetryPolicy::from_config(config),
         }
     }
 }
@@ -158,44 +169,34 @@ impl Tool for ClawHubInstallTool {
             force,
             skip_gates: false,
             skip_security: self.skip_security,
+            skip_checksum: self.skip_checksum,
+            progress: None,
+            pin: false,
+            block_on_virustotal_detections: self.block_on_virustotal_detections,
         };
 
-        // Retry up to 3 times with brief delays for transient failures
-        let mut last_error = None;
-        for attempt in 1..=3 {
-            match gateway
-                .install(
-                    slug,
-                    version,
-                    &self.skills_dir,
-                    &self.lockfile_path,
-                    &options,
-                )
-                .await
-            {
-                Ok(result) => {
-                    let mut msg = result.message;
-                    if !result.success {
-                        return ToolResult::error(msg);
-                    }
-                    if result.requires_restart {
-                        msg.push_str("\nRestart MicroClaw or run /reload-skills to activate.");
-                    }
-                    return ToolResult::success(msg);
+        let result = retry_with_backoff(self.retry, || {
+            gateway.install(
+                slug,
+                version,
+                &self.skills_dir,
+                &self.lockfile_path,
+                &options,
+            )
+        })
+        .await;
+        match result {
+            Ok(result) => {
+                let mut msg = result.message;
+                if !result.success {
+                    return ToolResult::error(msg);
                 }
-                Err(e) => {
-                    last_error = Some(e);
-                    if attempt < 3 {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    }
+                if result.requires_restart {
+                    msg.push_str("\nRestart MicroClaw or run /reload-skills to activate.");
                 }
+                ToolResult::success(msg)
             }
-        }
-
-        // All retries failed - return the last error
-        match last_error {
-            Some(e) => ToolResult::error(e.to_string()),
-            None => ToolResult::error("Unexpected error during installation".into()),
+            Err(e) => ToolResult::error(e.to_string()),
         }
     }
 }
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use arc_swap::ArcSwap;
+
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +13,7 @@ use crate::codex_auth::{
 };
 use crate::plugins::PluginsConfig;
 use microclaw_core::error::MicroClawError;
+use microclaw_core::http::HttpClientSettings;
 pub use microclaw_tools::sandbox::{SandboxBackend, SandboxConfig, SandboxMode, SecurityProfile};
 pub use microclaw_tools::types::WorkingDirIsolation;
 use microclaw_tools::web_content_validation::WebContentValidationConfig;
@@ -29,12 +34,45 @@ fn default_api_key() -> String {
 fn default_model() -> String {
     String::new()
 }
+fn default_embedding_max_retries() -> u32 {
+    3
+}
+fn default_embedding_cache_size() -> usize {
+    1024
+}
+fn default_usage_report_top_senders() -> usize {
+    5
+}
+fn default_browser_binary() -> String {
+    microclaw_tools::command_runner::agent_browser_program()
+}
+fn default_browser_allowed_commands() -> Vec<String> {
+    crate::tools::browser::default_allowed_commands()
+}
+fn default_browser_max_sessions() -> usize {
+    8
+}
+fn default_browser_session_idle_secs() -> u64 {
+    1800
+}
+fn default_browser_max_output_chars() -> usize {
+    30000
+}
+fn default_fetch_url_max_bytes() -> usize {
+    100_000
+}
+fn default_http_timeout_secs() -> u64 {
+    60
+}
 fn default_max_tokens() -> u32 {
     8192
 }
 fn default_max_tool_iterations() -> usize {
     100
 }
+fn default_max_concurrent_agents() -> usize {
+    4
+}
 fn default_compaction_timeout_secs() -> u64 {
     180
 }
@@ -71,6 +109,12 @@ fn default_working_dir() -> String {
 fn default_working_dir_isolation() -> WorkingDirIsolation {
     WorkingDirIsolation::Chat
 }
+fn default_skills_watch_debounce_ms() -> u64 {
+    500
+}
+fn default_read_file_max_bytes() -> u64 {
+    10 * 1024 * 1024
+}
 fn default_sandbox_image() -> String {
     "ubuntu:25.10".into()
 }
@@ -122,6 +166,9 @@ fn default_web_session_idle_ttl_seconds() -> u64 {
 fn default_allow_group_slash_without_mention() -> bool {
     false
 }
+fn default_reset_default_scope() -> ResetScope {
+    ResetScope::All
+}
 
 fn default_model_prices() -> Vec<ModelPrice> {
     Vec::new()
@@ -138,6 +185,14 @@ fn default_soul_path() -> Option<String> {
 fn default_clawhub_registry() -> String {
     "https://clawhub.ai".into()
 }
+
+fn default_clawhub_timeout_secs() -> u64 {
+    30
+}
+
+fn default_clawhub_max_retries() -> u32 {
+    2
+}
 fn default_voice_provider() -> String {
     "openai".into()
 }
@@ -145,7 +200,7 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ClawHubConfig {
     /// ClawHub registry URL
     #[serde(default = "default_clawhub_registry", rename = "clawhub_registry")]
@@ -159,6 +214,25 @@ pub struct ClawHubConfig {
     /// Skip security warnings for ClawHub installs
     #[serde(default, rename = "clawhub_skip_security_warnings")]
     pub skip_security_warnings: bool,
+    /// Skip SHA-256 verification of downloaded skill ZIPs
+    #[serde(default, rename = "clawhub_skip_checksum")]
+    pub skip_checksum: bool,
+    /// Timeout in seconds for connecting to the registry and for each read
+    /// from it; downloads may run longer while data keeps arriving (0 disables it)
+    #[serde(
+        default = "default_clawhub_timeout_secs",
+        rename = "clawhub_timeout_secs"
+    )]
+    pub timeout_secs: u64,
+    /// Extra attempts after a failed registry call (0 fails fast)
+    #[serde(
+        default = "default_clawhub_max_retries",
+        rename = "clawhub_max_retries"
+    )]
+    pub max_retries: u32,
+    /// Refuse installs with more VirusTotal detections than this (unset disables it)
+    #[serde(default, rename = "clawhub_block_on_virustotal_detections")]
+    pub block_on_virustotal_detections: Option<u32>,
 }
 
 impl Default for ClawHubConfig {
@@ -168,6 +242,10 @@ impl Default for ClawHubConfig {
             token: None,
             agent_tools_enabled: default_true(),
             skip_security_warnings: false,
+            skip_checksum: false,
+            timeout_secs: default_clawhub_timeout_secs(),
+            max_retries: default_clawhub_max_retries(),
+            block_on_virustotal_detections: None,
         }
     }
 }
@@ -176,46 +254,172 @@ fn is_local_web_host(host: &str) -> bool {
     h == "127.0.0.1" || h == "localhost" || h == "::1"
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ModelPrice {
     pub model: String,
     pub input_per_million_usd: f64,
     pub output_per_million_usd: f64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// One entry in `llm_fallbacks`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FallbackProvider {
+    /// Provider id, as for `llm_provider`.
+    pub provider: String,
+    pub model: String,
+    /// Base URL; empty uses the provider's default endpoint.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// API key; unset reuses the primary `api_key`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// Log line format: human-readable `text` or one JSON object per line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// What `/reset` clears for a chat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetScope {
+    /// Only the live agent session; stored chat history is kept.
+    Session,
+    /// The session and the stored chat history.
+    All,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Daily,
+    Weekly,
+    #[default]
+    Monthly,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    #[default]
+    Global,
+    PerChat,
+}
+
+fn default_budget_warn_ratio() -> f64 {
+    0.8
+}
+
+/// Spending cap checked before each agent run. Usage counts from the start of
+/// the current calendar period (UTC).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UsageBudget {
+    #[serde(default)]
+    pub period: BudgetPeriod,
+    #[serde(default)]
+    pub scope: BudgetScope,
+    /// Total (input + output) tokens allowed per period.
+    #[serde(default)]
+    pub max_tokens: Option<i64>,
+    /// USD allowed per period, estimated from `model_prices`.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    /// Fraction of a limit at which replies carry a warning notice.
+    #[serde(default = "default_budget_warn_ratio")]
+    pub warn_ratio: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     // --- LLM / API ---
+    /// LLM provider id (anthropic, openai, openrouter, ollama, ...; `custom` for other OpenAI-compatible APIs).
     #[serde(default = "default_llm_provider")]
     pub llm_provider: String,
+    /// API key for the LLM provider (optional for ollama and openai-codex).
     #[serde(default = "default_api_key")]
     pub api_key: String,
+    /// Model name; empty selects the provider default.
     #[serde(default = "default_model")]
     pub model: String,
+    /// Override the provider API base URL.
     #[serde(default)]
     pub llm_base_url: Option<String>,
+    /// Providers tried in order when the primary fails with a server-side
+    /// error (5xx, overloaded, rate limited, timeout). Client errors such as a
+    /// rejected request do not fail over.
+    #[serde(default)]
+    pub llm_fallbacks: Vec<FallbackProvider>,
+    /// Maximum output tokens per LLM response.
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+    /// Maximum tool-use iterations per message.
     #[serde(default = "default_max_tool_iterations")]
     pub max_tool_iterations: usize,
+    /// Agent runs one channel executes at once; further messages queue.
+    #[serde(default = "default_max_concurrent_agents")]
+    pub max_concurrent_agents: usize,
+    /// Messages one sender may send per minute in a chat; 0 disables the
+    /// limit. Override per channel with `channels.<name>.messages_per_minute`.
+    #[serde(default)]
+    pub messages_per_minute: u32,
+    /// Timeout for the summarization call used during session compaction.
     #[serde(default = "default_compaction_timeout_secs")]
     pub compaction_timeout_secs: u64,
+    /// Number of recent chat messages loaded as context when no session exists.
     #[serde(default = "default_max_history_messages")]
     pub max_history_messages: usize,
+    /// Maximum size of an incoming document or attachment, in MB.
     #[serde(default = "default_max_document_size_mb")]
     pub max_document_size_mb: u64,
+    /// Token budget for structured memories injected into the prompt.
     #[serde(default = "default_memory_token_budget")]
     pub memory_token_budget: usize,
+    /// Session length that triggers compaction.
     #[serde(default = "default_max_session_messages")]
     pub max_session_messages: usize,
+    /// Number of recent messages kept verbatim when compacting.
     #[serde(default = "default_compact_keep_recent")]
     pub compact_keep_recent: usize,
+    /// Default timeout for tool execution, in seconds.
     #[serde(default = "default_tool_timeout_secs")]
     pub default_tool_timeout_secs: u64,
+    /// Per-tool timeout overrides in seconds, keyed by tool name.
     #[serde(default)]
     pub tool_timeout_overrides: HashMap<String, u64>,
+    /// agent-browser executable used by the browser tool (name on PATH or absolute path).
+    #[serde(default = "default_browser_binary")]
+    pub browser_binary: String,
+    /// Browser session name prefix; defaults to one derived from `bot_username`.
+    #[serde(default)]
+    pub browser_session: Option<String>,
+    /// Browser commands the agent may run, matched on the leading word(s)
+    /// (e.g. `open`, `get`, `storage local`). `screenshot` and `pdf` write
+    /// files and must be listed explicitly.
+    #[serde(default = "default_browser_allowed_commands")]
+    pub browser_allowed_commands: Vec<String>,
+    /// Disable the browser allow list and shell metacharacter checks.
+    #[serde(default)]
+    pub browser_allow_all_commands: bool,
+    /// Maximum browser sessions kept open at once; the least recently used is
+    /// closed beyond this (0 = unlimited).
+    #[serde(default = "default_browser_max_sessions")]
+    pub browser_max_sessions: usize,
+    /// Close browser sessions unused for this many seconds (0 = never).
+    #[serde(default = "default_browser_session_idle_secs")]
+    pub browser_session_idle_secs: u64,
+    /// Browser output longer than this many characters is written to a file in
+    /// the chat working dir and replaced by a preview plus the file path.
+    #[serde(default = "default_browser_max_output_chars")]
+    pub browser_max_output_chars: usize,
+    /// Default timeout for MCP server requests, in seconds.
     #[serde(default = "default_mcp_request_timeout_secs")]
     pub default_mcp_request_timeout_secs: u64,
+    /// Show model thinking blocks in replies.
     #[serde(default)]
     pub show_thinking: bool,
     /// OpenAI-compatible request-body overrides applied for all models/providers.
@@ -232,36 +436,71 @@ pub struct Config {
     pub openai_compat_body_overrides_by_model: HashMap<String, HashMap<String, serde_json::Value>>,
 
     // --- Paths & environment ---
+    /// Root directory for runtime data, skills, and memory.
     #[serde(default = "default_data_dir")]
     pub data_dir: String,
+    /// Override the skills directory (defaults to <data_dir>/skills).
     #[serde(default)]
     pub skills_dir: Option<String>,
+    /// Remove unmodified built-in skills that newer releases no longer ship.
+    #[serde(default)]
+    pub prune_builtin_skills: bool,
+    /// Reload skills automatically when files under the skills directory change.
+    #[serde(default)]
+    pub skills_watch: bool,
+    /// Quiet period after the last skills change before reloading.
+    #[serde(default = "default_skills_watch_debounce_ms")]
+    pub skills_watch_debounce_ms: u64,
+    /// Working directory for file and shell tools.
     #[serde(default = "default_working_dir")]
     pub working_dir: String,
+    /// Whether chats share one working directory or get one each.
     #[serde(default = "default_working_dir_isolation")]
     pub working_dir_isolation: WorkingDirIsolation,
+    /// Make read_file/write_file reject absolute paths and `..`, keeping them
+    /// inside the chat's working directory. On by default; set `false` to let
+    /// them reach the rest of the filesystem.
+    #[serde(default = "default_true")]
+    pub file_tools_confine_to_working_dir: bool,
+    /// Maximum bytes read_file loads from one file (0 = unlimited).
+    #[serde(default = "default_read_file_max_bytes")]
+    pub read_file_max_bytes: u64,
+    /// Container sandbox for shell tools.
     #[serde(default)]
     pub sandbox: SandboxConfig,
+    /// IANA timezone used for scheduling (e.g. UTC, US/Eastern).
     #[serde(default = "default_timezone")]
     pub timezone: String,
+    /// Chat ids allowed to run cross-chat and admin operations.
     #[serde(default = "default_control_chat_ids")]
     pub control_chat_ids: Vec<i64>,
+    /// Legacy Discord bot token; prefer `channels.discord`.
     #[serde(default)]
     pub discord_bot_token: Option<String>,
+    /// Legacy Discord channel allowlist; prefer `channels.discord`.
     #[serde(default)]
     pub discord_allowed_channels: Vec<u64>,
+    /// Legacy: respond in Discord guilds without an @mention.
     #[serde(default)]
     pub discord_no_mention: bool,
+    /// Allow slash commands in group chats without mentioning the bot.
     #[serde(default = "default_allow_group_slash_without_mention")]
     pub allow_group_slash_without_mention: bool,
+    /// What a bare `/reset` clears: the live session only, or session and history.
+    #[serde(default = "default_reset_default_scope")]
+    pub reset_default_scope: ResetScope,
 
     // --- Web UI ---
+    /// Enable the Web UI and HTTP API.
     #[serde(default = "default_web_enabled")]
     pub web_enabled: bool,
+    /// Web server bind address.
     #[serde(default = "default_web_host")]
     pub web_host: String,
+    /// Web server port.
     #[serde(default = "default_web_port")]
     pub web_port: u16,
+    /// Bearer token required by the web API.
     #[serde(default)]
     pub web_auth_token: Option<String>,
     #[serde(default = "default_web_max_inflight_per_session")]
@@ -278,24 +517,65 @@ pub struct Config {
     pub web_fetch_validation: WebContentValidationConfig,
     #[serde(default)]
     pub web_fetch_url_validation: WebFetchUrlValidationConfig,
+    /// Response bodies returned by fetch_url are cut at this many bytes.
+    #[serde(default = "default_fetch_url_max_bytes")]
+    pub fetch_url_max_bytes: usize,
+    /// Let fetch_url reach private, loopback and link-local addresses.
+    #[serde(default)]
+    pub fetch_url_allow_private_networks: bool,
 
     // --- Embedding ---
+    /// Embedding provider for semantic memory (openai or ollama).
     #[serde(default)]
     pub embedding_provider: Option<String>,
     #[serde(default)]
     pub embedding_api_key: Option<String>,
     #[serde(default)]
     pub embedding_base_url: Option<String>,
+    /// Embedding model name.
     #[serde(default)]
     pub embedding_model: Option<String>,
+    /// Embedding vector dimension.
     #[serde(default)]
     pub embedding_dim: Option<usize>,
+    /// Max cached embedding vectors kept in memory; 0 disables the cache.
+    #[serde(default = "default_embedding_cache_size")]
+    pub embedding_cache_size: usize,
+    /// Extra attempts for embedding requests that hit 429/5xx or a network error.
+    #[serde(default = "default_embedding_max_retries")]
+    pub embedding_max_retries: u32,
+    /// L2-normalize returned vectors so cosine similarity behaves across providers.
+    #[serde(default = "default_true")]
+    pub embedding_normalize: bool,
+    /// Truncate embedding input to roughly this many characters; 0 disables truncation.
+    #[serde(default)]
+    pub embedding_max_chars: usize,
     #[serde(default)]
     pub openai_api_key: Option<String>,
 
+    // --- Outbound HTTP ---
+    /// Proxy for plain-HTTP requests; unset falls back to the HTTP_PROXY env var.
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// Proxy for HTTPS requests; unset falls back to the HTTPS_PROXY env var.
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that bypass `http_proxy`/`https_proxy`.
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    /// Whole-request timeout for shared outbound HTTP clients; 0 disables it.
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+
     // --- Pricing ---
     #[serde(default = "default_model_prices")]
     pub model_prices: Vec<ModelPrice>,
+    /// Rows in the per-sender section of `/usage`; 0 hides it.
+    #[serde(default = "default_usage_report_top_senders")]
+    pub usage_report_top_senders: usize,
+    /// Optional token/cost cap enforced before calling the LLM.
+    #[serde(default)]
+    pub usage_budget: Option<UsageBudget>,
 
     // --- Reflector ---
     #[serde(default = "default_reflector_enabled")]
@@ -303,6 +583,24 @@ pub struct Config {
     #[serde(default = "default_reflector_interval_mins")]
     pub reflector_interval_mins: u64,
 
+    // --- Observability ---
+    /// `json` emits structured records for log shippers; secret-named fields
+    /// are masked.
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Port for the Prometheus `/metrics` endpoint; unset disables it.
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    /// Bind address for the metrics endpoint.
+    #[serde(default = "default_web_host")]
+    pub metrics_host: String,
+    /// Port for the `/healthz` and `/readyz` probes; unset disables them.
+    #[serde(default)]
+    pub health_port: Option<u16>,
+    /// Bind address for the health probes.
+    #[serde(default = "default_web_host")]
+    pub health_host: String,
+
     // --- Soul ---
     /// Path to a SOUL.md file that defines the bot's personality, voice, and values.
     /// If not set, looks for SOUL.md in data_dir root, then current directory.
@@ -331,6 +629,7 @@ pub struct Config {
     /// Each value is channel-specific config deserialized by the adapter.
     /// If empty, synthesized from legacy flat fields below in post_deserialize().
     #[serde(default)]
+    #[schemars(with = "HashMap<String, serde_json::Value>")]
     pub channels: HashMap<String, serde_yaml::Value>,
 
     // --- Legacy channel fields (deprecated, use `channels:` instead) ---
@@ -386,6 +685,31 @@ impl Config {
             .map(ToOwned::to_owned)
     }
 
+    /// Per-sender message limit for `channel`: the account's, then the
+    /// channel's `messages_per_minute`, then the global setting.
+    pub fn messages_per_minute_for_channel(&self, channel: &str) -> u32 {
+        let read = |v: Option<&serde_yaml::Value>| {
+            v.and_then(|v| v.get("messages_per_minute"))
+                .and_then(|v| v.as_u64())
+                .map(|v| v.min(u64::from(u32::MAX)) as u32)
+        };
+        let (base_channel, account_id) = match channel.split_once('.') {
+            Some((base, account)) => (base, Some(account)),
+            None => (channel, None),
+        };
+        let channel_cfg = self.channels.get(base_channel);
+        account_id
+            .and_then(|id| {
+                read(
+                    channel_cfg
+                        .and_then(|v| v.get("accounts"))
+                        .and_then(|v| v.get(id)),
+                )
+            })
+            .or_else(|| read(channel_cfg))
+            .unwrap_or(self.messages_per_minute)
+    }
+
     pub fn bot_username_for_channel(&self, channel: &str) -> String {
         let channel_override = self
             .channels
@@ -471,18 +795,30 @@ impl Config {
             api_key: "key".into(),
             model: "claude-sonnet-4-5-20250929".into(),
             llm_base_url: None,
+            llm_fallbacks: vec![],
             max_tokens: 8192,
             max_tool_iterations: 100,
+            max_concurrent_agents: 4,
+            messages_per_minute: 0,
             compaction_timeout_secs: 180,
             max_history_messages: 50,
             max_document_size_mb: 100,
             memory_token_budget: 1500,
             data_dir: default_data_dir(),
             skills_dir: None,
+            prune_builtin_skills: false,
+            skills_watch: false,
+            skills_watch_debounce_ms: default_skills_watch_debounce_ms(),
             working_dir: default_working_dir(),
             working_dir_isolation: WorkingDirIsolation::Chat,
+            file_tools_confine_to_working_dir: true,
+            read_file_max_bytes: default_read_file_max_bytes(),
             sandbox: SandboxConfig::default(),
             openai_api_key: None,
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: None,
+            http_timeout_secs: 60,
             timezone: "UTC".into(),
             allowed_groups: vec![],
             control_chat_ids: vec![],
@@ -490,11 +826,19 @@ impl Config {
             compact_keep_recent: 20,
             default_tool_timeout_secs: default_tool_timeout_secs(),
             tool_timeout_overrides: HashMap::new(),
+            browser_binary: default_browser_binary(),
+            browser_session: None,
+            browser_allowed_commands: default_browser_allowed_commands(),
+            browser_allow_all_commands: false,
+            browser_max_sessions: default_browser_max_sessions(),
+            browser_session_idle_secs: default_browser_session_idle_secs(),
+            browser_max_output_chars: default_browser_max_output_chars(),
             default_mcp_request_timeout_secs: default_mcp_request_timeout_secs(),
             discord_bot_token: None,
             discord_allowed_channels: vec![],
             discord_no_mention: false,
             allow_group_slash_without_mention: false,
+            reset_default_scope: ResetScope::All,
             show_thinking: false,
             openai_compat_body_overrides: HashMap::new(),
             openai_compat_body_overrides_by_provider: HashMap::new(),
@@ -510,14 +854,27 @@ impl Config {
             web_session_idle_ttl_seconds: 300,
             web_fetch_validation: WebContentValidationConfig::default(),
             web_fetch_url_validation: WebFetchUrlValidationConfig::default(),
+            fetch_url_max_bytes: default_fetch_url_max_bytes(),
+            fetch_url_allow_private_networks: false,
             model_prices: vec![],
+            usage_report_top_senders: 5,
+            usage_budget: None,
             embedding_provider: None,
             embedding_api_key: None,
             embedding_base_url: None,
             embedding_model: None,
             embedding_dim: None,
+            embedding_cache_size: 1024,
+            embedding_max_retries: 3,
+            embedding_normalize: true,
+            embedding_max_chars: 0,
             reflector_enabled: true,
             reflector_interval_mins: 15,
+            log_format: LogFormat::Text,
+            metrics_port: None,
+            metrics_host: "127.0.0.1".into(),
+            health_port: None,
+            health_host: "127.0.0.1".into(),
             soul_path: None,
             clawhub: ClawHubConfig::default(),
             plugins: PluginsConfig::default(),
@@ -584,6 +941,9 @@ impl Config {
         if std::path::Path::new("./microclaw.config.yml").exists() {
             return Ok(Some(PathBuf::from("./microclaw.config.yml")));
         }
+        if std::path::Path::new("./microclaw.config.toml").exists() {
+            return Ok(Some(PathBuf::from("./microclaw.config.toml")));
+        }
         Ok(None)
     }
 
@@ -619,26 +979,139 @@ impl Config {
         self.inferred_channel_enabled(&needle)
     }
 
-    /// Load config from YAML file.
+    /// Proxy and timeout settings for outbound HTTP clients.
+    pub fn http_client_settings(&self) -> HttpClientSettings {
+        HttpClientSettings {
+            http_proxy: self.http_proxy.clone(),
+            https_proxy: self.https_proxy.clone(),
+            no_proxy: self.no_proxy.clone(),
+            all_proxy: None,
+            timeout: (self.http_timeout_secs > 0)
+                .then(|| std::time::Duration::from_secs(self.http_timeout_secs)),
+            connect_timeout: None,
+            read_timeout: None,
+        }
+    }
+
+    /// Channels that are enabled in `channels`, sorted by name.
+    pub fn enabled_channel_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .channels
+            .keys()
+            .filter(|name| self.channel_enabled(name))
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Human-readable summary printed by `microclaw config validate`.
+    pub fn validation_summary(&self) -> String {
+        let channels = self.enabled_channel_names();
+        format!(
+            "provider: {}\nmodel: {}\nchannels: {}",
+            self.llm_provider,
+            self.model,
+            if channels.is_empty() {
+                "(none)".to_string()
+            } else {
+                channels.join(", ")
+            }
+        )
+    }
+
+    /// Load config from the first YAML or TOML file found.
     pub fn load() -> Result<Self, MicroClawError> {
         let yaml_path = Self::resolve_config_path()?;
 
         if let Some(path) = yaml_path {
-            let path_str = path.to_string_lossy().to_string();
-            let content = std::fs::read_to_string(&path)
-                .map_err(|e| MicroClawError::Config(format!("Failed to read {path_str}: {e}")))?;
-            let mut config: Config = serde_yaml::from_str(&content)
-                .map_err(|e| MicroClawError::Config(format!("Failed to parse {path_str}: {e}")))?;
-            config.post_deserialize()?;
-            return Ok(config);
+            return Self::load_from_path(&path);
         }
 
         // No config file found at all
         Err(MicroClawError::Config(
-            "No microclaw.config.yaml found. Run `microclaw setup` to create one.".into(),
+            "No microclaw.config.yaml (or .yml/.toml) found. Run `microclaw setup` to create one."
+                .into(),
         ))
     }
 
+    /// Load and validate a config file at an explicit path. The format is
+    /// picked from the file extension (`.toml` is TOML, anything else YAML).
+    pub fn load_from_path(path: &Path) -> Result<Self, MicroClawError> {
+        let path_str = path.to_string_lossy().to_string();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| MicroClawError::Config(format!("Failed to read {path_str}: {e}")))?;
+        let parsed: Result<Config, String> = match ConfigFormat::from_path(path) {
+            ConfigFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::from_str(&content).map_err(|e| e.to_string()),
+        };
+        let mut config = parsed
+            .map_err(|e| MicroClawError::Config(format!("Failed to parse {path_str}: {e}")))?;
+        config.post_deserialize()?;
+        Ok(config)
+    }
+
+    /// Re-read `path` and swap it into `live`. On failure the previous config
+    /// stays in place and the error is returned.
+    pub fn reload_into(path: &Path, live: &ArcSwap<Config>) -> Result<(), MicroClawError> {
+        let config = Self::load_from_path(path)?;
+        live.store(Arc::new(config));
+        Ok(())
+    }
+
+    /// Watch `path` and hot-reload it into `live` on every change.
+    ///
+    /// Agent knobs (`model`, `max_history_messages`, `max_tool_iterations`,
+    /// `max_session_messages`, `compact_keep_recent`) and LLM settings
+    /// (`max_tokens`, provider, credentials, fallbacks) apply to the next
+    /// request. Channel tokens and allow-lists baked into channel runtimes
+    /// take effect on the next connection or restart.
+    pub fn watch(path: PathBuf, live: Arc<ArcSwap<Config>>) -> Result<(), MicroClawError> {
+        use notify::{EventKind, RecursiveMode, Watcher};
+
+        let file_name = path.file_name().map(ToOwned::to_owned).ok_or_else(|| {
+            MicroClawError::Config(format!("Invalid config path: {}", path.display()))
+        })?;
+        // Watch the parent directory so editors that replace the file atomically still trigger.
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)
+            .map_err(|e| MicroClawError::Config(format!("Failed to start config watcher: {e}")))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                MicroClawError::Config(format!("Failed to watch {}: {e}", dir.display()))
+            })?;
+
+        std::thread::spawn(move || {
+            let _watcher = watcher;
+            for event in rx {
+                let Ok(event) = event else {
+                    continue;
+                };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+                if !event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == Some(file_name.as_os_str()))
+                {
+                    continue;
+                }
+                match Self::reload_into(&path, &live) {
+                    Ok(()) => tracing::info!("Reloaded config from {}", path.display()),
+                    Err(e) => tracing::error!("Config reload failed, keeping previous config: {e}"),
+                }
+            }
+        });
+        Ok(())
+    }
+
     /// Apply post-deserialization normalization and validation.
     pub(crate) fn post_deserialize(&mut self) -> Result<(), MicroClawError> {
         self.llm_provider = self.llm_provider.trim().to_lowercase();
@@ -713,6 +1186,15 @@ impl Config {
                 self.embedding_base_url = None;
             }
         }
+        for proxy in [
+            &mut self.http_proxy,
+            &mut self.https_proxy,
+            &mut self.no_proxy,
+        ] {
+            if proxy.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                *proxy = None;
+            }
+        }
         if let Some(v) = &self.embedding_model {
             let m = v.trim().to_string();
             self.embedding_model = if m.is_empty() { None } else { Some(m) };
@@ -809,6 +1291,22 @@ impl Config {
         if self.memory_token_budget == 0 {
             self.memory_token_budget = default_memory_token_budget();
         }
+        for (idx, fallback) in self.llm_fallbacks.iter_mut().enumerate() {
+            fallback.provider = fallback.provider.trim().to_lowercase();
+            fallback.model = fallback.model.trim().to_string();
+            if fallback.provider.is_empty() || fallback.model.is_empty() {
+                return Err(MicroClawError::Config(format!(
+                    "llm_fallbacks[{idx}] must set provider and model"
+                )));
+            }
+            if fallback
+                .base_url
+                .as_deref()
+                .is_some_and(|url| url.trim().is_empty())
+            {
+                fallback.base_url = None;
+            }
+        }
         for price in &mut self.model_prices {
             price.model = price.model.trim().to_string();
             if price.model.is_empty() {
@@ -830,6 +1328,32 @@ impl Config {
             }
         }
 
+        if let Some(budget) = &self.usage_budget {
+            if budget.max_tokens.is_none() && budget.max_cost_usd.is_none() {
+                return Err(MicroClawError::Config(
+                    "usage_budget must set max_tokens and/or max_cost_usd".into(),
+                ));
+            }
+            if budget.max_tokens.is_some_and(|v| v <= 0) {
+                return Err(MicroClawError::Config(
+                    "usage_budget.max_tokens must be > 0".into(),
+                ));
+            }
+            if budget
+                .max_cost_usd
+                .is_some_and(|v| !(v.is_finite() && v > 0.0))
+            {
+                return Err(MicroClawError::Config(
+                    "usage_budget.max_cost_usd must be > 0".into(),
+                ));
+            }
+            if !(budget.warn_ratio > 0.0 && budget.warn_ratio <= 1.0) {
+                return Err(MicroClawError::Config(
+                    "usage_budget.warn_ratio must be in (0, 1]".into(),
+                ));
+            }
+        }
+
         // Synthesize `channels` map from legacy flat fields if empty
         if self.channels.is_empty() {
             if !self.telegram_bot_token.trim().is_empty() {
@@ -908,7 +1432,10 @@ impl Config {
             ));
         }
         if self.api_key.is_empty() && !provider_allows_empty_api_key(&self.llm_provider) {
-            return Err(MicroClawError::Config("api_key is required".into()));
+            return Err(MicroClawError::Config(format!(
+                "api_key is required for llm_provider '{}'",
+                self.llm_provider
+            )));
         }
         if is_openai_codex_provider(&self.llm_provider) {
             if !self.api_key.trim().is_empty() {
@@ -967,6 +1494,41 @@ impl Config {
         )
     }
 
+    /// Session name for the browser tool: `browser_session` if set, otherwise
+    /// derived from `bot_username` so separate bots don't share browser state.
+    pub fn browser_session_name(&self) -> String {
+        let raw = self
+            .browser_session
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                let bot = self.bot_username.trim().trim_start_matches('@');
+                if bot.is_empty() {
+                    "microclaw".to_string()
+                } else {
+                    format!("microclaw-{bot}")
+                }
+            });
+        let sanitized: String = raw
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let trimmed = sanitized.trim_matches('-');
+        if trimmed.is_empty() {
+            "microclaw".to_string()
+        } else {
+            trimmed.to_string()
+        }
+    }
+
     pub fn tool_timeout_secs(&self, tool_name: &str, fallback: u64) -> u64 {
         let normalized = tool_name.trim().to_ascii_lowercase();
         if let Some(timeout_secs) = self.tool_timeout_overrides.get(&normalized) {
@@ -995,6 +1557,38 @@ impl Config {
         std::fs::write(path, content)?;
         Ok(())
     }
+
+    /// Save config as TOML to the given path.
+    pub fn save_toml(&self, path: &str) -> Result<(), MicroClawError> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| MicroClawError::Config(format!("Failed to serialize config: {e}")))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Save config in the format implied by the path's extension.
+    pub fn save_to_path(&self, path: &Path) -> Result<(), MicroClawError> {
+        let path_str = path.to_string_lossy();
+        match ConfigFormat::from_path(path) {
+            ConfigFormat::Yaml => self.save_yaml(&path_str),
+            ConfigFormat::Toml => self.save_toml(&path_str),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
 }
 
 fn normalize_body_override_params(
@@ -1027,6 +1621,8 @@ mod tests {
         let config: Config = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(config.clawhub.registry, "https://clawhub.ai");
         assert!(config.clawhub.agent_tools_enabled);
+        assert_eq!(config.clawhub.timeout_secs, 30);
+        assert_eq!(config.clawhub.max_retries, 2);
     }
 
     #[test]
@@ -1124,6 +1720,7 @@ voice_transcription_command: "whisper-mlx --file {file}"
             config.working_dir_isolation,
             WorkingDirIsolation::Chat
         ));
+        assert!(config.file_tools_confine_to_working_dir);
         assert!(matches!(config.sandbox.mode, SandboxMode::Off));
         assert_eq!(config.max_document_size_mb, 100);
         assert_eq!(config.timezone, "UTC");
@@ -1301,6 +1898,23 @@ voice_transcription_command: "whisper-mlx --file {file}"
         assert!(msg.contains("Invalid timezone"));
     }
 
+    #[test]
+    fn test_post_deserialize_llm_fallbacks() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_fallbacks:\n  - provider: ' OpenRouter '\n    model: openai/gpt-5.2\n    base_url: ''\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.llm_fallbacks.len(), 1);
+        assert_eq!(config.llm_fallbacks[0].provider, "openrouter");
+        assert!(config.llm_fallbacks[0].base_url.is_none());
+
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_fallbacks:\n  - provider: groq\n    model: ''\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("llm_fallbacks[0] must set provider and model"));
+    }
+
     #[test]
     fn test_post_deserialize_missing_api_key() {
         let yaml = "telegram_bot_token: tok\nbot_username: bot\n";
@@ -1310,6 +1924,111 @@ voice_transcription_command: "whisper-mlx --file {file}"
         assert!(msg.contains("api_key is required"));
     }
 
+    #[test]
+    fn test_load_from_path_reports_validation_errors() {
+        let path =
+            std::env::temp_dir().join(format!("microclaw_validate_{}.yaml", uuid::Uuid::new_v4()));
+
+        std::fs::write(
+            &path,
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\ntimezone: Mars/Olympus\n",
+        )
+        .unwrap();
+        let msg = Config::load_from_path(&path).unwrap_err().to_string();
+        assert!(msg.contains("Invalid timezone: Mars/Olympus"), "{msg}");
+
+        std::fs::write(
+            &path,
+            "llm_provider: openai\ntelegram_bot_token: tok\nbot_username: bot\n",
+        )
+        .unwrap();
+        let msg = Config::load_from_path(&path).unwrap_err().to_string();
+        assert!(
+            msg.contains("api_key is required for llm_provider 'openai'"),
+            "{msg}"
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_toml_config_loads_with_yaml_defaults() {
+        let dir = std::env::temp_dir().join(format!("microclaw_toml_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let yaml_path = dir.join("microclaw.config.yaml");
+        let toml_path = dir.join("microclaw.config.toml");
+        std::fs::write(
+            &yaml_path,
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nchannels:\n  telegram:\n    enabled: true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &toml_path,
+            "telegram_bot_token = \"tok\"\nbot_username = \"bot\"\napi_key = \"key\"\n\n[channels.telegram]\nenabled = true\n",
+        )
+        .unwrap();
+
+        let from_yaml = Config::load_from_path(&yaml_path).unwrap();
+        let from_toml = Config::load_from_path(&toml_path).unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&from_yaml).unwrap(),
+            serde_yaml::to_string(&from_toml).unwrap()
+        );
+        assert_eq!(from_toml.enabled_channel_names(), vec!["telegram"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_config_format_dispatches_on_extension() {
+        let _guard = env_lock();
+        let dir = std::env::temp_dir().join(format!("microclaw_fmt_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a/microclaw.config.TOML")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("microclaw.config.yml")),
+            ConfigFormat::Yaml
+        );
+
+        let mut config = Config::test_defaults();
+        config.model = "toml-model".into();
+        let toml_path = dir.join("custom.toml");
+        config.save_to_path(&toml_path).unwrap();
+        let written = std::fs::read_to_string(&toml_path).unwrap();
+        assert!(written.contains("model = \"toml-model\""), "{written}");
+
+        let prev = std::env::var("MICROCLAW_CONFIG").ok();
+        std::env::set_var("MICROCLAW_CONFIG", &toml_path);
+        let loaded = Config::load();
+        match prev {
+            Some(v) => std::env::set_var("MICROCLAW_CONFIG", v),
+            None => std::env::remove_var("MICROCLAW_CONFIG"),
+        }
+        assert_eq!(loaded.unwrap().model, "toml-model");
+
+        // TOML content behind a .yaml extension is parsed as YAML and rejected.
+        let yaml_path = dir.join("custom.yaml");
+        std::fs::copy(&toml_path, &yaml_path).unwrap();
+        assert!(Config::load_from_path(&yaml_path).is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_validation_summary_lists_provider_model_and_channels() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmodel: gpt-test\nllm_provider: OpenAI\nchannels:\n  telegram:\n    enabled: true\n  discord:\n    enabled: false\n  web:\n    enabled: true\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.enabled_channel_names(), vec!["telegram", "web"]);
+        assert_eq!(
+            config.validation_summary(),
+            "provider: openai\nmodel: gpt-test\nchannels: telegram, web"
+        );
+    }
+
     #[test]
     fn test_post_deserialize_openai_codex_allows_empty_api_key() {
         let _guard = env_lock();
@@ -1422,6 +2141,17 @@ channels:
         assert!(!config.channel_enabled("web"));
     }
 
+    #[test]
+    fn test_messages_per_minute_channel_overrides() {
+        let yaml = "api_key: key\nmessages_per_minute: 10\nchannels:\n  matrix:\n    messages_per_minute: 5\n    accounts:\n      work:\n        messages_per_minute: 2\n      home: {}\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(config.messages_per_minute_for_channel("telegram"), 10);
+        assert_eq!(config.messages_per_minute_for_channel("matrix"), 5);
+        assert_eq!(config.messages_per_minute_for_channel("matrix.work"), 2);
+        assert_eq!(config.messages_per_minute_for_channel("matrix.home"), 5);
+    }
+
     #[test]
     fn test_post_deserialize_openai_default_model() {
         let yaml =
@@ -1704,6 +2434,57 @@ model_prices:
             .contains("model_prices entries must include non-empty model"));
     }
 
+    #[test]
+    fn test_log_format_defaults_to_text_and_parses_json() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.log_format, LogFormat::Text);
+        let config: Config = serde_yaml::from_str(&format!("{yaml}log_format: json\n")).unwrap();
+        assert_eq!(config.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn test_browser_session_name_defaults_to_bot_username() {
+        let mut config = Config::test_defaults();
+        config.bot_username = "@My Bot".into();
+        assert_eq!(config.browser_session_name(), "microclaw-my-bot");
+        config.browser_session = Some("Shared".into());
+        assert_eq!(config.browser_session_name(), "shared");
+        config.browser_session = None;
+        config.bot_username = String::new();
+        assert_eq!(config.browser_session_name(), "microclaw");
+    }
+
+    #[test]
+    fn test_usage_budget_parse_and_validate() {
+        let yaml = r#"
+telegram_bot_token: tok
+bot_username: bot
+api_key: key
+usage_budget:
+  period: weekly
+  scope: per_chat
+  max_tokens: 1000
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        let budget = config.usage_budget.unwrap();
+        assert_eq!(budget.period, BudgetPeriod::Weekly);
+        assert_eq!(budget.scope, BudgetScope::PerChat);
+        assert_eq!(budget.warn_ratio, 0.8);
+
+        let yaml = r#"
+telegram_bot_token: tok
+bot_username: bot
+api_key: key
+usage_budget:
+  period: monthly
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("max_tokens and/or max_cost_usd"));
+    }
+
     #[test]
     fn test_config_yaml_with_all_optional_fields() {
         let yaml = r#"
@@ -1730,6 +2511,36 @@ discord_allowed_channels: [111, 222]
         assert_eq!(config.discord_allowed_channels, vec![111, 222]);
     }
 
+    #[test]
+    fn test_config_reload_into_swaps_valid_and_rejects_invalid() {
+        let path =
+            std::env::temp_dir().join(format!("microclaw_reload_{}.yaml", uuid::Uuid::new_v4()));
+        let live = ArcSwap::from_pointee(test_config());
+
+        std::fs::write(
+            &path,
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmax_history_messages: 7\n",
+        )
+        .unwrap();
+        Config::reload_into(&path, &live).unwrap();
+        assert_eq!(live.load().max_history_messages, 7);
+
+        std::fs::write(
+            &path,
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmax_history_messages: 9\ntimezone: Not/AZone\n",
+        )
+        .unwrap();
+        let err = Config::reload_into(&path, &live).unwrap_err();
+        assert!(err.to_string().contains("Invalid timezone"));
+        assert_eq!(live.load().max_history_messages, 7);
+
+        std::fs::write(&path, "max_history_messages: [not a number").unwrap();
+        assert!(Config::reload_into(&path, &live).is_err());
+        assert_eq!(live.load().max_history_messages, 7);
+
+        std::fs::remove_file(path).ok();
+    }
+
     #[test]
     fn test_config_save_yaml() {
         let config = test_config();
@@ -0,0 +1,133 @@
+//! JSON Schema for `microclaw.config.yaml`, emitted by `microclaw config schema`
+//! so editors (e.g. the YAML language server) can autocomplete and validate.
+
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+use crate::channels::dingtalk::DingTalkChannelConfig;
+use crate::channels::discord::DiscordChannelConfig;
+use crate::channels::email::EmailChannelConfig;
+use crate::channels::feishu::FeishuChannelConfig;
+use crate::channels::imessage::IMessageChannelConfig;
+use crate::channels::irc::IrcChannelConfig;
+use crate::channels::matrix::MatrixChannelConfig;
+use crate::channels::nostr::NostrChannelConfig;
+use crate::channels::qq::QQChannelConfig;
+use crate::channels::signal::SignalChannelConfig;
+use crate::channels::slack::SlackChannelConfig;
+use crate::channels::telegram::TelegramChannelConfig;
+use crate::channels::whatsapp::WhatsAppChannelConfig;
+use crate::config::Config;
+
+fn add_channel<T: JsonSchema>(
+    generator: &mut schemars::SchemaGenerator,
+    channels: &mut Map<String, Value>,
+    name: &str,
+) {
+    channels.insert(name.to_string(), generator.subschema_for::<T>().to_value());
+}
+
+/// Build the config schema (draft-07), including the per-channel configs that
+/// `Config::channels` only holds as untyped YAML.
+pub fn config_json_schema() -> Value {
+    let mut generator = SchemaSettings::draft07().for_deserialize().into_generator();
+
+    let mut channels = Map::new();
+    add_channel::<DingTalkChannelConfig>(&mut generator, &mut channels, "dingtalk");
+    add_channel::<DiscordChannelConfig>(&mut generator, &mut channels, "discord");
+    add_channel::<EmailChannelConfig>(&mut generator, &mut channels, "email");
+    add_channel::<FeishuChannelConfig>(&mut generator, &mut channels, "feishu");
+    add_channel::<IMessageChannelConfig>(&mut generator, &mut channels, "imessage");
+    add_channel::<IrcChannelConfig>(&mut generator, &mut channels, "irc");
+    add_channel::<MatrixChannelConfig>(&mut generator, &mut channels, "matrix");
+    add_channel::<NostrChannelConfig>(&mut generator, &mut channels, "nostr");
+    add_channel::<QQChannelConfig>(&mut generator, &mut channels, "qq");
+    add_channel::<SignalChannelConfig>(&mut generator, &mut channels, "signal");
+    add_channel::<SlackChannelConfig>(&mut generator, &mut channels, "slack");
+    add_channel::<TelegramChannelConfig>(&mut generator, &mut channels, "telegram");
+    add_channel::<WhatsAppChannelConfig>(&mut generator, &mut channels, "whatsapp");
+    channels.insert(
+        "web".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean" },
+                "host": { "type": "string" },
+                "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+                "auth_token": { "type": ["string", "null"] },
+            },
+        }),
+    );
+
+    let mut schema = generator.root_schema_for::<Config>().to_value();
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        if let Some(channels_schema) = properties
+            .get_mut("channels")
+            .and_then(Value::as_object_mut)
+        {
+            channels_schema.insert("properties".to_string(), Value::Object(channels));
+        }
+        if let Some(provider) = properties
+            .get_mut("llm_provider")
+            .and_then(Value::as_object_mut)
+        {
+            provider.insert(
+                "enum".to_string(),
+                json!(crate::setup::provider_preset_ids()),
+            );
+        }
+        if let Some(voice) = properties
+            .get_mut("voice_provider")
+            .and_then(Value::as_object_mut)
+        {
+            voice.insert("enum".to_string(), json!(["openai", "local"]));
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_json_schema_parses_and_has_known_keys() {
+        let text = serde_json::to_string_pretty(&config_json_schema()).unwrap();
+        let schema: Value = serde_json::from_str(&text).unwrap();
+
+        for key in [
+            "llm_provider",
+            "model",
+            "max_tokens",
+            "timezone",
+            "channels",
+        ] {
+            assert!(
+                schema.pointer(&format!("/properties/{key}")).is_some(),
+                "missing {key}"
+            );
+        }
+        let providers = schema
+            .pointer("/properties/llm_provider/enum")
+            .and_then(Value::as_array)
+            .unwrap();
+        assert!(providers.contains(&json!("anthropic")));
+        assert_eq!(
+            schema.pointer("/properties/max_history_messages/default"),
+            Some(&json!(50))
+        );
+
+        let matrix_ref = schema
+            .pointer("/properties/channels/properties/matrix/$ref")
+            .and_then(Value::as_str)
+            .unwrap();
+        let matrix_path = matrix_ref.trim_start_matches('#');
+        assert!(schema
+            .pointer(&format!("{matrix_path}/properties/homeserver_url"))
+            .is_some());
+        assert!(schema
+            .pointer(&format!("{matrix_path}/properties/accounts"))
+            .is_some());
+    }
+}
@@ -181,7 +181,7 @@ fn migrate_channels_config() -> anyhow::Result<Option<(PathBuf, usize)>> {
     let mut cfg = Config::load()?;
     let changed = migrate_channels_to_accounts(&mut cfg);
     if changed > 0 {
-        cfg.save_yaml(&path.to_string_lossy())?;
+        cfg.save_to_path(&path)?;
     }
     Ok(Some((path, changed)))
 }
@@ -1,14 +1,29 @@
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use lru::LruCache;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
 
 use crate::config::Config;
 
 #[async_trait]
 pub trait EmbeddingProvider: Send + Sync {
     async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+    /// Embed several texts, returning vectors in input order. Providers whose
+    /// API accepts a list override this to send a single request.
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut out = Vec::with_capacity(texts.len());
+        for text in texts {
+            out.push(self.embed(text).await?);
+        }
+        Ok(out)
+    }
     fn model(&self) -> &str;
     fn dimension(&self) -> usize;
 }
@@ -19,6 +34,9 @@ pub struct OpenAIEmbeddingProvider {
     api_key: String,
     model: String,
     dim: usize,
+    max_retries: u32,
+    normalize: bool,
+    max_chars: usize,
 }
 
 pub struct OllamaEmbeddingProvider {
@@ -26,12 +44,163 @@ pub struct OllamaEmbeddingProvider {
     base_url: String,
     model: String,
     dim: usize,
+    max_retries: u32,
+    normalize: bool,
+    max_chars: usize,
+}
+
+/// Scale `vector` to unit L2 length in place. All-zero vectors are left as-is.
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+fn finish_embedding(mut vector: Vec<f32>, normalize: bool) -> Vec<f32> {
+    if normalize {
+        l2_normalize(&mut vector);
+    }
+    vector
+}
+
+/// Cut `text` to at most `max_chars` characters on a char boundary. 0 disables.
+fn truncate_chars(text: &str, max_chars: usize) -> Cow<'_, str> {
+    if max_chars == 0 {
+        return Cow::Borrowed(text);
+    }
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            tracing::debug!(
+                "Truncating embedding input from {} to {max_chars} chars",
+                text.chars().count()
+            );
+            Cow::Borrowed(&text[..end])
+        }
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Rough token count for a word: ~4 chars per token, at least one.
+fn estimate_word_tokens(word: &str) -> usize {
+    word.chars().count().div_ceil(4).max(1)
+}
+
+/// Token-aware variant of [`truncate_chars`]: keeps whole words while their
+/// estimated token count fits in `max_chars / 4`, so the cut lands between
+/// words instead of mid-token. Falls back to a char cut for one giant word.
+fn truncate_token_estimate(text: &str, max_chars: usize) -> Cow<'_, str> {
+    if max_chars == 0 {
+        return Cow::Borrowed(text);
+    }
+    let budget = (max_chars / 4).max(1);
+    let mut used = 0;
+    let mut end = 0;
+    let mut offset = 0;
+    for piece in text.split_inclusive(char::is_whitespace) {
+        let word = piece.trim();
+        if !word.is_empty() {
+            used += estimate_word_tokens(word);
+            if used > budget {
+                if end == 0 {
+                    return truncate_chars(text, max_chars);
+                }
+                tracing::debug!(
+                    "Truncating embedding input to ~{budget} estimated tokens ({end} of {} bytes)",
+                    text.len()
+                );
+                return Cow::Borrowed(&text[..end]);
+            }
+            end = offset + piece.trim_end().len();
+        }
+        offset += piece.len();
+    }
+    Cow::Borrowed(text)
+}
+
+const EMBEDDING_RETRY_BASE: Duration = Duration::from_millis(500);
+const EMBEDDING_RETRY_MAX: Duration = Duration::from_secs(30);
+
+/// Whether an embedding request should be retried: connection errors
+/// (`None`), 429 and 5xx are transient; any other status fails immediately.
+fn should_retry_embedding(status: Option<reqwest::StatusCode>) -> bool {
+    match status {
+        None => true,
+        Some(status) => {
+            status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        }
+    }
+}
+
+/// Delay before retry number `attempt` (0-based): `Retry-After` when the
+/// server sent one, otherwise exponential backoff. Both are capped.
+fn embedding_retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| EMBEDDING_RETRY_BASE.saturating_mul(2u32.saturating_pow(attempt)))
+        .min(EMBEDDING_RETRY_MAX)
+}
+
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// POST built by `build`, retrying transient failures up to `max_retries`
+/// extra times. Returns the first successful response.
+async fn send_embedding_request<F>(max_retries: u32, build: F) -> Result<reqwest::Response>
+where
+    F: Fn() -> reqwest::RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        let (err, retry_after) = match build().send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = parse_retry_after(response.headers());
+                let body = response.text().await.unwrap_or_default();
+                let err = anyhow!("embedding request failed ({}): {}", status, body);
+                if !should_retry_embedding(Some(status)) {
+                    return Err(err);
+                }
+                (err, retry_after)
+            }
+            Err(e) => (e.into(), None),
+        };
+        if attempt >= max_retries {
+            return Err(err);
+        }
+        let delay = embedding_retry_delay(attempt, retry_after);
+        tracing::debug!(
+            "Embedding request failed (attempt {}), retrying in {:?}: {}",
+            attempt + 1,
+            delay,
+            err
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
 }
 
 #[derive(Debug, Serialize)]
 struct OpenAIEmbeddingRequest<'a> {
     model: &'a str,
-    input: &'a str,
+    input: OpenAIEmbeddingInput<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum OpenAIEmbeddingInput<'a> {
+    One(&'a str),
+    Many(&'a [&'a str]),
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,6 +210,8 @@ struct OpenAIEmbeddingResponse {
 
 #[derive(Debug, Deserialize)]
 struct OpenAIEmbeddingData {
+    #[serde(default)]
+    index: usize,
     embedding: Vec<f32>,
 }
 
@@ -66,38 +237,77 @@ fn infer_default_dim(provider: &str, model: &str) -> usize {
             }
         }
         "ollama" => 1024,
+        "local" | "fastembed" => 384,
         _ => 1536,
     }
 }
 
-#[async_trait]
-impl EmbeddingProvider for OpenAIEmbeddingProvider {
-    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+impl OpenAIEmbeddingProvider {
+    async fn request(&self, input: OpenAIEmbeddingInput<'_>) -> Result<Vec<OpenAIEmbeddingData>> {
         let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
-        let response = self
-            .client
-            .post(url)
-            .bearer_auth(&self.api_key)
-            .json(&OpenAIEmbeddingRequest {
-                model: &self.model,
-                input: text,
-            })
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("embedding request failed: {}", body));
-        }
+        let request = OpenAIEmbeddingRequest {
+            model: &self.model,
+            input,
+        };
+        let response = send_embedding_request(self.max_retries, || {
+            self.client
+                .post(&url)
+                .bearer_auth(&self.api_key)
+                .json(&request)
+        })
+        .await?;
 
         let body: OpenAIEmbeddingResponse = response.json().await?;
-        let embedding = body
-            .data
+        Ok(body.data)
+    }
+}
+
+/// Order response rows by their `index` so output matches input order.
+fn order_openai_embeddings(
+    mut data: Vec<OpenAIEmbeddingData>,
+    expected: usize,
+) -> Result<Vec<Vec<f32>>> {
+    if data.len() != expected {
+        return Err(anyhow!(
+            "embedding response had {} vectors for {} inputs",
+            data.len(),
+            expected
+        ));
+    }
+    data.sort_by_key(|d| d.index);
+    Ok(data.into_iter().map(|d| d.embedding).collect())
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAIEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let embedding = self
+            .request(OpenAIEmbeddingInput::One(&truncate_token_estimate(
+                text,
+                self.max_chars,
+            )))
+            .await?
             .into_iter()
             .next()
             .ok_or_else(|| anyhow!("empty embedding response"))?
             .embedding;
-        Ok(embedding)
+        Ok(finish_embedding(embedding, self.normalize))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let truncated: Vec<Cow<'_, str>> = texts
+            .iter()
+            .map(|t| truncate_token_estimate(t, self.max_chars))
+            .collect();
+        let inputs: Vec<&str> = truncated.iter().map(|t| t.as_ref()).collect();
+        let data = self.request(OpenAIEmbeddingInput::Many(&inputs)).await?;
+        Ok(order_openai_embeddings(data, texts.len())?
+            .into_iter()
+            .map(|v| finish_embedding(v, self.normalize))
+            .collect())
     }
 
     fn model(&self) -> &str {
@@ -113,23 +323,136 @@ impl EmbeddingProvider for OpenAIEmbeddingProvider {
 impl EmbeddingProvider for OllamaEmbeddingProvider {
     async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         let url = format!("{}/api/embeddings", self.base_url.trim_end_matches('/'));
-        let response = self
-            .client
-            .post(url)
-            .json(&OllamaEmbeddingRequest {
-                model: &self.model,
-                prompt: text,
+        let request = OllamaEmbeddingRequest {
+            model: &self.model,
+            prompt: &truncate_chars(text, self.max_chars),
+        };
+        let response =
+            send_embedding_request(self.max_retries, || self.client.post(&url).json(&request))
+                .await?;
+
+        let body: OllamaEmbeddingResponse = response.json().await?;
+        Ok(finish_embedding(body.embedding, self.normalize))
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+}
+
+/// Local ONNX embeddings via fastembed; no API key or network service needed
+/// once the model has been downloaded into `cache_dir`.
+#[cfg(feature = "fastembed")]
+pub struct FastEmbedProvider {
+    model: String,
+    dim: usize,
+    cache_dir: std::path::PathBuf,
+    normalize: bool,
+    max_chars: usize,
+    engine: OnceCell<Arc<Mutex<fastembed::TextEmbedding>>>,
+}
+
+#[cfg(feature = "fastembed")]
+pub const FASTEMBED_DEFAULT_MODEL: &str = "BAAI/bge-small-en-v1.5";
+
+/// Resolve a configured model name against fastembed's catalogue. Accepts the
+/// enum name (`BGESmallENV15`), the full model code, or just its last segment
+/// (`bge-small-en-v1.5`), case-insensitively.
+#[cfg(feature = "fastembed")]
+fn resolve_fastembed_model(name: &str) -> Option<fastembed::ModelInfo<fastembed::EmbeddingModel>> {
+    let wanted = name.trim();
+    let wanted_tail = wanted.rsplit('/').next().unwrap_or(wanted);
+    fastembed::TextEmbedding::list_supported_models()
+        .into_iter()
+        .find(|info| {
+            let tail = info
+                .model_code
+                .rsplit('/')
+                .next()
+                .unwrap_or(&info.model_code);
+            format!("{:?}", info.model).eq_ignore_ascii_case(wanted)
+                || info.model_code.eq_ignore_ascii_case(wanted)
+                || tail.eq_ignore_ascii_case(wanted_tail)
+        })
+}
+
+#[cfg(feature = "fastembed")]
+impl FastEmbedProvider {
+    pub fn new(
+        model: &str,
+        dim: Option<usize>,
+        cache_dir: std::path::PathBuf,
+        normalize: bool,
+        max_chars: usize,
+    ) -> Option<Self> {
+        let info = resolve_fastembed_model(model)?;
+        Some(Self {
+            model: model.to_string(),
+            dim: dim.unwrap_or(info.dim),
+            cache_dir,
+            normalize,
+            max_chars,
+            engine: OnceCell::new(),
+        })
+    }
+
+    /// Load the model on first use, downloading it into `cache_dir` if needed.
+    async fn engine(&self) -> Result<Arc<Mutex<fastembed::TextEmbedding>>> {
+        self.engine
+            .get_or_try_init(|| async {
+                let info = resolve_fastembed_model(&self.model)
+                    .ok_or_else(|| anyhow!("unknown fastembed model: {}", self.model))?;
+                let cache_dir = self.cache_dir.clone();
+                let engine = tokio::task::spawn_blocking(move || {
+                    std::fs::create_dir_all(&cache_dir)?;
+                    fastembed::TextEmbedding::try_new(
+                        fastembed::TextInitOptions::new(info.model)
+                            .with_cache_dir(cache_dir)
+                            .with_show_download_progress(false),
+                    )
+                })
+                .await??;
+                Ok(Arc::new(Mutex::new(engine)))
             })
-            .send()
-            .await?;
+            .await
+            .cloned()
+    }
+}
 
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("embedding request failed: {}", body));
-        }
+#[cfg(feature = "fastembed")]
+#[async_trait]
+impl EmbeddingProvider for FastEmbedProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("fastembed returned no embedding"))
+    }
 
-        let body: OllamaEmbeddingResponse = response.json().await?;
-        Ok(body.embedding)
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let engine = self.engine().await?;
+        let texts: Vec<String> = texts
+            .iter()
+            .map(|t| truncate_chars(t, self.max_chars).into_owned())
+            .collect();
+        let vectors = tokio::task::spawn_blocking(move || {
+            let mut engine = engine
+                .lock()
+                .map_err(|_| anyhow!("fastembed engine lock poisoned"))?;
+            engine.embed(texts, None)
+        })
+        .await??;
+        Ok(vectors
+            .into_iter()
+            .map(|v| finish_embedding(v, self.normalize))
+            .collect())
     }
 
     fn model(&self) -> &str {
@@ -141,6 +464,92 @@ impl EmbeddingProvider for OllamaEmbeddingProvider {
     }
 }
 
+type EmbeddingSlot = Arc<OnceCell<Vec<f32>>>;
+
+/// Decorator that keeps recently computed vectors in a bounded LRU keyed by
+/// `(model, text)`. Concurrent misses for the same text share one upstream call.
+pub struct CachingEmbeddingProvider {
+    inner: Arc<dyn EmbeddingProvider>,
+    cache: Mutex<LruCache<[u8; 32], EmbeddingSlot>>,
+}
+
+impl CachingEmbeddingProvider {
+    pub fn new(inner: Arc<dyn EmbeddingProvider>, capacity: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn cache_key(&self, text: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.inner.model().as_bytes());
+        hasher.update([0u8]);
+        hasher.update(text.as_bytes());
+        hasher.finalize().into()
+    }
+
+    fn slot(&self, text: &str) -> EmbeddingSlot {
+        let key = self.cache_key(text);
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get_or_insert(key, || Arc::new(OnceCell::new()))
+            .clone()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CachingEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let slot = self.slot(text);
+        slot.get_or_try_init(|| self.inner.embed(text))
+            .await
+            .cloned()
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let slots: Vec<EmbeddingSlot> = texts.iter().map(|text| self.slot(text)).collect();
+        let misses: Vec<usize> = (0..texts.len())
+            .filter(|&i| !slots[i].initialized())
+            .collect();
+        if !misses.is_empty() {
+            let miss_texts: Vec<&str> = misses.iter().map(|&i| texts[i]).collect();
+            let vectors = self.inner.embed_batch(&miss_texts).await?;
+            for (&i, vector) in misses.iter().zip(vectors) {
+                // A concurrent caller may have filled the slot first; keep theirs.
+                let _ = slots[i].set(vector);
+            }
+        }
+        slots
+            .iter()
+            .map(|slot| {
+                slot.get()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("embedding batch returned too few vectors"))
+            })
+            .collect()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+}
+
+/// Wrap `provider` in a [`CachingEmbeddingProvider`] unless `capacity` is 0.
+pub fn with_cache(
+    provider: Arc<dyn EmbeddingProvider>,
+    capacity: usize,
+) -> Arc<dyn EmbeddingProvider> {
+    match NonZeroUsize::new(capacity) {
+        Some(capacity) => Arc::new(CachingEmbeddingProvider::new(provider, capacity)),
+        None => provider,
+    }
+}
+
 pub fn create_provider(config: &Config) -> Option<Arc<dyn EmbeddingProvider>> {
     #[cfg(not(feature = "sqlite-vec"))]
     {
@@ -166,14 +575,16 @@ pub fn create_provider(config: &Config) -> Option<Arc<dyn EmbeddingProvider>> {
             .unwrap_or_else(|| match provider.as_str() {
                 "openai" => "text-embedding-3-small".to_string(),
                 "ollama" => "nomic-embed-text".to_string(),
+                #[cfg(feature = "fastembed")]
+                "local" | "fastembed" => FASTEMBED_DEFAULT_MODEL.to_string(),
                 _ => "text-embedding-3-small".to_string(),
             });
         let dim = config
             .embedding_dim
             .unwrap_or_else(|| infer_default_dim(&provider, &model));
-        let client = reqwest::Client::new();
+        let client = crate::http_client::build_http_client(config);
 
-        match provider.as_str() {
+        let provider: Arc<dyn EmbeddingProvider> = match provider.as_str() {
             "openai" => {
                 let api_key = config.embedding_api_key.clone().unwrap_or_default();
                 if api_key.trim().is_empty() {
@@ -183,28 +594,59 @@ pub fn create_provider(config: &Config) -> Option<Arc<dyn EmbeddingProvider>> {
                     .embedding_base_url
                     .clone()
                     .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
-                Some(Arc::new(OpenAIEmbeddingProvider {
+                Arc::new(OpenAIEmbeddingProvider {
                     client,
                     base_url,
                     api_key,
                     model,
                     dim,
-                }))
+                    max_retries: config.embedding_max_retries,
+                    normalize: config.embedding_normalize,
+                    max_chars: config.embedding_max_chars,
+                })
             }
             "ollama" => {
                 let base_url = config
                     .embedding_base_url
                     .clone()
                     .unwrap_or_else(|| "http://127.0.0.1:11434".to_string());
-                Some(Arc::new(OllamaEmbeddingProvider {
+                Arc::new(OllamaEmbeddingProvider {
                     client,
                     base_url,
                     model,
                     dim,
-                }))
+                    max_retries: config.embedding_max_retries,
+                    normalize: config.embedding_normalize,
+                    max_chars: config.embedding_max_chars,
+                })
             }
-            _ => None,
-        }
+            #[cfg(feature = "fastembed")]
+            "local" | "fastembed" => {
+                let cache_dir = config.data_root_dir().join("models").join("fastembed");
+                match FastEmbedProvider::new(
+                    &model,
+                    config.embedding_dim,
+                    cache_dir,
+                    config.embedding_normalize,
+                    config.embedding_max_chars,
+                ) {
+                    Some(provider) => Arc::new(provider),
+                    None => {
+                        tracing::warn!("Unknown fastembed model '{model}'; embeddings disabled");
+                        return None;
+                    }
+                }
+            }
+            #[cfg(not(feature = "fastembed"))]
+            "local" | "fastembed" => {
+                tracing::warn!(
+                    "embedding_provider '{provider}' requires building with the `fastembed` feature; embeddings disabled"
+                );
+                return None;
+            }
+            _ => return None,
+        };
+        Some(with_cache(provider, config.embedding_cache_size))
     }
 }
 
@@ -219,6 +661,172 @@ mod tests {
         cfg
     }
 
+    struct EchoLenProvider;
+
+    #[async_trait]
+    impl EmbeddingProvider for EchoLenProvider {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(vec![text.len() as f32])
+        }
+        fn model(&self) -> &str {
+            "echo"
+        }
+        fn dimension(&self) -> usize {
+            1
+        }
+    }
+
+    struct CountingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingProvider {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            Ok(vec![text.len() as f32])
+        }
+        fn model(&self) -> &str {
+            "counting"
+        }
+        fn dimension(&self) -> usize {
+            1
+        }
+    }
+
+    fn counting() -> Arc<CountingProvider> {
+        Arc::new(CountingProvider {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_caching_provider_hits_misses_and_evicts() {
+        let inner = counting();
+        let cached = with_cache(inner.clone(), 2);
+        let calls = || inner.calls.load(std::sync::atomic::Ordering::SeqCst);
+
+        assert_eq!(cached.embed("aa").await.unwrap(), vec![2.0]);
+        assert_eq!(cached.embed("aa").await.unwrap(), vec![2.0]);
+        assert_eq!(calls(), 1);
+
+        cached.embed("bbb").await.unwrap();
+        cached.embed("aa").await.unwrap(); // refresh "aa"; "bbb" is now LRU
+        cached.embed("c").await.unwrap(); // evicts "bbb"
+        assert_eq!(calls(), 3);
+        cached.embed("aa").await.unwrap();
+        assert_eq!(calls(), 3);
+        cached.embed("bbb").await.unwrap();
+        assert_eq!(calls(), 4);
+
+        let cached = with_cache(inner.clone(), 4);
+        cached.embed("aa").await.unwrap();
+        assert_eq!(calls(), 5);
+        let batch = cached.embed_batch(&["aa", "dddd", "bbb"]).await.unwrap();
+        assert_eq!(batch, vec![vec![2.0], vec![4.0], vec![3.0]]);
+        assert_eq!(calls(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_caching_provider_concurrent_miss_calls_once() {
+        let inner = counting();
+        let cached = with_cache(inner.clone(), 8);
+        let results =
+            futures_util::future::join_all((0..8).map(|_| cached.embed("same text"))).await;
+        assert!(results.iter().all(|r| r.as_ref().unwrap() == &vec![9.0]));
+        assert_eq!(inner.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_with_cache_zero_disables() {
+        let inner: Arc<dyn EmbeddingProvider> = counting();
+        let wrapped = with_cache(inner.clone(), 0);
+        assert!(Arc::ptr_eq(&inner, &wrapped));
+    }
+
+    #[test]
+    fn test_should_retry_embedding_by_status() {
+        use reqwest::StatusCode;
+        assert!(should_retry_embedding(None));
+        assert!(should_retry_embedding(Some(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(should_retry_embedding(Some(
+            StatusCode::INTERNAL_SERVER_ERROR
+        )));
+        assert!(should_retry_embedding(Some(StatusCode::BAD_GATEWAY)));
+        assert!(should_retry_embedding(Some(
+            StatusCode::SERVICE_UNAVAILABLE
+        )));
+        assert!(!should_retry_embedding(Some(StatusCode::BAD_REQUEST)));
+        assert!(!should_retry_embedding(Some(StatusCode::UNAUTHORIZED)));
+        assert!(!should_retry_embedding(Some(StatusCode::NOT_FOUND)));
+    }
+
+    #[test]
+    fn test_embedding_retry_delay_backoff_and_retry_after() {
+        assert_eq!(embedding_retry_delay(0, None), Duration::from_millis(500));
+        assert_eq!(embedding_retry_delay(2, None), Duration::from_secs(2));
+        assert_eq!(embedding_retry_delay(20, None), EMBEDDING_RETRY_MAX);
+        assert_eq!(
+            embedding_retry_delay(0, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            embedding_retry_delay(0, Some(Duration::from_secs(600))),
+            EMBEDDING_RETRY_MAX
+        );
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(3)));
+    }
+
+    #[tokio::test]
+    async fn test_default_embed_batch_preserves_order() {
+        let out = EchoLenProvider
+            .embed_batch(&["a", "abc", "ab"])
+            .await
+            .unwrap();
+        assert_eq!(out, vec![vec![1.0], vec![3.0], vec![2.0]]);
+    }
+
+    #[test]
+    fn test_openai_batch_request_serializes_all_inputs() {
+        let texts = ["first", "second", "third"];
+        let body = serde_json::to_value(OpenAIEmbeddingRequest {
+            model: "text-embedding-3-small",
+            input: OpenAIEmbeddingInput::Many(&texts),
+        })
+        .unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "model": "text-embedding-3-small",
+                "input": ["first", "second", "third"]
+            })
+        );
+
+        let single = serde_json::to_value(OpenAIEmbeddingRequest {
+            model: "m",
+            input: OpenAIEmbeddingInput::One("only"),
+        })
+        .unwrap();
+        assert_eq!(single["input"], "only");
+    }
+
+    #[test]
+    fn test_openai_batch_response_is_reordered_by_index() {
+        let response: OpenAIEmbeddingResponse = serde_json::from_value(serde_json::json!({
+            "data": [
+                {"index": 1, "embedding": [1.0]},
+                {"index": 0, "embedding": [0.0]}
+            ]
+        }))
+        .unwrap();
+        let ordered = order_openai_embeddings(response.data, 2).unwrap();
+        assert_eq!(ordered, vec![vec![0.0], vec![1.0]]);
+    }
+
     #[test]
     fn test_create_provider_without_config_returns_none() {
         let cfg = base_config();
@@ -241,4 +849,58 @@ mod tests {
             Some("text-embedding-3-small")
         );
     }
+
+    #[cfg(all(feature = "sqlite-vec", not(feature = "fastembed")))]
+    #[test]
+    fn test_create_provider_local_without_feature_returns_none() {
+        let mut cfg = base_config();
+        cfg.embedding_provider = Some("local".into());
+        assert!(create_provider(&cfg).is_none());
+    }
+
+    #[cfg(feature = "fastembed")]
+    #[tokio::test]
+    async fn test_fastembed_provider_embeds_with_model_dimension() {
+        let dir = std::env::temp_dir().join("microclaw_fastembed_test");
+        let provider = FastEmbedProvider::new(FASTEMBED_DEFAULT_MODEL, None, dir, true, 0).unwrap();
+        assert_eq!(provider.dimension(), 384);
+        let vector = provider.embed("hello from microclaw").await.unwrap();
+        assert_eq!(vector.len(), provider.dimension());
+    }
+
+    #[test]
+    fn test_l2_normalize_scales_to_unit_length() {
+        let mut v = vec![3.0, 4.0];
+        l2_normalize(&mut v);
+        assert!((v[0] - 0.6).abs() < 1e-6);
+        assert!((v[1] - 0.8).abs() < 1e-6);
+        let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_l2_normalize_leaves_zero_vector_unchanged() {
+        let mut v = vec![0.0; 4];
+        l2_normalize(&mut v);
+        assert_eq!(v, vec![0.0; 4]);
+        assert_eq!(finish_embedding(vec![3.0, 4.0], false), vec![3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_truncate_chars_shortens_long_input_on_char_boundary() {
+        let text = "héllo wörld";
+        assert_eq!(truncate_chars(text, 4), "héll");
+        assert_eq!(truncate_chars(text, 100), text);
+        assert_eq!(truncate_chars(text, 0), text);
+    }
+
+    #[test]
+    fn test_truncate_token_estimate_cuts_between_words() {
+        let long = "word ".repeat(100);
+        let cut = truncate_token_estimate(&long, 40);
+        assert_eq!(cut, "word ".repeat(10).trim_end());
+        assert_eq!(truncate_token_estimate("short text", 40), "short text");
+        let giant = "x".repeat(100);
+        assert_eq!(truncate_token_estimate(&giant, 40).len(), 40);
+    }
 }
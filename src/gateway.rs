@@ -266,7 +266,11 @@ fn resolve_config_path(cwd: &Path) -> Option<PathBuf> {
         });
     }
 
-    for candidate in ["microclaw.config.yaml", "microclaw.config.yml"] {
+    for candidate in [
+        "microclaw.config.yaml",
+        "microclaw.config.yml",
+        "microclaw.config.toml",
+    ] {
         let path = cwd.join(candidate);
         if path.exists() {
             return Some(path);
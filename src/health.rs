@@ -0,0 +1,229 @@
+//! Liveness and readiness probes, served on `health_port` when set.
+//!
+//! `/healthz` answers as soon as the process is up. `/readyz` returns 503
+//! until every registered channel adapter reports a live connection and the
+//! database answers a query, and lists every channel's status either way.
+//! Channels report through a global registry so adapters need no handle.
+//! Webhook-only adapters count as connected once started; their ingress is
+//! the web server, which reports its own status once its listener is bound.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use microclaw_storage::db::{call_blocking, Database};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelStatus {
+    /// Adapter task started but has not completed its first connection.
+    Starting,
+    Connected,
+    Disconnected,
+}
+
+/// Connection status per channel adapter, keyed by channel name.
+#[derive(Default)]
+pub struct ChannelHealth {
+    statuses: Mutex<BTreeMap<String, ChannelStatus>>,
+}
+
+impl ChannelHealth {
+    fn with_statuses<R>(&self, f: impl FnOnce(&mut BTreeMap<String, ChannelStatus>) -> R) -> R {
+        let mut guard = self
+            .statuses
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut guard)
+    }
+
+    /// Track `channel` as starting unless it has already reported.
+    pub fn register(&self, channel: &str) {
+        self.with_statuses(|s| {
+            s.entry(channel.to_string())
+                .or_insert(ChannelStatus::Starting);
+        });
+    }
+
+    pub fn set_connected(&self, channel: &str, connected: bool) {
+        let status = if connected {
+            ChannelStatus::Connected
+        } else {
+            ChannelStatus::Disconnected
+        };
+        self.with_statuses(|s| {
+            s.insert(channel.to_string(), status);
+        });
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, ChannelStatus> {
+        self.with_statuses(|s| s.clone())
+    }
+}
+
+fn channel_health() -> &'static Arc<ChannelHealth> {
+    static HEALTH: OnceLock<Arc<ChannelHealth>> = OnceLock::new();
+    HEALTH.get_or_init(|| Arc::new(ChannelHealth::default()))
+}
+
+/// A channel adapter has started; it counts as not yet connected.
+pub fn register_channel(channel: &str) {
+    channel_health().register(channel);
+}
+
+/// A channel adapter gained or lost its connection.
+pub fn report_channel_connected(channel: &str, connected: bool) {
+    channel_health().set_connected(channel, connected);
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub config_loaded: bool,
+    pub db_reachable: bool,
+    pub channels: BTreeMap<String, ChannelStatus>,
+}
+
+/// Evaluate readiness from channel statuses and a live database query.
+pub async fn readiness(channels: &ChannelHealth, db: Arc<Database>) -> Readiness {
+    let db_reachable = call_blocking(db, |db| db.get_meta("health_probe"))
+        .await
+        .is_ok();
+    let channels = channels.snapshot();
+    let all_connected =
+        !channels.is_empty() && channels.values().all(|s| *s == ChannelStatus::Connected);
+    Readiness {
+        // The probe server only starts after config has been loaded.
+        ready: db_reachable && all_connected,
+        config_loaded: true,
+        db_reachable,
+        channels,
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    channels: Arc<ChannelHealth>,
+    db: Arc<Database>,
+}
+
+async fn healthz_handler() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn readyz_handler(State(state): State<HealthState>) -> impl IntoResponse {
+    let report = readiness(&state.channels, state.db).await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+fn build_router(channels: Arc<ChannelHealth>, db: Arc<Database>) -> axum::Router {
+    axum::Router::new()
+        .route("/healthz", axum::routing::get(healthz_handler))
+        .route("/readyz", axum::routing::get(readyz_handler))
+        .with_state(HealthState { channels, db })
+}
+
+/// Serve `GET /healthz` and `GET /readyz` on `host:port` in the background.
+pub fn spawn_health_server(host: String, port: u16, db: Arc<Database>) {
+    tokio::spawn(async move {
+        let addr = format!("{host}:{port}");
+        let listener = match tokio::net::TcpListener::bind(&addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Health endpoint disabled: failed to bind {addr}: {e}");
+                return;
+            }
+        };
+        info!("Health probes available at http://{addr}/healthz and /readyz");
+        let app = build_router(channel_health().clone(), db);
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!("Health endpoint stopped: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_db() -> (Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("mc_health_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        (db, dir)
+    }
+
+    async fn get(app: &axum::Router, path: &str) -> (StatusCode, serde_json::Value) {
+        let req = Request::builder().uri(path).body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readyz_waits_for_every_registered_channel() {
+        let (db, dir) = test_db();
+        let channels = Arc::new(ChannelHealth::default());
+        let app = build_router(channels.clone(), db);
+
+        let (status, body) = get(&app, "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+
+        let (status, body) = get(&app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["db_reachable"], true);
+
+        channels.register("matrix");
+        channels.register("web");
+        let (status, body) = get(&app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["channels"]["matrix"], "starting");
+
+        channels.set_connected("matrix", true);
+        let (status, body) = get(&app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["channels"]["matrix"], "connected");
+        assert_eq!(body["channels"]["web"], "starting");
+
+        channels.set_connected("web", true);
+        let (status, body) = get(&app, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+
+        channels.set_connected("matrix", false);
+        let (status, body) = get(&app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["channels"]["matrix"], "disconnected");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_register_does_not_reset_a_reported_status() {
+        let channels = ChannelHealth::default();
+        channels.set_connected("telegram", true);
+        channels.register("telegram");
+        assert_eq!(
+            channels.snapshot().get("telegram"),
+            Some(&ChannelStatus::Connected)
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Outbound HTTP clients built from the shared proxy/timeout config.
+
+use microclaw_core::http::HttpClientSettings;
+use tracing::warn;
+
+use crate::config::Config;
+
+/// Build a `reqwest::Client` honoring `http_proxy`/`https_proxy`/`no_proxy`.
+/// Invalid proxy settings are logged and a default client is returned.
+pub fn build_http_client(config: &Config) -> reqwest::Client {
+    build_http_client_with_settings(&config.http_client_settings())
+}
+
+/// Like [`build_http_client`], for callers that adjust the settings first
+/// (e.g. a per-service timeout).
+pub fn build_http_client_with_settings(settings: &HttpClientSettings) -> reqwest::Client {
+    match microclaw_core::http::build_http_client(settings) {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("{e}; falling back to a default HTTP client");
+            reqwest::Client::new()
+        }
+    }
+}
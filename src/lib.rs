@@ -1,18 +1,26 @@
 pub mod agent_engine;
+pub mod budget;
 pub mod channels;
 pub mod chat_commands;
 pub mod clawhub;
 pub mod codex_auth;
 pub mod config;
+pub mod config_schema;
 pub mod doctor;
 pub mod embedding;
 pub mod gateway;
+pub mod health;
 pub mod hooks;
+pub mod http_client;
 pub mod llm;
 pub mod mcp;
 pub mod memory_backend;
+#[cfg(feature = "sqlite-vec")]
+pub mod message_index;
+pub mod metrics;
 pub mod otlp;
 pub mod plugins;
+pub mod rate_limit;
 pub(crate) mod run_control;
 pub mod runtime;
 pub mod scheduler;
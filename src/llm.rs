@@ -7,6 +7,7 @@ use tracing::warn;
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 use crate::codex_auth::{
     codex_config_default_openai_base_url, is_openai_codex_provider,
@@ -18,7 +19,7 @@ use crate::config::WorkingDirIsolation;
 use microclaw_core::error::MicroClawError;
 use microclaw_core::llm_types::{
     ContentBlock, ImageSource, Message, MessageContent, MessagesRequest, MessagesResponse,
-    ResponseContentBlock, ToolDefinition, Usage,
+    ResponseContentBlock, ServedBy, ToolDefinition, Usage,
 };
 
 /// Remove orphaned `ToolResult` blocks whose `tool_use_id` does not match any
@@ -204,10 +205,563 @@ pub trait LlmProvider: Send + Sync {
     }
 }
 
+/// Wire protocol an `llm_provider` id is served over. Adding a provider that
+/// speaks one of these is a `PROVIDERS` entry; a new protocol is a new
+/// variant plus its `LlmProvider` impl.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmBackend {
+    Anthropic,
+    OpenAiCompat,
+}
+
+/// One provider in the registry shared by the setup wizard and
+/// [`create_provider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderSpec {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub backend: LlmBackend,
+    /// Endpoint used when `llm_base_url` is unset. Empty means the backend's
+    /// own default.
+    pub default_base_url: &'static str,
+    /// Models the setup wizard offers, the first being the default.
+    pub models: &'static [&'static str],
+}
+
+/// Any OpenAI-compatible endpoint reached through `llm_base_url`; also the
+/// fallback for ids missing from [`PROVIDERS`].
+const CUSTOM_PROVIDER: ProviderSpec = ProviderSpec {
+    id: "custom",
+    label: "Custom (manual config)",
+    backend: LlmBackend::OpenAiCompat,
+    default_base_url: "",
+    models: &["custom-model"],
+};
+
+pub const PROVIDERS: &[ProviderSpec] = &[
+    ProviderSpec {
+        id: "openai",
+        label: "OpenAI",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.openai.com/v1",
+        models: &["gpt-5.2", "gpt-5", "gpt-5-mini"],
+    },
+    ProviderSpec {
+        id: "openai-codex",
+        label: "OpenAI Codex",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "",
+        models: &["gpt-5.3-codex", "gpt-5.2-codex", "gpt-5-codex"],
+    },
+    ProviderSpec {
+        id: "openrouter",
+        label: "OpenRouter",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://openrouter.ai/api/v1",
+        models: &[
+            "openrouter/auto",
+            "openai/gpt-5.2",
+            "anthropic/claude-sonnet-4.5",
+        ],
+    },
+    ProviderSpec {
+        id: "groq",
+        label: "Groq",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.groq.com/openai/v1",
+        models: &["llama-3.3-70b-versatile", "openai/gpt-oss-120b"],
+    },
+    ProviderSpec {
+        id: "anthropic",
+        label: "Anthropic",
+        backend: LlmBackend::Anthropic,
+        default_base_url: "",
+        models: &[
+            "claude-sonnet-4-5-20250929",
+            "claude-opus-4-6-20260205",
+            "claude-haiku-4-5-20250929",
+        ],
+    },
+    ProviderSpec {
+        id: "ollama",
+        label: "Ollama (local)",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "http://127.0.0.1:11434/v1",
+        models: &["llama3.2", "qwen2.5-coder:7b", "mistral"],
+    },
+    ProviderSpec {
+        id: "google",
+        label: "Google DeepMind",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://generativelanguage.googleapis.com/v1beta/openai",
+        models: &[
+            "gemini-2.5-pro",
+            "gemini-2.5-flash",
+            "gemini-2.5-flash-lite",
+        ],
+    },
+    ProviderSpec {
+        id: "alibaba",
+        label: "Alibaba Cloud (Qwen / DashScope)",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://dashscope.aliyuncs.com/compatible-mode/v1",
+        models: &["qwen3-max", "qwen3-plus", "qwen-max-latest"],
+    },
+    ProviderSpec {
+        id: "deepseek",
+        label: "DeepSeek",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.deepseek.com/v1",
+        models: &["deepseek-chat", "deepseek-reasoner", "deepseek-v3"],
+    },
+    ProviderSpec {
+        id: "moonshot",
+        label: "Moonshot AI (Kimi)",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.moonshot.cn/v1",
+        models: &["kimi-k2.5", "kimi-k2", "kimi-latest"],
+    },
+    ProviderSpec {
+        id: "mistral",
+        label: "Mistral AI",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.mistral.ai/v1",
+        models: &[
+            "mistral-large-latest",
+            "mistral-medium-latest",
+            "ministral-8b-latest",
+        ],
+    },
+    ProviderSpec {
+        id: "azure",
+        label: "Microsoft Azure AI",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url:
+            "https://YOUR-RESOURCE.openai.azure.com/openai/deployments/YOUR-DEPLOYMENT",
+        models: &["gpt-5.2", "gpt-5", "gpt-4.1"],
+    },
+    ProviderSpec {
+        id: "bedrock",
+        label: "Amazon AWS Bedrock",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://bedrock-runtime.YOUR-REGION.amazonaws.com/openai/v1",
+        models: &[
+            "anthropic.claude-opus-4-6-v1",
+            "anthropic.claude-sonnet-4-5-v2",
+            "anthropic.claude-haiku-4-5-v1",
+        ],
+    },
+    ProviderSpec {
+        id: "zhipu",
+        label: "Zhipu AI (GLM / Z.AI)",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://open.bigmodel.cn/api/paas/v4",
+        models: &["glm-4.7", "glm-4.7-flash", "glm-4.5-air"],
+    },
+    ProviderSpec {
+        id: "minimax",
+        label: "MiniMax",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.minimax.io/v1",
+        models: &["MiniMax-M2.5", "MiniMax-M2.5-Thinking", "MiniMax-M2.1"],
+    },
+    ProviderSpec {
+        id: "cohere",
+        label: "Cohere",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.cohere.ai/compatibility/v1",
+        models: &[
+            "command-a-03-2025",
+            "command-r-plus-08-2024",
+            "command-r-08-2024",
+        ],
+    },
+    ProviderSpec {
+        id: "tencent",
+        label: "Tencent AI Lab",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.hunyuan.cloud.tencent.com/v1",
+        models: &[
+            "hunyuan-t1-latest",
+            "hunyuan-turbos-latest",
+            "hunyuan-standard-latest",
+        ],
+    },
+    ProviderSpec {
+        id: "xai",
+        label: "xAI",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.x.ai/v1",
+        models: &["grok-4", "grok-4-fast", "grok-3"],
+    },
+    ProviderSpec {
+        id: "huggingface",
+        label: "Hugging Face",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://router.huggingface.co/v1",
+        models: &[
+            "Qwen/Qwen3-Coder-Next",
+            "meta-llama/Llama-3.3-70B-Instruct",
+            "deepseek-ai/DeepSeek-V3",
+        ],
+    },
+    ProviderSpec {
+        id: "together",
+        label: "Together AI",
+        backend: LlmBackend::OpenAiCompat,
+        default_base_url: "https://api.together.xyz/v1",
+        models: &[
+            "deepseek-ai/DeepSeek-V3",
+            "meta-llama/Llama-3.3-70B-Instruct-Turbo",
+            "Qwen/Qwen3-Coder-480B-A35B-Instruct-FP8",
+        ],
+    },
+    CUSTOM_PROVIDER,
+];
+
+/// Registry entry for `provider`, if it is a known id.
+pub fn find_provider(provider: &str) -> Option<&'static ProviderSpec> {
+    let provider = provider.trim();
+    PROVIDERS
+        .iter()
+        .find(|p| p.id.eq_ignore_ascii_case(provider))
+}
+
+/// Look up how to talk to `provider`. Unknown ids are treated like `custom`:
+/// OpenAI-compatible endpoints reached through `llm_base_url`.
+pub fn provider_spec(provider: &str) -> &'static ProviderSpec {
+    find_provider(provider).unwrap_or(&CUSTOM_PROVIDER)
+}
+
 pub fn create_provider(config: &Config) -> Box<dyn LlmProvider> {
-    match config.llm_provider.trim().to_lowercase().as_str() {
-        "anthropic" => Box::new(AnthropicProvider::new(config)),
-        _ => Box::new(OpenAiProvider::new(config)),
+    let primary = create_backend(config);
+    if config.llm_fallbacks.is_empty() {
+        return primary;
+    }
+    let fallbacks = config
+        .llm_fallbacks
+        .iter()
+        .map(|fallback| {
+            let mut fallback_config = config.clone();
+            fallback_config.llm_provider = fallback.provider.clone();
+            fallback_config.model = fallback.model.clone();
+            fallback_config.llm_base_url = fallback.base_url.clone();
+            if let Some(api_key) = &fallback.api_key {
+                fallback_config.api_key = api_key.clone();
+            }
+            FallbackBackend {
+                provider: fallback.provider.clone(),
+                model: fallback.model.clone(),
+                llm: create_backend(&fallback_config),
+            }
+        })
+        .collect();
+    Box::new(FallbackChainProvider { primary, fallbacks })
+}
+
+fn create_backend(config: &Config) -> Box<dyn LlmProvider> {
+    match provider_spec(&config.llm_provider).backend {
+        LlmBackend::Anthropic => Box::new(AnthropicProvider::new(config)),
+        LlmBackend::OpenAiCompat => Box::new(OpenAiProvider::new(config)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Hot reload
+// ---------------------------------------------------------------------------
+
+/// Provider built from the live config and rebuilt whenever it is reloaded,
+/// so LLM settings such as `max_tokens` apply from the next request on.
+pub struct ReloadingProvider {
+    live: Arc<arc_swap::ArcSwap<Config>>,
+    /// The config snapshot `llm` was built from, and the provider itself.
+    current: Mutex<(Arc<Config>, Arc<dyn LlmProvider>)>,
+}
+
+impl ReloadingProvider {
+    pub fn new(live: Arc<arc_swap::ArcSwap<Config>>) -> Self {
+        let config = live.load_full();
+        let llm: Arc<dyn LlmProvider> = Arc::from(create_provider(&config));
+        Self {
+            live,
+            current: Mutex::new((config, llm)),
+        }
+    }
+
+    fn provider(&self) -> Arc<dyn LlmProvider> {
+        let config = self.live.load_full();
+        let mut current = self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !Arc::ptr_eq(&current.0, &config) {
+            current.1 = Arc::from(create_provider(&config));
+            current.0 = config;
+        }
+        current.1.clone()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReloadingProvider {
+    async fn send_message(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.provider().send_message(system, messages, tools).await
+    }
+
+    async fn send_message_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.provider()
+            .send_message_with_model(system, messages, tools, model_override)
+            .await
+    }
+
+    async fn send_message_stream(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.provider()
+            .send_message_stream(system, messages, tools, text_tx)
+            .await
+    }
+
+    async fn send_message_stream_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.provider()
+            .send_message_stream_with_model(system, messages, tools, text_tx, model_override)
+            .await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fallback chain
+// ---------------------------------------------------------------------------
+
+/// Whether `err` points at the provider rather than the request, so another
+/// provider may succeed: 5xx, overload, rate limiting, timeouts and
+/// connection failures. Rejected requests (other 4xx) never qualify.
+pub fn is_failover_error(err: &MicroClawError) -> bool {
+    match err {
+        MicroClawError::RateLimited => true,
+        MicroClawError::Http(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status()
+                    .is_some_and(|s| s.is_server_error() || s.as_u16() == 429)
+        }
+        MicroClawError::LlmApi(message) => {
+            let lower = message.to_ascii_lowercase();
+            if let Some(code) = lower
+                .strip_prefix("http ")
+                .and_then(|rest| rest.get(..3))
+                .and_then(|code| code.parse::<u16>().ok())
+            {
+                return code >= 500 || code == 429;
+            }
+            // Anthropic reports "<error_type>: <message>".
+            lower.starts_with("overloaded_error")
+                || lower.starts_with("api_error")
+                || lower.starts_with("rate_limit_error")
+                || lower.contains("timed out")
+        }
+        _ => false,
+    }
+}
+
+struct FallbackBackend {
+    provider: String,
+    model: String,
+    llm: Box<dyn LlmProvider>,
+}
+
+/// Sends to the primary provider and, on a failover error, to each of
+/// `llm_fallbacks` in turn. Responses from a fallback carry `served_by`.
+struct FallbackChainProvider {
+    primary: Box<dyn LlmProvider>,
+    fallbacks: Vec<FallbackBackend>,
+}
+
+impl FallbackChainProvider {
+    fn next_fallback(&self, idx: usize, err: &MicroClawError) -> Option<&FallbackBackend> {
+        let fallback = self.fallbacks.get(idx)?;
+        warn!(
+            "LLM request failed ({err}); failing over to {}/{}",
+            fallback.provider, fallback.model
+        );
+        Some(fallback)
+    }
+
+    /// Run one streaming attempt, relaying its deltas to `text_tx` as they
+    /// arrive. Also reports whether any delta reached the caller: once part of
+    /// a reply has been shown, retrying on another backend would append a
+    /// second, unrelated reply to it.
+    async fn relay_stream_attempt(
+        llm: &dyn LlmProvider,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> (Result<MessagesResponse, MicroClawError>, bool) {
+        let Some(text_tx) = text_tx else {
+            let result = llm
+                .send_message_stream_with_model(system, messages, tools, None, model_override)
+                .await;
+            return (result, false);
+        };
+        let (relay_tx, mut relay_rx) = tokio::sync::mpsc::unbounded_channel();
+        let attempt = llm.send_message_stream_with_model(
+            system,
+            messages,
+            tools,
+            Some(&relay_tx),
+            model_override,
+        );
+        tokio::pin!(attempt);
+        let mut streamed = false;
+        let result = loop {
+            tokio::select! {
+                biased;
+                Some(delta) = relay_rx.recv() => {
+                    streamed = true;
+                    let _ = text_tx.send(delta);
+                }
+                result = &mut attempt => break result,
+            }
+        };
+        while let Ok(delta) = relay_rx.try_recv() {
+            streamed = true;
+            let _ = text_tx.send(delta);
+        }
+        (result, streamed)
+    }
+
+    fn served_by(fallback: &FallbackBackend, mut response: MessagesResponse) -> MessagesResponse {
+        response.served_by = Some(ServedBy {
+            provider: fallback.provider.clone(),
+            model: fallback.model.clone(),
+        });
+        response
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FallbackChainProvider {
+    async fn send_message(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_message_with_model(system, messages, tools, None)
+            .await
+    }
+
+    async fn send_message_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let mut err = match self
+            .primary
+            .send_message_with_model(system, messages.clone(), tools.clone(), model_override)
+            .await
+        {
+            Err(e) if is_failover_error(&e) => e,
+            result => return result,
+        };
+        let mut idx = 0;
+        while let Some(fallback) = self.next_fallback(idx, &err) {
+            idx += 1;
+            err = match fallback
+                .llm
+                .send_message_with_model(
+                    system,
+                    messages.clone(),
+                    tools.clone(),
+                    Some(&fallback.model),
+                )
+                .await
+            {
+                Ok(response) => return Ok(Self::served_by(fallback, response)),
+                Err(e) if is_failover_error(&e) => e,
+                Err(e) => return Err(e),
+            };
+        }
+        Err(err)
+    }
+
+    async fn send_message_stream(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_message_stream_with_model(system, messages, tools, text_tx, None)
+            .await
+    }
+
+    async fn send_message_stream_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let (result, streamed) = Self::relay_stream_attempt(
+            self.primary.as_ref(),
+            system,
+            messages.clone(),
+            tools.clone(),
+            text_tx,
+            model_override,
+        )
+        .await;
+        let mut err = match result {
+            Err(e) if is_failover_error(&e) && !streamed => e,
+            result => return result,
+        };
+        let mut idx = 0;
+        while let Some(fallback) = self.next_fallback(idx, &err) {
+            idx += 1;
+            let (result, streamed) = Self::relay_stream_attempt(
+                fallback.llm.as_ref(),
+                system,
+                messages.clone(),
+                tools.clone(),
+                text_tx,
+                Some(&fallback.model),
+            )
+            .await;
+            err = match result {
+                Ok(response) => return Ok(Self::served_by(fallback, response)),
+                Err(e) if is_failover_error(&e) && !streamed => e,
+                Err(e) => return Err(e),
+            };
+        }
+        Err(err)
     }
 }
 
@@ -601,6 +1155,7 @@ fn build_stream_response(
         content,
         stop_reason: normalize_stop_reason(stop_reason),
         usage,
+        served_by: None,
     }
 }
 
@@ -765,10 +1320,12 @@ fn resolve_openai_compat_base(provider: &str, configured_base: &str) -> String {
         return "https://chatgpt.com/backend-api/codex".to_string();
     }
 
-    if trimmed.is_empty() {
-        "https://api.openai.com/v1".to_string()
-    } else {
-        trimmed
+    if !trimmed.is_empty() {
+        return trimmed;
+    }
+    match provider_spec(provider).default_base_url {
+        "" => "https://api.openai.com/v1".to_string(),
+        base => base.to_string(),
     }
 }
 
@@ -1114,7 +1671,10 @@ impl LlmProvider for OpenAiProvider {
                 continue;
             }
             if let Ok(err) = serde_json::from_str::<OaiErrorResponse>(&text) {
-                return Err(MicroClawError::LlmApi(err.error.message));
+                return Err(MicroClawError::LlmApi(format!(
+                    "HTTP {status}: {}",
+                    err.error.message
+                )));
             }
             return Err(MicroClawError::LlmApi(format!("HTTP {status}: {text}")));
         }
@@ -1222,7 +1782,10 @@ impl LlmProvider for OpenAiProvider {
                 continue;
             }
             if let Ok(err) = serde_json::from_str::<OaiErrorResponse>(&text) {
-                return Err(MicroClawError::LlmApi(err.error.message));
+                return Err(MicroClawError::LlmApi(format!(
+                    "HTTP {status}: {}",
+                    err.error.message
+                )));
             }
             return Err(MicroClawError::LlmApi(format!("HTTP {status}: {text}")));
         };
@@ -1296,6 +1859,7 @@ impl LlmProvider for OpenAiProvider {
             content,
             stop_reason: normalize_stop_reason(stop_reason),
             usage,
+            served_by: None,
         })
     }
 }
@@ -1383,7 +1947,10 @@ impl OpenAiProvider {
 
             let text = response.text().await.unwrap_or_default();
             if let Ok(err) = serde_json::from_str::<OaiErrorResponse>(&text) {
-                return Err(MicroClawError::LlmApi(err.error.message));
+                return Err(MicroClawError::LlmApi(format!(
+                    "HTTP {status}: {}",
+                    err.error.message
+                )));
             }
             return Err(MicroClawError::LlmApi(format!("HTTP {status}: {text}")));
         }
@@ -1812,6 +2379,7 @@ fn translate_oai_responses_response(resp: OaiResponsesResponse) -> MessagesRespo
             input_tokens: usage.input_tokens,
             output_tokens: usage.output_tokens,
         }),
+        served_by: None,
     }
 }
 
@@ -1825,6 +2393,7 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             };
         }
     };
@@ -1847,8 +2416,7 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
     let has_tool_calls = tool_calls.is_some();
     if let Some(tool_calls) = tool_calls {
         for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or_default();
+            let input = parse_tool_input(&tc.function.arguments);
             content.push(ResponseContentBlock::ToolUse {
                 id: tc.id,
                 name: tc.function.name,
@@ -1886,6 +2454,7 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
         content,
         stop_reason,
         usage,
+        served_by: None,
     }
 }
 
@@ -2869,6 +3438,409 @@ mod tests {
         assert_eq!(base, "https://api.openai.com/v1");
     }
 
+    // -----------------------------------------------------------------------
+    // Fallback chain
+    // -----------------------------------------------------------------------
+
+    type ModelsSeen = std::sync::Arc<std::sync::Mutex<Vec<Option<String>>>>;
+
+    struct ScriptedLlm {
+        reply: fn() -> Result<MessagesResponse, MicroClawError>,
+        models_seen: ModelsSeen,
+    }
+
+    impl ScriptedLlm {
+        fn boxed(reply: fn() -> Result<MessagesResponse, MicroClawError>) -> Box<Self> {
+            Box::new(ScriptedLlm {
+                reply,
+                models_seen: ModelsSeen::default(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedLlm {
+        async fn send_message(
+            &self,
+            system: &str,
+            messages: Vec<Message>,
+            tools: Option<Vec<ToolDefinition>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            self.send_message_with_model(system, messages, tools, None)
+                .await
+        }
+
+        async fn send_message_with_model(
+            &self,
+            _system: &str,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<ToolDefinition>>,
+            model_override: Option<&str>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            self.models_seen
+                .lock()
+                .unwrap()
+                .push(model_override.map(str::to_string));
+            (self.reply)()
+        }
+    }
+
+    fn ok_reply() -> Result<MessagesResponse, MicroClawError> {
+        Ok(MessagesResponse {
+            content: vec![ResponseContentBlock::Text {
+                text: "from fallback".into(),
+            }],
+            stop_reason: Some("end_turn".into()),
+            usage: None,
+            served_by: None,
+        })
+    }
+
+    fn overloaded_reply() -> Result<MessagesResponse, MicroClawError> {
+        Err(MicroClawError::LlmApi(
+            "overloaded_error: Overloaded".into(),
+        ))
+    }
+
+    fn bad_request_reply() -> Result<MessagesResponse, MicroClawError> {
+        Err(MicroClawError::LlmApi(
+            "HTTP 400 Bad Request: messages: field required".into(),
+        ))
+    }
+
+    /// A chain whose single fallback always succeeds, plus the model
+    /// overrides that fallback received.
+    fn chain(
+        primary: fn() -> Result<MessagesResponse, MicroClawError>,
+    ) -> (FallbackChainProvider, ModelsSeen) {
+        let fallback = ScriptedLlm::boxed(ok_reply);
+        let fallback_models = fallback.models_seen.clone();
+        let provider = FallbackChainProvider {
+            primary: ScriptedLlm::boxed(primary),
+            fallbacks: vec![FallbackBackend {
+                provider: "openrouter".into(),
+                model: "openai/gpt-5.2".into(),
+                llm: fallback,
+            }],
+        };
+        (provider, fallback_models)
+    }
+
+    #[test]
+    fn test_is_failover_error_classification() {
+        assert!(is_failover_error(&MicroClawError::LlmApi(
+            "overloaded_error: Overloaded".into()
+        )));
+        assert!(is_failover_error(&MicroClawError::LlmApi(
+            "HTTP 503 Service Unavailable: upstream down".into()
+        )));
+        assert!(is_failover_error(&MicroClawError::LlmApi(
+            "HTTP 529 <unknown status code>: busy".into()
+        )));
+        assert!(is_failover_error(&MicroClawError::RateLimited));
+        assert!(!is_failover_error(&MicroClawError::LlmApi(
+            "HTTP 400 Bad Request: invalid".into()
+        )));
+        assert!(!is_failover_error(&MicroClawError::LlmApi(
+            "invalid_request_error: prompt is too long".into()
+        )));
+        assert!(!is_failover_error(&MicroClawError::LlmApi(
+            "HTTP 401 Unauthorized: bad key".into()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_fails_over_on_overloaded_primary() {
+        let (provider, fallback_models) = chain(overloaded_reply);
+        let response = provider
+            .send_message_with_model("sys", vec![], None, Some("claude-primary"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.served_by,
+            Some(ServedBy {
+                provider: "openrouter".into(),
+                model: "openai/gpt-5.2".into(),
+            })
+        );
+        // The fallback runs its own model, not the primary's override.
+        assert_eq!(
+            *fallback_models.lock().unwrap(),
+            vec![Some("openai/gpt-5.2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_does_not_fail_over_on_bad_request() {
+        let (provider, fallback_models) = chain(bad_request_reply);
+        let err = provider
+            .send_message("sys", vec![], None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 400"));
+        assert!(fallback_models.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_returns_last_error_when_all_fail() {
+        let provider = FallbackChainProvider {
+            primary: ScriptedLlm::boxed(overloaded_reply),
+            fallbacks: vec![FallbackBackend {
+                provider: "groq".into(),
+                model: "llama-3.3-70b-versatile".into(),
+                llm: ScriptedLlm::boxed(|| {
+                    Err(MicroClawError::LlmApi(
+                        "HTTP 502 Bad Gateway: try later".into(),
+                    ))
+                }),
+            }],
+        };
+        let err = provider
+            .send_message("sys", vec![], None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 502"));
+    }
+
+    /// Streams one delta, then fails with an overload.
+    struct FailsMidStreamLlm;
+
+    #[async_trait]
+    impl LlmProvider for FailsMidStreamLlm {
+        async fn send_message(
+            &self,
+            _system: &str,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<ToolDefinition>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            overloaded_reply()
+        }
+
+        async fn send_message_stream(
+            &self,
+            _system: &str,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<ToolDefinition>>,
+            text_tx: Option<&UnboundedSender<String>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            if let Some(tx) = text_tx {
+                let _ = tx.send("partial ".into());
+            }
+            overloaded_reply()
+        }
+    }
+
+    fn drain(rx: &mut tokio::sync::mpsc::UnboundedReceiver<String>) -> Vec<String> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_streams_fallback_when_primary_sent_nothing() {
+        let (provider, fallback_models) = chain(overloaded_reply);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let response = provider
+            .send_message_stream("sys", vec![], None, Some(&tx))
+            .await
+            .unwrap();
+        assert!(response.served_by.is_some());
+        assert_eq!(fallback_models.lock().unwrap().len(), 1);
+        assert_eq!(drain(&mut rx), vec!["from fallback".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_does_not_fail_over_after_streaming_started() {
+        let fallback = ScriptedLlm::boxed(ok_reply);
+        let fallback_models = fallback.models_seen.clone();
+        let provider = FallbackChainProvider {
+            primary: Box::new(FailsMidStreamLlm),
+            fallbacks: vec![FallbackBackend {
+                provider: "openrouter".into(),
+                model: "openai/gpt-5.2".into(),
+                llm: fallback,
+            }],
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let err = provider
+            .send_message_stream("sys", vec![], None, Some(&tx))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("overloaded"));
+        assert!(fallback_models.lock().unwrap().is_empty());
+        assert_eq!(drain(&mut rx), vec!["partial ".to_string()]);
+
+        // Without a stream consumer nothing was shown, so failover still applies.
+        let response = provider
+            .send_message_stream("sys", vec![], None, None)
+            .await
+            .unwrap();
+        assert!(response.served_by.is_some());
+    }
+
+    #[test]
+    fn test_provider_spec_selects_backend() {
+        assert_eq!(provider_spec("anthropic").backend, LlmBackend::Anthropic);
+        assert_eq!(provider_spec(" Anthropic ").backend, LlmBackend::Anthropic);
+        for id in [
+            "openai",
+            "groq",
+            "openrouter",
+            "google",
+            "custom",
+            "unheard-of",
+        ] {
+            assert_eq!(provider_spec(id).backend, LlmBackend::OpenAiCompat, "{id}");
+        }
+        assert_eq!(provider_spec("unheard-of").id, "custom");
+    }
+
+    #[tokio::test]
+    async fn test_reloading_provider_applies_new_max_tokens() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (body_tx, body_rx) = mpsc::channel::<String>();
+        let _server = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(2)))
+                    .unwrap();
+                let mut req = Vec::new();
+                let mut buf = [0u8; 8192];
+                loop {
+                    let n = stream.read(&mut buf).unwrap_or(0);
+                    req.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&req).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let len = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= len || n == 0 {
+                            let _ = body_tx.send(body.to_string());
+                            break;
+                        }
+                    }
+                    if n == 0 {
+                        break;
+                    }
+                }
+                let body = r#"{"choices":[{"message":{"role":"assistant","content":"ok"},"finish_reason":"stop"}],"usage":{"prompt_tokens":1,"completion_tokens":1}}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let mut config = Config::test_defaults();
+        config.llm_provider = "custom".into();
+        config.model = "test-model".into();
+        config.llm_base_url = Some(format!("http://{addr}/v1"));
+        config.max_tokens = 256;
+        let live = Arc::new(arc_swap::ArcSwap::from_pointee(config.clone()));
+        let provider = ReloadingProvider::new(live.clone());
+
+        let messages = || {
+            vec![Message {
+                role: "user".into(),
+                content: MessageContent::Text("hi".into()),
+            }]
+        };
+        provider.send_message("", messages(), None).await.unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(&body_rx.recv_timeout(Duration::from_secs(2)).unwrap()).unwrap();
+        assert_eq!(body["max_tokens"], 256);
+
+        config.max_tokens = 1024;
+        live.store(Arc::new(config));
+        provider.send_message("", messages(), None).await.unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(&body_rx.recv_timeout(Duration::from_secs(2)).unwrap()).unwrap();
+        assert_eq!(body["max_tokens"], 1024);
+    }
+
+    #[test]
+    fn test_registry_covers_every_setup_provider() {
+        for id in [
+            "alibaba",
+            "moonshot",
+            "zhipu",
+            "minimax",
+            "cohere",
+            "tencent",
+            "huggingface",
+        ] {
+            let spec = find_provider(id).unwrap_or_else(|| panic!("{id} missing"));
+            assert!(!spec.default_base_url.is_empty(), "{id}");
+            assert_eq!(
+                resolve_openai_compat_base(id, ""),
+                spec.default_base_url,
+                "{id}"
+            );
+        }
+        let mut ids: Vec<&str> = PROVIDERS.iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), PROVIDERS.len(), "duplicate provider ids");
+    }
+
+    #[test]
+    fn test_resolve_openai_compat_base_uses_provider_default() {
+        assert_eq!(
+            resolve_openai_compat_base("groq", ""),
+            "https://api.groq.com/openai/v1"
+        );
+        assert_eq!(
+            resolve_openai_compat_base("OpenRouter", "  "),
+            "https://openrouter.ai/api/v1"
+        );
+        // An explicit llm_base_url always wins; unknown ids fall back to OpenAI.
+        assert_eq!(
+            resolve_openai_compat_base("groq", "https://proxy.local/v1/"),
+            "https://proxy.local/v1"
+        );
+        assert_eq!(
+            resolve_openai_compat_base("custom", ""),
+            "https://api.openai.com/v1"
+        );
+    }
+
+    #[test]
+    fn test_translate_oai_response_tool_call_without_arguments() {
+        // Some compatible backends (Groq, Gemini) send "" for no-arg calls.
+        let oai = OaiResponse {
+            choices: vec![OaiChoice {
+                message: OaiMessage {
+                    content: None,
+                    reasoning_content: None,
+                    tool_calls: Some(vec![OaiToolCall {
+                        id: "call_1".into(),
+                        function: OaiFunction {
+                            name: "list_skills".into(),
+                            arguments: String::new(),
+                        },
+                    }]),
+                },
+                finish_reason: Some("tool_calls".into()),
+            }],
+            usage: None,
+        };
+        let resp = translate_oai_response(oai);
+        match &resp.content[0] {
+            ResponseContentBlock::ToolUse { name, input, .. } => {
+                assert_eq!(name, "list_skills");
+                assert_eq!(input, &json!({}));
+            }
+            _ => panic!("Expected ToolUse"),
+        }
+    }
+
     #[test]
     fn test_resolve_anthropic_messages_url_defaults() {
         let url = resolve_anthropic_messages_url("");
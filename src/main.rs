@@ -1,10 +1,11 @@
 use argon2::password_hash::{rand_core::OsRng, PasswordHashString, SaltString};
 use argon2::{Argon2, PasswordHasher};
-use clap::{Args, CommandFactory, Parser, Subcommand};
-use microclaw::config::Config;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use microclaw::config::{Config, LogFormat};
 use microclaw::error::MicroClawError;
 use microclaw::{
-    builtin_skills, db, doctor, gateway, hooks, logging, mcp, memory, runtime, setup, skills,
+    builtin_skills, config_schema, db, doctor, gateway, hooks, logging, mcp, memory, runtime,
+    setup, skills,
 };
 use std::path::{Path, PathBuf};
 use tracing::info;
@@ -51,7 +52,7 @@ enum MainCommand {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
-    /// Manage ClawHub skills (search/install/list/inspect)
+    /// Manage ClawHub skills (search/install/update/uninstall/verify/list/inspect)
     Skill {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
@@ -63,8 +64,12 @@ enum MainCommand {
     },
     /// Manage Web UI configurations
     Web(WebCommand),
+    /// Inspect and validate the config file
+    Config(ConfigCommand),
     /// Re-embed active memories (requires `sqlite-vec` feature)
     Reembed,
+    /// Print the token usage report (text, json or csv)
+    Usage(UsageCommand),
     /// Show version
     Version,
 }
@@ -82,6 +87,29 @@ struct SetupCommand {
     quiet: bool,
 }
 
+#[derive(Debug, Args)]
+struct UsageCommand {
+    /// Output format; json and csv are meant for dashboards and spreadsheets
+    #[arg(long, value_enum, default_value_t = UsageFormat::Text)]
+    format: UsageFormat,
+    /// Limit the chat section to this chat (required for the text report)
+    #[arg(long)]
+    chat_id: Option<i64>,
+    /// Start of a custom range: RFC3339 or relative like `30d`, `12h`
+    #[arg(long)]
+    since: Option<String>,
+    /// End of a custom range (exclusive): RFC3339 or relative like `1d`
+    #[arg(long)]
+    until: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum UsageFormat {
+    Text,
+    Json,
+    Csv,
+}
+
 #[derive(Debug, Args)]
 struct WebCommand {
     #[command(subcommand)]
@@ -98,6 +126,61 @@ enum WebAction {
     PasswordClear,
 }
 
+#[derive(Debug, Args)]
+struct ConfigCommand {
+    #[command(subcommand)]
+    action: ConfigAction,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigAction {
+    /// Load and validate the config without starting any channel
+    Validate {
+        /// Config file to check (defaults to MICROCLAW_CONFIG or ./microclaw.config.yaml)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Print a JSON Schema for microclaw.config.yaml (for editor autocomplete)
+    Schema {
+        /// Write the schema to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn handle_config_cli(action: ConfigAction) -> anyhow::Result<()> {
+    match action {
+        ConfigAction::Validate { path } => {
+            let loaded = match path.as_deref() {
+                Some(path) => Config::load_from_path(path),
+                None => Config::load(),
+            };
+            match loaded {
+                Ok(config) => {
+                    println!("\x1b[32mvalid\x1b[0m");
+                    println!("{}", config.validation_summary());
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("\x1b[31minvalid\x1b[0m: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        ConfigAction::Schema { output } => {
+            let schema = serde_json::to_string_pretty(&config_schema::config_json_schema())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, format!("{schema}\n"))?;
+                    println!("Wrote config schema to {}", path.display());
+                }
+                None => println!("{schema}"),
+            }
+            Ok(())
+        }
+    }
+}
+
 fn print_version() {
     println!("microclaw {VERSION}");
 }
@@ -302,6 +385,44 @@ fn collect_mcp_config_paths(data_root: &Path) -> Vec<PathBuf> {
     paths
 }
 
+async fn print_usage_report(command: UsageCommand) -> anyhow::Result<()> {
+    use microclaw_storage::usage;
+
+    let config = Config::load()?;
+    let range = usage::UsageRange::parse(
+        command.since.as_deref(),
+        command.until.as_deref(),
+        chrono::Utc::now(),
+    )
+    .map_err(|e| anyhow::anyhow!(e))?;
+    let db = std::sync::Arc::new(db::Database::new(&config.runtime_data_dir())?);
+    let output = match command.format {
+        UsageFormat::Text => {
+            let chat_id = command
+                .chat_id
+                .ok_or_else(|| anyhow::anyhow!("--chat-id is required for the text report"))?;
+            match &range {
+                Some(range) => usage::build_usage_report_for_range(db, chat_id, range).await,
+                None => {
+                    usage::build_usage_report(db, chat_id, config.usage_report_top_senders).await
+                }
+            }
+        }
+        UsageFormat::Json => usage::build_usage_report_json(db, command.chat_id, range.as_ref())
+            .await
+            .and_then(|report| serde_json::to_string_pretty(&report).map_err(|e| e.to_string())),
+        UsageFormat::Csv => {
+            usage::build_usage_report_csv(db, command.chat_id, range.as_ref()).await
+        }
+    }
+    .map_err(|e| anyhow::anyhow!(e))?;
+    print!("{output}");
+    if !output.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
 async fn reembed_memories() -> anyhow::Result<()> {
     let config = Config::load()?;
 
@@ -337,31 +458,36 @@ async fn reembed_memories() -> anyhow::Result<()> {
 
         let mut success = 0usize;
         let mut failed = 0usize;
-        for (i, (id, content)) in memories.iter().enumerate() {
-            match provider.embed(content).await {
-                Ok(embedding) => {
-                    if let Err(e) = db.upsert_memory_vec(*id, &embedding) {
-                        eprintln!("  [{}] DB error: {}", id, e);
-                        failed += 1;
-                    } else {
-                        let _ = db.update_memory_embedding_model(*id, provider.model());
-                        success += 1;
+        let mut done = 0usize;
+        for chunk in memories.chunks(20) {
+            let texts: Vec<&str> = chunk.iter().map(|(_, content)| content.as_str()).collect();
+            match provider.embed_batch(&texts).await {
+                Ok(embeddings) => {
+                    for ((id, _), embedding) in chunk.iter().zip(embeddings) {
+                        if let Err(e) = db.upsert_memory_vec(*id, &embedding) {
+                            eprintln!("  [{}] DB error: {}", id, e);
+                            failed += 1;
+                        } else {
+                            let _ = db.update_memory_embedding_model(*id, provider.model());
+                            success += 1;
+                        }
                     }
                 }
                 Err(e) => {
-                    eprintln!("  [{}] Embed error: {}", id, e);
-                    failed += 1;
+                    for (id, _) in chunk {
+                        eprintln!("  [{}] Embed error: {}", id, e);
+                    }
+                    failed += chunk.len();
                 }
             }
-            if (i + 1) % 20 == 0 {
-                println!(
-                    "  Progress: {}/{} (ok={}, fail={})",
-                    i + 1,
-                    memories.len(),
-                    success,
-                    failed
-                );
-            }
+            done += chunk.len();
+            println!(
+                "  Progress: {}/{} (ok={}, fail={})",
+                done,
+                memories.len(),
+                success,
+                failed
+            );
         }
 
         println!("Done! {} embedded, {} failed", success, failed);
@@ -406,6 +532,10 @@ async fn main() -> anyhow::Result<()> {
             handle_web_cli(web.action)?;
             return Ok(());
         }
+        Some(MainCommand::Config(config)) => {
+            handle_config_cli(config.action)?;
+            return Ok(());
+        }
         Some(MainCommand::Skill { args }) => {
             let config = Config::load()?;
             microclaw::clawhub::cli::handle_skill_cli(&args, &config).await?;
@@ -418,6 +548,9 @@ async fn main() -> anyhow::Result<()> {
         Some(MainCommand::Reembed) => {
             return reembed_memories().await;
         }
+        Some(MainCommand::Usage(usage)) => {
+            return print_usage_report(usage).await;
+        }
         Some(MainCommand::Version) => {
             print_version();
             return Ok(());
@@ -453,12 +586,34 @@ async fn main() -> anyhow::Result<()> {
     let legacy_skills_dir = data_root_dir.join("skills");
     migrate_legacy_runtime_layout(&data_root_dir, Path::new(&runtime_data_dir));
     migrate_legacy_skills_dir(&legacy_skills_dir, Path::new(&skills_data_dir));
-    builtin_skills::ensure_builtin_skills(Path::new(&skills_data_dir))?;
 
+    let json_logs = config.log_format == LogFormat::Json;
     if std::env::var("MICROCLAW_GATEWAY").is_ok() {
-        logging::init_logging(&runtime_data_dir)?;
+        logging::init_logging(&runtime_data_dir, json_logs)?;
     } else {
-        logging::init_console_logging();
+        logging::init_console_logging(json_logs);
+    }
+
+    let seeded = builtin_skills::ensure_builtin_skills(Path::new(&skills_data_dir))?;
+    if !seeded.created.is_empty() {
+        info!(
+            "Installed {} new built-in skills: {}",
+            seeded.created.len(),
+            seeded.created.join(", ")
+        );
+    }
+    if !seeded.updated.is_empty() {
+        info!("Updated built-in skills: {}", seeded.updated.join(", "));
+    }
+    if config.prune_builtin_skills {
+        let lock = microclaw_clawhub::lockfile::read_lockfile(&config.clawhub_lockfile_path())?;
+        let pruned =
+            builtin_skills::prune_retired_builtin_skills(Path::new(&skills_data_dir), |name| {
+                microclaw_clawhub::lockfile::is_clawhub_managed(&lock, name)
+            })?;
+        if !pruned.is_empty() {
+            info!("Pruned retired built-in skills: {}", pruned.join(", "));
+        }
     }
 
     let db = db::Database::new(&runtime_data_dir)?;
@@ -468,11 +623,14 @@ async fn main() -> anyhow::Result<()> {
     info!("Memory manager initialized");
 
     let skill_manager = skills::SkillManager::from_skills_dir(&skills_data_dir);
-    let discovered = skill_manager.discover_skills();
+    let discovered = skill_manager.reload();
     info!(
         "Skill manager initialized ({} skills discovered)",
-        discovered.len()
+        discovered.loaded()
     );
+    for (slug, error) in &discovered.errors {
+        tracing::warn!("Skill '{}' could not be loaded: {}", slug, error);
+    }
 
     // Initialize MCP servers (optional, configured via <data_root>/mcp.json and <data_root>/mcp.d/*.json)
     let mcp_config_paths = collect_mcp_config_paths(&data_root_dir);
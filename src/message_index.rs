@@ -0,0 +1,187 @@
+//! Background embedding of stored chat messages for `search_history`.
+//!
+//! The database hands every newly stored message to this indexer, which
+//! batches them, embeds the batch in one provider call and upserts the
+//! vectors keyed by message id. Failures are logged and dropped so they never
+//! hold up the chat flow.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{debug, warn};
+
+use crate::chat_commands::is_slash_command;
+use crate::embedding::EmbeddingProvider;
+use microclaw_storage::db::{call_blocking, Database, StoredMessage};
+
+const INDEX_BATCH_SIZE: usize = 32;
+const INDEX_BATCH_WINDOW: Duration = Duration::from_millis(500);
+
+/// Whether a stored message is worth embedding. Commands such as `/reset`
+/// and empty messages are noise for semantic search.
+pub fn should_index(msg: &StoredMessage) -> bool {
+    let content = msg.content.trim();
+    !content.is_empty() && !is_slash_command(content)
+}
+
+/// Subscribe to the database's stored-message feed and index it until the
+/// database drops the sender.
+pub fn spawn_message_indexer(db: Arc<Database>, embedding: Arc<dyn EmbeddingProvider>) {
+    let rx = db.subscribe_message_index();
+    tokio::spawn(run_message_indexer(db, embedding, rx));
+}
+
+async fn run_message_indexer(
+    db: Arc<Database>,
+    embedding: Arc<dyn EmbeddingProvider>,
+    mut rx: UnboundedReceiver<StoredMessage>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = tokio::time::Instant::now() + INDEX_BATCH_WINDOW;
+        while batch.len() < INDEX_BATCH_SIZE {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(msg)) => batch.push(msg),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        index_batch(&db, embedding.as_ref(), batch).await;
+    }
+}
+
+/// Embed and store vectors for `batch`, skipping messages that should not be
+/// indexed. Returns how many vectors were written.
+pub async fn index_batch(
+    db: &Arc<Database>,
+    embedding: &dyn EmbeddingProvider,
+    batch: Vec<StoredMessage>,
+) -> usize {
+    let batch: Vec<StoredMessage> = batch.into_iter().filter(should_index).collect();
+    if batch.is_empty() {
+        return 0;
+    }
+    let texts: Vec<&str> = batch.iter().map(|m| m.content.as_str()).collect();
+    let vectors = match embedding.embed_batch(&texts).await {
+        Ok(v) if v.len() == batch.len() => v,
+        Ok(v) => {
+            warn!(
+                "Message indexing skipped: provider returned {} vectors for {} messages",
+                v.len(),
+                batch.len()
+            );
+            return 0;
+        }
+        Err(e) => {
+            warn!(
+                "Message indexing failed to embed {} messages: {e}",
+                batch.len()
+            );
+            return 0;
+        }
+    };
+
+    let count = batch.len();
+    let result = call_blocking(db.clone(), move |db| {
+        let mut written = 0;
+        for (msg, vector) in batch.iter().zip(vectors.iter()) {
+            db.upsert_message_vec(&msg.id, msg.chat_id, vector)?;
+            written += 1;
+        }
+        Ok(written)
+    })
+    .await;
+    match result {
+        Ok(written) => {
+            debug!("Indexed {written} messages");
+            written
+        }
+        Err(e) => {
+            warn!("Message indexing failed to store {count} vectors: {e}");
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingEmbedding {
+        batch_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for CountingEmbedding {
+        async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0])
+        }
+        async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            self.batch_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|t| vec![t.len() as f32, 1.0, 0.0])
+                .collect())
+        }
+        fn model(&self) -> &str {
+            "counting-test"
+        }
+        fn dimension(&self) -> usize {
+            3
+        }
+    }
+
+    fn message(id: &str, content: &str) -> StoredMessage {
+        StoredMessage {
+            id: id.into(),
+            chat_id: 100,
+            sender_name: "alice".into(),
+            content: content.into(),
+            is_from_bot: false,
+            timestamp: "2024-01-01T00:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn test_should_index_skips_commands_and_blank_messages() {
+        assert!(should_index(&message("m1", "the deploy is done")));
+        assert!(!should_index(&message("m2", "/reset")));
+        assert!(!should_index(&message("m3", "@bot /status")));
+        assert!(!should_index(&message("m4", "   ")));
+    }
+
+    #[tokio::test]
+    async fn test_stored_messages_are_indexed_in_one_batch() {
+        let dir = std::env::temp_dir().join(format!("mc_msg_index_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        db.prepare_vector_index(3).unwrap();
+        let provider = Arc::new(CountingEmbedding {
+            batch_calls: AtomicUsize::new(0),
+        });
+        let mut rx = db.subscribe_message_index();
+
+        for msg in [
+            message("m1", "deploy finished"),
+            message("m2", "/reset"),
+            message("m3", "lunch at noon"),
+        ] {
+            db.store_message(&msg).unwrap();
+        }
+        let mut queued = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            queued.push(msg);
+        }
+        assert_eq!(queued.len(), 3);
+
+        let written = index_batch(&db, provider.as_ref(), queued).await;
+        assert_eq!(written, 2);
+        assert_eq!(provider.batch_calls.load(Ordering::SeqCst), 1);
+        let hits = db.knn_messages(&[15.0, 1.0, 0.0], 5, Some(100)).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|(m, _)| m.id != "m2"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
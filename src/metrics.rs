@@ -0,0 +1,313 @@
+//! Process-wide runtime metrics.
+//!
+//! One store backs every exporter: the web `/api/metrics` endpoints and their
+//! persisted history, the OTLP exporter, and the Prometheus text endpoint
+//! served on `metrics_port` when set. It is global so channel adapters and the
+//! agent engine can record events without threading a handle through every
+//! call. Rendering produces the Prometheus text exposition format (0.0.4).
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+/// Upper bounds, in seconds, of the agent turn duration histogram buckets.
+const TURN_BUCKETS_SECS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+#[derive(Clone, Debug, Default)]
+pub struct Histogram {
+    /// Cumulative count per entry of `TURN_BUCKETS_SECS`.
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; TURN_BUCKETS_SECS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(TURN_BUCKETS_SECS) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    pub http_requests: i64,
+    pub request_ok: i64,
+    pub request_error: i64,
+    pub request_latency_ms: VecDeque<i64>,
+    pub llm_completions: i64,
+    pub llm_input_tokens: i64,
+    pub llm_output_tokens: i64,
+    pub tool_executions: i64,
+    pub tool_success: i64,
+    pub tool_error: i64,
+    pub tool_policy_blocks: i64,
+    pub mcp_calls: i64,
+    pub mcp_rate_limited_rejections: i64,
+    pub mcp_bulkhead_rejections: i64,
+    pub mcp_circuit_open_rejections: i64,
+    pub messages_received: BTreeMap<String, i64>,
+    pub agent_turns: BTreeMap<String, Histogram>,
+    pub tool_invocations: BTreeMap<(String, &'static str), i64>,
+    pub errors: BTreeMap<&'static str, i64>,
+}
+
+impl Metrics {
+    pub fn messages_received_total(&self) -> i64 {
+        self.messages_received.values().sum()
+    }
+
+    pub fn errors_total(&self) -> i64 {
+        self.errors.values().sum()
+    }
+}
+
+fn store() -> &'static Mutex<Metrics> {
+    static STORE: OnceLock<Mutex<Metrics>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Metrics::default()))
+}
+
+/// Run `f` against the process-wide metrics store.
+pub fn with_metrics<R>(f: impl FnOnce(&mut Metrics) -> R) -> R {
+    let mut guard = store()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut guard)
+}
+
+pub fn snapshot() -> Metrics {
+    with_metrics(|m| m.clone())
+}
+
+/// An inbound user message reached a channel handler.
+pub fn record_message_received(channel: &str) {
+    with_metrics(|m| *m.messages_received.entry(channel.to_string()).or_default() += 1);
+}
+
+/// One agent run finished, successfully or not.
+pub fn observe_agent_turn(channel: &str, elapsed: Duration) {
+    with_metrics(|m| {
+        m.agent_turns
+            .entry(channel.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64())
+    });
+}
+
+/// One tool call finished. Approval and execution-policy rejections count as
+/// policy blocks rather than tool errors.
+pub fn record_tool_invocation(tool: &str, is_error: bool, error_type: Option<&str>) {
+    let status = if is_error { "error" } else { "ok" };
+    with_metrics(|m| {
+        m.tool_executions += 1;
+        if tool.starts_with("mcp") {
+            m.mcp_calls += 1;
+        }
+        if !is_error {
+            m.tool_success += 1;
+        } else if matches!(
+            error_type,
+            Some("approval_required" | "execution_policy_blocked")
+        ) {
+            m.tool_policy_blocks += 1;
+        } else {
+            m.tool_error += 1;
+        }
+        *m.tool_invocations
+            .entry((tool.to_string(), status))
+            .or_default() += 1
+    });
+}
+
+pub fn record_llm_tokens(input_tokens: u32, output_tokens: u32) {
+    with_metrics(|m| {
+        m.llm_input_tokens += i64::from(input_tokens);
+        m.llm_output_tokens += i64::from(output_tokens);
+    });
+}
+
+/// Count a failure; `kind` is a short fixed label such as `llm` or `agent`.
+pub fn record_error(kind: &'static str) {
+    with_metrics(|m| *m.errors.entry(kind).or_default() += 1);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Current metrics in Prometheus text exposition format.
+pub fn render() -> String {
+    let guard = snapshot();
+    let mut out = String::new();
+
+    out.push_str(
+        "# HELP microclaw_messages_received_total Inbound messages received per channel.\n",
+    );
+    out.push_str("# TYPE microclaw_messages_received_total counter\n");
+    for (channel, count) in &guard.messages_received {
+        let _ = writeln!(
+            out,
+            "microclaw_messages_received_total{{channel=\"{}\"}} {count}",
+            escape_label(channel)
+        );
+    }
+
+    out.push_str("# HELP microclaw_agent_turn_duration_seconds Duration of agent runs.\n");
+    out.push_str("# TYPE microclaw_agent_turn_duration_seconds histogram\n");
+    for (channel, histogram) in &guard.agent_turns {
+        let channel = escape_label(channel);
+        for (bound, count) in TURN_BUCKETS_SECS.iter().zip(&histogram.buckets) {
+            let _ = writeln!(
+                out,
+                "microclaw_agent_turn_duration_seconds_bucket{{channel=\"{channel}\",le=\"{bound}\"}} {count}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "microclaw_agent_turn_duration_seconds_bucket{{channel=\"{channel}\",le=\"+Inf\"}} {}",
+            histogram.count
+        );
+        let _ = writeln!(
+            out,
+            "microclaw_agent_turn_duration_seconds_sum{{channel=\"{channel}\"}} {}",
+            histogram.sum
+        );
+        let _ = writeln!(
+            out,
+            "microclaw_agent_turn_duration_seconds_count{{channel=\"{channel}\"}} {}",
+            histogram.count
+        );
+    }
+
+    out.push_str("# HELP microclaw_tool_invocations_total Tool calls by tool name and outcome.\n");
+    out.push_str("# TYPE microclaw_tool_invocations_total counter\n");
+    for ((tool, status), count) in &guard.tool_invocations {
+        let _ = writeln!(
+            out,
+            "microclaw_tool_invocations_total{{tool=\"{}\",status=\"{status}\"}} {count}",
+            escape_label(tool)
+        );
+    }
+
+    out.push_str("# HELP microclaw_llm_tokens_total LLM tokens consumed by direction.\n");
+    out.push_str("# TYPE microclaw_llm_tokens_total counter\n");
+    for (direction, count) in [
+        ("input", guard.llm_input_tokens),
+        ("output", guard.llm_output_tokens),
+    ] {
+        let _ = writeln!(
+            out,
+            "microclaw_llm_tokens_total{{direction=\"{direction}\"}} {count}"
+        );
+    }
+
+    out.push_str("# HELP microclaw_errors_total Errors by kind.\n");
+    out.push_str("# TYPE microclaw_errors_total counter\n");
+    for (kind, count) in &guard.errors {
+        let _ = writeln!(out, "microclaw_errors_total{{kind=\"{kind}\"}} {count}");
+    }
+
+    out
+}
+
+async fn metrics_handler() -> impl axum::response::IntoResponse {
+    (
+        [("content-type", "text/plain; version=0.0.4; charset=utf-8")],
+        render(),
+    )
+}
+
+/// Serve `GET /metrics` on `host:port` in the background.
+pub fn spawn_metrics_server(host: String, port: u16) {
+    tokio::spawn(async move {
+        let addr = format!("{host}:{port}");
+        let listener = match tokio::net::TcpListener::bind(&addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Metrics endpoint disabled: failed to bind {addr}: {e}");
+                return;
+            }
+        };
+        info!("Prometheus metrics available at http://{addr}/metrics");
+        let app = axum::Router::new().route("/metrics", axum::routing::get(metrics_handler));
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!("Metrics endpoint stopped: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_contains_registered_metrics_after_events() {
+        record_message_received("metrics-test");
+        record_message_received("metrics-test");
+        observe_agent_turn("metrics-test", Duration::from_millis(1500));
+        record_tool_invocation("metrics_test_tool", false, None);
+        record_tool_invocation("metrics_test_tool", true, Some("tool_error"));
+        record_llm_tokens(120, 30);
+        record_error("llm");
+
+        let text = render();
+        assert!(text.contains("microclaw_messages_received_total{channel=\"metrics-test\"} 2"));
+        assert!(text.contains(
+            "microclaw_agent_turn_duration_seconds_bucket{channel=\"metrics-test\",le=\"1\"} 0"
+        ));
+        assert!(text.contains(
+            "microclaw_agent_turn_duration_seconds_bucket{channel=\"metrics-test\",le=\"2.5\"} 1"
+        ));
+        assert!(text
+            .contains("microclaw_agent_turn_duration_seconds_count{channel=\"metrics-test\"} 1"));
+        assert!(text.contains(
+            "microclaw_tool_invocations_total{tool=\"metrics_test_tool\",status=\"ok\"} 1"
+        ));
+        assert!(text.contains(
+            "microclaw_tool_invocations_total{tool=\"metrics_test_tool\",status=\"error\"} 1"
+        ));
+        assert!(text.contains("microclaw_llm_tokens_total{direction=\"input\"}"));
+        assert!(text.contains("microclaw_errors_total{kind=\"llm\"}"));
+        for name in [
+            "microclaw_messages_received_total",
+            "microclaw_agent_turn_duration_seconds",
+            "microclaw_tool_invocations_total",
+            "microclaw_llm_tokens_total",
+            "microclaw_errors_total",
+        ] {
+            assert!(text.contains(&format!("# TYPE {name} ")), "missing {name}");
+        }
+    }
+
+    #[test]
+    fn test_tool_invocations_feed_the_shared_totals() {
+        let before = snapshot();
+        record_tool_invocation("mcp_shared_totals_test", false, None);
+        record_tool_invocation("bash", true, Some("approval_required"));
+        record_tool_invocation("bash", true, Some("tool_error"));
+        let after = snapshot();
+        assert!(after.tool_executions >= before.tool_executions + 3);
+        assert!(after.mcp_calls > before.mcp_calls);
+        assert!(after.tool_success > before.tool_success);
+        assert!(after.tool_policy_blocks > before.tool_policy_blocks);
+        assert!(after.tool_error > before.tool_error);
+    }
+
+    #[test]
+    fn test_escape_label_quotes_and_backslashes() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
@@ -25,6 +25,8 @@ pub struct OtlpMetricSnapshot {
     pub mcp_rate_limited_rejections: i64,
     pub mcp_bulkhead_rejections: i64,
     pub mcp_circuit_open_rejections: i64,
+    pub messages_received: i64,
+    pub errors: i64,
     pub active_sessions: i64,
 }
 
@@ -231,6 +233,8 @@ fn build_metrics_payload(
             mcp_rate_limited_rejections: 0,
             mcp_bulkhead_rejections: 0,
             mcp_circuit_open_rejections: 0,
+            messages_received: 0,
+            errors: 0,
             active_sessions: 0,
         }]
     } else {
@@ -331,6 +335,24 @@ fn build_metrics_payload(
                 .collect(),
             start_ts,
         ),
+        sum_metric(
+            "messages_received",
+            "Total inbound messages across channels",
+            points
+                .iter()
+                .map(|p| (p.timestamp_unix_nano, p.messages_received))
+                .collect(),
+            start_ts,
+        ),
+        sum_metric(
+            "errors",
+            "Total agent and LLM errors",
+            points
+                .iter()
+                .map(|p| (p.timestamp_unix_nano, p.errors))
+                .collect(),
+            start_ts,
+        ),
         gauge_metric(
             "active_sessions",
             "Current active sessions",
@@ -424,6 +446,8 @@ mod tests {
                 mcp_rate_limited_rejections: 2,
                 mcp_bulkhead_rejections: 1,
                 mcp_circuit_open_rejections: 0,
+                messages_received: 7,
+                errors: 1,
                 active_sessions: 2,
             }],
         );
@@ -440,6 +464,9 @@ mod tests {
         assert!(metrics
             .iter()
             .any(|m| m.name == "microclaw_mcp_circuit_open_rejections"));
+        assert!(metrics
+            .iter()
+            .any(|m| m.name == "microclaw_messages_received"));
     }
 
     #[test]
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_tools::sandbox::{SandboxExecOptions, SandboxExecResult, SandboxMode, SandboxRouter};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::warn;
@@ -52,7 +53,7 @@ impl PluginExecutionPolicy {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct PluginsConfig {
     #[serde(default = "default_plugin_enabled")]
     pub enabled: bool,
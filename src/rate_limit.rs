@@ -0,0 +1,249 @@
+//! Per-sender message rate limiting. Each `(channel, chat, sender)` gets a
+//! token bucket holding up to `messages_per_minute` messages that refills
+//! continuously; messages beyond it are dropped before the agent runs.
+
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lru::LruCache;
+use tracing::info;
+
+use crate::runtime::AppState;
+
+/// Sent once when a sender first goes over the limit.
+pub const RATE_LIMIT_NOTICE: &str =
+    "You're sending messages too quickly. Please slow down; messages sent in the meantime are ignored.";
+
+/// Senders tracked at once; beyond it the least recently active is forgotten.
+const MAX_TRACKED_SENDERS: usize = 10_000;
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket allowing `per_minute` messages per minute.
+    pub fn per_minute(per_minute: u32, now: Instant) -> Self {
+        let capacity = f64::from(per_minute.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    /// Take one message's worth of tokens if available.
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    Allow,
+    /// Over the limit for the first time since the last allowed message;
+    /// tell the sender to slow down.
+    Notify,
+    /// Over the limit and already told.
+    Drop,
+}
+
+struct SenderBucket {
+    per_minute: u32,
+    bucket: TokenBucket,
+    notified: bool,
+}
+
+type SenderKey = (String, i64, String);
+
+/// Token buckets for recently active senders, shared through `AppState`.
+pub struct SenderRateLimiter {
+    senders: Mutex<LruCache<SenderKey, SenderBucket>>,
+}
+
+impl Default for SenderRateLimiter {
+    fn default() -> Self {
+        Self::with_capacity(MAX_TRACKED_SENDERS)
+    }
+}
+
+impl SenderRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            senders: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+        }
+    }
+
+    /// Count one message from `sender`. A `per_minute` of 0 disables the limit.
+    pub fn check(
+        &self,
+        channel: &str,
+        chat_id: i64,
+        sender: &str,
+        per_minute: u32,
+        now: Instant,
+    ) -> RateDecision {
+        if per_minute == 0 {
+            return RateDecision::Allow;
+        }
+        let Ok(mut senders) = self.senders.lock() else {
+            return RateDecision::Allow;
+        };
+        let key = (channel.to_string(), chat_id, sender.to_string());
+        let entry = senders.get_or_insert_mut(key, || SenderBucket {
+            per_minute,
+            bucket: TokenBucket::per_minute(per_minute, now),
+            notified: false,
+        });
+        if entry.per_minute != per_minute {
+            entry.per_minute = per_minute;
+            entry.bucket = TokenBucket::per_minute(per_minute, now);
+        }
+        if entry.bucket.try_take(now) {
+            entry.notified = false;
+            RateDecision::Allow
+        } else if entry.notified {
+            RateDecision::Drop
+        } else {
+            entry.notified = true;
+            RateDecision::Notify
+        }
+    }
+}
+
+/// Whether a message from `sender` should be processed. Over the limit, the
+/// sender is told once through the channel adapter and the message is dropped.
+pub async fn allow_sender_message(
+    state: &AppState,
+    channel_name: &str,
+    chat_id: i64,
+    sender: &str,
+    external_chat_id: &str,
+) -> bool {
+    let per_minute = state
+        .live_config
+        .load()
+        .messages_per_minute_for_channel(channel_name);
+    match state
+        .rate_limiter
+        .check(channel_name, chat_id, sender, per_minute, Instant::now())
+    {
+        RateDecision::Allow => true,
+        RateDecision::Notify => {
+            info!(
+                "Rate limit: {channel_name} chat_id={chat_id} sender={sender} exceeded {per_minute}/min"
+            );
+            if let Some(adapter) = state.channel_registry.get(channel_name) {
+                let _ = adapter.send_text(external_chat_id, RATE_LIMIT_NOTICE).await;
+            }
+            false
+        }
+        RateDecision::Drop => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_token_bucket_rejects_then_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::per_minute(3, start);
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start));
+
+        // 3/min refills one token every 20 seconds.
+        assert!(!bucket.try_take(start + Duration::from_secs(10)));
+        assert!(bucket.try_take(start + Duration::from_secs(21)));
+        assert!(!bucket.try_take(start + Duration::from_secs(22)));
+
+        // Never refills past capacity.
+        let later = start + Duration::from_secs(3600);
+        assert!(bucket.try_take(later));
+        assert!(bucket.try_take(later));
+        assert!(bucket.try_take(later));
+        assert!(!bucket.try_take(later));
+    }
+
+    #[test]
+    fn test_limiter_notifies_once_per_sender() {
+        let limiter = SenderRateLimiter::new();
+        let now = Instant::now();
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 1, now),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 1, now),
+            RateDecision::Notify
+        );
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 1, now),
+            RateDecision::Drop
+        );
+        assert_eq!(
+            limiter.check("matrix", 1, "@b:x", 1, now),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check("matrix", 2, "@a:x", 1, now),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 0, now),
+            RateDecision::Allow
+        );
+
+        let later = now + Duration::from_secs(61);
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 1, later),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check("matrix", 1, "@a:x", 1, later),
+            RateDecision::Notify
+        );
+    }
+
+    #[test]
+    fn test_limiter_forgets_least_recently_active_sender_at_capacity() {
+        let limiter = SenderRateLimiter::with_capacity(2);
+        let now = Instant::now();
+        assert_eq!(limiter.check("irc", 1, "a", 1, now), RateDecision::Allow);
+        assert_eq!(limiter.check("irc", 1, "b", 1, now), RateDecision::Allow);
+        assert_eq!(limiter.check("irc", 1, "a", 1, now), RateDecision::Notify);
+
+        // "b" is now the least recently active and makes room for "c".
+        assert_eq!(limiter.check("irc", 1, "c", 1, now), RateDecision::Allow);
+        assert_eq!(limiter.check("irc", 1, "a", 1, now), RateDecision::Drop);
+        assert_eq!(limiter.check("irc", 1, "b", 1, now), RateDecision::Allow);
+    }
+}
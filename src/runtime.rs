@@ -2,9 +2,13 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
+use arc_swap::ArcSwap;
 use futures_util::FutureExt;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use tracing::{info, warn};
 
 use crate::channels::dingtalk::{build_dingtalk_runtime_contexts, DingTalkRuntimeContext};
@@ -32,6 +36,7 @@ use crate::hooks::HookManager;
 use crate::llm::LlmProvider;
 use crate::memory::MemoryManager;
 use crate::memory_backend::MemoryBackend;
+use crate::rate_limit::SenderRateLimiter;
 use crate::skills::SkillManager;
 use crate::tools::ToolRegistry;
 use crate::web::WebAdapter;
@@ -39,7 +44,10 @@ use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_storage::db::Database;
 
 pub struct AppState {
+    /// Config as loaded at startup; used for wiring that only happens once.
     pub config: Config,
+    /// Hot-reloaded config for knobs read per request (see `Config::watch`).
+    pub live_config: Arc<ArcSwap<Config>>,
     pub channel_registry: Arc<ChannelRegistry>,
     pub db: Arc<Database>,
     pub memory: MemoryManager,
@@ -50,6 +58,11 @@ pub struct AppState {
     pub embedding: Option<Arc<dyn EmbeddingProvider>>,
     pub memory_backend: Arc<MemoryBackend>,
     pub tools: ToolRegistry,
+    /// Cancelled on SIGINT/SIGTERM; channel loops stop taking new work.
+    pub shutdown: CancellationToken,
+    /// In-flight message handlers, awaited on shutdown.
+    pub tasks: TaskTracker,
+    pub rate_limiter: SenderRateLimiter,
 }
 
 fn prepare_channel_runtimes<T, Build, Register, ModelOverride>(
@@ -118,6 +131,42 @@ where
     }
 }
 
+/// How long shutdown waits for in-flight handlers before exiting anyway.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn wait_for_shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .map_err(|e| anyhow!("Failed to listen for SIGTERM: {e}"))?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result.map_err(|e| anyhow!("Failed to listen for Ctrl-C: {e}"))
+            }
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c()
+            .await
+            .map_err(|e| anyhow!("Failed to listen for Ctrl-C: {e}"))
+    }
+}
+
+/// Signal channel loops to stop and wait up to `timeout` for tracked tasks.
+/// Returns `false` if some were still running when the timeout expired.
+pub async fn drain_tasks(
+    shutdown: &CancellationToken,
+    tasks: &TaskTracker,
+    timeout: Duration,
+) -> bool {
+    shutdown.cancel();
+    tasks.close();
+    tokio::time::timeout(timeout, tasks.wait()).await.is_ok()
+}
+
 pub async fn run(
     config: Config,
     db: Database,
@@ -126,7 +175,6 @@ pub async fn run(
     mcp_manager: crate::mcp::McpManager,
 ) -> anyhow::Result<()> {
     let db = Arc::new(db);
-    let llm = crate::llm::create_provider(&config);
     let embedding = crate::embedding::create_provider(&config);
     #[cfg(feature = "sqlite-vec")]
     {
@@ -137,6 +185,8 @@ pub async fn run(
             .unwrap_or(1536);
         if let Err(e) = db.prepare_vector_index(dim) {
             warn!("Failed to initialize sqlite-vec index: {e}");
+        } else if let Some(provider) = embedding.clone() {
+            crate::message_index::spawn_message_indexer(db.clone(), provider);
         }
     }
 
@@ -215,6 +265,8 @@ pub async fn run(
                 runtime.channel_name.clone(),
                 runtime.homeserver_url.clone(),
                 runtime.access_token.clone(),
+                runtime.markdown,
+                runtime.http_client.clone(),
             )));
         },
         |_| None,
@@ -416,14 +468,37 @@ pub async fn run(
         memory_backend.clone(),
     );
 
+    // Semantic history search needs both the vector index and an embedder.
+    if cfg!(feature = "sqlite-vec") && embedding.is_some() {
+        tools.add_tool(Box::new(
+            crate::tools::search_history::SearchHistoryTool::new(db.clone(), embedding.clone()),
+        ));
+    }
+
     for (server, tool_info) in mcp_manager.all_tools() {
         tools.add_tool(Box::new(crate::tools::mcp::McpTool::new(server, tool_info)));
     }
 
     let hooks = Arc::new(HookManager::from_config(&config).with_db(db.clone()));
 
+    let live_config = Arc::new(ArcSwap::from_pointee(config.clone()));
+    match Config::resolve_config_path() {
+        Ok(Some(path)) => {
+            if let Err(e) = Config::watch(path.clone(), live_config.clone()) {
+                warn!("Config hot-reload disabled: {e}");
+            } else {
+                info!("Watching {} for config changes", path.display());
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Config hot-reload disabled: {e}"),
+    }
+    let llm: Box<dyn LlmProvider> =
+        Box::new(crate::llm::ReloadingProvider::new(live_config.clone()));
+
     let state = Arc::new(AppState {
         config,
+        live_config,
         channel_registry,
         db,
         memory,
@@ -434,8 +509,41 @@ pub async fn run(
         embedding,
         memory_backend,
         tools,
+        shutdown: CancellationToken::new(),
+        tasks: TaskTracker::new(),
+        rate_limiter: SenderRateLimiter::new(),
     });
 
+    if state.config.skills_watch {
+        let skills_dir = std::path::PathBuf::from(state.config.skills_data_dir());
+        let debounce = Duration::from_millis(state.config.skills_watch_debounce_ms);
+        let watch_state = state.clone();
+        match SkillManager::watch(skills_dir.clone(), debounce, move || {
+            let reload = watch_state.skills.reload();
+            info!("Skills changed on disk. {}", reload.summary());
+        }) {
+            Ok(()) => info!("Watching {} for skill changes", skills_dir.display()),
+            Err(e) => warn!("Skill auto-reload disabled: {e}"),
+        }
+    }
+
+    if let Some(port) = state.config.metrics_port {
+        crate::metrics::spawn_metrics_server(state.config.metrics_host.clone(), port);
+    }
+    if let Some(port) = state.config.health_port {
+        crate::health::spawn_health_server(
+            state.config.health_host.clone(),
+            port,
+            state.db.clone(),
+        );
+    }
+
+    // Track every channel before any adapter task runs so `/readyz` cannot
+    // report ready while some of them have not been spawned yet.
+    for name in state.channel_registry.names() {
+        crate::health::register_channel(name);
+    }
+
     crate::scheduler::spawn_scheduler(state.clone());
     crate::scheduler::spawn_reflector(state.clone());
 
@@ -649,9 +757,18 @@ pub async fn run(
 
     if has_active_channels {
         info!("Runtime active; waiting for Ctrl-C");
-        tokio::signal::ctrl_c()
-            .await
-            .map_err(|e| anyhow!("Failed to listen for Ctrl-C: {e}"))?;
+        wait_for_shutdown_signal().await?;
+        info!(
+            "Shutting down; waiting up to {}s for {} in-flight task(s)",
+            SHUTDOWN_DRAIN_TIMEOUT.as_secs(),
+            state.tasks.len()
+        );
+        if !drain_tasks(&state.shutdown, &state.tasks, SHUTDOWN_DRAIN_TIMEOUT).await {
+            warn!(
+                "Shutdown timed out with {} task(s) still running",
+                state.tasks.len()
+            );
+        }
         Ok(())
     } else {
         Err(anyhow!(
@@ -659,3 +776,36 @@ pub async fn run(
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_drain_tasks_waits_for_tracked_tasks() {
+        let shutdown = CancellationToken::new();
+        let tasks = TaskTracker::new();
+        let finished = Arc::new(AtomicUsize::new(0));
+        for delay_ms in [10, 50, 100] {
+            let finished = finished.clone();
+            tasks.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        assert!(drain_tasks(&shutdown, &tasks, Duration::from_secs(5)).await);
+        assert!(shutdown.is_cancelled());
+        assert_eq!(finished.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_drain_tasks_gives_up_after_timeout() {
+        let shutdown = CancellationToken::new();
+        let tasks = TaskTracker::new();
+        tasks.spawn(tokio::time::sleep(Duration::from_secs(60)));
+
+        assert!(!drain_tasks(&shutdown, &tasks, Duration::from_millis(20)).await);
+    }
+}
@@ -301,9 +301,23 @@ async fn backfill_embeddings(state: &Arc<AppState>) {
         Ok(rows) => rows,
         Err(_) => return,
     };
-    for mem in pending {
-        let _ = upsert_memory_embedding(state, mem.id, &mem.content).await;
-    }
+    let Some(provider) = &state.embedding else {
+        return;
+    };
+    let texts: Vec<&str> = pending.iter().map(|m| m.content.as_str()).collect();
+    let Ok(embeddings) = provider.embed_batch(&texts).await else {
+        return;
+    };
+    let model_name = provider.model().to_string();
+    let rows: Vec<(i64, Vec<f32>)> = pending.iter().map(|m| m.id).zip(embeddings).collect();
+    let _ = call_blocking(state.db.clone(), move |db| {
+        for (memory_id, embedding) in rows {
+            db.upsert_memory_vec(memory_id, &embedding)?;
+            db.update_memory_embedding_model(memory_id, &model_name)?;
+        }
+        Ok(())
+    })
+    .await;
 }
 
 pub fn spawn_reflector(state: Arc<AppState>) {
@@ -22,6 +22,7 @@ use crate::codex_auth::{
     resolve_openai_codex_auth,
 };
 use crate::config::{Config, SandboxBackend, SandboxMode};
+use crate::llm::{find_provider, provider_spec, LlmBackend, ProviderSpec, PROVIDERS};
 use microclaw_core::error::MicroClawError;
 use microclaw_core::text::floor_char_boundary;
 
@@ -134,221 +135,24 @@ fn default_working_dir_for_setup() -> String {
         .to_string()
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum ProviderProtocol {
-    Anthropic,
-    OpenAiCompat,
-}
-
-#[derive(Clone, Copy)]
-struct ProviderPreset {
-    id: &'static str,
-    label: &'static str,
-    protocol: ProviderProtocol,
-    default_base_url: &'static str,
-    models: &'static [&'static str],
-}
-
-const PROVIDER_PRESETS: &[ProviderPreset] = &[
-    ProviderPreset {
-        id: "openai",
-        label: "OpenAI",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.openai.com/v1",
-        models: &["gpt-5.2", "gpt-5", "gpt-5-mini"],
-    },
-    ProviderPreset {
-        id: "openai-codex",
-        label: "OpenAI Codex",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "",
-        models: &["gpt-5.3-codex", "gpt-5.2-codex", "gpt-5-codex"],
-    },
-    ProviderPreset {
-        id: "openrouter",
-        label: "OpenRouter",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://openrouter.ai/api/v1",
-        models: &[
-            "openrouter/auto",
-            "openai/gpt-5.2",
-            "anthropic/claude-sonnet-4.5",
-        ],
-    },
-    ProviderPreset {
-        id: "anthropic",
-        label: "Anthropic",
-        protocol: ProviderProtocol::Anthropic,
-        default_base_url: "",
-        models: &[
-            "claude-sonnet-4-5-20250929",
-            "claude-opus-4-6-20260205",
-            "claude-haiku-4-5-20250929",
-        ],
-    },
-    ProviderPreset {
-        id: "ollama",
-        label: "Ollama (local)",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "http://127.0.0.1:11434/v1",
-        models: &["llama3.2", "qwen2.5-coder:7b", "mistral"],
-    },
-    ProviderPreset {
-        id: "google",
-        label: "Google DeepMind",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://generativelanguage.googleapis.com/v1beta/openai",
-        models: &[
-            "gemini-2.5-pro",
-            "gemini-2.5-flash",
-            "gemini-2.5-flash-lite",
-        ],
-    },
-    ProviderPreset {
-        id: "alibaba",
-        label: "Alibaba Cloud (Qwen / DashScope)",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://dashscope.aliyuncs.com/compatible-mode/v1",
-        models: &["qwen3-max", "qwen3-plus", "qwen-max-latest"],
-    },
-    ProviderPreset {
-        id: "deepseek",
-        label: "DeepSeek",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.deepseek.com/v1",
-        models: &["deepseek-chat", "deepseek-reasoner", "deepseek-v3"],
-    },
-    ProviderPreset {
-        id: "moonshot",
-        label: "Moonshot AI (Kimi)",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.moonshot.cn/v1",
-        models: &["kimi-k2.5", "kimi-k2", "kimi-latest"],
-    },
-    ProviderPreset {
-        id: "mistral",
-        label: "Mistral AI",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.mistral.ai/v1",
-        models: &[
-            "mistral-large-latest",
-            "mistral-medium-latest",
-            "ministral-8b-latest",
-        ],
-    },
-    ProviderPreset {
-        id: "azure",
-        label: "Microsoft Azure AI",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url:
-            "https://YOUR-RESOURCE.openai.azure.com/openai/deployments/YOUR-DEPLOYMENT",
-        models: &["gpt-5.2", "gpt-5", "gpt-4.1"],
-    },
-    ProviderPreset {
-        id: "bedrock",
-        label: "Amazon AWS Bedrock",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://bedrock-runtime.YOUR-REGION.amazonaws.com/openai/v1",
-        models: &[
-            "anthropic.claude-opus-4-6-v1",
-            "anthropic.claude-sonnet-4-5-v2",
-            "anthropic.claude-haiku-4-5-v1",
-        ],
-    },
-    ProviderPreset {
-        id: "zhipu",
-        label: "Zhipu AI (GLM / Z.AI)",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://open.bigmodel.cn/api/paas/v4",
-        models: &["glm-4.7", "glm-4.7-flash", "glm-4.5-air"],
-    },
-    ProviderPreset {
-        id: "minimax",
-        label: "MiniMax",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.minimax.io/v1",
-        models: &["MiniMax-M2.5", "MiniMax-M2.5-Thinking", "MiniMax-M2.1"],
-    },
-    ProviderPreset {
-        id: "cohere",
-        label: "Cohere",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.cohere.ai/compatibility/v1",
-        models: &[
-            "command-a-03-2025",
-            "command-r-plus-08-2024",
-            "command-r-08-2024",
-        ],
-    },
-    ProviderPreset {
-        id: "tencent",
-        label: "Tencent AI Lab",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.hunyuan.cloud.tencent.com/v1",
-        models: &[
-            "hunyuan-t1-latest",
-            "hunyuan-turbos-latest",
-            "hunyuan-standard-latest",
-        ],
-    },
-    ProviderPreset {
-        id: "xai",
-        label: "xAI",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.x.ai/v1",
-        models: &["grok-4", "grok-4-fast", "grok-3"],
-    },
-    ProviderPreset {
-        id: "huggingface",
-        label: "Hugging Face",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://router.huggingface.co/v1",
-        models: &[
-            "Qwen/Qwen3-Coder-Next",
-            "meta-llama/Llama-3.3-70B-Instruct",
-            "deepseek-ai/DeepSeek-V3",
-        ],
-    },
-    ProviderPreset {
-        id: "together",
-        label: "Together AI",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "https://api.together.xyz/v1",
-        models: &[
-            "deepseek-ai/DeepSeek-V3",
-            "meta-llama/Llama-3.3-70B-Instruct-Turbo",
-            "Qwen/Qwen3-Coder-480B-A35B-Instruct-FP8",
-        ],
-    },
-    ProviderPreset {
-        id: "custom",
-        label: "Custom (manual config)",
-        protocol: ProviderProtocol::OpenAiCompat,
-        default_base_url: "",
-        models: &["custom-model"],
-    },
-];
-
-fn find_provider_preset(provider: &str) -> Option<&'static ProviderPreset> {
-    PROVIDER_PRESETS
-        .iter()
-        .find(|p| p.id.eq_ignore_ascii_case(provider))
+/// Provider ids offered by the setup wizard.
+pub fn provider_preset_ids() -> Vec<&'static str> {
+    PROVIDERS.iter().map(|p| p.id).collect()
 }
 
-fn provider_protocol(provider: &str) -> ProviderProtocol {
-    find_provider_preset(provider)
-        .map(|p| p.protocol)
-        .unwrap_or(ProviderProtocol::OpenAiCompat)
+/// Models the setup wizard offers for `provider`; empty for custom providers.
+pub fn preset_models_for_provider(provider: &str) -> &'static [&'static str] {
+    find_provider(provider).map(|p| p.models).unwrap_or(&[])
 }
 
 fn default_model_for_provider(provider: &str) -> &'static str {
-    find_provider_preset(provider)
+    find_provider(provider)
         .and_then(|p| p.models.first().copied())
         .unwrap_or("gpt-5.2")
 }
 
 fn provider_display(provider: &str) -> String {
-    if let Some(preset) = find_provider_preset(provider) {
+    if let Some(preset) = find_provider(provider) {
         format!("{} - {}", preset.id, preset.label)
     } else {
         format!("{provider} - custom")
@@ -422,7 +226,7 @@ impl SetupApp {
             .cloned()
             .unwrap_or_else(|| "anthropic".into());
         let default_model = default_model_for_provider(&provider);
-        let default_base_url = find_provider_preset(&provider)
+        let default_base_url = find_provider(&provider)
             .map(|p| p.default_base_url)
             .unwrap_or("");
         let llm_api_key = existing.get("LLM_API_KEY").cloned().unwrap_or_default();
@@ -1229,10 +1033,10 @@ impl SetupApp {
             field.value = provider.to_string();
         }
         if let Some(base) = self.fields.iter_mut().find(|f| f.key == "LLM_BASE_URL") {
-            let next_default = find_provider_preset(provider)
+            let next_default = find_provider(provider)
                 .map(|p| p.default_base_url)
                 .unwrap_or("");
-            let old_default = find_provider_preset(&old_provider)
+            let old_default = find_provider(&old_provider)
                 .map(|p| p.default_base_url)
                 .unwrap_or("");
             if old_base_url.trim().is_empty() || old_base_url == old_default {
@@ -1240,7 +1044,7 @@ impl SetupApp {
             }
         }
         if let Some(model) = self.fields.iter_mut().find(|f| f.key == "LLM_MODEL") {
-            let old_in_old_preset = find_provider_preset(&old_provider)
+            let old_in_old_preset = find_provider(&old_provider)
                 .map(|p| p.models.iter().any(|m| *m == old_model))
                 .unwrap_or(false);
             if old_model.trim().is_empty() || old_in_old_preset {
@@ -1251,25 +1055,25 @@ impl SetupApp {
 
     fn cycle_provider(&mut self, direction: i32) {
         let current = self.field_value("LLM_PROVIDER");
-        let current_idx = PROVIDER_PRESETS
+        let current_idx = PROVIDERS
             .iter()
             .position(|p| p.id.eq_ignore_ascii_case(&current))
-            .unwrap_or(PROVIDER_PRESETS.len() - 1);
+            .unwrap_or(PROVIDERS.len() - 1);
         let next_idx = if direction < 0 {
             if current_idx == 0 {
-                PROVIDER_PRESETS.len() - 1
+                PROVIDERS.len() - 1
             } else {
                 current_idx - 1
             }
         } else {
-            (current_idx + 1) % PROVIDER_PRESETS.len()
+            (current_idx + 1) % PROVIDERS.len()
         };
-        self.set_provider(PROVIDER_PRESETS[next_idx].id);
+        self.set_provider(PROVIDERS[next_idx].id);
     }
 
     fn cycle_model(&mut self, direction: i32) {
         let provider = self.field_value("LLM_PROVIDER");
-        let preset = match find_provider_preset(&provider) {
+        let preset = match find_provider(&provider) {
             Some(p) => p,
             None => return,
         };
@@ -1297,15 +1101,15 @@ impl SetupApp {
     }
 
     fn provider_index(&self, provider: &str) -> usize {
-        PROVIDER_PRESETS
+        PROVIDERS
             .iter()
             .position(|p| p.id.eq_ignore_ascii_case(provider))
-            .unwrap_or(PROVIDER_PRESETS.len().saturating_sub(1))
+            .unwrap_or(PROVIDERS.len().saturating_sub(1))
     }
 
     fn model_options(&self) -> Vec<String> {
         let provider = self.field_value("LLM_PROVIDER");
-        if let Some(preset) = find_provider_preset(&provider) {
+        if let Some(preset) = find_provider(&provider) {
             preset.models.iter().map(|m| (*m).to_string()).collect()
         } else {
             vec![self.field_value("LLM_MODEL")]
@@ -1374,7 +1178,7 @@ impl SetupApp {
         let kind = picker.kind;
         let selected = picker.selected;
         let options_len = match kind {
-            PickerKind::Provider => PROVIDER_PRESETS.len(),
+            PickerKind::Provider => PROVIDERS.len(),
             PickerKind::Model => self.model_picker_options().len(),
             PickerKind::Channels => Self::channel_options().len(),
         };
@@ -1413,7 +1217,7 @@ impl SetupApp {
         };
         match picker.kind {
             PickerKind::Provider => {
-                if let Some(preset) = PROVIDER_PRESETS.get(picker.selected) {
+                if let Some(preset) = PROVIDERS.get(picker.selected) {
                     self.set_provider(preset.id);
                     self.status = format!("Provider set to {}", preset.id);
                 }
@@ -1461,7 +1265,7 @@ impl SetupApp {
             | "DISCORD_MODEL" | "LLM_API_KEY" => String::new(),
             "LLM_PROVIDER" => "anthropic".into(),
             "LLM_MODEL" => default_model_for_provider(&provider).into(),
-            "LLM_BASE_URL" => find_provider_preset(&provider)
+            "LLM_BASE_URL" => find_provider(&provider)
                 .map(|p| p.default_base_url.to_string())
                 .unwrap_or_default(),
             "DATA_DIR" => default_data_dir_for_setup(),
@@ -1648,15 +1452,15 @@ fn perform_online_validation(
     }
 
     // --- LLM validation: send a minimal "hi" message ---
-    let preset = find_provider_preset(provider);
-    let protocol = provider_protocol(provider);
+    let preset = find_provider(provider);
+    let protocol = provider_spec(provider).backend;
     let model = if model.is_empty() {
         default_model_for_provider(provider).to_string()
     } else {
         model.to_string()
     };
 
-    if protocol == ProviderProtocol::Anthropic {
+    if protocol == LlmBackend::Anthropic {
         let mut base = if base_url.is_empty() {
             "https://api.anthropic.com".to_string()
         } else {
@@ -1830,7 +1634,7 @@ fn switch_to_max_completion_tokens(body: &mut serde_json::Value) -> bool {
 fn resolve_openai_compat_validation_base(
     provider: &str,
     base_url: &str,
-    preset: Option<&ProviderPreset>,
+    preset: Option<&ProviderSpec>,
 ) -> String {
     let resolved = if base_url.is_empty() {
         preset
@@ -2332,7 +2136,7 @@ fn draw_ui(frame: &mut ratatui::Frame<'_>, app: &SetupApp) {
         let (title, options): (&str, Vec<String>) = match picker.kind {
             PickerKind::Provider => (
                 "Select LLM Provider",
-                PROVIDER_PRESETS
+                PROVIDERS
                     .iter()
                     .map(|p| format!("{} - {}", p.id, p.label))
                     .collect(),
@@ -2608,7 +2412,7 @@ pub fn enable_sandbox_in_config() -> Result<String, MicroClawError> {
     cfg.sandbox.backend = SandboxBackend::Auto;
     cfg.sandbox.no_network = true;
     cfg.sandbox.require_runtime = false;
-    cfg.save_yaml(&path.to_string_lossy())?;
+    cfg.save_to_path(&path)?;
     Ok(path.to_string_lossy().to_string())
 }
 
@@ -2984,7 +2788,7 @@ sandbox:
 
     #[test]
     fn test_resolve_openai_compat_validation_base_keeps_non_v1_prefix() {
-        let preset = find_provider_preset("zhipu");
+        let preset = find_provider("zhipu");
         let base = resolve_openai_compat_validation_base("zhipu", "", preset);
         assert_eq!(base, "https://open.bigmodel.cn/api/paas/v4");
     }
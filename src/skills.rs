@@ -1,5 +1,11 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::error::MicroClawError;
 
 #[derive(Debug, Clone)]
 pub struct SkillMetadata {
@@ -8,6 +14,8 @@ pub struct SkillMetadata {
     pub dir_path: PathBuf,
     pub platforms: Vec<String>,
     pub deps: Vec<String>,
+    /// Other skills (by slug) this skill builds on
+    pub dependencies: Vec<String>,
     pub source: String,
     pub version: Option<String>,
     pub updated_at: Option<String>,
@@ -31,6 +39,8 @@ struct SkillFrontmatter {
     #[serde(default)]
     deps: Vec<String>,
     #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
     compatibility: SkillCompatibility,
     #[serde(default)]
     source: Option<String>,
@@ -80,8 +90,77 @@ struct SkillCompatibility {
     deps: Vec<String>,
 }
 
+/// One local skill in `skill available --json`
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SkillListEntry {
+    /// Directory name under the skills dir
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    /// `builtin`, `clawhub` or `local`
+    pub source: String,
+    /// Loaded and usable on this host
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+/// What changed between two `SkillManager::reload` calls
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillReload {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Skill directories that could not be loaded, with the reason
+    pub errors: Vec<(String, String)>,
+}
+
+impl SkillReload {
+    /// Number of skills loaded after the reload
+    pub fn loaded(&self) -> usize {
+        self.added.len() + self.unchanged.len()
+    }
+
+    /// One-line reply for `/reload-skills`, e.g.
+    /// `Reloaded 4 skills from disk: +2 -1, 1 error: foo (invalid frontmatter)`
+    pub fn summary(&self) -> String {
+        let mut text = format!("Reloaded {} skills from disk", self.loaded());
+        let mut parts = Vec::new();
+        if !self.added.is_empty() || !self.removed.is_empty() {
+            parts.push(format!("+{} -{}", self.added.len(), self.removed.len()));
+        }
+        if !self.errors.is_empty() {
+            let noun = if self.errors.len() == 1 {
+                "error"
+            } else {
+                "errors"
+            };
+            let details: Vec<String> = self
+                .errors
+                .iter()
+                .map(|(slug, message)| format!("{slug} ({message})"))
+                .collect();
+            parts.push(format!(
+                "{} {}: {}",
+                self.errors.len(),
+                noun,
+                details.join(", ")
+            ));
+        }
+        if parts.is_empty() {
+            text.push('.');
+        } else {
+            text.push_str(": ");
+            text.push_str(&parts.join(", "));
+        }
+        text
+    }
+}
+
 pub struct SkillManager {
     skills_dir: PathBuf,
+    /// Skill names seen by the last `reload`, to report what changed
+    loaded: Mutex<BTreeSet<String>>,
 }
 
 const MAX_SKILLS_CATALOG_ITEMS: usize = 40;
@@ -92,13 +171,17 @@ impl SkillManager {
     pub fn from_skills_dir(skills_dir: &str) -> Self {
         SkillManager {
             skills_dir: PathBuf::from(skills_dir),
+            loaded: Mutex::new(BTreeSet::new()),
         }
     }
 
     #[allow(dead_code)]
     pub fn new(data_dir: &str) -> Self {
         let skills_dir = PathBuf::from(data_dir).join("skills");
-        SkillManager { skills_dir }
+        SkillManager {
+            skills_dir,
+            loaded: Mutex::new(BTreeSet::new()),
+        }
     }
 
     /// Discover all skills that are available on the current platform and satisfy dependency checks.
@@ -115,9 +198,30 @@ impl SkillManager {
         statuses
     }
 
-    /// Reload skills from disk (live reload)
-    pub fn reload(&self) -> Vec<SkillMetadata> {
-        self.discover_skills()
+    /// Reload skills from disk (live reload) and report what changed since the
+    /// previous reload, warning about names that collide once case is ignored.
+    pub fn reload(&self) -> SkillReload {
+        let (statuses, errors) = self.scan_skills();
+        for names in case_collisions(&statuses) {
+            tracing::warn!(
+                "Skills differ only by case: {}; lookups prefer an exact match",
+                names.join(", ")
+            );
+        }
+        let current: BTreeSet<String> = statuses
+            .into_iter()
+            .filter(|s| s.available)
+            .map(|s| s.meta.name)
+            .collect();
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        let reload = SkillReload {
+            added: current.difference(&loaded).cloned().collect(),
+            removed: loaded.difference(&current).cloned().collect(),
+            unchanged: current.intersection(&loaded).cloned().collect(),
+            errors,
+        };
+        *loaded = current;
+        reload
     }
 
     fn discover_skills_internal(&self, include_unavailable: bool) -> Vec<SkillMetadata> {
@@ -128,10 +232,17 @@ impl SkillManager {
     }
 
     fn discover_skill_statuses(&self) -> Vec<SkillAvailability> {
+        self.scan_skills().0
+    }
+
+    /// Parse every skill directory, returning the skills that loaded and the
+    /// `(directory, reason)` of those that did not.
+    fn scan_skills(&self) -> (Vec<SkillAvailability>, Vec<(String, String)>) {
         let mut statuses = Vec::new();
+        let mut errors = Vec::new();
         let entries = match std::fs::read_dir(&self.skills_dir) {
             Ok(e) => e,
-            Err(_) => return statuses,
+            Err(_) => return (statuses, errors),
         };
 
         for entry in entries.flatten() {
@@ -143,26 +254,41 @@ impl SkillManager {
             if !skill_md.exists() {
                 continue;
             }
-            if let Ok(content) = std::fs::read_to_string(&skill_md) {
-                if let Some((meta, _body)) = parse_skill_md(&content, &path) {
-                    match self.skill_is_available(&meta) {
-                        Ok(()) => statuses.push(SkillAvailability {
-                            meta,
-                            available: true,
-                            reason: None,
-                        }),
-                        Err(reason) => statuses.push(SkillAvailability {
-                            meta,
-                            available: false,
-                            reason: Some(reason),
-                        }),
-                    };
+            let slug = entry.file_name().to_string_lossy().to_string();
+            let content = match std::fs::read_to_string(&skill_md) {
+                Ok(content) => content,
+                Err(e) => {
+                    errors.push((slug, format!("unreadable SKILL.md: {e}")));
+                    continue;
                 }
-            }
+            };
+            let Some((meta, _body)) = parse_skill_md(&content, &path) else {
+                let problems = frontmatter_problems(&content);
+                let message = if problems.is_empty() {
+                    "invalid frontmatter".to_string()
+                } else {
+                    problems.join("; ")
+                };
+                errors.push((slug, message));
+                continue;
+            };
+            match self.skill_is_available(&meta) {
+                Ok(()) => statuses.push(SkillAvailability {
+                    meta,
+                    available: true,
+                    reason: None,
+                }),
+                Err(reason) => statuses.push(SkillAvailability {
+                    meta,
+                    available: false,
+                    reason: Some(reason),
+                }),
+            };
         }
 
         statuses.sort_by(|a, b| a.meta.name.cmp(&b.meta.name));
-        statuses
+        errors.sort();
+        (statuses, errors)
     }
 
     /// Load a skill by name if it is available on the current platform.
@@ -171,13 +297,12 @@ impl SkillManager {
     }
 
     /// Load a skill with availability diagnostics.
+    /// Names match case-insensitively; an exact match wins when several
+    /// skills differ only by case.
     pub fn load_skill_checked(&self, name: &str) -> Result<(SkillMetadata, String), String> {
         let all_skills = self.discover_skills_with_status(true);
 
-        for skill in all_skills {
-            if skill.meta.name != name {
-                continue;
-            }
+        if let Some(skill) = find_by_name(all_skills, name) {
             if !skill.available {
                 let reason = skill
                     .reason
@@ -320,12 +445,168 @@ impl SkillManager {
         output
     }
 
+    /// Every skill directory, including unavailable and unloadable ones, as
+    /// structured entries. `builtin` and `clawhub` name the slugs seeded from
+    /// the binary and recorded in the ClawHub lockfile.
+    pub fn list_skills_json(&self, builtin: &[String], clawhub: &[String]) -> Vec<SkillListEntry> {
+        let classify = |slug: &str, declared: &str| {
+            if clawhub.iter().any(|s| s == slug) || declared == "clawhub" {
+                "clawhub"
+            } else if builtin.iter().any(|s| s == slug) {
+                "builtin"
+            } else {
+                "local"
+            }
+            .to_string()
+        };
+        let (statuses, errors) = self.scan_skills();
+        let mut entries: Vec<SkillListEntry> = statuses
+            .into_iter()
+            .map(|status| {
+                let slug = status
+                    .meta
+                    .dir_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| status.meta.name.clone());
+                SkillListEntry {
+                    source: classify(&slug, &status.meta.source),
+                    slug,
+                    name: status.meta.name,
+                    description: status.meta.description,
+                    enabled: status.available,
+                    diagnostics: status.reason.into_iter().collect(),
+                }
+            })
+            .collect();
+        entries.extend(errors.into_iter().map(|(slug, error)| SkillListEntry {
+            source: classify(&slug, ""),
+            name: slug.clone(),
+            slug,
+            description: String::new(),
+            enabled: false,
+            diagnostics: vec![error],
+        }));
+        entries.sort_by(|a, b| a.slug.cmp(&b.slug));
+        entries
+    }
+
+    /// Check a skill directory (or its `SKILL.md`) and describe every problem
+    /// that would keep it from loading or from being listed usefully.
+    pub fn validate_skill(path: &std::path::Path) -> Result<(), Vec<String>> {
+        let skill_md = if path.is_dir() {
+            path.join("SKILL.md")
+        } else {
+            path.to_path_buf()
+        };
+        let content = std::fs::read_to_string(&skill_md)
+            .map_err(|e| vec![format!("cannot read {}: {e}", skill_md.display())])?;
+        let problems = frontmatter_problems(&content);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Watch `skills_dir` recursively and call `on_change` once per burst of
+    /// file events, after `debounce` passes without further changes.
+    pub fn watch<F>(
+        skills_dir: PathBuf,
+        debounce: Duration,
+        on_change: F,
+    ) -> Result<(), MicroClawError>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        use notify::{EventKind, RecursiveMode, Watcher};
+
+        let (event_tx, event_rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(event_tx)
+            .map_err(|e| MicroClawError::Config(format!("Failed to start skills watcher: {e}")))?;
+        watcher
+            .watch(&skills_dir, RecursiveMode::Recursive)
+            .map_err(|e| {
+                MicroClawError::Config(format!("Failed to watch {}: {e}", skills_dir.display()))
+            })?;
+
+        let (change_tx, change_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _watcher = watcher;
+            for event in event_rx {
+                let Ok(event) = event else {
+                    continue;
+                };
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    continue;
+                }
+                if change_tx.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+        std::thread::spawn(move || debounce_events(change_rx, debounce, on_change));
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn skills_dir(&self) -> &PathBuf {
         &self.skills_dir
     }
 }
 
+/// Call `fire` once for each burst of events on `events`: after the first
+/// event, keep absorbing events until `window` passes quietly. Returns when
+/// the sender is dropped, firing for any burst still pending.
+fn debounce_events<F: FnMut()>(events: Receiver<()>, window: Duration, mut fire: F) {
+    while events.recv().is_ok() {
+        loop {
+            match events.recv_timeout(window) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    fire();
+                    return;
+                }
+            }
+        }
+        fire();
+    }
+}
+
+/// Skill with `name`, preferring an exact match over a case-insensitive one
+fn find_by_name(statuses: Vec<SkillAvailability>, name: &str) -> Option<SkillAvailability> {
+    let name = name.trim();
+    let mut folded = None;
+    for skill in statuses {
+        if skill.meta.name == name {
+            return Some(skill);
+        }
+        if folded.is_none() && skill.meta.name.to_lowercase() == name.to_lowercase() {
+            folded = Some(skill);
+        }
+    }
+    folded
+}
+
+/// Groups of skill names that are equal once lowercased
+fn case_collisions(statuses: &[SkillAvailability]) -> Vec<Vec<String>> {
+    let mut by_key: std::collections::BTreeMap<String, Vec<String>> = Default::default();
+    for skill in statuses {
+        by_key
+            .entry(skill.meta.name.to_lowercase())
+            .or_default()
+            .push(skill.meta.name.clone());
+    }
+    by_key
+        .into_values()
+        .filter(|names| names.len() > 1)
+        .collect()
+}
+
 fn current_platform() -> &'static str {
     if cfg!(target_os = "macos") {
         "darwin"
@@ -450,39 +731,144 @@ fn normalize_single_line_frontmatter(content: &str) -> Option<String> {
     Some(format!("---\n{yaml}\n---\n{body}"))
 }
 
-/// Parse a SKILL.md file, extracting frontmatter via YAML and body.
-/// Returns None if the file lacks valid frontmatter with a name field.
-fn parse_skill_md(content: &str, dir_path: &std::path::Path) -> Option<(SkillMetadata, String)> {
-    let trimmed = content.trim_start_matches('\u{feff}');
+/// Why a SKILL.md's frontmatter could not be read. Displays as a message
+/// for skill authors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FrontmatterError {
+    Missing,
+    Unclosed,
+    /// `line` is the line of the file as written, when serde_yaml reports one.
+    Yaml {
+        line: Option<usize>,
+        message: String,
+    },
+}
 
-    // Try normalizing single-line frontmatter if standard format not found
+impl std::fmt::Display for FrontmatterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrontmatterError::Missing => {
+                write!(
+                    f,
+                    "missing frontmatter: SKILL.md must start with a `---` line"
+                )
+            }
+            FrontmatterError::Unclosed => write!(f, "frontmatter is not closed with a `---` line"),
+            FrontmatterError::Yaml {
+                line: Some(line),
+                message,
+            } => write!(f, "unparseable YAML at line {line}: {message}"),
+            FrontmatterError::Yaml {
+                line: None,
+                message,
+            } => write!(f, "unparseable YAML: {message}"),
+        }
+    }
+}
+
+/// A SKILL.md split into its parsed frontmatter and trimmed body.
+struct SkillMd {
+    frontmatter: SkillFrontmatter,
+    body: String,
+    /// False when no `---` line ends the frontmatter. The loader still reads
+    /// the rest of the file as YAML, as it always has; only lint objects.
+    closed: bool,
+}
+
+/// Split a SKILL.md into its parsed frontmatter and trimmed body. Accepts a
+/// `---` block closed by `---` or `...`, and single-line frontmatter.
+fn split_skill_md(content: &str) -> Result<SkillMd, FrontmatterError> {
+    let trimmed = content.trim_start_matches('\u{feff}');
     let normalized;
-    let input = if !trimmed.starts_with("---\n") && !trimmed.starts_with("---\r\n") {
-        normalized = normalize_single_line_frontmatter(trimmed)?;
-        &normalized
+    let (input, single_line) = if trimmed.starts_with("---\n") || trimmed.starts_with("---\r\n") {
+        (trimmed, false)
+    } else if let Some(n) = normalize_single_line_frontmatter(trimmed) {
+        normalized = n;
+        (normalized.as_str(), true)
     } else {
-        trimmed
+        return Err(FrontmatterError::Missing);
     };
 
-    let mut lines = input.lines();
-    let _ = lines.next()?; // opening ---
-
     let mut yaml_block = String::new();
-    let mut consumed = 0usize;
-    for line in lines {
-        consumed += line.len() + 1;
+    let mut body_start = None;
+    let mut offset = 0;
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        if idx == 0 {
+            continue; // opening ---
+        }
+        let line = line.trim_end_matches(['\n', '\r']);
         if line.trim() == "---" || line.trim() == "..." {
+            body_start = Some(offset);
             break;
         }
         yaml_block.push_str(line);
         yaml_block.push('\n');
     }
+    let closed = body_start.is_some();
+    let body_start = body_start.unwrap_or(input.len());
 
-    if yaml_block.trim().is_empty() {
-        return None;
+    let frontmatter = if yaml_block.trim().is_empty() {
+        SkillFrontmatter::default()
+    } else {
+        serde_yaml::from_str(&yaml_block).map_err(|e| {
+            if !closed {
+                // The YAML error is most likely the body running into it.
+                return FrontmatterError::Unclosed;
+            }
+            // serde_yaml appends its own block-relative position; report the
+            // file line instead (+1 for the opening `---`). Single-line
+            // frontmatter sits entirely on line 1.
+            let message = e.to_string();
+            let message = message.split(" at line ").next().unwrap_or_default();
+            FrontmatterError::Yaml {
+                line: e
+                    .location()
+                    .map(|loc| if single_line { 1 } else { loc.line() + 1 }),
+                message: message.to_string(),
+            }
+        })?
+    };
+    let body = input[body_start..].trim().to_string();
+    Ok(SkillMd {
+        frontmatter,
+        body,
+        closed,
+    })
+}
+
+/// Problems with a SKILL.md's frontmatter, worded for skill authors.
+fn frontmatter_problems(content: &str) -> Vec<String> {
+    let fm = match split_skill_md(content) {
+        Ok(md) if !md.closed => return vec![FrontmatterError::Unclosed.to_string()],
+        Ok(md) => md.frontmatter,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut problems = Vec::new();
+    if fm
+        .name
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default()
+        .is_empty()
+    {
+        problems.push("missing required field: name".to_string());
+    }
+    if fm.description.trim().is_empty() {
+        problems.push("missing required field: description".to_string());
     }
+    problems
+}
 
-    let fm: SkillFrontmatter = serde_yaml::from_str(&yaml_block).ok()?;
+/// Parse a SKILL.md file, extracting frontmatter via YAML and body.
+/// Returns None if the file lacks valid frontmatter with a name field.
+fn parse_skill_md(content: &str, dir_path: &std::path::Path) -> Option<(SkillMetadata, String)> {
+    let SkillMd {
+        frontmatter: fm,
+        body,
+        ..
+    } = split_skill_md(content).ok()?;
     let name = fm.name?.trim().to_string();
     if name.is_empty() {
         return None;
@@ -508,20 +894,14 @@ fn parse_skill_md(content: &str, dir_path: &std::path::Path) -> Option<(SkillMet
     deps.sort();
     deps.dedup();
 
-    let header_len = if let Some(idx) = input.find("\n---\n") {
-        idx + 5
-    } else if let Some(idx) = input.find("\n...\n") {
-        idx + 5
-    } else {
-        // fallback to consumed length from line-by-line scan
-        4 + consumed
-    };
-
-    let body = input
-        .get(header_len..)
-        .unwrap_or_default()
-        .trim()
-        .to_string();
+    let mut dependencies: Vec<String> = fm
+        .dependencies
+        .into_iter()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .collect();
+    dependencies.sort();
+    dependencies.dedup();
 
     Some((
         SkillMetadata {
@@ -530,6 +910,7 @@ fn parse_skill_md(content: &str, dir_path: &std::path::Path) -> Option<(SkillMet
             dir_path: dir_path.to_path_buf(),
             platforms,
             deps,
+            dependencies,
             source: fm
                 .source
                 .map(|s| s.trim().to_string())
@@ -559,6 +940,7 @@ name: pdf
 description: Convert documents to PDF
 platforms: [linux, darwin]
 deps: [pandoc]
+dependencies: [markdown, " latex "]
 ---
 Use this skill to convert documents.
 "#;
@@ -570,6 +952,7 @@ Use this skill to convert documents.
         assert_eq!(meta.description, "Convert documents to PDF");
         assert_eq!(meta.platforms, vec!["darwin", "linux"]);
         assert_eq!(meta.deps, vec!["pandoc"]);
+        assert_eq!(meta.dependencies, vec!["latex", "markdown"]);
         assert_eq!(meta.source, "local");
         assert!(body.contains("Use this skill"));
     }
@@ -797,4 +1180,245 @@ nope
         assert!(err.contains("available --all"));
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    fn write_skill(dir: &std::path::Path, folder: &str, name: &str) {
+        let skill_dir = dir.join(folder);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: {name} skill\n---\nbody of {folder}\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_skill_checked_is_case_insensitive() {
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_case_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        write_skill(&dir, "weather", "Weather");
+        let sm = SkillManager::from_skills_dir(dir.to_str().unwrap());
+        for query in ["weather", "WEATHER", "Weather"] {
+            let (meta, _) = sm.load_skill_checked(query).unwrap();
+            assert_eq!(meta.name, "Weather");
+        }
+        assert!(sm.list_skills_formatted().contains("• Weather —"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_case_collisions_prefer_exact_match() {
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_collision_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        write_skill(&dir, "pdf-lower", "pdf");
+        write_skill(&dir, "pdf-upper", "PDF");
+        write_skill(&dir, "docx", "docx");
+        let sm = SkillManager::from_skills_dir(dir.to_str().unwrap());
+        let statuses = sm.discover_skills_with_status(true);
+        assert_eq!(
+            case_collisions(&statuses),
+            vec![vec!["PDF".to_string(), "pdf".to_string()]]
+        );
+        let (_, body) = sm.load_skill_checked("pdf").unwrap();
+        assert!(body.contains("pdf-lower"));
+        let (_, body) = sm.load_skill_checked("PDF").unwrap();
+        assert!(body.contains("pdf-upper"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reload_reports_added_removed_and_errors() {
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_reload_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        write_skill(&dir, "alpha", "alpha");
+        let sm = SkillManager::from_skills_dir(dir.to_str().unwrap());
+        let first = sm.reload();
+        assert_eq!(first.added, vec!["alpha"]);
+        assert!(first.errors.is_empty());
+
+        write_skill(&dir, "beta", "beta");
+        let broken = dir.join("broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(
+            broken.join("SKILL.md"),
+            "---\ndescription: no name\n---\nbody\n",
+        )
+        .unwrap();
+        let second = sm.reload();
+        assert_eq!(second.added, vec!["beta"]);
+        assert_eq!(second.unchanged, vec!["alpha"]);
+        assert!(second.removed.is_empty());
+        assert_eq!(second.errors.len(), 1);
+        assert_eq!(second.errors[0].0, "broken");
+        assert_eq!(
+            second.summary(),
+            "Reloaded 2 skills from disk: +1 -0, 1 error: broken (missing required field: name)"
+        );
+
+        std::fs::remove_dir_all(dir.join("alpha")).unwrap();
+        let third = sm.reload();
+        assert_eq!(third.removed, vec!["alpha"]);
+        assert_eq!(third.loaded(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reload_summary_without_changes() {
+        let reload = SkillReload {
+            unchanged: vec!["pdf".into()],
+            ..Default::default()
+        };
+        assert_eq!(reload.summary(), "Reloaded 1 skills from disk.");
+    }
+
+    #[test]
+    fn test_validate_skill_reports_missing_fields() {
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_validate_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        let skill = dir.join("draft");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\nname: draft\n---\nbody\n").unwrap();
+        assert_eq!(
+            SkillManager::validate_skill(&skill).unwrap_err(),
+            vec!["missing required field: description"]
+        );
+
+        std::fs::write(skill.join("SKILL.md"), "---\nlicense: MIT\n---\nbody\n").unwrap();
+        let problems = SkillManager::validate_skill(&skill.join("SKILL.md")).unwrap_err();
+        assert!(problems.contains(&"missing required field: name".to_string()));
+        assert!(problems.contains(&"missing required field: description".to_string()));
+
+        std::fs::write(skill.join("SKILL.md"), "no frontmatter here").unwrap();
+        let problems = SkillManager::validate_skill(&skill).unwrap_err();
+        assert!(problems[0].starts_with("missing frontmatter"));
+
+        write_skill(&dir, "ok", "ok");
+        assert!(SkillManager::validate_skill(&dir.join("ok")).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_loader_and_lint_frontmatter_edge_cases() {
+        let dir = std::path::Path::new("/tmp/skill");
+        // Unclosed frontmatter still loads, read to the end of the file as
+        // before, but lint flags it.
+        let unclosed = "---\nname: open\ndescription: never closed\n";
+        let (meta, body) = parse_skill_md(unclosed, dir).unwrap();
+        assert_eq!(meta.name, "open");
+        assert!(body.is_empty());
+        assert_eq!(
+            frontmatter_problems(unclosed),
+            vec!["frontmatter is not closed with a `---` line".to_string()]
+        );
+
+        let crlf = "---\r\nname: win\r\ndescription: CRLF\r\n---\r\nBody\r\n";
+        assert!(frontmatter_problems(crlf).is_empty());
+        let (meta, body) = parse_skill_md(crlf, dir).unwrap();
+        assert_eq!(meta.name, "win");
+        assert_eq!(body, "Body");
+
+        // Single-line frontmatter is all on line 1 of the file.
+        let single = "--- name: x description: [oops --- body";
+        assert!(parse_skill_md(single, dir).is_none());
+        let problems = frontmatter_problems(single);
+        assert_eq!(problems.len(), 1);
+        assert!(
+            problems[0].starts_with("unparseable YAML at line 1:"),
+            "{}",
+            problems[0]
+        );
+    }
+
+    #[test]
+    fn test_validate_skill_reports_yaml_line() {
+        let content = "---\nname: bad\ndescription: [unclosed\n---\nbody\n";
+        let problems = frontmatter_problems(content);
+        assert_eq!(problems.len(), 1);
+        assert!(
+            problems[0].starts_with("unparseable YAML at line 3: description"),
+            "{}",
+            problems[0]
+        );
+
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_bad_yaml_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        let skill = dir.join("bad");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("SKILL.md"), content).unwrap();
+        let reload = SkillManager::from_skills_dir(dir.to_str().unwrap()).reload();
+        assert_eq!(reload.errors.len(), 1);
+        assert!(reload.errors[0].1.contains("unparseable YAML"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_list_skills_json_classifies_sources() {
+        let dir = std::env::temp_dir().join(format!(
+            "microclaw_skills_json_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        write_skill(&dir, "pdf", "pdf");
+        write_skill(&dir, "weather-pro", "weather-pro");
+        write_skill(&dir, "notes", "notes");
+        let broken = dir.join("broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("SKILL.md"), "no frontmatter").unwrap();
+
+        let sm = SkillManager::from_skills_dir(dir.to_str().unwrap());
+        let entries = sm.list_skills_json(&["pdf".into()], &["weather-pro".into()]);
+        let sources: Vec<(&str, &str, bool)> = entries
+            .iter()
+            .map(|e| (e.slug.as_str(), e.source.as_str(), e.enabled))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("broken", "local", false),
+                ("notes", "local", true),
+                ("pdf", "builtin", true),
+                ("weather-pro", "clawhub", true),
+            ]
+        );
+        assert!(entries[0].diagnostics[0].starts_with("missing frontmatter"));
+
+        let value = serde_json::to_value(&entries).unwrap();
+        assert_eq!(value[3]["source"], "clawhub");
+        assert!(value[1].get("diagnostics").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_debounce_coalesces_bursts() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let fired = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = fired.clone();
+        let handle = std::thread::spawn(move || {
+            debounce_events(rx, Duration::from_millis(100), || {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+        });
+
+        // An editor saving several times in quick succession
+        for _ in 0..5 {
+            tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        std::thread::sleep(Duration::from_millis(400));
+        assert_eq!(fired.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        tx.send(()).unwrap();
+        drop(tx);
+        handle.join().unwrap();
+        assert_eq!(fired.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
 }
@@ -1,18 +1,240 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use serde_json::json;
-use tracing::info;
+use tracing::{info, warn};
 
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_core::text::floor_char_boundary;
 use microclaw_tools::command_runner::agent_browser_program;
 
 use super::{auth_context_from_input, schema_object, Tool, ToolResult};
+use crate::config::WorkingDirIsolation;
+
+/// Program, arguments and (for `screenshot`) the expected output file and
+/// the chat's screenshot directory.
+#[derive(Debug)]
+struct BrowserInvocation {
+    program: String,
+    session: String,
+    args: Vec<String>,
+    screenshot_path: Option<PathBuf>,
+    screenshot_dir: Option<PathBuf>,
+}
+
+const SCREENSHOT_EXTENSIONS: &[&str] = &[".png", ".jpg", ".jpeg", ".webp"];
+
+/// Commands allowed when `browser_allowed_commands` is not configured. Leaves
+/// out `eval` (arbitrary JS), `upload` and `state` (local file access),
+/// `network` (request rewriting) and `screenshot`/`pdf` (write files to any
+/// path the agent names).
+pub const DEFAULT_ALLOWED_COMMANDS: &[&str] = &[
+    "open",
+    "back",
+    "forward",
+    "reload",
+    "close",
+    "click",
+    "dblclick",
+    "fill",
+    "type",
+    "press",
+    "hover",
+    "select",
+    "check",
+    "uncheck",
+    "drag",
+    "scroll",
+    "scrollintoview",
+    "get",
+    "is",
+    "snapshot",
+    "cookies",
+    "storage",
+    "tab",
+    "frame",
+    "dialog",
+    "set",
+    "wait",
+    "find",
+];
+
+pub fn default_allowed_commands() -> Vec<String> {
+    DEFAULT_ALLOWED_COMMANDS
+        .iter()
+        .map(|c| c.to_string())
+        .collect()
+}
+
+/// Shell control sequences rejected in the raw command. A lone `&` is allowed
+/// so URLs with query strings still work.
+const SHELL_METACHARACTERS: &[&str] = &[";", "|", "`", "$(", "&&", ">", "<", "\n", "\r"];
+
+fn find_shell_metacharacter(command: &str) -> Option<&'static str> {
+    SHELL_METACHARACTERS
+        .iter()
+        .copied()
+        .find(|meta| command.contains(meta))
+}
+
+/// True when an allow-list entry (one or more words, e.g. `get` or
+/// `storage local`) matches the leading words of the command.
+fn command_is_allowed(allowed: &[String], command_args: &[String]) -> bool {
+    allowed.iter().any(|entry| {
+        let words: Vec<&str> = entry.split_whitespace().collect();
+        !words.is_empty()
+            && words.len() <= command_args.len()
+            && words
+                .iter()
+                .zip(command_args)
+                .all(|(word, arg)| word.eq_ignore_ascii_case(arg))
+    })
+}
+
+/// Find the image path agent-browser reports after `screenshot`, e.g.
+/// `✓ Screenshot saved to /tmp/shot.png`. The last match wins.
+fn extract_screenshot_path(output: &str) -> Option<PathBuf> {
+    output
+        .split_whitespace()
+        .rev()
+        .map(|token| {
+            token
+                .trim_start_matches(['"', '\'', '`', '('])
+                .trim_end_matches(['"', '\'', '`', ')', ',', ';', '.', ':'])
+        })
+        .find(|token| {
+            let lower = token.to_ascii_lowercase();
+            SCREENSHOT_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+        })
+        .map(PathBuf::from)
+}
+
+/// The image a `screenshot` command produced: the requested path, or a path
+/// agent-browser reports inside `screenshot_dir`. Anything else named in the
+/// output (which may echo page text) is never attached.
+fn screenshot_attachment(requested: &Path, screenshot_dir: &Path, stdout: &str) -> Option<PathBuf> {
+    if requested.is_file() {
+        return Some(requested.to_path_buf());
+    }
+    let reported = extract_screenshot_path(stdout)?.canonicalize().ok()?;
+    let dir = screenshot_dir.canonicalize().ok()?;
+    (reported.starts_with(&dir) && reported.is_file()).then_some(reported)
+}
+
+/// Characters of an oversized output kept inline as a preview.
+const OUTPUT_PREVIEW_CHARS: usize = 2000;
+
+/// Last-use times of the sessions this tool has started, used to cap how many
+/// stay open and to close idle ones.
+#[derive(Default)]
+struct SessionTracker {
+    last_used: HashMap<String, Instant>,
+    /// Sessions with an idle reaper task already waiting on them.
+    reaping: HashSet<String>,
+}
+
+/// Result of checking a session against the idle limit.
+#[derive(Debug, PartialEq)]
+enum IdleCheck {
+    /// Still in use; check again after this long.
+    Wait(Duration),
+    /// Idle past the limit and dropped from the tracker; close it.
+    Reaped,
+    /// Already evicted by other means.
+    Gone,
+}
+
+impl SessionTracker {
+    /// Record a use of `session` and return the sessions that should be closed:
+    /// those idle longer than `idle` (zero disables), then the least recently
+    /// used ones beyond `max_sessions` (zero disables).
+    fn touch(
+        &mut self,
+        session: &str,
+        now: Instant,
+        max_sessions: usize,
+        idle: Duration,
+    ) -> Vec<String> {
+        self.last_used.insert(session.to_string(), now);
+        let mut evicted = Vec::new();
+        if !idle.is_zero() {
+            self.last_used.retain(|name, last| {
+                let keep = name == session || now.duration_since(*last) <= idle;
+                if !keep {
+                    evicted.push(name.clone());
+                }
+                keep
+            });
+        }
+        if max_sessions > 0 && self.last_used.len() > max_sessions {
+            let mut by_age: Vec<(String, Instant)> = self
+                .last_used
+                .iter()
+                .filter(|(name, _)| name.as_str() != session)
+                .map(|(name, last)| (name.clone(), *last))
+                .collect();
+            by_age.sort_by_key(|(_, last)| *last);
+            let excess = self.last_used.len() - max_sessions;
+            for (name, _) in by_age.into_iter().take(excess) {
+                self.last_used.remove(&name);
+                evicted.push(name);
+            }
+        }
+        evicted
+    }
+
+    /// Record that a command in `session` finished at `now`. Returns true when
+    /// no reaper is waiting on the session yet, so the caller should start one.
+    fn schedule_reap(&mut self, session: &str, now: Instant) -> bool {
+        self.last_used.insert(session.to_string(), now);
+        self.reaping.insert(session.to_string())
+    }
+
+    fn check_idle(&mut self, session: &str, now: Instant, idle: Duration) -> IdleCheck {
+        let Some(last) = self.last_used.get(session) else {
+            self.reaping.remove(session);
+            return IdleCheck::Gone;
+        };
+        let elapsed = now.saturating_duration_since(*last);
+        if elapsed < idle {
+            return IdleCheck::Wait(idle - elapsed);
+        }
+        self.last_used.remove(session);
+        self.reaping.remove(session);
+        IdleCheck::Reaped
+    }
+}
+
+/// Best-effort `close` for sessions evicted by the tracker.
+async fn close_browser_sessions(binary: &str, sessions: Vec<String>) {
+    for session in sessions {
+        info!("Closing idle browser session '{session}'");
+        let close = tokio::process::Command::new(binary)
+            .args(["--session", session.as_str(), "close"])
+            .output();
+        match tokio::time::timeout(Duration::from_secs(10), close).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!("Failed to close browser session '{session}': {e}"),
+            Err(_) => warn!("Timed out closing browser session '{session}'"),
+        }
+    }
+}
 
 pub struct BrowserTool {
     data_dir: PathBuf,
     default_timeout_secs: u64,
+    binary: String,
+    session: String,
+    allowed_commands: Vec<String>,
+    allow_all_commands: bool,
+    max_sessions: usize,
+    session_idle: Duration,
+    sessions: Arc<Mutex<SessionTracker>>,
+    max_output_chars: usize,
+    working_dir: Option<(PathBuf, WorkingDirIsolation)>,
 }
 
 fn split_browser_command(command: &str) -> Result<Vec<String>, String> {
@@ -75,9 +297,63 @@ impl BrowserTool {
         BrowserTool {
             data_dir: PathBuf::from(data_dir).join("groups"),
             default_timeout_secs: 30,
+            binary: agent_browser_program(),
+            session: "microclaw".to_string(),
+            allowed_commands: default_allowed_commands(),
+            allow_all_commands: false,
+            max_sessions: 8,
+            session_idle: Duration::from_secs(1800),
+            sessions: Arc::new(Mutex::new(SessionTracker::default())),
+            max_output_chars: 30000,
+            working_dir: None,
         }
     }
 
+    /// Outputs longer than this are written to a file instead of returned inline.
+    pub fn with_max_output_chars(mut self, max_chars: usize) -> Self {
+        self.max_output_chars = max_chars;
+        self
+    }
+
+    /// Working directory for overflow files, resolved per chat like the file tools.
+    pub fn with_working_dir(mut self, working_dir: &str, isolation: WorkingDirIsolation) -> Self {
+        self.working_dir = Some((PathBuf::from(working_dir), isolation));
+        self
+    }
+
+    /// Maximum number of browser sessions kept open at once (0 = unlimited).
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    /// Close sessions unused for this long (0 = never).
+    pub fn with_session_idle_secs(mut self, secs: u64) -> Self {
+        self.session_idle = Duration::from_secs(secs);
+        self
+    }
+
+    pub fn with_allowed_commands(mut self, allowed: Vec<String>) -> Self {
+        self.allowed_commands = allowed;
+        self
+    }
+
+    /// Skip the allow list and metacharacter checks entirely.
+    pub fn with_allow_all_commands(mut self, allow_all: bool) -> Self {
+        self.allow_all_commands = allow_all;
+        self
+    }
+
+    pub fn with_binary(mut self, binary: impl Into<String>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = session.into();
+        self
+    }
+
     pub fn with_default_timeout_secs(mut self, timeout_secs: u64) -> Self {
         self.default_timeout_secs = timeout_secs;
         self
@@ -89,13 +365,191 @@ impl BrowserTool {
             .join("browser-profile")
     }
 
-    fn session_name_for_chat(chat_id: i64) -> String {
+    fn session_name_for_chat(&self, chat_id: i64) -> String {
         let normalized = if chat_id < 0 {
             format!("neg{}", chat_id.unsigned_abs())
         } else {
             chat_id.to_string()
         };
-        format!("microclaw-chat-{normalized}")
+        format!("{}-chat-{normalized}", self.session)
+    }
+
+    /// Directory `screenshot` writes to when the agent gives no path.
+    fn screenshot_dir(&self, chat_id: Option<i64>) -> PathBuf {
+        let dir = match chat_id {
+            Some(id) => self.data_dir.join(id.to_string()),
+            None => self.data_dir.join("shared"),
+        };
+        dir.join("browser-screenshots")
+    }
+
+    /// Where `screenshot` writes when the agent gives no path, so the file can
+    /// be found and delivered afterwards.
+    fn default_screenshot_path(&self, chat_id: Option<i64>) -> PathBuf {
+        self.screenshot_dir(chat_id).join(format!(
+            "screenshot-{}.png",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S%3f")
+        ))
+    }
+
+    fn output_dir(&self, input: &serde_json::Value) -> PathBuf {
+        match &self.working_dir {
+            Some((dir, isolation)) => {
+                super::resolve_tool_working_dir(dir, *isolation, input).join("browser-output")
+            }
+            None => {
+                let dir = match auth_context_from_input(input) {
+                    Some(auth) => self.data_dir.join(auth.caller_chat_id.to_string()),
+                    None => self.data_dir.join("shared"),
+                };
+                dir.join("browser-output")
+            }
+        }
+    }
+
+    /// Return `text` as is when it fits, otherwise write it to a file in the
+    /// chat's working dir and return a preview plus the file path. Falls back to
+    /// truncation if the file can't be written.
+    fn fit_output(&self, mut text: String, input: &serde_json::Value) -> String {
+        let total_chars = text.chars().count();
+        if total_chars <= self.max_output_chars {
+            return text;
+        }
+        let path = self.output_dir(input).join(format!(
+            "output-{}.txt",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S%3f")
+        ));
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, &text));
+        match written {
+            Ok(()) => {
+                let path = std::path::absolute(&path).unwrap_or(path);
+                let preview: String = text
+                    .chars()
+                    .take(OUTPUT_PREVIEW_CHARS.min(self.max_output_chars))
+                    .collect();
+                format!(
+                    "{preview}\n... (output is {total_chars} chars; full output written to {})\n\
+                     Read it with read_file or send it with send_message attachment_path.",
+                    path.display()
+                )
+            }
+            Err(e) => {
+                warn!("Failed to write browser output to {}: {e}", path.display());
+                let cutoff = floor_char_boundary(&text, self.max_output_chars);
+                text.truncate(cutoff);
+                text.push_str("\n... (output truncated)");
+                text
+            }
+        }
+    }
+
+    /// Close `session` once it has been idle for `session_idle` after its
+    /// last command finished, instead of waiting for the next command.
+    fn reap_when_idle(&self, session: &str) {
+        if self.session_idle.is_zero() {
+            return;
+        }
+        let start_reaper = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .schedule_reap(session, Instant::now());
+        if !start_reaper {
+            return;
+        }
+        let sessions = self.sessions.clone();
+        let binary = self.binary.clone();
+        let idle = self.session_idle;
+        let session = session.to_string();
+        tokio::spawn(async move {
+            let mut wait = idle;
+            loop {
+                tokio::time::sleep(wait).await;
+                let check = sessions
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .check_idle(&session, Instant::now(), idle);
+                match check {
+                    IdleCheck::Wait(remaining) => wait = remaining,
+                    IdleCheck::Reaped => {
+                        close_browser_sessions(&binary, vec![session]).await;
+                        return;
+                    }
+                    IdleCheck::Gone => return,
+                }
+            }
+        });
+    }
+
+    /// Program and argument list for one tool call.
+    fn build_invocation(&self, input: &serde_json::Value) -> Result<BrowserInvocation, String> {
+        let command = input
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Missing 'command' parameter".to_string())?;
+        if !self.allow_all_commands {
+            if let Some(meta) = find_shell_metacharacter(command) {
+                return Err(format!(
+                    "Browser command contains shell metacharacter '{}'",
+                    meta.escape_default()
+                ));
+            }
+        }
+
+        let auth = auth_context_from_input(input);
+        let session_name = auth
+            .as_ref()
+            .map(|auth| self.session_name_for_chat(auth.caller_chat_id))
+            .unwrap_or_else(|| self.session.clone());
+
+        let mut args = vec!["--session".to_string(), session_name.clone()];
+        if let Some(auth) = auth.as_ref() {
+            let path = self.profile_path(auth.caller_chat_id);
+            args.push("--profile".to_string());
+            args.push(path.to_string_lossy().to_string());
+        }
+
+        let mut command_args = match split_browser_command(command) {
+            Ok(parts) if !parts.is_empty() => parts,
+            Ok(_) => return Err("Empty browser command".into()),
+            Err(e) => {
+                return Err(format!(
+                    "Invalid browser command syntax (quote parsing failed): {e}"
+                ));
+            }
+        };
+        if !self.allow_all_commands && !command_is_allowed(&self.allowed_commands, &command_args) {
+            return Err(format!(
+                "Browser command '{}' not permitted (allowed: {})",
+                command_args[0],
+                self.allowed_commands.join(", ")
+            ));
+        }
+        let mut screenshot_path = None;
+        let mut screenshot_dir = None;
+        if command_args[0] == "screenshot" {
+            screenshot_dir = Some(self.screenshot_dir(auth.as_ref().map(|a| a.caller_chat_id)));
+            match command_args[1..].iter().find(|a| !a.starts_with('-')) {
+                Some(path) => screenshot_path = Some(PathBuf::from(path)),
+                None => {
+                    let path =
+                        self.default_screenshot_path(auth.as_ref().map(|a| a.caller_chat_id));
+                    command_args.insert(1, path.to_string_lossy().to_string());
+                    screenshot_path = Some(path);
+                }
+            }
+        }
+        args.extend(command_args);
+        Ok(BrowserInvocation {
+            program: self.binary.clone(),
+            session: session_name,
+            args,
+            screenshot_path,
+            screenshot_dir,
+        })
     }
 }
 
@@ -122,7 +576,7 @@ impl Tool for BrowserTool {
                 **Data extraction**: get text/html/value/attr/title/url/count/box <sel>\n\
                 **State checks**: is visible/enabled/checked <sel>\n\
                 **Snapshot**: snapshot (-i for interactive only, -c for compact)\n\
-                **Screenshot/PDF**: screenshot [path] (--full for full page), pdf <path>\n\
+                **Screenshot/PDF**: screenshot [path] (--full for full page; the image is sent to the chat automatically), pdf <path>\n\
                 **JavaScript**: eval <js>\n\
                 **Cookies**: cookies, cookies set <name> <val>, cookies clear\n\
                 **Storage**: storage local [key], storage local set <k> <v>, storage local clear (same for session)\n\
@@ -151,49 +605,45 @@ impl Tool for BrowserTool {
     }
 
     async fn execute(&self, input: serde_json::Value) -> ToolResult {
-        let command = match input.get("command").and_then(|v| v.as_str()) {
-            Some(c) => c,
-            None => return ToolResult::error("Missing 'command' parameter".into()),
-        };
-
         let timeout_secs = input
             .get("timeout_secs")
             .and_then(|v| v.as_u64())
             .unwrap_or(self.default_timeout_secs);
 
-        let auth = auth_context_from_input(&input);
-
-        let session_name = auth
-            .as_ref()
-            .map(|auth| Self::session_name_for_chat(auth.caller_chat_id))
-            .unwrap_or_else(|| "microclaw".to_string());
-
-        let mut args = vec!["--session".to_string(), session_name];
-        if let Some(auth) = auth.as_ref() {
-            let path = self.profile_path(auth.caller_chat_id);
-            args.push("--profile".to_string());
-            args.push(path.to_string_lossy().to_string());
-        }
-
-        let command_args = match split_browser_command(command) {
-            Ok(parts) if !parts.is_empty() => parts,
-            Ok(_) => return ToolResult::error("Empty browser command".into()),
-            Err(e) => {
-                return ToolResult::error(format!(
-                    "Invalid browser command syntax (quote parsing failed): {e}"
-                ));
-            }
+        let BrowserInvocation {
+            program,
+            session,
+            args,
+            screenshot_path,
+            screenshot_dir,
+        } = match self.build_invocation(&input) {
+            Ok(invocation) => invocation,
+            Err(e) => return ToolResult::error(e),
         };
-        args.extend(command_args);
-
-        let program = agent_browser_program();
+        if let Some(dir) = screenshot_path.as_deref().and_then(|p| p.parent()) {
+            if !dir.as_os_str().is_empty() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+        }
+        let evicted = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .touch(
+                &session,
+                Instant::now(),
+                self.max_sessions,
+                self.session_idle,
+            );
+        close_browser_sessions(&self.binary, evicted).await;
         info!("Executing browser command via '{}'", program);
 
         let result = tokio::time::timeout(
-            std::time::Duration::from_secs(timeout_secs),
+            Duration::from_secs(timeout_secs),
             tokio::process::Command::new(&program).args(&args).output(),
         )
         .await;
+        self.reap_when_idle(&session);
 
         match result {
             Ok(Ok(output)) => {
@@ -216,21 +666,32 @@ impl Tool for BrowserTool {
                     result_text = format!("Command completed with exit code {exit_code}");
                 }
 
-                // Truncate very long output
-                if result_text.len() > 30000 {
-                    let cutoff = floor_char_boundary(&result_text, 30000);
-                    result_text.truncate(cutoff);
-                    result_text.push_str("\n... (output truncated)");
-                }
+                result_text = self.fit_output(result_text, &input);
 
                 if exit_code == 0 {
-                    ToolResult::success(result_text).with_status_code(exit_code)
+                    let attachment = screenshot_path
+                        .zip(screenshot_dir)
+                        .and_then(|(path, dir)| screenshot_attachment(&path, &dir, &stdout))
+                        .map(|p| std::path::absolute(&p).unwrap_or(p));
+                    match attachment {
+                        Some(path) => {
+                            result_text.push_str(&format!("\nattachment_path: {}", path.display()));
+                            ToolResult::success(result_text)
+                                .with_status_code(exit_code)
+                                .with_attachment_path(path)
+                        }
+                        None => ToolResult::success(result_text).with_status_code(exit_code),
+                    }
                 } else {
                     ToolResult::error(format!("Exit code {exit_code}\n{result_text}"))
                         .with_status_code(exit_code)
                         .with_error_type("process_exit")
                 }
             }
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => ToolResult::error(format!(
+                "Browser binary '{program}' not found. Install agent-browser or set browser_binary in the config."
+            ))
+            .with_error_type("binary_not_found"),
             Ok(Err(e)) => ToolResult::error(format!("Failed to execute agent-browser: {e}"))
                 .with_error_type("spawn_error"),
             Err(_) => ToolResult::error(format!(
@@ -284,14 +745,266 @@ mod tests {
 
     #[test]
     fn test_browser_session_name_for_chat() {
+        let tool = BrowserTool::new("/tmp/test-data");
+        assert_eq!(tool.session_name_for_chat(12345), "microclaw-chat-12345");
         assert_eq!(
-            BrowserTool::session_name_for_chat(12345),
-            "microclaw-chat-12345"
+            tool.session_name_for_chat(-100987),
+            "microclaw-chat-neg100987"
         );
+    }
+
+    #[test]
+    fn test_distinct_chats_get_distinct_sessions() {
+        let tool = BrowserTool::new("/tmp/test-data");
+        let session_for = |chat_id: i64| {
+            tool.build_invocation(&json!({
+                "command": "open https://example.com",
+                "__microclaw_auth": {"caller_chat_id": chat_id, "control_chat_ids": []}
+            }))
+            .unwrap()
+            .session
+        };
+        assert_ne!(session_for(1), session_for(2));
+        let shared = tool
+            .build_invocation(&json!({"command": "open https://example.com"}))
+            .unwrap();
+        assert_eq!(shared.session, "microclaw");
+    }
+
+    #[test]
+    fn test_session_tracker_caps_and_reaps() {
+        let mut tracker = SessionTracker::default();
+        let start = Instant::now();
+        let idle = Duration::from_secs(60);
+        assert!(tracker.touch("a", start, 2, idle).is_empty());
+        assert!(tracker
+            .touch("b", start + Duration::from_secs(1), 2, idle)
+            .is_empty());
+        // Over the cap: the least recently used session goes.
         assert_eq!(
-            BrowserTool::session_name_for_chat(-100987),
-            "microclaw-chat-neg100987"
+            tracker.touch("c", start + Duration::from_secs(2), 2, idle),
+            vec!["a".to_string()]
+        );
+        // Both older sessions have gone idle; the current one is kept.
+        let mut evicted = tracker.touch("b", start + Duration::from_secs(120), 2, idle);
+        evicted.sort();
+        assert_eq!(evicted, vec!["c".to_string()]);
+        assert_eq!(tracker.last_used.len(), 1);
+    }
+
+    #[test]
+    fn test_session_tracker_reaps_after_last_command_finishes() {
+        let mut tracker = SessionTracker::default();
+        let start = Instant::now();
+        let idle = Duration::from_secs(60);
+        assert!(tracker.schedule_reap("a", start));
+        // A second command reuses the reaper that is already waiting.
+        assert!(!tracker.schedule_reap("a", start + Duration::from_secs(30)));
+        assert_eq!(
+            tracker.check_idle("a", start + Duration::from_secs(60), idle),
+            IdleCheck::Wait(Duration::from_secs(30))
         );
+        assert_eq!(
+            tracker.check_idle("a", start + Duration::from_secs(90), idle),
+            IdleCheck::Reaped
+        );
+        assert!(tracker.last_used.is_empty());
+        assert_eq!(
+            tracker.check_idle("a", start + Duration::from_secs(91), idle),
+            IdleCheck::Gone
+        );
+        assert!(tracker.schedule_reap("a", start + Duration::from_secs(100)));
+    }
+
+    #[test]
+    fn test_browser_invocation_uses_configured_binary_and_session() {
+        let tool = BrowserTool::new("/tmp/test-data")
+            .with_binary("/opt/browser/agent-browser")
+            .with_session("microclaw-mybot");
+
+        let invocation = tool
+            .build_invocation(&json!({"command": "open https://example.com"}))
+            .unwrap();
+        assert_eq!(invocation.program, "/opt/browser/agent-browser");
+        assert_eq!(
+            invocation.args,
+            vec![
+                "--session",
+                "microclaw-mybot",
+                "open",
+                "https://example.com"
+            ]
+        );
+        assert!(invocation.screenshot_path.is_none());
+
+        let BrowserInvocation { args, .. } = tool
+            .build_invocation(&json!({
+                "command": "snapshot -i",
+                "__microclaw_auth": {"caller_chat_id": 42, "caller_channel": "telegram", "control_chat_ids": []}
+            }))
+            .unwrap();
+        assert_eq!(&args[..2], &["--session", "microclaw-mybot-chat-42"]);
+    }
+
+    #[test]
+    fn test_screenshot_gets_explicit_output_path() {
+        let tool =
+            BrowserTool::new("/tmp/test-data").with_allowed_commands(vec!["screenshot".into()]);
+        let invocation = tool
+            .build_invocation(&json!({
+                "command": "screenshot --full",
+                "__microclaw_auth": {"caller_chat_id": 7, "control_chat_ids": []}
+            }))
+            .unwrap();
+        let path = invocation.screenshot_path.unwrap();
+        assert!(path.starts_with("/tmp/test-data/groups/7/browser-screenshots"));
+        assert_eq!(invocation.args[4], "screenshot");
+        assert_eq!(invocation.args[5], path.to_string_lossy());
+        assert_eq!(invocation.args[6], "--full");
+
+        let invocation = tool
+            .build_invocation(&json!({"command": "screenshot /tmp/page.png"}))
+            .unwrap();
+        assert_eq!(
+            invocation.screenshot_path,
+            Some(PathBuf::from("/tmp/page.png"))
+        );
+    }
+
+    #[test]
+    fn test_extract_screenshot_path_from_output() {
+        assert_eq!(
+            extract_screenshot_path("✓ Screenshot saved to /tmp/agent/shot-1.png\n"),
+            Some(PathBuf::from("/tmp/agent/shot-1.png"))
+        );
+        assert_eq!(
+            extract_screenshot_path("Saved screenshot: \"./out/Page.JPEG\"."),
+            Some(PathBuf::from("./out/Page.JPEG"))
+        );
+        assert_eq!(
+            extract_screenshot_path("Navigated to https://example.com"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_screenshot_attachment_ignores_paths_outside_screenshot_dir() {
+        let root = std::env::temp_dir().join(format!("mc_browser_shot_{}", uuid::Uuid::new_v4()));
+        let dir = root.join("browser-screenshots");
+        std::fs::create_dir_all(&dir).unwrap();
+        let private = root.join("private.jpg");
+        std::fs::write(&private, b"secret").unwrap();
+        let shot = dir.join("shot-1.png");
+        std::fs::write(&shot, b"png").unwrap();
+        let missing = dir.join("requested.png");
+
+        let page_text = format!("Page says {}", private.display());
+        assert_eq!(screenshot_attachment(&missing, &dir, &page_text), None);
+        let saved = format!("✓ Screenshot saved to {}", shot.display());
+        assert_eq!(
+            screenshot_attachment(&missing, &dir, &saved),
+            Some(shot.canonicalize().unwrap())
+        );
+        assert_eq!(screenshot_attachment(&shot, &dir, &page_text), Some(shot));
+
+        let tool = BrowserTool::new("/tmp/test-data");
+        let invocation = tool
+            .build_invocation(&json!({"command": "get text body"}))
+            .unwrap();
+        assert!(invocation.screenshot_path.is_none());
+        assert!(invocation.screenshot_dir.is_none());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_browser_allow_list_rejects_other_commands() {
+        let tool = BrowserTool::new("/tmp/test-data");
+        let err = tool
+            .build_invocation(&json!({"command": "eval document.cookie"}))
+            .unwrap_err();
+        assert!(err.contains("not permitted"));
+        assert!(tool
+            .build_invocation(&json!({"command": "get text @e3"}))
+            .is_ok());
+        for command in ["screenshot", "pdf /tmp/page.pdf"] {
+            assert!(tool
+                .build_invocation(&json!({ "command": command }))
+                .unwrap_err()
+                .contains("not permitted"));
+        }
+
+        let tool =
+            BrowserTool::new("/tmp/test-data").with_allowed_commands(vec!["storage local".into()]);
+        assert!(tool
+            .build_invocation(&json!({"command": "storage local token"}))
+            .is_ok());
+        assert!(tool
+            .build_invocation(&json!({"command": "storage session"}))
+            .unwrap_err()
+            .contains("not permitted"));
+
+        let tool = BrowserTool::new("/tmp/test-data").with_allow_all_commands(true);
+        assert!(tool
+            .build_invocation(&json!({"command": "eval \"a(); b()\""}))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_browser_rejects_shell_metacharacters() {
+        let tool = BrowserTool::new("/tmp/test-data");
+        for command in [
+            "open https://example.com; rm -rf /",
+            "get text @e1 | tee /tmp/x",
+            "open $(whoami)",
+            "screenshot > /etc/passwd",
+        ] {
+            let err = tool
+                .build_invocation(&json!({ "command": command }))
+                .unwrap_err();
+            assert!(err.contains("metacharacter"), "{command}: {err}");
+        }
+        assert!(tool
+            .build_invocation(&json!({"command": "open https://example.com/?a=1&b=2"}))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_long_output_is_written_to_chat_working_dir() {
+        let root = std::env::temp_dir().join(format!("microclaw_browser_{}", uuid::Uuid::new_v4()));
+        let tool = BrowserTool::new("/tmp/test-data")
+            .with_max_output_chars(50)
+            .with_working_dir(root.to_str().unwrap(), WorkingDirIsolation::Chat);
+        let input = json!({
+            "command": "snapshot",
+            "__microclaw_auth": {"caller_chat_id": 9, "caller_channel": "telegram", "control_chat_ids": []}
+        });
+
+        assert_eq!(tool.fit_output("short".into(), &input), "short");
+
+        let long = "é".repeat(120);
+        let content = tool.fit_output(long.clone(), &input);
+        let path = content
+            .lines()
+            .find_map(|line| line.split("full output written to ").nth(1))
+            .map(|p| PathBuf::from(p.trim_end_matches(')')))
+            .unwrap();
+        assert!(path.starts_with(std::path::absolute(&root).unwrap().join("chat/telegram/9")));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), long);
+        assert!(content.starts_with(&"é".repeat(50)));
+        assert!(content.contains("120 chars"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_browser_missing_binary_is_reported_distinctly() {
+        let tool =
+            BrowserTool::new("/tmp/test-data").with_binary("/nonexistent/microclaw-agent-browser");
+        let result = tool.execute(json!({"command": "open about:blank"})).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("not found"));
+        assert_eq!(result.error_type.as_deref(), Some("binary_not_found"));
     }
 
     #[tokio::test]
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::info;
+
+use super::{authorize_chat_access, schema_object, Tool, ToolResult};
+use microclaw_channels::channel::{edit_chat_message, enforce_channel_policy};
+use microclaw_channels::channel_adapter::ChannelRegistry;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::Database;
+
+pub struct EditMessageTool {
+    registry: Arc<ChannelRegistry>,
+    db: Arc<Database>,
+}
+
+impl EditMessageTool {
+    pub fn new(registry: Arc<ChannelRegistry>, db: Arc<Database>) -> Self {
+        EditMessageTool { registry, db }
+    }
+}
+
+#[async_trait]
+impl Tool for EditMessageTool {
+    fn name(&self) -> &str {
+        "edit_message"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "edit_message".into(),
+            description: "Replace the text of a message the bot sent earlier (Telegram, Discord, Matrix). Use the message_id reported by send_message.".into(),
+            input_schema: schema_object(
+                json!({
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "The chat containing the message"
+                    },
+                    "message_id": {
+                        "type": "string",
+                        "description": "Id of the bot message to edit"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The new message text"
+                    }
+                }),
+                &["chat_id", "message_id", "text"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => id,
+            None => return ToolResult::error("Missing required parameter: chat_id".into()),
+        };
+        let message_id = match input.get("message_id").and_then(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .or_else(|| v.as_i64().map(|id| id.to_string()))
+        }) {
+            Some(id) if !id.trim().is_empty() => id.trim().to_string(),
+            _ => return ToolResult::error("Missing required parameter: message_id".into()),
+        };
+        let text = input
+            .get("text")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        if text.trim().is_empty() {
+            return ToolResult::error("Missing required parameter: text".into());
+        }
+
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) =
+            enforce_channel_policy(&self.registry, self.db.clone(), &input, chat_id).await
+        {
+            return ToolResult::error(e);
+        }
+
+        info!("edit_message: chat_id={chat_id}, message_id={message_id}");
+        match edit_chat_message(&self.registry, self.db.clone(), chat_id, &message_id, &text).await
+        {
+            Ok(()) => ToolResult::success(format!("Message {message_id} edited.")),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::WebAdapter;
+
+    fn test_tool() -> (EditMessageTool, Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("microclaw_edit_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let mut registry = ChannelRegistry::new();
+        registry.register(Arc::new(WebAdapter));
+        (
+            EditMessageTool::new(Arc::new(registry), db.clone()),
+            db,
+            dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_edit_message_argument_validation() {
+        let (tool, _db, dir) = test_tool();
+        let result = tool.execute(json!({"message_id": "1", "text": "x"})).await;
+        assert!(result
+            .content
+            .contains("Missing required parameter: chat_id"));
+        let result = tool.execute(json!({"chat_id": 1, "text": "x"})).await;
+        assert!(result
+            .content
+            .contains("Missing required parameter: message_id"));
+        let result = tool.execute(json!({"chat_id": 1, "message_id": "1"})).await;
+        assert!(result.content.contains("Missing required parameter: text"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_edit_message_unsupported_channel() {
+        let (tool, db, dir) = test_tool();
+        db.upsert_chat(5, Some("web-main"), "web").unwrap();
+        let result = tool
+            .execute(json!({"chat_id": 5, "message_id": "m1", "text": "fixed"}))
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("editing not supported"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
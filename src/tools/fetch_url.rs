@@ -0,0 +1,480 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use microclaw_core::http::HttpClientSettings;
+use microclaw_tools::web_fetch::{validate_web_fetch_url, WebFetchUrlValidationConfig};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::{schema_object, Tool, ToolResult};
+use microclaw_core::llm_types::ToolDefinition;
+
+const MAX_REDIRECTS: usize = 5;
+const MIN_TIMEOUT_SECS: u64 = 1;
+const MAX_TIMEOUT_SECS: u64 = 120;
+
+pub struct FetchUrlTool {
+    client: reqwest::Client,
+    http: HttpClientSettings,
+    default_timeout_secs: u64,
+    max_bytes: usize,
+    url_validation: WebFetchUrlValidationConfig,
+    allow_private_networks: bool,
+}
+
+/// True for addresses a fetch must never reach: loopback, private, link-local,
+/// carrier-grade NAT, unique-local, multicast, reserved and unspecified ranges.
+/// IPv4-mapped IPv6 addresses are checked as IPv4.
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b & 0xfe) == 18)
+                || a >= 240
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_blocked_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Resolve the URL's host and reject it if any address is internal.
+async fn check_host_addresses(url: &Url) -> Result<(), String> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| "URL must include a host".to_string())?;
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<IpAddr> = match literal.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => {
+            let port = url.port_or_known_default().unwrap_or(80);
+            tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|e| format!("failed to resolve '{host}': {e}"))?
+                .map(|addr| addr.ip())
+                .collect()
+        }
+    };
+    if addrs.is_empty() {
+        return Err(format!("'{host}' did not resolve to any address"));
+    }
+    if let Some(ip) = addrs.into_iter().find(|ip| is_blocked_ip(*ip)) {
+        return Err(format!(
+            "URL host '{host}' resolves to {ip}, a private or loopback address"
+        ));
+    }
+    Ok(())
+}
+
+/// DNS resolver that refuses hostnames resolving to internal addresses.
+///
+/// The connection uses exactly the addresses checked here, so a host that
+/// answers with a public IP for the pre-flight lookup and a private one on
+/// the next lookup (DNS rebinding) cannot slip through.
+struct PublicOnlyResolver;
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| is_blocked_ip(addr.ip())) {
+                return Err(format!(
+                    "'{host}' resolves to {}, a private or loopback address",
+                    addr.ip()
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+fn build_client(http: &HttpClientSettings, allow_private_networks: bool) -> reqwest::Client {
+    // Redirects are followed by hand so every hop is checked.
+    let configure = |builder: reqwest::ClientBuilder| {
+        let builder = builder
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent("MicroClaw/1.0");
+        if allow_private_networks {
+            builder
+        } else {
+            builder.dns_resolver(Arc::new(PublicOnlyResolver))
+        }
+    };
+    microclaw_core::http::http_client_builder(http)
+        .and_then(|builder| {
+            configure(builder)
+                .build()
+                .map_err(|e| format!("Failed to build HTTP client: {e}"))
+        })
+        .unwrap_or_else(|e| {
+            warn!("{e}; fetch_url falls back to a default HTTP client");
+            configure(reqwest::Client::builder())
+                .build()
+                .unwrap_or_default()
+        })
+}
+
+fn resolve_timeout_secs(input: &serde_json::Value, default_timeout_secs: u64) -> u64 {
+    input
+        .get("timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(default_timeout_secs)
+        .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)
+}
+
+fn parse_headers(value: Option<&serde_json::Value>) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    let Some(value) = value.filter(|v| !v.is_null()) else {
+        return Ok(headers);
+    };
+    let map = value
+        .as_object()
+        .ok_or_else(|| "'headers' must be an object of string values".to_string())?;
+    for (name, value) in map {
+        let value = value
+            .as_str()
+            .ok_or_else(|| format!("header '{name}' must be a string"))?;
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("invalid header name '{name}': {e}"))?;
+        let value =
+            HeaderValue::from_str(value).map_err(|e| format!("invalid value for '{name}': {e}"))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+/// Read at most `max_bytes` of the body; the flag reports whether more followed.
+async fn read_body_capped(
+    mut resp: reqwest::Response,
+    max_bytes: usize,
+) -> Result<(Vec<u8>, bool), String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+        let remaining = max_bytes - body.len();
+        if chunk.len() > remaining {
+            body.extend_from_slice(&chunk[..remaining]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+impl FetchUrlTool {
+    pub fn new(
+        http: &HttpClientSettings,
+        default_timeout_secs: u64,
+        max_bytes: usize,
+        url_validation: WebFetchUrlValidationConfig,
+    ) -> Self {
+        Self {
+            client: build_client(http, false),
+            http: http.clone(),
+            default_timeout_secs,
+            max_bytes,
+            url_validation,
+            allow_private_networks: false,
+        }
+    }
+
+    /// Allow requests to private, loopback and link-local addresses.
+    pub fn with_allow_private_networks(mut self, allow: bool) -> Self {
+        if allow != self.allow_private_networks {
+            self.client = build_client(&self.http, allow);
+        }
+        self.allow_private_networks = allow;
+        self
+    }
+
+    async fn check_url(&self, url: &Url) -> Result<(), String> {
+        validate_web_fetch_url(url.as_str(), self.url_validation.clone())?;
+        if !self.allow_private_networks {
+            check_host_addresses(url).await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch(&self, input: &serde_json::Value) -> Result<ToolResult, String> {
+        let url = input
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Missing required parameter: url".to_string())?;
+        let method = input
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        let body = input.get("body").and_then(|v| v.as_str());
+        let is_post = match method.as_str() {
+            "GET" if body.is_some() => return Err("'body' requires method POST".into()),
+            "GET" => false,
+            "POST" => true,
+            other => return Err(format!("Unsupported method '{other}' (use GET or POST)")),
+        };
+        let headers = parse_headers(input.get("headers"))?;
+        let timeout_secs = resolve_timeout_secs(input, self.default_timeout_secs);
+
+        let mut current = Url::parse(url).map_err(|e| format!("invalid URL: {e}"))?;
+        let mut redirects = 0usize;
+        let resp = loop {
+            self.check_url(&current).await?;
+            info!("fetch_url {method} {current}");
+            let request = if is_post {
+                self.client
+                    .post(current.clone())
+                    .body(body.unwrap_or_default().to_string())
+            } else {
+                self.client.get(current.clone())
+            };
+            let resp = request
+                .headers(headers.clone())
+                .timeout(Duration::from_secs(timeout_secs))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // POST redirects are returned as is rather than replayed.
+            if is_post || !resp.status().is_redirection() {
+                break resp;
+            }
+            if redirects >= MAX_REDIRECTS {
+                return Err(format!("too many redirects (max {MAX_REDIRECTS})"));
+            }
+            redirects += 1;
+            let location = resp
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .ok_or_else(|| "redirect response missing Location header".to_string())?
+                .to_str()
+                .map_err(|e| format!("invalid redirect Location header: {e}"))?;
+            current = current
+                .join(location)
+                .map_err(|e| format!("invalid redirect target '{location}': {e}"))?;
+        };
+
+        let status = resp.status();
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let (bytes, truncated) = read_body_capped(resp, self.max_bytes).await?;
+
+        let mut content = format!("HTTP {status}");
+        if !content_type.is_empty() {
+            content.push_str(&format!("\ncontent-type: {content_type}"));
+        }
+        content.push_str("\n\n");
+        content.push_str(&String::from_utf8_lossy(&bytes));
+        if truncated {
+            content.push_str(&format!("\n\n[Truncated at {} bytes]", self.max_bytes));
+        }
+        Ok(ToolResult::success(content).with_status_code(i32::from(status.as_u16())))
+    }
+}
+
+#[async_trait]
+impl Tool for FetchUrlTool {
+    fn name(&self) -> &str {
+        "fetch_url"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "fetch_url".into(),
+            description: format!(
+                "Make a raw HTTP GET (or POST) request and return the status line, content type and body as-is, without HTML parsing. \
+                 Use for JSON APIs and plain-text resources; use web_fetch for readable page text. \
+                 Private and loopback addresses are blocked. The body is cut at {} bytes.",
+                self.max_bytes
+            ),
+            input_schema: schema_object(
+                json!({
+                    "url": {
+                        "type": "string",
+                        "description": "The http(s) URL to request"
+                    },
+                    "method": {
+                        "type": "string",
+                        "enum": ["GET", "POST"],
+                        "description": "HTTP method (default GET)"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Extra request headers"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Request body (POST only)"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Timeout in seconds (defaults to configured tool timeout budget, max 120)"
+                    }
+                }),
+                &["url"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        match self.fetch(&input).await {
+            Ok(result) => result,
+            Err(e) => ToolResult::error(format!("Failed to fetch URL: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn tool(max_bytes: usize) -> FetchUrlTool {
+        FetchUrlTool::new(
+            &HttpClientSettings::default(),
+            5,
+            max_bytes,
+            WebFetchUrlValidationConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_is_blocked_ip_ranges() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(is_blocked_ip(ip.parse().unwrap()), "{ip} should be blocked");
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(
+                !is_blocked_ip(ip.parse().unwrap()),
+                "{ip} should be allowed"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_blocks_internal_hosts_before_request() {
+        let tool = tool(1000);
+        for url in [
+            "http://127.0.0.1:9/",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost/",
+        ] {
+            let result = tool.execute(json!({ "url": url })).await;
+            assert!(result.is_error, "{url}");
+            assert!(result.content.contains("private or loopback"), "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_refuses_hosts_resolving_to_loopback() {
+        use reqwest::dns::Resolve;
+        let err = PublicOnlyResolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("private or loopback"), "{err}");
+    }
+
+    #[test]
+    fn test_resolve_timeout_secs_clamps_bounds() {
+        assert_eq!(resolve_timeout_secs(&json!({"timeout_secs": 0}), 15), 1);
+        assert_eq!(
+            resolve_timeout_secs(&json!({"timeout_secs": 86400}), 15),
+            120
+        );
+        assert_eq!(resolve_timeout_secs(&json!({"timeout_secs": 5}), 15), 5);
+        assert_eq!(resolve_timeout_secs(&json!({}), 15), 15);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_rejects_bad_arguments() {
+        let tool = tool(1000);
+        let result = tool
+            .execute(json!({"url": "https://example.com", "method": "DELETE"}))
+            .await;
+        assert!(result.content.contains("Unsupported method"));
+        let result = tool
+            .execute(json!({"url": "https://example.com", "body": "x"}))
+            .await;
+        assert!(result.content.contains("requires method POST"));
+        let result = tool.execute(json!({"url": "ftp://example.com"})).await;
+        assert!(result.content.contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_caps_response_size() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let body = "x".repeat(5000);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+
+        let tool = tool(100).with_allow_private_networks(true);
+        let result = tool
+            .execute(json!({"url": format!("http://{addr}/data")}))
+            .await;
+        server.await.unwrap();
+
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(result.status_code, Some(200));
+        assert!(result
+            .content
+            .starts_with("HTTP 200 OK\ncontent-type: text/plain"));
+        assert!(result
+            .content
+            .contains(&format!("\n\n{}\n\n", "x".repeat(100))));
+        assert!(!result.content.contains(&"x".repeat(101)));
+        assert!(result.content.ends_with("[Truncated at 100 bytes]"));
+    }
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::skills::{SkillAvailability, SkillManager};
+use microclaw_core::llm_types::ToolDefinition;
+
+use super::{schema_object, Tool, ToolResult};
+
+pub struct ListSkillsTool {
+    skill_manager: SkillManager,
+}
+
+impl ListSkillsTool {
+    pub fn new(skills_dir: &str) -> Self {
+        ListSkillsTool {
+            skill_manager: SkillManager::from_skills_dir(skills_dir),
+        }
+    }
+}
+
+fn skill_json(skill: &SkillAvailability, detail: bool) -> serde_json::Value {
+    let meta = &skill.meta;
+    let slug = meta
+        .dir_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| meta.name.clone());
+    let mut value = json!({
+        "slug": slug,
+        "name": meta.name,
+        "description": meta.description,
+        "source": meta.source,
+        "version": meta.version,
+    });
+    if detail {
+        value["available"] = json!(skill.available);
+        value["reason"] = json!(skill.reason);
+        value["platforms"] = json!(meta.platforms);
+        value["deps"] = json!(meta.deps);
+        value["updated_at"] = json!(meta.updated_at);
+    }
+    value
+}
+
+#[async_trait]
+impl Tool for ListSkillsTool {
+    fn name(&self) -> &str {
+        "list_skills"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "list_skills".into(),
+            description: "List installed agent skills as JSON (slug, name, description, source, version) to decide which one to activate with activate_skill. Set detail=true to also include unavailable skills with the reason, platforms and dependencies.".into(),
+            input_schema: schema_object(
+                json!({
+                    "detail": {
+                        "type": "boolean",
+                        "description": "Include unavailable skills and availability diagnostics (default: false)"
+                    }
+                }),
+                &[],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let detail = input
+            .get("detail")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let skills: Vec<serde_json::Value> = self
+            .skill_manager
+            .discover_skills_with_status(detail)
+            .iter()
+            .map(|skill| skill_json(skill, detail))
+            .collect();
+        let output = json!({
+            "count": skills.len(),
+            "skills": skills,
+        });
+        match serde_json::to_string_pretty(&output) {
+            Ok(text) => ToolResult::success(text),
+            Err(e) => ToolResult::error(format!("Failed to serialize skills: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn test_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "microclaw_list_skills_test_{}",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn create_skill(base_dir: &std::path::Path, slug: &str, frontmatter: &str) {
+        let skill_dir = base_dir.join(slug);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\n{frontmatter}\n---\nInstructions\n"),
+        )
+        .unwrap();
+    }
+
+    fn cleanup(dir: &std::path::Path) {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn fixture() -> PathBuf {
+        let dir = test_dir();
+        create_skill(
+            &dir,
+            "pdf-tools",
+            "name: pdf\ndescription: Convert to PDF\nsource: clawhub\nversion: 1.2.0",
+        );
+        create_skill(&dir, "notes", "name: notes\ndescription: Take notes");
+        create_skill(
+            &dir,
+            "missing-dep",
+            "name: missing-dep\ndescription: Needs a tool\ndeps: [microclaw-no-such-binary]",
+        );
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_json_shape() {
+        let dir = fixture();
+        let tool = ListSkillsTool::new(dir.to_str().unwrap());
+        let result = tool.execute(json!({})).await;
+        assert!(!result.is_error);
+
+        let value: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(value["count"], 2);
+        let skills = value["skills"].as_array().unwrap();
+        assert_eq!(skills[0]["slug"], "notes");
+        assert_eq!(skills[0]["version"], serde_json::Value::Null);
+        assert_eq!(skills[1]["slug"], "pdf-tools");
+        assert_eq!(skills[1]["name"], "pdf");
+        assert_eq!(skills[1]["description"], "Convert to PDF");
+        assert_eq!(skills[1]["source"], "clawhub");
+        assert_eq!(skills[1]["version"], "1.2.0");
+        assert!(skills[1].get("available").is_none());
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_detail_includes_unavailable() {
+        let dir = fixture();
+        let tool = ListSkillsTool::new(dir.to_str().unwrap());
+        let result = tool.execute(json!({"detail": true})).await;
+        assert!(!result.is_error);
+
+        let value: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(value["count"], 3);
+        let missing = value["skills"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["slug"] == "missing-dep")
+            .unwrap();
+        assert_eq!(missing["available"], false);
+        assert!(missing["reason"]
+            .as_str()
+            .unwrap()
+            .contains("microclaw-no-such-binary"));
+        assert_eq!(missing["deps"], json!(["microclaw-no-such-binary"]));
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_empty_dir() {
+        let dir = test_dir();
+        let tool = ListSkillsTool::new(dir.to_str().unwrap());
+        let result = tool.execute(json!({})).await;
+        assert!(!result.is_error);
+        let value: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(value["count"], 0);
+        assert_eq!(value["skills"], json!([]));
+    }
+}
@@ -2,13 +2,18 @@ pub mod activate_skill;
 pub mod bash;
 pub mod browser;
 pub mod edit_file;
+pub mod edit_message;
 pub mod export_chat;
+pub mod fetch_url;
 pub mod glob;
 pub mod grep;
+pub mod list_skills;
 pub mod mcp;
 pub mod memory;
+pub mod react;
 pub mod read_file;
 pub mod schedule;
+pub mod search_history;
 pub mod send_message;
 pub mod structured_memory;
 pub mod sub_agent;
@@ -27,9 +32,9 @@ use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::Database;
 pub use microclaw_tools::runtime::{
-    auth_context_from_input, authorize_chat_access, resolve_tool_path, resolve_tool_working_dir,
-    schema_object, tool_execution_policy, tool_risk, validate_execution_policy, Tool,
-    ToolAuthContext, ToolResult, ToolRisk,
+    auth_context_from_input, authorize_chat_access, resolve_confined_tool_path, resolve_tool_path,
+    resolve_tool_working_dir, schema_object, tool_execution_policy, tool_risk,
+    validate_execution_policy, Tool, ToolAuthContext, ToolResult, ToolRisk,
 };
 use microclaw_tools::runtime::{inject_auth_context, require_high_risk_approval};
 use microclaw_tools::sandbox::{SandboxMode, SandboxRouter};
@@ -75,16 +80,31 @@ impl ToolRegistry {
             ),
             Box::new(
                 browser::BrowserTool::new(&config.data_dir)
-                    .with_default_timeout_secs(config.tool_timeout_secs("browser", 30)),
+                    .with_default_timeout_secs(config.tool_timeout_secs("browser", 30))
+                    .with_binary(config.browser_binary.clone())
+                    .with_session(config.browser_session_name())
+                    .with_allowed_commands(config.browser_allowed_commands.clone())
+                    .with_allow_all_commands(config.browser_allow_all_commands)
+                    .with_max_sessions(config.browser_max_sessions)
+                    .with_session_idle_secs(config.browser_session_idle_secs)
+                    .with_max_output_chars(config.browser_max_output_chars)
+                    .with_working_dir(&config.working_dir, config.working_dir_isolation),
+            ),
+            Box::new(
+                read_file::ReadFileTool::new_with_isolation(
+                    &config.working_dir,
+                    config.working_dir_isolation,
+                )
+                .with_confine_to_working_dir(config.file_tools_confine_to_working_dir)
+                .with_max_bytes(config.read_file_max_bytes),
+            ),
+            Box::new(
+                write_file::WriteFileTool::new_with_isolation(
+                    &config.working_dir,
+                    config.working_dir_isolation,
+                )
+                .with_confine_to_working_dir(config.file_tools_confine_to_working_dir),
             ),
-            Box::new(read_file::ReadFileTool::new_with_isolation(
-                &config.working_dir,
-                config.working_dir_isolation,
-            )),
-            Box::new(write_file::WriteFileTool::new_with_isolation(
-                &config.working_dir,
-                config.working_dir_isolation,
-            )),
             Box::new(edit_file::EditFileTool::new_with_isolation(
                 &config.working_dir,
                 config.working_dir_isolation,
@@ -108,6 +128,15 @@ impl ToolRegistry {
                 config.web_fetch_validation,
                 config.web_fetch_url_validation.clone(),
             )),
+            Box::new(
+                fetch_url::FetchUrlTool::new(
+                    &config.http_client_settings(),
+                    config.tool_timeout_secs("fetch_url", 15),
+                    config.fetch_url_max_bytes,
+                    config.web_fetch_url_validation.clone(),
+                )
+                .with_allow_private_networks(config.fetch_url_allow_private_networks),
+            ),
             Box::new(web_search::WebSearchTool::new(
                 config.tool_timeout_secs("web_search", 15),
             )),
@@ -121,6 +150,11 @@ impl ToolRegistry {
                 },
                 config.bot_username_overrides(),
             )),
+            Box::new(react::ReactTool::new(channel_registry.clone(), db.clone())),
+            Box::new(edit_message::EditMessageTool::new(
+                channel_registry.clone(),
+                db.clone(),
+            )),
             Box::new(schedule::ScheduleTaskTool::new(
                 channel_registry.clone(),
                 db.clone(),
@@ -160,6 +194,7 @@ impl ToolRegistry {
             )),
             Box::new(sub_agent::SubAgentTool::new(config, db.clone())),
             Box::new(activate_skill::ActivateSkillTool::new(&skills_data_dir)),
+            Box::new(list_skills::ListSkillsTool::new(&skills_data_dir)),
             Box::new(sync_skills::SyncSkillsTool::new(&skills_data_dir)),
             Box::new(todo::TodoReadTool::new(&config.data_dir)),
             Box::new(todo::TodoWriteTool::new(&config.data_dir)),
@@ -220,16 +255,31 @@ impl ToolRegistry {
             ),
             Box::new(
                 browser::BrowserTool::new(&config.data_dir)
-                    .with_default_timeout_secs(config.tool_timeout_secs("browser", 30)),
+                    .with_default_timeout_secs(config.tool_timeout_secs("browser", 30))
+                    .with_binary(config.browser_binary.clone())
+                    .with_session(config.browser_session_name())
+                    .with_allowed_commands(config.browser_allowed_commands.clone())
+                    .with_allow_all_commands(config.browser_allow_all_commands)
+                    .with_max_sessions(config.browser_max_sessions)
+                    .with_session_idle_secs(config.browser_session_idle_secs)
+                    .with_max_output_chars(config.browser_max_output_chars)
+                    .with_working_dir(&config.working_dir, config.working_dir_isolation),
+            ),
+            Box::new(
+                read_file::ReadFileTool::new_with_isolation(
+                    &config.working_dir,
+                    config.working_dir_isolation,
+                )
+                .with_confine_to_working_dir(config.file_tools_confine_to_working_dir)
+                .with_max_bytes(config.read_file_max_bytes),
+            ),
+            Box::new(
+                write_file::WriteFileTool::new_with_isolation(
+                    &config.working_dir,
+                    config.working_dir_isolation,
+                )
+                .with_confine_to_working_dir(config.file_tools_confine_to_working_dir),
             ),
-            Box::new(read_file::ReadFileTool::new_with_isolation(
-                &config.working_dir,
-                config.working_dir_isolation,
-            )),
-            Box::new(write_file::WriteFileTool::new_with_isolation(
-                &config.working_dir,
-                config.working_dir_isolation,
-            )),
             Box::new(edit_file::EditFileTool::new_with_isolation(
                 &config.working_dir,
                 config.working_dir_isolation,
@@ -252,6 +302,7 @@ impl ToolRegistry {
                 config.tool_timeout_secs("web_search", 15),
             )),
             Box::new(activate_skill::ActivateSkillTool::new(&skills_data_dir)),
+            Box::new(list_skills::ListSkillsTool::new(&skills_data_dir)),
             Box::new(structured_memory::StructuredMemorySearchTool::new(
                 db,
                 memory_backend,
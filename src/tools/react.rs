@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::info;
+
+use super::{authorize_chat_access, schema_object, Tool, ToolResult};
+use microclaw_channels::channel::{
+    enforce_channel_policy, remove_chat_reaction, send_chat_reaction,
+};
+use microclaw_channels::channel_adapter::ChannelRegistry;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::{call_blocking, Database};
+
+pub struct ReactTool {
+    registry: Arc<ChannelRegistry>,
+    db: Arc<Database>,
+}
+
+/// An emoji reaction is a short token without whitespace that isn't plain text.
+fn validate_emoji(emoji: &str) -> Result<(), String> {
+    if emoji.is_empty() {
+        return Err("Missing required parameter: emoji".into());
+    }
+    if emoji.chars().count() > 16
+        || emoji.contains(char::is_whitespace)
+        || emoji.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(format!("'{emoji}' is not a valid emoji reaction"));
+    }
+    Ok(())
+}
+
+impl ReactTool {
+    pub fn new(registry: Arc<ChannelRegistry>, db: Arc<Database>) -> Self {
+        ReactTool { registry, db }
+    }
+
+    /// Id of the newest user message in the chat, the default reaction target.
+    async fn latest_user_message_id(&self, chat_id: i64) -> Result<String, String> {
+        let history = call_blocking(self.db.clone(), move |db| {
+            db.get_recent_messages(chat_id, 20)
+        })
+        .await
+        .map_err(|e| format!("Failed to load recent messages: {e}"))?;
+        history
+            .into_iter()
+            .rev()
+            .find(|m| !m.is_from_bot)
+            .map(|m| m.id)
+            .ok_or_else(|| format!("No user message to react to in chat {chat_id}"))
+    }
+}
+
+#[async_trait]
+impl Tool for ReactTool {
+    fn name(&self) -> &str {
+        "react"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "react".into(),
+            description: "React to a message with an emoji (Telegram, Discord, Matrix). Without target_message_id, reacts to the latest user message in the chat. Set remove to withdraw a reaction the bot added earlier (Matrix).".into(),
+            input_schema: schema_object(
+                json!({
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "The chat containing the message"
+                    },
+                    "target_message_id": {
+                        "type": "string",
+                        "description": "Channel message id to react to (defaults to the latest user message)"
+                    },
+                    "emoji": {
+                        "type": "string",
+                        "description": "The reaction emoji, e.g. 👍"
+                    },
+                    "remove": {
+                        "type": "boolean",
+                        "description": "Withdraw the bot's earlier reaction with this emoji instead of adding one (default false)"
+                    }
+                }),
+                &["chat_id", "emoji"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => id,
+            None => return ToolResult::error("Missing required parameter: chat_id".into()),
+        };
+        let emoji = input
+            .get("emoji")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim();
+        if let Err(e) = validate_emoji(emoji) {
+            return ToolResult::error(e);
+        }
+        let remove = input
+            .get("remove")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let target = input
+            .get("target_message_id")
+            .and_then(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .or_else(|| v.as_i64().map(|id| id.to_string()))
+            })
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) =
+            enforce_channel_policy(&self.registry, self.db.clone(), &input, chat_id).await
+        {
+            return ToolResult::error(e);
+        }
+
+        let target = match target {
+            Some(id) => id,
+            None => match self.latest_user_message_id(chat_id).await {
+                Ok(id) => id,
+                Err(e) => return ToolResult::error(e),
+            },
+        };
+
+        info!("react: chat_id={chat_id}, target={target}, emoji={emoji}, remove={remove}");
+        if remove {
+            return match remove_chat_reaction(
+                &self.registry,
+                self.db.clone(),
+                chat_id,
+                &target,
+                emoji,
+            )
+            .await
+            {
+                Ok(()) => ToolResult::success(format!("Removed {emoji} from message {target}.")),
+                Err(e) => ToolResult::error(e),
+            };
+        }
+        match send_chat_reaction(&self.registry, self.db.clone(), chat_id, &target, emoji).await {
+            Ok(()) => ToolResult::success(format!("Reacted {emoji} to message {target}.")),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::WebAdapter;
+    use microclaw_storage::db::StoredMessage;
+
+    fn test_tool() -> (ReactTool, Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("microclaw_react_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let mut registry = ChannelRegistry::new();
+        registry.register(Arc::new(WebAdapter));
+        (ReactTool::new(Arc::new(registry), db.clone()), db, dir)
+    }
+
+    #[test]
+    fn test_validate_emoji() {
+        assert!(validate_emoji("👍").is_ok());
+        assert!(validate_emoji("❤‍🔥").is_ok());
+        assert!(validate_emoji("").is_err());
+        assert!(validate_emoji("thanks").is_err());
+        assert!(validate_emoji("👍 👍").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_react_argument_validation() {
+        let (tool, _db, dir) = test_tool();
+        let result = tool.execute(json!({"emoji": "👍"})).await;
+        assert!(result
+            .content
+            .contains("Missing required parameter: chat_id"));
+        let result = tool.execute(json!({"chat_id": 1})).await;
+        assert!(result.content.contains("Missing required parameter: emoji"));
+        let result = tool.execute(json!({"chat_id": 1, "emoji": "ok"})).await;
+        assert!(result.content.contains("not a valid emoji"));
+        let result = tool
+            .execute(json!({
+                "chat_id": 2,
+                "emoji": "👍",
+                "__microclaw_auth": {"caller_chat_id": 1, "control_chat_ids": []}
+            }))
+            .await;
+        assert!(result.content.contains("Permission denied"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_react_unsupported_channel() {
+        let (tool, db, dir) = test_tool();
+        db.upsert_chat(5, Some("web-main"), "web").unwrap();
+        db.store_message(&StoredMessage {
+            id: "m1".into(),
+            chat_id: 5,
+            sender_name: "alice".into(),
+            content: "hi".into(),
+            is_from_bot: false,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        })
+        .unwrap();
+
+        let result = tool.execute(json!({"chat_id": 5, "emoji": "👍"})).await;
+        assert!(result.is_error);
+        assert!(result
+            .content
+            .contains("reactions not supported on this channel"));
+
+        let result = tool
+            .execute(json!({"chat_id": 5, "emoji": "👍", "remove": true}))
+            .await;
+        assert!(result.is_error);
+        assert!(result
+            .content
+            .contains("reactions not supported on this channel"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
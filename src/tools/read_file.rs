@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde_json::json;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
 use tracing::info;
 
 use crate::config::WorkingDirIsolation;
@@ -11,6 +12,38 @@ use super::{schema_object, Tool, ToolResult};
 pub struct ReadFileTool {
     working_dir: PathBuf,
     working_dir_isolation: WorkingDirIsolation,
+    confine_to_working_dir: bool,
+    max_bytes: u64,
+}
+
+/// Read at most `max_bytes` (0 = no limit) of a UTF-8 file. Returns the text
+/// and whether it was cut short; a multi-byte character split by the cut is
+/// dropped.
+async fn read_capped(path: &Path, max_bytes: u64) -> std::io::Result<(String, bool)> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = Vec::new();
+    if max_bytes == 0 {
+        file.read_to_end(&mut buf).await?;
+    } else {
+        file.take(max_bytes + 1).read_to_end(&mut buf).await?;
+    }
+    let truncated = max_bytes > 0 && buf.len() as u64 > max_bytes;
+    if truncated {
+        buf.truncate(max_bytes as usize);
+    }
+    match String::from_utf8(buf) {
+        Ok(text) => Ok((text, truncated)),
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            Ok((String::from_utf8(bytes).unwrap_or_default(), truncated))
+        }
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )),
+    }
 }
 
 impl ReadFileTool {
@@ -25,8 +58,22 @@ impl ReadFileTool {
         Self {
             working_dir: PathBuf::from(working_dir),
             working_dir_isolation,
+            confine_to_working_dir: false,
+            max_bytes: 0,
         }
     }
+
+    /// Reject absolute paths and `..` instead of resolving them.
+    pub fn with_confine_to_working_dir(mut self, confine: bool) -> Self {
+        self.confine_to_working_dir = confine;
+        self
+    }
+
+    /// Stop reading after this many bytes (0 = no limit).
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
 }
 
 #[async_trait]
@@ -66,7 +113,14 @@ impl Tool for ReadFileTool {
         };
         let working_dir =
             super::resolve_tool_working_dir(&self.working_dir, self.working_dir_isolation, &input);
-        let resolved_path = super::resolve_tool_path(&working_dir, path);
+        let resolved_path = if self.confine_to_working_dir {
+            match super::resolve_confined_tool_path(&working_dir, path) {
+                Ok(p) => p,
+                Err(e) => return ToolResult::error(e),
+            }
+        } else {
+            super::resolve_tool_path(&working_dir, path)
+        };
         let resolved_path_str = resolved_path.to_string_lossy().to_string();
 
         if let Err(msg) = microclaw_tools::path_guard::check_path(&resolved_path_str) {
@@ -75,8 +129,8 @@ impl Tool for ReadFileTool {
 
         info!("Reading file: {}", resolved_path.display());
 
-        let content = match tokio::fs::read_to_string(&resolved_path).await {
-            Ok(c) => c,
+        let (content, truncated) = match read_capped(&resolved_path, self.max_bytes).await {
+            Ok(read) => read,
             Err(e) => return ToolResult::error(format!("Failed to read file: {e}")),
         };
 
@@ -99,7 +153,14 @@ impl Tool for ReadFileTool {
             .map(|(i, line)| format!("{:>6}\t{}", offset + i + 1, line))
             .collect();
 
-        ToolResult::success(selected.join("\n"))
+        let mut output = selected.join("\n");
+        if truncated {
+            output.push_str(&format!(
+                "\n... (read stopped at the {}-byte limit)",
+                self.max_bytes
+            ));
+        }
+        ToolResult::success(output)
     }
 }
 
@@ -162,6 +223,40 @@ mod tests {
         assert!(result.content.contains("Missing 'path'"));
     }
 
+    #[tokio::test]
+    async fn test_read_file_stops_at_byte_cap() {
+        let dir = std::env::temp_dir().join(format!("microclaw_rf4_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("big.txt");
+        std::fs::write(&file, "abcdé\nsecond line").unwrap();
+
+        // The cap lands inside 'é', which is dropped rather than mangled.
+        let tool = ReadFileTool::new(".").with_max_bytes(5);
+        let result = tool.execute(json!({"path": file.to_str().unwrap()})).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("1\tabcd\n"));
+        assert!(!result.content.contains("second"));
+        assert!(result.content.contains("5-byte limit"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_confined_rejects_traversal() {
+        let root = std::env::temp_dir().join(format!("microclaw_rf5_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("shared")).unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+
+        let tool = ReadFileTool::new(root.to_str().unwrap()).with_confine_to_working_dir(true);
+        for path in ["../secret.txt", root.join("secret.txt").to_str().unwrap()] {
+            let result = tool.execute(json!({ "path": path })).await;
+            assert!(result.is_error);
+            assert!(result.content.contains("outside the working directory"));
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[tokio::test]
     async fn test_read_file_resolves_relative_to_working_dir() {
         let root = std::env::temp_dir().join(format!("microclaw_rf3_{}", uuid::Uuid::new_v4()));
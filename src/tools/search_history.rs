@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{auth_context_from_input, authorize_chat_access, schema_object, Tool, ToolResult};
+use crate::embedding::EmbeddingProvider;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_core::text::floor_char_boundary;
+use microclaw_storage::db::{Database, StoredMessage};
+
+const SNIPPET_CHARS: usize = 200;
+pub const DEFAULT_SEARCH_LIMIT: usize = 5;
+const MAX_SEARCH_LIMIT: usize = 20;
+
+/// A stored message matched by a semantic history search.
+#[derive(Debug, Clone)]
+pub struct HistoryHit {
+    pub message: StoredMessage,
+    pub distance: f32,
+}
+
+/// Embed `query` and return the nearest stored messages, optionally limited
+/// to one chat. Errors are user-facing strings explaining why search is
+/// unavailable or what failed.
+pub async fn search_history(
+    db: Arc<Database>,
+    embedding: Option<&Arc<dyn EmbeddingProvider>>,
+    query: &str,
+    chat_id: Option<i64>,
+    limit: usize,
+) -> Result<Vec<HistoryHit>, String> {
+    #[cfg(not(feature = "sqlite-vec"))]
+    {
+        let _ = (db, embedding, query, chat_id, limit);
+        Err("History search is unavailable: this build was compiled without the `sqlite-vec` feature.".into())
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    {
+        let Some(provider) = embedding else {
+            return Err(
+                "History search is unavailable: no embedding provider is configured (set `embedding_provider`)."
+                    .into(),
+            );
+        };
+        let query_vec = provider
+            .embed(query)
+            .await
+            .map_err(|e| format!("Failed to embed query: {e}"))?;
+        let rows = microclaw_storage::db::call_blocking(db, move |db| {
+            db.knn_messages(&query_vec, limit, chat_id)
+        })
+        .await
+        .map_err(|e| format!("History search failed: {e}"))?;
+        Ok(rows
+            .into_iter()
+            .map(|(message, distance)| HistoryHit { message, distance })
+            .collect())
+    }
+}
+
+/// Render hits as one line per message: chat, timestamp, sender and a
+/// single-line snippet.
+pub fn format_history_hits(hits: &[HistoryHit]) -> String {
+    if hits.is_empty() {
+        return "No matching messages found.".to_string();
+    }
+    hits.iter()
+        .map(|hit| {
+            let msg = &hit.message;
+            let sender = if msg.is_from_bot {
+                "bot"
+            } else {
+                msg.sender_name.as_str()
+            };
+            format!(
+                "[chat {}] [{}] {}: {}",
+                msg.chat_id,
+                msg.timestamp,
+                sender,
+                snippet(&msg.content)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn snippet(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.len() <= SNIPPET_CHARS {
+        return flat;
+    }
+    let end = floor_char_boundary(&flat, SNIPPET_CHARS);
+    format!("{}…", &flat[..end])
+}
+
+pub struct SearchHistoryTool {
+    db: Arc<Database>,
+    embedding: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+impl SearchHistoryTool {
+    pub fn new(db: Arc<Database>, embedding: Option<Arc<dyn EmbeddingProvider>>) -> Self {
+        SearchHistoryTool { db, embedding }
+    }
+}
+
+#[async_trait]
+impl Tool for SearchHistoryTool {
+    fn name(&self) -> &str {
+        "search_history"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "search_history".into(),
+            description: "Semantically search past chat messages. Returns the closest matching messages with chat id and timestamp, even when they share no keywords with the query.".into(),
+            input_schema: schema_object(
+                json!({
+                    "query": {
+                        "type": "string",
+                        "description": "What to look for, in natural language"
+                    },
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "Chat to search (defaults to the current chat)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of results to return (default 5, max 20)"
+                    }
+                }),
+                &["query"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let query = match input.get("query").and_then(|v| v.as_str()) {
+            Some(q) if !q.trim().is_empty() => q.trim().to_string(),
+            _ => return ToolResult::error("Missing or empty 'query' parameter".into()),
+        };
+        let limit = input
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, MAX_SEARCH_LIMIT))
+            .unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => {
+                if let Err(e) = authorize_chat_access(&input, id) {
+                    return ToolResult::error(e);
+                }
+                Some(id)
+            }
+            None => auth_context_from_input(&input).map(|a| a.caller_chat_id),
+        };
+
+        match search_history(
+            self.db.clone(),
+            self.embedding.as_ref(),
+            &query,
+            chat_id,
+            limit,
+        )
+        .await
+        {
+            Ok(hits) => ToolResult::success(format_history_hits(&hits)),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> (Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("mc_search_hist_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        (db, dir)
+    }
+
+    #[test]
+    fn test_format_history_hits_truncates_and_flattens() {
+        let hits = vec![HistoryHit {
+            message: StoredMessage {
+                id: "m1".into(),
+                chat_id: 7,
+                sender_name: "alice".into(),
+                content: format!("line one\nline two {}", "x".repeat(300)),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            },
+            distance: 0.1,
+        }];
+        let text = format_history_hits(&hits);
+        assert!(text.starts_with("[chat 7] [2024-01-01T00:00:00Z] alice: line one line two"));
+        assert!(text.ends_with('…'));
+        assert_eq!(format_history_hits(&[]), "No matching messages found.");
+    }
+
+    #[tokio::test]
+    async fn test_search_without_embedding_reports_unavailable() {
+        let (db, dir) = test_db();
+        let tool = SearchHistoryTool::new(db, None);
+        let result = tool.execute(json!({"query": "deploy"})).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("History search is unavailable"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    struct KeywordEmbedding;
+
+    #[cfg(feature = "sqlite-vec")]
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedding {
+        async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            Ok(vec![
+                if text.contains("deploy") { 1.0 } else { 0.0 },
+                if text.contains("lunch") { 1.0 } else { 0.0 },
+                0.1,
+            ])
+        }
+        fn model(&self) -> &str {
+            "keyword-test"
+        }
+        fn dimension(&self) -> usize {
+            3
+        }
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[tokio::test]
+    async fn test_search_history_returns_nearest_messages() {
+        let (db, dir) = test_db();
+        db.prepare_vector_index(3).unwrap();
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(KeywordEmbedding);
+        for (id, chat_id, content) in [
+            ("m1", 100, "Deploy finished on staging"),
+            ("m2", 100, "Lunch at the usual place?"),
+            ("m3", 200, "Deploy to prod is blocked"),
+        ] {
+            db.store_message(&StoredMessage {
+                id: id.into(),
+                chat_id,
+                sender_name: "alice".into(),
+                content: content.into(),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:00Z".into(),
+            })
+            .unwrap();
+            let vector = provider.embed(content).await.unwrap();
+            db.upsert_message_vec(id, chat_id, &vector).unwrap();
+        }
+
+        let tool = SearchHistoryTool::new(db.clone(), Some(provider));
+        let result = tool
+            .execute(json!({
+                "query": "how did the deploy go",
+                "limit": 1,
+                "__microclaw_auth": {"caller_chat_id": 100, "control_chat_ids": []}
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("[chat 100]"));
+        assert!(result.content.contains("Deploy finished on staging"));
+        assert!(!result.content.contains("prod"));
+
+        let denied = tool
+            .execute(json!({
+                "query": "deploy",
+                "chat_id": 200,
+                "__microclaw_auth": {"caller_chat_id": 100, "control_chat_ids": []}
+            }))
+            .await;
+        assert!(denied.is_error);
+        assert!(denied.content.contains("Permission denied"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -7,7 +7,7 @@ use tracing::{info, warn};
 
 use super::{authorize_chat_access, schema_object, Tool, ToolResult};
 use microclaw_channels::channel::{
-    deliver_and_store_bot_message, enforce_channel_policy, get_required_chat_routing,
+    deliver_and_store_bot_message_with_id, enforce_channel_policy, get_required_chat_routing,
 };
 use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_core::llm_types::ToolDefinition;
@@ -78,7 +78,7 @@ impl Tool for SendMessageTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "send_message".into(),
-            description: "Send a message mid-conversation. Supports text for all channels, and attachments for Telegram/Discord/Slack via attachment_path.".into(),
+            description: "Send a message mid-conversation. Supports text for all channels, and attachments via attachment_path on channels that support them (e.g. Telegram, Discord, Slack, Matrix).".into(),
             input_schema: schema_object(
                 json!({
                     "chat_id": {
@@ -156,6 +156,22 @@ impl Tool for SendMessageTool {
                 chat_id, routing.channel_name, path
             );
 
+            let adapter = match self.registry.get(&routing.channel_name) {
+                Some(a) => a,
+                None => {
+                    return ToolResult::error(format!(
+                        "No adapter registered for channel '{}'",
+                        routing.channel_name
+                    ))
+                }
+            };
+            if !adapter.capabilities().attachments {
+                return ToolResult::error(format!(
+                    "attachments not supported for {}",
+                    routing.channel_name
+                ));
+            }
+
             let file_path = PathBuf::from(&path);
             if !file_path.is_file() {
                 warn!(
@@ -179,16 +195,6 @@ impl Tool for SendMessageTool {
                 }
             });
 
-            let adapter = match self.registry.get(&routing.channel_name) {
-                Some(a) => a,
-                None => {
-                    return ToolResult::error(format!(
-                        "No adapter registered for channel '{}'",
-                        routing.channel_name
-                    ))
-                }
-            };
-
             let external_chat_id = match self.resolve_external_chat_id(chat_id).await {
                 Ok(v) => v,
                 Err(e) => return ToolResult::error(e),
@@ -231,7 +237,7 @@ impl Tool for SendMessageTool {
                     Ok(routing) => self.bot_username_for_channel(&routing.channel_name),
                     Err(_) => self.default_bot_username.clone(),
                 };
-            match deliver_and_store_bot_message(
+            match deliver_and_store_bot_message_with_id(
                 &self.registry,
                 self.db.clone(),
                 &sender_name,
@@ -240,9 +246,11 @@ impl Tool for SendMessageTool {
             )
             .await
             {
-                Ok(_) => {
+                Ok(message_id) => {
                     info!("send_message text sent: chat_id={}", chat_id);
-                    ToolResult::success("Message sent successfully.".into())
+                    ToolResult::success(format!(
+                        "Message sent successfully (message_id: {message_id})."
+                    ))
                 }
                 Err(e) => {
                     warn!(
@@ -262,6 +270,7 @@ mod tests {
     use crate::web::WebAdapter;
     use microclaw_channels::channel::ConversationKind;
     use microclaw_channels::channel_adapter::ChannelAdapter;
+    use microclaw_channels::channel_adapter::ChannelCapabilities;
     use microclaw_channels::channel_adapter::ChannelRegistry;
     use serde_json::json;
     use std::path::Path;
@@ -300,6 +309,13 @@ mod tests {
             true
         }
 
+        fn capabilities(&self) -> ChannelCapabilities {
+            ChannelCapabilities {
+                attachments: true,
+                ..ChannelCapabilities::default()
+            }
+        }
+
         async fn send_text(&self, _external_chat_id: &str, _text: &str) -> Result<(), String> {
             Ok(())
         }
@@ -314,6 +330,50 @@ mod tests {
         }
     }
 
+    /// Stands in for a network adapter and records what it was asked to send.
+    struct RecordingAdapter {
+        name: &'static str,
+        routes: Vec<(&'static str, ConversationKind)>,
+        sent: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    impl RecordingAdapter {
+        fn new(name: &'static str, routes: Vec<(&'static str, ConversationKind)>) -> Self {
+            Self {
+                name,
+                routes,
+                sent: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ChannelAdapter for RecordingAdapter {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
+            self.routes.clone()
+        }
+
+        async fn send_text(&self, external_chat_id: &str, text: &str) -> Result<(), String> {
+            self.send_text_with_id(external_chat_id, text)
+                .await
+                .map(|_| ())
+        }
+
+        async fn send_text_with_id(
+            &self,
+            external_chat_id: &str,
+            text: &str,
+        ) -> Result<Option<String>, String> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push((external_chat_id.to_string(), text.to_string()));
+            Ok(Some(format!("{}-msg-{}", self.name, sent.len())))
+        }
+    }
+
     #[tokio::test]
     async fn test_send_message_permission_denied_before_network() {
         let (db, dir) = test_db();
@@ -338,6 +398,63 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[tokio::test]
+    async fn test_send_message_routes_matrix_chat_to_matrix_adapter() {
+        let (db, dir) = test_db();
+        let matrix_chat = db
+            .resolve_or_create_chat_id("matrix", "!room:example.org", Some("ops"), "matrix")
+            .unwrap();
+        db.upsert_chat(4242, Some("legacy"), "private").unwrap();
+
+        let matrix = Arc::new(RecordingAdapter::new(
+            "matrix",
+            vec![
+                ("matrix", ConversationKind::Group),
+                ("matrix_dm", ConversationKind::Private),
+            ],
+        ));
+        let telegram = Arc::new(RecordingAdapter::new(
+            "telegram",
+            vec![("private", ConversationKind::Private)],
+        ));
+        let mut registry = ChannelRegistry::new();
+        registry.register(matrix.clone());
+        registry.register(telegram.clone());
+        let tool = SendMessageTool::new(
+            Arc::new(registry),
+            db.clone(),
+            "bot".into(),
+            std::collections::HashMap::new(),
+        );
+
+        let result = tool
+            .execute(json!({"chat_id": matrix_chat, "text": "deploy finished"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("matrix-msg-1"));
+        assert_eq!(
+            matrix.sent.lock().unwrap().as_slice(),
+            &[(
+                "!room:example.org".to_string(),
+                "deploy finished".to_string()
+            )]
+        );
+        assert!(telegram.sent.lock().unwrap().is_empty());
+        let stored = db.get_all_messages(matrix_chat).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].id, "matrix-msg-1");
+        assert!(stored[0].is_from_bot);
+
+        // Legacy chat types without a channel column still resolve to Telegram.
+        let result = tool
+            .execute(json!({"chat_id": 4242, "text": "legacy hello"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(telegram.sent.lock().unwrap().len(), 1);
+        assert_eq!(matrix.sent.lock().unwrap().len(), 1);
+        cleanup(&dir);
+    }
+
     #[tokio::test]
     async fn test_send_message_web_target_writes_to_db() {
         let (db, dir) = test_db();
@@ -501,4 +618,49 @@ mod tests {
         assert!(result.content.contains("not supported for web"));
         cleanup(&dir);
     }
+
+    #[tokio::test]
+    async fn test_send_attachment_allowed_for_matrix() {
+        use crate::channels::matrix::MatrixAdapter;
+
+        let adapter = MatrixAdapter::new(
+            "matrix".into(),
+            "http://127.0.0.1:9".into(),
+            "token".into(),
+            true,
+            reqwest::Client::new(),
+        );
+        assert!(adapter.capabilities().attachments);
+
+        let (db, dir) = test_db();
+        let chat_id = db
+            .resolve_or_create_chat_id("matrix", "!room:example.org", Some("room"), "matrix")
+            .unwrap();
+        let attachment = dir.join("report.txt");
+        std::fs::write(&attachment, "hello").unwrap();
+
+        let mut registry = ChannelRegistry::new();
+        registry.register(Arc::new(adapter));
+        let tool = SendMessageTool::new(
+            Arc::new(registry),
+            db,
+            "bot".into(),
+            std::collections::HashMap::new(),
+        );
+        let result = tool
+            .execute(json!({
+                "chat_id": chat_id,
+                "attachment_path": attachment.to_string_lossy(),
+            }))
+            .await;
+        // Delivery fails against the unreachable homeserver, but the tool
+        // must get that far instead of rejecting the attachment up front.
+        assert!(result.is_error);
+        assert!(
+            !result.content.contains("not supported"),
+            "{}",
+            result.content
+        );
+        cleanup(&dir);
+    }
 }
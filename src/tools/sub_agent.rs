@@ -99,8 +99,10 @@ impl Tool for SubAgentTool {
                     .as_ref()
                     .map(|a| a.caller_channel.clone())
                     .unwrap_or_else(|| "sub_agent".to_string());
-                let provider = self.config.llm_provider.clone();
-                let model = self.config.model.clone();
+                let (provider, model) = match &response.served_by {
+                    Some(served) => (served.provider.clone(), served.model.clone()),
+                    None => (self.config.llm_provider.clone(), self.config.model.clone()),
+                };
                 let input_tokens = i64::from(usage.input_tokens);
                 let output_tokens = i64::from(usage.output_tokens);
                 let _ = call_blocking(self.db.clone(), move |db| {
@@ -265,7 +267,7 @@ mod tests {
         let config = test_config();
         let registry = ToolRegistry::new_sub_agent(&config, test_db());
         let defs = registry.definitions();
-        assert_eq!(defs.len(), 12);
+        assert_eq!(defs.len(), 13);
     }
 
     #[test]
@@ -286,6 +288,7 @@ mod tests {
         assert!(names.contains(&"web_fetch"));
         assert!(names.contains(&"read_memory"));
         assert!(names.contains(&"structured_memory_search"));
+        assert!(names.contains(&"list_skills"));
 
         // Should NOT include
         assert!(!names.contains(&"sub_agent"));
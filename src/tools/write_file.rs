@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde_json::json;
 use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
 use tracing::info;
 
 use crate::config::WorkingDirIsolation;
@@ -11,6 +12,7 @@ use super::{schema_object, Tool, ToolResult};
 pub struct WriteFileTool {
     working_dir: PathBuf,
     working_dir_isolation: WorkingDirIsolation,
+    confine_to_working_dir: bool,
 }
 
 impl WriteFileTool {
@@ -25,8 +27,15 @@ impl WriteFileTool {
         Self {
             working_dir: PathBuf::from(working_dir),
             working_dir_isolation,
+            confine_to_working_dir: false,
         }
     }
+
+    /// Reject absolute paths and `..` instead of resolving them.
+    pub fn with_confine_to_working_dir(mut self, confine: bool) -> Self {
+        self.confine_to_working_dir = confine;
+        self
+    }
 }
 
 #[async_trait]
@@ -38,7 +47,7 @@ impl Tool for WriteFileTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "write_file".into(),
-            description: "Write content to a file. Creates the file and any parent directories if they don't exist. Overwrites existing content.".into(),
+            description: "Write content to a file. Creates the file and any parent directories if they don't exist. Overwrites existing content unless `append` is true.".into(),
             input_schema: schema_object(
                 json!({
                     "path": {
@@ -48,6 +57,10 @@ impl Tool for WriteFileTool {
                     "content": {
                         "type": "string",
                         "description": "The content to write"
+                    },
+                    "append": {
+                        "type": "boolean",
+                        "description": "Append to the end of the file instead of overwriting it (default false)"
                     }
                 }),
                 &["path", "content"],
@@ -62,7 +75,14 @@ impl Tool for WriteFileTool {
         };
         let working_dir =
             super::resolve_tool_working_dir(&self.working_dir, self.working_dir_isolation, &input);
-        let resolved_path = super::resolve_tool_path(&working_dir, path);
+        let resolved_path = if self.confine_to_working_dir {
+            match super::resolve_confined_tool_path(&working_dir, path) {
+                Ok(p) => p,
+                Err(e) => return ToolResult::error(e),
+            }
+        } else {
+            super::resolve_tool_path(&working_dir, path)
+        };
         let resolved_path_str = resolved_path.to_string_lossy().to_string();
 
         if let Err(msg) = microclaw_tools::path_guard::check_path(&resolved_path_str) {
@@ -98,6 +118,30 @@ impl Tool for WriteFileTool {
             }
         }
 
+        let append = input
+            .get("append")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if append {
+            let appended = async {
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&resolved_path)
+                    .await?;
+                file.write_all(content.as_bytes()).await?;
+                file.flush().await
+            }
+            .await;
+            return match appended {
+                Ok(()) => ToolResult::success(format!(
+                    "Successfully appended to {}",
+                    resolved_path.display()
+                )),
+                Err(e) => ToolResult::error(format!("Failed to write file: {e}")),
+            };
+        }
+
         match tokio::fs::write(&resolved_path, content).await {
             Ok(()) => {
                 ToolResult::success(format!("Successfully wrote to {}", resolved_path.display()))
@@ -159,6 +203,46 @@ mod tests {
         assert!(result.content.contains("Missing 'content'"));
     }
 
+    #[tokio::test]
+    async fn test_write_file_confined_rejects_traversal() {
+        let root = std::env::temp_dir().join(format!("microclaw_wf4_{}", uuid::Uuid::new_v4()));
+        let tool = WriteFileTool::new(root.to_str().unwrap()).with_confine_to_working_dir(true);
+
+        for path in ["../escape.txt", "/tmp/escape.txt"] {
+            let result = tool.execute(json!({"path": path, "content": "x"})).await;
+            assert!(result.is_error);
+            assert!(result.content.contains("outside the working directory"));
+        }
+        assert!(!root.join("escape.txt").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_write_append_then_read_roundtrip() {
+        let root = std::env::temp_dir().join(format!("microclaw_wf5_{}", uuid::Uuid::new_v4()));
+        let work = root.to_str().unwrap();
+        let writer = WriteFileTool::new(work).with_confine_to_working_dir(true);
+        let reader =
+            crate::tools::read_file::ReadFileTool::new(work).with_confine_to_working_dir(true);
+
+        let result = writer
+            .execute(json!({"path": "notes/log.txt", "content": "first\n"}))
+            .await;
+        assert!(!result.is_error);
+        let result = writer
+            .execute(json!({"path": "notes/log.txt", "content": "second\n", "append": true}))
+            .await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("appended"));
+
+        let result = reader.execute(json!({"path": "notes/log.txt"})).await;
+        assert!(!result.is_error);
+        assert_eq!(result.content, "     1\tfirst\n     2\tsecond");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[tokio::test]
     async fn test_write_file_resolves_relative_to_working_dir() {
         let root = std::env::temp_dir().join(format!("microclaw_wf3_{}", uuid::Uuid::new_v4()));
@@ -16,9 +16,7 @@ use tokio::sync::{broadcast, Mutex};
 use tracing::{error, info, warn};
 
 use crate::agent_engine::{process_with_agent_with_events, AgentEvent, AgentRequestContext};
-use crate::chat_commands::{
-    build_model_response, build_status_response, maybe_handle_plugin_command,
-};
+use crate::chat_commands::{build_status_response, maybe_handle_plugin_command, run_model_command};
 use crate::config::{Config, WorkingDirIsolation};
 use crate::otlp::{OtlpExporter, OtlpMetricSnapshot};
 use crate::runtime::AppState;
@@ -76,7 +74,6 @@ struct WebState {
     session_hub: SessionHub,
     request_hub: RequestHub,
     auth_hub: AuthHub,
-    metrics: Arc<Mutex<WebMetrics>>,
     otlp: Option<Arc<OtlpExporter>>,
     limits: WebLimits,
 }
@@ -87,25 +84,6 @@ struct AuthHub {
     api_key_buckets: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
 }
 
-#[derive(Clone, Debug, Default)]
-struct WebMetrics {
-    http_requests: i64,
-    request_ok: i64,
-    request_error: i64,
-    request_latency_ms: VecDeque<i64>,
-    llm_completions: i64,
-    llm_input_tokens: i64,
-    llm_output_tokens: i64,
-    tool_executions: i64,
-    tool_success: i64,
-    tool_error: i64,
-    tool_policy_blocks: i64,
-    mcp_calls: i64,
-    mcp_rate_limited_rejections: i64,
-    mcp_bulkhead_rejections: i64,
-    mcp_circuit_open_rejections: i64,
-}
-
 #[derive(Clone, Debug)]
 struct RunEvent {
     id: u64,
@@ -539,60 +517,28 @@ impl AuthHub {
     }
 }
 
-async fn metrics_http_inc(state: &WebState) {
-    let mut m = state.metrics.lock().await;
-    m.http_requests += 1;
+fn metrics_http_inc() {
+    crate::metrics::with_metrics(|m| m.http_requests += 1);
 }
 
-async fn metrics_llm_completion_inc(state: &WebState) {
-    let mut m = state.metrics.lock().await;
-    m.llm_completions += 1;
+fn metrics_llm_completion_inc() {
+    crate::metrics::with_metrics(|m| m.llm_completions += 1);
 }
 
 const METRICS_LATENCY_SAMPLE_CAP: usize = 4096;
 
-async fn metrics_record_request_result(state: &WebState, ok: bool, latency_ms: i64) {
-    let mut m = state.metrics.lock().await;
-    if ok {
-        m.request_ok += 1;
-        m.request_latency_ms.push_back(latency_ms.max(0));
-        if m.request_latency_ms.len() > METRICS_LATENCY_SAMPLE_CAP {
-            let _ = m.request_latency_ms.pop_front();
-        }
-    } else {
-        m.request_error += 1;
-    }
-}
-
-async fn metrics_apply_agent_event(state: &WebState, evt: &AgentEvent) {
-    let mut m = state.metrics.lock().await;
-    match evt {
-        AgentEvent::ToolStart { name } => {
-            m.tool_executions += 1;
-            if name.starts_with("mcp") {
-                m.mcp_calls += 1;
+fn metrics_record_request_result(ok: bool, latency_ms: i64) {
+    crate::metrics::with_metrics(|m| {
+        if ok {
+            m.request_ok += 1;
+            m.request_latency_ms.push_back(latency_ms.max(0));
+            if m.request_latency_ms.len() > METRICS_LATENCY_SAMPLE_CAP {
+                let _ = m.request_latency_ms.pop_front();
             }
+        } else {
+            m.request_error += 1;
         }
-        AgentEvent::ToolResult {
-            is_error,
-            error_type,
-            ..
-        } => {
-            if *is_error {
-                if matches!(
-                    error_type.as_deref(),
-                    Some("approval_required" | "execution_policy_blocked")
-                ) {
-                    m.tool_policy_blocks += 1;
-                } else {
-                    m.tool_error += 1;
-                }
-            } else {
-                m.tool_success += 1;
-            }
-        }
-        _ => {}
-    }
+    });
 }
 
 fn percentile_p95(values: &VecDeque<i64>) -> Option<i64> {
@@ -606,7 +552,7 @@ fn percentile_p95(values: &VecDeque<i64>) -> Option<i64> {
 }
 
 async fn persist_metrics_snapshot(state: &WebState) -> Result<(), (StatusCode, String)> {
-    let snapshot = state.metrics.lock().await.clone();
+    let snapshot = crate::metrics::snapshot();
     let active_sessions = state.request_hub.active_sessions().await as i64;
     let now = chrono::Utc::now();
     let bucket_ts_ms = (now.timestamp() / 60) * 60 * 1000;
@@ -648,6 +594,8 @@ async fn persist_metrics_snapshot(state: &WebState) -> Result<(), (StatusCode, S
             mcp_rate_limited_rejections: snapshot.mcp_rate_limited_rejections,
             mcp_bulkhead_rejections: snapshot.mcp_bulkhead_rejections,
             mcp_circuit_open_rejections: snapshot.mcp_circuit_open_rejections,
+            messages_received: snapshot.messages_received_total(),
+            errors: snapshot.errors_total(),
             active_sessions,
         };
         tokio::spawn(async move {
@@ -1002,7 +950,7 @@ async fn api_health(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
     let since_24h = (chrono::Utc::now() - chrono::Duration::hours(24)).to_rfc3339();
     let task_summary_24h = call_blocking(state.app_state.db.clone(), move |db| {
@@ -1156,14 +1104,18 @@ async fn api_usage(
     State(state): State<WebState>,
     Query(query): Query<UsageQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let session_key = normalize_session_key(query.session_key.as_deref());
     let chat_id = resolve_chat_id_for_session_key_read(&state, &session_key).await?;
-    let report = build_usage_report(state.app_state.db.clone(), chat_id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let report = build_usage_report(
+        state.app_state.db.clone(),
+        chat_id,
+        state.app_state.config.usage_report_top_senders,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
     let memory_observability = call_blocking(state.app_state.db.clone(), move |db| {
         db.get_memory_observability_summary(Some(chat_id))
     })
@@ -1197,7 +1149,7 @@ async fn api_memory_observability(
     State(state): State<WebState>,
     Query(query): Query<MemoryObservabilityQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let scope = query
@@ -1293,7 +1245,7 @@ async fn api_send(
     State(state): State<WebState>,
     Json(body): Json<SendRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Write).await?;
     let start = Instant::now();
     let session_key = normalize_session_key(body.session_key.as_deref());
@@ -1310,14 +1262,14 @@ async fn api_send(
             reason = %msg,
             "Request rejected by limiter"
         );
-        metrics_record_request_result(&state, false, start.elapsed().as_millis() as i64).await;
+        metrics_record_request_result(false, start.elapsed().as_millis() as i64);
         return Err((status, msg));
     }
     let result = send_and_store_response(state.clone(), body).await;
     if result.is_ok() {
-        metrics_llm_completion_inc(&state).await;
+        metrics_llm_completion_inc();
     }
-    metrics_record_request_result(&state, result.is_ok(), start.elapsed().as_millis() as i64).await;
+    metrics_record_request_result(result.is_ok(), start.elapsed().as_millis() as i64);
     state
         .request_hub
         .end_with_limits(&session_key, &identity.actor, &state.limits)
@@ -1381,12 +1333,6 @@ async fn send_and_store_response_with_events(
         .unwrap_or("web-user")
         .to_string();
 
-    let before_usage = call_blocking(state.app_state.db.clone(), move |db| {
-        db.get_llm_usage_summary(Some(chat_id))
-    })
-    .await
-    .ok();
-
     if let Some(explicit_chat_id) = parsed_chat_id {
         let is_web = get_chat_routing(
             &state.app_state.channel_registry,
@@ -1405,6 +1351,7 @@ async fn send_and_store_response_with_events(
         }
     }
 
+    crate::metrics::record_message_received("web");
     if let Some(command_response) = handle_web_slash_command(&state, &text, chat_id).await {
         let bot_username = state.app_state.config.bot_username_for_channel("web");
         deliver_and_store_bot_message(
@@ -1443,33 +1390,10 @@ async fn send_and_store_response_with_events(
         chat_id,
         chat_type: "web",
     };
-    let response = if let Some(tx) = event_tx {
-        process_with_agent_with_events(&state.app_state, request_ctx, None, None, Some(tx))
+    let response =
+        process_with_agent_with_events(&state.app_state, request_ctx, None, None, event_tx)
             .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-    } else {
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-        let result =
-            process_with_agent_with_events(&state.app_state, request_ctx, None, None, Some(&tx))
-                .await
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
-        drop(tx);
-        while let Some(evt) = rx.recv().await {
-            metrics_apply_agent_event(&state, &evt).await;
-        }
-        result?
-    };
-
-    let after_usage = call_blocking(state.app_state.db.clone(), move |db| {
-        db.get_llm_usage_summary(Some(chat_id))
-    })
-    .await
-    .ok();
-    if let (Some(before), Some(after)) = (before_usage, after_usage) {
-        let mut m = state.metrics.lock().await;
-        m.llm_input_tokens += (after.input_tokens - before.input_tokens).max(0);
-        m.llm_output_tokens += (after.output_tokens - before.output_tokens).max(0);
-    }
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let bot_username = state.app_state.config.bot_username_for_channel("web");
     deliver_and_store_bot_message(
@@ -1496,12 +1420,20 @@ async fn handle_web_slash_command(state: &WebState, text: &str, chat_id: i64) ->
         return None;
     }
 
-    if trimmed == "/reset" {
-        let _ = call_blocking(state.app_state.db.clone(), move |db| {
-            db.clear_chat_context(chat_id)
-        })
-        .await;
-        return Some("Context cleared (session + chat history).".to_string());
+    if trimmed == "/reset" || trimmed.starts_with("/reset ") {
+        return Some(
+            crate::chat_commands::run_reset_command(
+                state.app_state.db.clone(),
+                chat_id,
+                trimmed,
+                state.app_state.config.reset_default_scope,
+            )
+            .await,
+        );
+    }
+
+    if trimmed == "/help" {
+        return Some(crate::chat_commands::build_help_text(&[]));
     }
 
     if trimmed == "/stop" {
@@ -1517,8 +1449,7 @@ async fn handle_web_slash_command(state: &WebState, text: &str, chat_id: i64) ->
     }
 
     if trimmed == "/reload-skills" {
-        let reloaded = state.app_state.skills.reload();
-        return Some(format!("Reloaded {} skills from disk.", reloaded.len()));
+        return Some(state.app_state.skills.reload().summary());
     }
 
     if trimmed == "/archive" {
@@ -1542,8 +1473,26 @@ async fn handle_web_slash_command(state: &WebState, text: &str, chat_id: i64) ->
         return Some("No session to archive.".to_string());
     }
 
+    if trimmed == "/search" || trimmed.starts_with("/search ") {
+        return Some(
+            crate::chat_commands::run_search_command(
+                state.app_state.db.clone(),
+                state.app_state.embedding.as_ref(),
+                chat_id,
+                trimmed,
+            )
+            .await,
+        );
+    }
+
     if trimmed == "/usage" {
-        return match build_usage_report(state.app_state.db.clone(), chat_id).await {
+        return match build_usage_report(
+            state.app_state.db.clone(),
+            chat_id,
+            state.app_state.config.usage_report_top_senders,
+        )
+        .await
+        {
             Ok(report) => Some(report),
             Err(e) => Some(format!("Failed to query usage statistics: {e}")),
         };
@@ -1562,12 +1511,17 @@ async fn handle_web_slash_command(state: &WebState, text: &str, chat_id: i64) ->
     }
 
     if trimmed == "/model" || trimmed.starts_with("/model ") {
-        return Some(build_model_response(
-            &state.app_state.config,
-            &state.app_state.llm_model_overrides,
-            "web",
-            trimmed,
-        ));
+        return Some(
+            run_model_command(
+                state.app_state.db.clone(),
+                &state.app_state.config,
+                &state.app_state.llm_model_overrides,
+                "web",
+                chat_id,
+                trimmed,
+            )
+            .await,
+        );
     }
 
     if let Some(plugin_response) =
@@ -1583,7 +1537,7 @@ async fn api_audit_logs(
     State(state): State<WebState>,
     Query(query): Query<AuditQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Admin).await?;
     let limit = query.limit.unwrap_or(200).clamp(1, 2000);
     let kind = query.kind.map(|k| k.trim().to_string());
@@ -1638,7 +1592,6 @@ pub async fn start_web_server(state: Arc<AppState>) {
         session_hub: SessionHub::default(),
         request_hub: RequestHub::default(),
         auth_hub: AuthHub::default(),
-        metrics: Arc::new(Mutex::new(WebMetrics::default())),
         otlp: OtlpExporter::from_config(&state.config),
         limits,
     };
@@ -1672,14 +1625,17 @@ pub async fn start_web_server(state: Arc<AppState>) {
         Ok(listener) => listener,
         Err(e) => {
             error!("Failed to bind web server at {}: {}", addr, e);
+            crate::health::report_channel_connected("web", false);
             return;
         }
     };
 
     info!("Web UI available at http://{addr}");
+    crate::health::report_channel_connected("web", true);
     if let Err(e) = axum::serve(listener, router).await {
         error!("Web server error: {e}");
     }
+    crate::health::report_channel_connected("web", false);
 }
 
 async fn asset_file(Path(file): Path<String>) -> impl IntoResponse {
@@ -1818,6 +1774,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             })
         }
 
@@ -1858,6 +1815,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             })
         }
     }
@@ -1884,6 +1842,7 @@ mod tests {
                     }],
                     stop_reason: Some("tool_use".into()),
                     usage: None,
+                    served_by: None,
                 });
             }
             Ok(microclaw_core::llm_types::MessagesResponse {
@@ -1892,6 +1851,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             })
         }
     }
@@ -1917,6 +1877,7 @@ mod tests {
         let channel_registry = Arc::new(registry);
         let state = AppState {
             config: cfg.clone(),
+            live_config: Arc::new(arc_swap::ArcSwap::from_pointee(cfg.clone())),
             channel_registry: channel_registry.clone(),
             db: db.clone(),
             memory: MemoryManager::new(&runtime_dir),
@@ -1927,6 +1888,9 @@ mod tests {
             embedding: None,
             memory_backend: memory_backend.clone(),
             tools: ToolRegistry::new(&cfg, channel_registry, db, memory_backend),
+            shutdown: tokio_util::sync::CancellationToken::new(),
+            tasks: tokio_util::task::TaskTracker::new(),
+            rate_limiter: crate::rate_limit::SenderRateLimiter::new(),
         };
         Arc::new(state)
     }
@@ -1948,7 +1912,6 @@ mod tests {
             session_hub: SessionHub::default(),
             request_hub: RequestHub::default(),
             auth_hub: AuthHub::default(),
-            metrics: Arc::new(Mutex::new(WebMetrics::default())),
             otlp: None,
             limits,
         }
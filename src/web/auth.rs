@@ -12,7 +12,7 @@ pub(super) async fn api_auth_status(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let hash = call_blocking(state.app_state.db.clone(), |db| db.get_auth_password_hash())
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -37,7 +37,7 @@ pub(super) async fn api_auth_set_password(
     State(state): State<WebState>,
     Json(body): Json<SetPasswordRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let has_password = call_blocking(state.app_state.db.clone(), |db| db.get_auth_password_hash())
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -96,7 +96,7 @@ pub(super) async fn api_auth_login(
     State(state): State<WebState>,
     Json(body): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let client_key = client_key_from_headers_with_config(&headers, &state.app_state.config);
     let allowed = state
         .auth_hub
@@ -185,7 +185,7 @@ pub(super) async fn api_auth_logout(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     if let Some(session_id) = parse_cookie(&headers, "mc_session") {
         let _ = call_blocking(state.app_state.db.clone(), move |db| {
             db.revoke_auth_session(&session_id)
@@ -206,7 +206,7 @@ pub(super) async fn api_auth_api_keys(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Admin).await?;
     let keys = call_blocking(state.app_state.db.clone(), |db| db.list_api_keys())
         .await
@@ -235,7 +235,7 @@ pub(super) async fn api_auth_create_api_key(
     State(state): State<WebState>,
     Json(body): Json<CreateApiKeyRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
     let label = body.label.trim().to_string();
     if label.is_empty() {
@@ -291,7 +291,7 @@ pub(super) async fn api_auth_revoke_api_key(
     State(state): State<WebState>,
     Path(key_id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
     let revoked = call_blocking(state.app_state.db.clone(), move |db| {
         db.revoke_api_key(key_id)
@@ -317,7 +317,7 @@ pub(super) async fn api_auth_rotate_api_key(
     Path(key_id): Path<i64>,
     Json(body): Json<RotateApiKeyRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
     let keys = call_blocking(state.app_state.db.clone(), |db| db.list_api_keys())
         .await
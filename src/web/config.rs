@@ -5,7 +5,7 @@ pub(super) async fn api_get_config(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let path = config_path_for_save()?;
@@ -21,7 +21,7 @@ pub(super) async fn api_config_self_check(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let mut warnings = Vec::<ConfigWarning>::new();
@@ -406,7 +406,7 @@ pub(super) async fn api_update_config(
     State(state): State<WebState>,
     Json(body): Json<UpdateConfigRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
 
     let mut cfg = state.app_state.config.clone();
@@ -560,7 +560,7 @@ pub(super) async fn api_update_config(
     }
 
     let path = config_path_for_save()?;
-    cfg.save_yaml(&path.to_string_lossy())
+    cfg.save_to_path(&path)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     audit_log(
@@ -4,11 +4,11 @@ pub(super) async fn api_metrics(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
     persist_metrics_snapshot(&state).await?;
 
-    let snapshot = state.metrics.lock().await.clone();
+    let snapshot = crate::metrics::snapshot();
     let active_sessions = state.request_hub.active_sessions().await as i64;
     Ok(Json(json!({
         "ok": true,
@@ -36,11 +36,11 @@ pub(super) async fn api_metrics_summary(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
     persist_metrics_snapshot(&state).await?;
 
-    let snapshot = state.metrics.lock().await.clone();
+    let snapshot = crate::metrics::snapshot();
     let active_sessions = state.request_hub.active_sessions().await as i64;
     let request_total = snapshot.request_ok + snapshot.request_error;
     let request_success_rate = if request_total > 0 {
@@ -138,7 +138,7 @@ pub(super) async fn api_metrics_history(
     State(state): State<WebState>,
     Query(query): Query<MetricsHistoryQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
     persist_metrics_snapshot(&state).await?;
 
@@ -4,7 +4,7 @@ pub(super) async fn api_sessions(
     headers: HeaderMap,
     State(state): State<WebState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let chats = call_blocking(state.app_state.db.clone(), |db| db.get_recent_chats(400))
@@ -23,7 +23,7 @@ pub(super) async fn api_history(
     State(state): State<WebState>,
     Query(query): Query<HistoryQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
 
     let session_key = normalize_session_key(query.session_key.as_deref());
@@ -65,7 +65,7 @@ pub(super) async fn api_reset(
     State(state): State<WebState>,
     Json(body): Json<ResetRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Approvals).await?;
 
     let session_key = normalize_session_key(body.session_key.as_deref());
@@ -122,7 +122,7 @@ pub(super) async fn api_delete_session(
     State(state): State<WebState>,
     Json(body): Json<ResetRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Approvals).await?;
 
     let session_key = normalize_session_key(body.session_key.as_deref());
@@ -152,7 +152,7 @@ pub(super) async fn api_sessions_fork(
     State(state): State<WebState>,
     Json(body): Json<ForkSessionRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Approvals).await?;
 
     let source_session_key = normalize_session_key(Some(&body.source_session_key));
@@ -262,7 +262,7 @@ pub(super) async fn api_sessions_tree(
     State(state): State<WebState>,
     Query(query): Query<SessionTreeQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     require_scope(&state, &headers, AuthScope::Read).await?;
     let limit = query.limit.unwrap_or(1000).clamp(1, 5000);
     let rows = call_blocking(state.app_state.db.clone(), move |db| {
@@ -6,13 +6,13 @@ pub(super) async fn api_send_stream(
     State(state): State<WebState>,
     Json(body): Json<SendRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Write).await?;
     let start = Instant::now();
 
     let text = body.message.trim().to_string();
     if text.is_empty() {
-        metrics_record_request_result(&state, false, start.elapsed().as_millis() as i64).await;
+        metrics_record_request_result(false, start.elapsed().as_millis() as i64);
         return Err((StatusCode::BAD_REQUEST, "message is required".into()));
     }
 
@@ -30,7 +30,7 @@ pub(super) async fn api_send_stream(
             reason = %msg,
             "Request rejected by limiter"
         );
-        metrics_record_request_result(&state, false, start.elapsed().as_millis() as i64).await;
+        metrics_record_request_result(false, start.elapsed().as_millis() as i64);
         return Err((status, msg));
     }
 
@@ -69,7 +69,6 @@ pub(super) async fn api_send_stream(
 
             let (evt_tx, mut evt_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
             let run_hub = state_for_task.run_hub.clone();
-            let state_for_events = state_for_task.clone();
             let run_id_for_events = run_id_for_task.clone();
             let run_history_limit = limits.run_history_limit;
             let forward = tokio::spawn(async move {
@@ -87,11 +86,6 @@ pub(super) async fn api_send_stream(
                                 .await;
                         }
                         AgentEvent::ToolStart { name } => {
-                            super::metrics_apply_agent_event(
-                                &state_for_events,
-                                &AgentEvent::ToolStart { name: name.clone() },
-                            )
-                            .await;
                             run_hub
                                 .publish(
                                     &run_id_for_events,
@@ -110,19 +104,6 @@ pub(super) async fn api_send_stream(
                             bytes,
                             error_type,
                         } => {
-                            super::metrics_apply_agent_event(
-                                &state_for_events,
-                                &AgentEvent::ToolResult {
-                                    name: name.clone(),
-                                    is_error,
-                                    preview: preview.clone(),
-                                    duration_ms,
-                                    status_code,
-                                    bytes,
-                                    error_type: error_type.clone(),
-                                },
-                            )
-                            .await;
                             run_hub
                                 .publish(
                                     &run_id_for_events,
@@ -160,13 +141,8 @@ pub(super) async fn api_send_stream(
                 .await
             {
                 Ok(resp) => {
-                    metrics_llm_completion_inc(&state_for_task).await;
-                    metrics_record_request_result(
-                        &state_for_task,
-                        true,
-                        run_start.elapsed().as_millis() as i64,
-                    )
-                    .await;
+                    metrics_llm_completion_inc();
+                    metrics_record_request_result(true, run_start.elapsed().as_millis() as i64);
                     let response_text = resp
                         .0
                         .get("response")
@@ -185,12 +161,7 @@ pub(super) async fn api_send_stream(
                         .await;
                 }
                 Err((_, err_msg)) => {
-                    metrics_record_request_result(
-                        &state_for_task,
-                        false,
-                        run_start.elapsed().as_millis() as i64,
-                    )
-                    .await;
+                    metrics_record_request_result(false, run_start.elapsed().as_millis() as i64);
                     state_for_task
                         .run_hub
                         .publish(
@@ -211,12 +182,7 @@ pub(super) async fn api_send_stream(
             .await
             .is_err();
         if panicked {
-            metrics_record_request_result(
-                &state_for_task,
-                false,
-                run_start.elapsed().as_millis() as i64,
-            )
-            .await;
+            metrics_record_request_result(false, run_start.elapsed().as_millis() as i64);
             state_for_task
                 .run_hub
                 .publish(
@@ -266,7 +232,7 @@ pub(super) async fn api_stream(
     State(state): State<WebState>,
     Query(query): Query<StreamQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Read).await?;
     let start = Instant::now();
 
@@ -362,7 +328,7 @@ pub(super) async fn api_run_status(
     State(state): State<WebState>,
     Query(query): Query<RunStatusQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    metrics_http_inc(&state).await;
+    metrics_http_inc();
     let identity = require_scope(&state, &headers, AuthScope::Read).await?;
     let (done, last_event_id) = match state
         .run_hub
@@ -1,6 +1,6 @@
 //! Integration tests for configuration loading and validation.
 
-use microclaw::config::{Config, WorkingDirIsolation};
+use microclaw::config::{Config, LogFormat, WorkingDirIsolation};
 
 /// Helper to create a minimal valid config for testing.
 fn minimal_config() -> Config {
@@ -11,17 +11,29 @@ fn minimal_config() -> Config {
         api_key: "test-key".into(),
         model: String::new(),
         llm_base_url: None,
+        llm_fallbacks: vec![],
         max_tokens: 8192,
         max_tool_iterations: 25,
+        max_concurrent_agents: 4,
+        messages_per_minute: 0,
         max_history_messages: 50,
         max_document_size_mb: 100,
         memory_token_budget: 1500,
         data_dir: "./microclaw.data".into(),
         skills_dir: None,
+        prune_builtin_skills: false,
+        skills_watch: false,
+        skills_watch_debounce_ms: 500,
         working_dir: "./tmp".into(),
         working_dir_isolation: WorkingDirIsolation::Chat,
+        file_tools_confine_to_working_dir: true,
+        read_file_max_bytes: 10 * 1024 * 1024,
         sandbox: microclaw::config::SandboxConfig::default(),
         openai_api_key: None,
+        http_proxy: None,
+        https_proxy: None,
+        no_proxy: None,
+        http_timeout_secs: 60,
         timezone: "UTC".into(),
         allowed_groups: vec![],
         control_chat_ids: vec![],
@@ -29,12 +41,20 @@ fn minimal_config() -> Config {
         compact_keep_recent: 20,
         default_tool_timeout_secs: 30,
         tool_timeout_overrides: std::collections::HashMap::new(),
+        browser_binary: "agent-browser".into(),
+        browser_session: None,
+        browser_allowed_commands: microclaw::tools::browser::default_allowed_commands(),
+        browser_allow_all_commands: false,
+        browser_max_sessions: 8,
+        browser_session_idle_secs: 1800,
+        browser_max_output_chars: 30000,
         default_mcp_request_timeout_secs: 120,
         compaction_timeout_secs: 180,
         discord_bot_token: None,
         discord_allowed_channels: vec![],
         discord_no_mention: false,
         allow_group_slash_without_mention: false,
+        reset_default_scope: microclaw::config::ResetScope::All,
         show_thinking: false,
         openai_compat_body_overrides: std::collections::HashMap::new(),
         openai_compat_body_overrides_by_provider: std::collections::HashMap::new(),
@@ -52,14 +72,27 @@ fn minimal_config() -> Config {
             microclaw_tools::web_content_validation::WebContentValidationConfig::default(),
         web_fetch_url_validation: microclaw_tools::web_fetch::WebFetchUrlValidationConfig::default(
         ),
+        fetch_url_max_bytes: 100_000,
+        fetch_url_allow_private_networks: false,
         model_prices: vec![],
+        usage_report_top_senders: 5,
+        usage_budget: None,
         embedding_provider: None,
         embedding_api_key: None,
         embedding_base_url: None,
         embedding_model: None,
         embedding_dim: None,
+        embedding_cache_size: 1024,
+        embedding_max_retries: 3,
+        embedding_normalize: true,
+        embedding_max_chars: 0,
         reflector_enabled: true,
         reflector_interval_mins: 15,
+        log_format: LogFormat::Text,
+        metrics_port: None,
+        metrics_host: "127.0.0.1".into(),
+        health_port: None,
+        health_host: "127.0.0.1".into(),
         soul_path: None,
         clawhub: microclaw::config::ClawHubConfig::default(),
         plugins: microclaw::plugins::PluginsConfig::default(),